@@ -0,0 +1,226 @@
+use crate::config::wallet_config::{ConfigSource, WalletConfig};
+use crate::config::wallet_registry::{AccountSource, WalletAccount, WalletRegistry};
+use crate::error::WalletError;
+use crate::solana::keystore::SolanaKeystore;
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+pub struct AccountManager {
+    config: WalletConfig,
+}
+
+impl AccountManager {
+    pub fn new(config: WalletConfig) -> Self {
+        Self { config }
+    }
+
+    /// Affiche tous les comptes du wallet (le compte par défaut est marqué d'une "*").
+    pub fn list(&self) -> Result<(), WalletError> {
+        let registry = self.load()?;
+
+        self.config.output.json(json!({
+            "default_account": registry.default_account,
+            "accounts": registry.accounts,
+        }));
+        if registry.accounts.is_empty() {
+            self.config.output.line("No account in wallet.");
+            return Ok(());
+        }
+
+        self.config.output.line(format!(
+            "  {:<5} {:<16} {:<10} {:<44} {:<20} CREATED AT",
+            "INDEX", "LABEL", "SOURCE", "PUBKEY", "DERIVATION PATH"
+        ));
+        for account in &registry.accounts {
+            let marker = if registry.default_account == Some(account.index) {
+                "*"
+            } else {
+                " "
+            };
+            self.config.output.line(format!(
+                "{} {:<5} {:<16} {:<10} {:<44} {:<20} {}",
+                marker,
+                account.index,
+                account.label,
+                account.source.to_string(),
+                account.pubkey,
+                account.derivation_path.as_deref().unwrap_or("-"),
+                account.created_at
+            ));
+        }
+        Ok(())
+    }
+
+    /// Ajoute un compte au wallet : importé depuis un fichier de paire de clés, ou watch-only depuis une clé publique.
+    ///
+    /// # Arguments:
+    /// - label - Le label du compte.
+    /// - keypair_path - Le fichier de paire de clés à importer (en clair ou keystore chiffré).
+    /// - pubkey - La clé publique d'un compte watch-only.
+    pub fn add(
+        &self,
+        label: &str,
+        keypair_path: Option<&str>,
+        pubkey: Option<&str>,
+    ) -> Result<(), WalletError> {
+        self.update(|registry| {
+            let (pubkey, keypair_path, source) = match (keypair_path, pubkey) {
+                (Some(keypair_path), _) => (
+                    SolanaKeystore::read_pubkey(keypair_path)?.to_string(),
+                    Some(keypair_path.to_string()),
+                    AccountSource::Imported,
+                ),
+                (None, Some(pubkey)) => (
+                    Pubkey::from_str(pubkey)
+                        .map_err(|_| {
+                            WalletError::InvalidPubkey("Invalid public key format".to_string())
+                        })?
+                        .to_string(),
+                    None,
+                    AccountSource::WatchOnly,
+                ),
+                (None, None) => {
+                    return Err(WalletError::InvalidInput(
+                        "A keypair file or a public key is required".to_string(),
+                    ))
+                }
+            };
+
+            let index = registry
+                .add(label, &pubkey, None, keypair_path, source)
+                .map_err(WalletError::Account)?;
+            Ok((
+                format!(
+                    "Account '{}' added (index {}, {}): {}",
+                    label, index, source, pubkey
+                ),
+                registry
+                    .require(&index.to_string())
+                    .map_err(WalletError::Account)?
+                    .clone(),
+            ))
+        })
+        .map_err(|e| e.context("Failed to add account"))
+    }
+
+    /// Renomme un compte.
+    pub fn rename(&self, selector: &str, new_label: &str) -> Result<(), WalletError> {
+        self.update(|registry| {
+            registry
+                .rename(selector, new_label)
+                .map_err(WalletError::Account)?;
+            Ok((
+                format!("Account '{}' renamed to '{}'", selector, new_label),
+                registry
+                    .require(new_label)
+                    .map_err(WalletError::Account)?
+                    .clone(),
+            ))
+        })
+        .map_err(|e| e.context("Failed to rename account"))
+    }
+
+    /// Supprime un compte de l'index du wallet (le fichier de la paire de clés est conservé).
+    pub fn remove(&self, selector: &str) -> Result<(), WalletError> {
+        self.update(|registry| {
+            let account = registry.remove(selector).map_err(WalletError::Account)?;
+            let message = match &account.keypair_path {
+                Some(keypair_path) => format!(
+                    "Account '{}' removed from wallet (keypair file kept: {})",
+                    account.label, keypair_path
+                ),
+                None => format!("Account '{}' removed from wallet", account.label),
+            };
+            Ok((message, account))
+        })
+        .map_err(|e| e.context("Failed to remove account"))
+    }
+
+    /// Définit le compte utilisé par défaut par "send", "pubkey" et "balance".
+    pub fn set_default(&self, selector: &str) -> Result<(), WalletError> {
+        self.update(|registry| {
+            let account = registry
+                .set_default(selector)
+                .map_err(WalletError::Account)?;
+            Ok((
+                format!("Default account: '{}' ({})", account.label, account.pubkey),
+                account.clone(),
+            ))
+        })
+        .map_err(|e| e.context("Failed to set default account"))
+    }
+
+    /// Retourne le compte sélectionné : celui donné par "--account", sinon le compte par défaut de la configuration
+    /// (`WALLET_ACCOUNT` ou profil), sinon le compte par défaut du wallet.
+    /// Retourne None si aucun compte n'est sélectionné (la paire de clés de `KEYPAIR_PATH` est alors utilisée),
+    /// ou si une paire de clés est donnée avec "--keypair" sans "--account".
+    pub fn resolve(&self, selector: Option<&str>) -> Result<Option<WalletAccount>, WalletError> {
+        if selector.is_none()
+            && self.config.source("keypair_path") == ConfigSource::Flag("--keypair")
+        {
+            return Ok(None);
+        }
+
+        let registry = self.load()?;
+        match selector.or(self.config.default_account.as_deref()) {
+            Some(selector) => Ok(Some(
+                registry
+                    .require(selector)
+                    .map_err(WalletError::Account)?
+                    .clone(),
+            )),
+            None => Ok(registry.default_account().cloned()),
+        }
+    }
+
+    /// Retourne une copie de la configuration dont `keypair_path` pointe vers la paire de clés du compte sélectionné.
+    /// Un compte watch-only ne peut pas signer : l'erreur indique alors la signature hors ligne ("tx build/sign/broadcast").
+    pub fn config_for_account(&self, selector: Option<&str>) -> Result<WalletConfig, WalletError> {
+        let mut config = self.config.clone();
+        if let Some(account) = self.resolve(selector)? {
+            config.keypair_path = account.keypair_path.ok_or_else(|| {
+                WalletError::Account(format!(
+                    "Account '{}' is watch-only: it has no keypair on this machine. \
+                     Build an unsigned transaction with \"tx build --account {}\", \
+                     sign it with \"tx sign\" on the machine holding its key, then \"tx broadcast\" it",
+                    account.label, account.label
+                ))
+            })?;
+        }
+        Ok(config)
+    }
+
+    /// Retourne la clé publique du compte sélectionné (y compris pour un compte watch-only).
+    pub fn pubkey_for_account(&self, selector: Option<&str>) -> Result<Pubkey, WalletError> {
+        match self.resolve(selector)? {
+            Some(WalletAccount {
+                keypair_path: Some(keypair_path),
+                ..
+            }) => SolanaKeystore::read_pubkey(&keypair_path),
+            Some(account) => Ok(Pubkey::from_str(&account.pubkey)?),
+            None => SolanaKeystore::read_pubkey(&self.config.keypair_path),
+        }
+    }
+
+    fn load(&self) -> Result<WalletRegistry, WalletError> {
+        WalletRegistry::load(&self.config.wallet_registry_path)
+            .map_err(|e| WalletError::Account(format!("Failed to load wallet registry: {}", e)))
+    }
+
+    /// Charge l'index du wallet, applique une modification, l'enregistre si elle a réussi, puis affiche son résultat
+    /// (un message, et le compte modifié en JSON).
+    fn update<F>(&self, change: F) -> Result<(), WalletError>
+    where
+        F: FnOnce(&mut WalletRegistry) -> Result<(String, WalletAccount), WalletError>,
+    {
+        let mut registry = self.load()?;
+        let (message, account) = change(&mut registry)?;
+        registry
+            .save(&self.config.wallet_registry_path)
+            .map_err(|e| WalletError::Account(format!("Failed to save wallet registry: {}", e)))?;
+        self.config.output.line(message);
+        self.config.output.json(json!({ "account": account }));
+        Ok(())
+    }
+}
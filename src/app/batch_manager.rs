@@ -0,0 +1,369 @@
+use crate::app::mainnet_guard::MainnetGuard;
+use crate::app::spending_limits::SpendingLimits;
+use crate::config::wallet_config::WalletConfig;
+use crate::error::WalletError;
+use crate::solana::amount::{AmountUnit, SolAmount};
+use crate::solana::balance::SolanaBalance;
+use crate::solana::batch::{BatchPayment, BatchTransactionStatus, SolanaBatch};
+use crate::solana::batch_file::{BatchFile, PaymentStatus};
+use crate::solana::keystore::SolanaKeystore;
+use crate::solana::rpc::WalletRpc;
+use crate::solana::transaction::SolanaTransaction;
+use serde_json::json;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Signature, Signer};
+use solana_sdk::transaction::Transaction;
+use std::collections::HashSet;
+use std::fs;
+use std::rc::Rc;
+use std::str::FromStr;
+
+pub struct BatchManager {
+    config: WalletConfig,
+    rpc: Rc<dyn WalletRpc>,
+}
+
+impl BatchManager {
+    pub fn new(config: WalletConfig, rpc: Rc<dyn WalletRpc>) -> Self {
+        Self { config, rpc }
+    }
+
+    /// Envoie les paiements d'un fichier CSV ("recipient,amount[,memo]"), en regroupant plusieurs transferts par transaction.
+    ///
+    /// L'état de chaque paiement est enregistré dans un fichier de résultats : relancée après une interruption,
+    /// la commande reprend où elle s'était arrêtée, sans jamais renvoyer un paiement peut-être déjà effectué.
+    ///
+    /// # Arguments:
+    /// - csv_path - Le chemin du fichier CSV.
+    /// - results_path - Le chemin du fichier de résultats (par défaut : "<csv_path>.results.json").
+    /// - unit - L'unité des montants du fichier (lamports ou SOL).
+    /// - yes - Envoie sans demander de confirmation.
+    pub fn send_batch(
+        &self,
+        csv_path: &str,
+        results_path: Option<&str>,
+        unit: AmountUnit,
+        yes: bool,
+    ) -> Result<(), WalletError> {
+        // Tous les paiements sont validés avant le moindre envoi.
+        let content = fs::read_to_string(csv_path).map_err(|e| {
+            WalletError::File(format!("Failed to read CSV file {}: {}", csv_path, e))
+        })?;
+        let payments = SolanaBatch::parse_csv(&content, unit).map_err(|errors| {
+            WalletError::InvalidInput(format!(
+                "Invalid CSV file {}:\n  {}",
+                csv_path,
+                errors.join("\n  ")
+            ))
+        })?;
+        if payments.is_empty() {
+            return Err(WalletError::InvalidInput(format!(
+                "No payment found in {}",
+                csv_path
+            )));
+        }
+
+        let sender = SolanaKeystore::read_keypair(&self.config.keypair_path)
+            .map_err(|e| e.context("Failed to read keypair from file"))?;
+        let sender_pubkey = sender.pubkey();
+
+        // Reprise d'un envoi interrompu, ou nouveau fichier de résultats.
+        let results_path = results_path
+            .map(String::from)
+            .unwrap_or_else(|| format!("{}.results.json", csv_path));
+        let mut batch_file = match BatchFile::load(&results_path)? {
+            Some(batch_file) => {
+                batch_file
+                    .check(&sender_pubkey.to_string(), &content)
+                    .map_err(|e| {
+                        WalletError::File(format!("Cannot resume from {}: {}", results_path, e))
+                    })?;
+                self.config
+                    .output
+                    .line(format!("Resuming batch from {}", results_path));
+                batch_file
+            }
+            None => BatchFile::new(&sender_pubkey.to_string(), &content, &payments),
+        };
+        self.check_pending(&mut batch_file, &results_path)?;
+
+        let unsent_lines = batch_file.lines_with_status(PaymentStatus::Unsent);
+        let already_sent = batch_file.lines_with_status(PaymentStatus::Sent).len();
+        let remaining: Vec<BatchPayment> = payments
+            .into_iter()
+            .filter(|payment| unsent_lines.contains(&payment.line))
+            .collect();
+        if remaining.is_empty() {
+            self.config.output.line(format!(
+                "All {} payments were already sent (see {})",
+                already_sent, results_path
+            ));
+            self.config.output.json(json!({
+                "results_file": results_path,
+                "sender": sender_pubkey.to_string(),
+                "transactions": [],
+                "payments": batch_file.payments,
+            }));
+            return Ok(());
+        }
+
+        // Un paiement trop petit pour créer le compte de son destinataire ferait échouer toute sa transaction,
+        // à chaque nouvelle tentative : ces paiements sont refusés avant le moindre envoi.
+        let rent_reserve = self.rpc.get_minimum_balance_for_rent_exemption(0)?;
+        let below_rent =
+            SolanaBatch::payments_below_rent(self.rpc.as_ref(), &remaining, rent_reserve)?;
+        if !below_rent.is_empty() {
+            let lines: Vec<String> = below_rent
+                .iter()
+                .map(|payment| {
+                    format!(
+                        "Line {}: {} lamports to {}",
+                        payment.line, payment.lamports, payment.recipient
+                    )
+                })
+                .collect();
+            return Err(WalletError::InvalidInput(format!(
+                "Payments below the rent-exempt minimum ({} lamports) to accounts which do not exist yet:\n  {}",
+                rent_reserve,
+                lines.join("\n  ")
+            )));
+        }
+
+        // Regroupement des transferts en transactions, résumé, puis confirmation (sauf avec "--yes").
+        let batches = SolanaBatch::pack(&sender_pubkey, &remaining)?;
+        let messages: Vec<Message> = batches
+            .iter()
+            .map(|batch| SolanaBatch::message(&sender_pubkey, batch))
+            .collect();
+        let total = remaining
+            .iter()
+            .try_fold(0u64, |total, payment| total.checked_add(payment.lamports))
+            .ok_or("The total amount of the batch is too large")?;
+        let fee = SolanaBatch::estimate_fees(self.rpc.as_ref(), &messages)?;
+        let balance = SolanaBalance::new(Rc::clone(&self.rpc))
+            .get_balance_by_pubkey(&sender_pubkey.to_string())?;
+
+        self.print_preview(
+            &sender_pubkey,
+            &remaining,
+            already_sent,
+            batches.len(),
+            total,
+            fee,
+            balance,
+        );
+        // L'expéditeur garde sa réserve d'exemption de rente, sauf si le lot vide exactement son compte.
+        let spent = u128::from(total) + u128::from(fee);
+        let needed = spent + u128::from(rent_reserve);
+        if u128::from(balance) != spent && u128::from(balance) < needed {
+            return Err(WalletError::InsufficientFunds(format!(
+                "Insufficient balance: {} lamports available, {} lamports needed (payments + fees + rent-exempt reserve of {} lamports)",
+                balance, needed, rent_reserve
+            )));
+        }
+        // Chaque transaction, et le total du lot, comptent dans les plafonds de dépense du profil.
+        let totals: Vec<u64> = batches
+            .iter()
+            .map(|batch| {
+                batch.iter().fold(0u64, |total, payment| {
+                    total.saturating_add(payment.lamports)
+                })
+            })
+            .collect();
+        let spending_limits = SpendingLimits::new(self.config.clone());
+        spending_limits.check(&totals)?;
+        MainnetGuard::new(&self.config, self.rpc.as_ref()).check("send a batch of payments")?;
+        if !yes && !self.config.output.confirm("Send these payments? [y/N]:") {
+            return Err(WalletError::Cancelled("Batch cancelled".to_string()));
+        }
+        batch_file.save(&results_path)?;
+
+        let mut signatures = Vec::with_capacity(batches.len());
+        for (index, ((batch, message), lamports)) in
+            batches.iter().zip(messages).zip(totals).enumerate()
+        {
+            let (blockhash, last_valid_block_height) =
+                SolanaBatch::latest_blockhash(self.rpc.as_ref())?;
+            let mut transaction = Transaction::new_unsigned(message);
+            transaction.try_sign(&[&sender], blockhash)?;
+            let signature = transaction.signatures[0].to_string();
+
+            // La signature est enregistrée avant la diffusion : après une interruption, l'état de
+            // la transaction est vérifié sur le réseau avant de renvoyer ses paiements.
+            let lines: Vec<usize> = batch.iter().map(|payment| payment.line).collect();
+            batch_file.set_pending(&lines, &signature, lamports, last_valid_block_height);
+            batch_file.save(&results_path)?;
+
+            if let Err(e) = SolanaTransaction::broadcast(self.rpc.as_ref(), &transaction) {
+                return Err(WalletError::Transaction(format!(
+                    "Transaction {} failed: {}\nIts payments are pending in {}: run the command again to resume (they are sent again only if the transaction was not confirmed)",
+                    signature, e, results_path
+                )));
+            }
+            batch_file.set_transaction_status(&signature, PaymentStatus::Sent, None);
+            batch_file.save(&results_path)?;
+            spending_limits.record(lamports, &signature)?;
+            self.config.output.line(format!(
+                "Transaction {}/{} confirmed: {} ({} payments)",
+                index + 1,
+                batches.len(),
+                signature,
+                lines.len()
+            ));
+            signatures.push(signature);
+        }
+
+        self.config.output.line(format!(
+            "Batch complete: {} payments sent ({} SOL) in {} transactions. Results written to {}",
+            remaining.len(),
+            SolAmount::format_sol(total),
+            batches.len(),
+            results_path
+        ));
+        self.config.output.json(json!({
+            "results_file": results_path,
+            "sender": sender_pubkey.to_string(),
+            "total": SolAmount::json(total),
+            "transactions": signatures,
+            "payments": batch_file.payments,
+        }));
+        Ok(())
+    }
+
+    /// Vérifie sur le réseau l'état des transactions en attente d'un envoi interrompu. Leurs paiements sont marqués
+    /// comme envoyés si elles ont été confirmées, et à renvoyer si elles ont échoué ou si leur blockhash a expiré.
+    ///
+    /// # Returns:
+    /// Retourne une erreur si une transaction peut encore être confirmée : renvoyer ses paiements pourrait payer deux fois.
+    fn check_pending(
+        &self,
+        batch_file: &mut BatchFile,
+        results_path: &str,
+    ) -> Result<(), WalletError> {
+        let pending = batch_file.pending_transactions();
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        let spending_limits = SpendingLimits::new(self.config.clone());
+        let mut block_height = None;
+        for (signature, last_valid_block_height) in pending {
+            let parsed = Signature::from_str(&signature)
+                .map_err(|_| format!("Invalid signature in {}: {}", results_path, signature))?;
+            match SolanaBatch::transaction_status(self.rpc.as_ref(), &parsed)? {
+                BatchTransactionStatus::Confirmed => {
+                    let lamports = batch_file.transaction_lamports(&signature);
+                    let count =
+                        batch_file.set_transaction_status(&signature, PaymentStatus::Sent, None);
+                    self.config.output.line(format!(
+                        "Transaction {} was confirmed: {} payments marked as sent",
+                        signature, count
+                    ));
+                    // La transaction a été confirmée après l'interruption de l'envoi (ex : délai dépassé, Ctrl-C) :
+                    // elle n'a pas encore été comptée dans le plafond de dépense du jour.
+                    batch_file.save(results_path)?;
+                    spending_limits.record(lamports, &signature)?;
+                }
+                BatchTransactionStatus::Failed(e) => {
+                    let count = batch_file.set_transaction_status(
+                        &signature,
+                        PaymentStatus::Unsent,
+                        Some(e.clone()),
+                    );
+                    self.config.output.line(format!(
+                        "Transaction {} failed ({}): {} payments will be sent again",
+                        signature, e, count
+                    ));
+                }
+                BatchTransactionStatus::NotFound => {
+                    let current = match block_height {
+                        Some(height) => height,
+                        None => *block_height.insert(SolanaBatch::block_height(self.rpc.as_ref())?),
+                    };
+                    if current <= last_valid_block_height {
+                        batch_file.save(results_path)?;
+                        return Err(WalletError::Transaction(format!(
+                            "Transaction {} is not confirmed yet but may still be (valid until block height {}, current: {}): run the command again in a minute",
+                            signature, last_valid_block_height, current
+                        )));
+                    }
+                    let count = batch_file.set_transaction_status(
+                        &signature,
+                        PaymentStatus::Unsent,
+                        Some("Transaction expired".to_string()),
+                    );
+                    self.config.output.line(format!(
+                        "Transaction {} expired without being confirmed: {} payments will be sent again",
+                        signature, count
+                    ));
+                }
+            }
+        }
+        batch_file.save(results_path)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn print_preview(
+        &self,
+        sender: &Pubkey,
+        payments: &[BatchPayment],
+        already_sent: usize,
+        transactions: usize,
+        total: u64,
+        fee: u64,
+        balance: u64,
+    ) {
+        let sol = |lamports: u64| {
+            format!(
+                "{} SOL ({} lamports)",
+                SolAmount::format_sol(lamports),
+                lamports
+            )
+        };
+        let recipients: HashSet<&Pubkey> =
+            payments.iter().map(|payment| &payment.recipient).collect();
+
+        self.config.output.line("Batch preview:");
+        self.config
+            .output
+            .line(format!("  From:          {}", sender));
+        self.config.output.line(format!(
+            "  Payments:      {} ({} recipients, {} already sent)",
+            payments.len(),
+            recipients.len(),
+            already_sent
+        ));
+        self.config
+            .output
+            .line(format!("  Total amount:  {}", sol(total)));
+        self.config
+            .output
+            .line(format!("  Transactions:  {}", transactions));
+        self.config
+            .output
+            .line(format!("  Estimated fee: {}", sol(fee)));
+        match balance
+            .checked_sub(total)
+            .and_then(|remaining| remaining.checked_sub(fee))
+        {
+            Some(balance_after) => self
+                .config
+                .output
+                .line(format!("  Balance after: {}", sol(balance_after))),
+            None => self.config.output.line(format!(
+                "  Balance after: insufficient (balance: {})",
+                sol(balance)
+            )),
+        }
+
+        if recipients.len() < payments.len() {
+            self.config.output.line("Warning: some recipients appear on several lines: they will be paid several times.");
+        }
+        if recipients.contains(sender) {
+            self.config.output.line(
+                "Warning: the sender is one of the recipients: you are sending SOL to yourself.",
+            );
+        }
+    }
+}
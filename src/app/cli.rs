@@ -1,36 +1,176 @@
+use crate::app::account_manager::AccountManager;
+use crate::app::batch_manager::BatchManager;
+use crate::app::config_manager::ConfigManager;
+use crate::app::discovery_manager::DiscoveryManager;
+use crate::app::history_manager::{HistoryFormat, HistoryManager};
 use crate::app::keypair_manager::KeypairManager;
+use crate::app::message_manager::MessageManager;
+use crate::app::mnemonic_manager::MnemonicManager;
+use crate::app::nonce_manager::NonceManager;
+use crate::app::stake_manager::{StakeAddress, StakeAuthorities, StakeManager};
+use crate::app::token_manager::TokenManager;
 use crate::app::transaction_manager::TransactionManager;
 use crate::app::wallet_manager::WalletManager;
-use crate::config::wallet_config::WalletConfig;
-use clap::{Arg, ArgMatches, Command};
+use crate::bip::derivation_scheme::DerivationScheme;
+use crate::bip::mnemonic::{BipMnemonic, LANGUAGES};
+use crate::config::config_file::{moniker_profile, moniker_url};
+use crate::config::output::{Output, OutputFormat};
+use crate::config::wallet_config::{ConfigFlags, WalletConfig};
+use crate::error::WalletError;
+use crate::solana::amount::{AmountUnit, SolAmount, TransferAmount};
+use crate::solana::cluster::Cluster;
+use crate::solana::compute_budget::MAX_COMPUTE_UNIT_LIMIT;
+use crate::solana::history::HistoryPage;
+use crate::solana::memo::MAX_MEMO_LENGTH;
+use crate::solana::message::{MessageFormat, SignatureEncoding};
+use crate::solana::rpc::{SolanaRpc, WalletRpc};
+use clap::parser::ValueSource;
+use clap::{Arg, ArgAction, ArgGroup, ArgMatches, Command};
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::fs;
+use std::rc::Rc;
+use std::str::FromStr;
 
 pub struct AppCli {
     config: WalletConfig,
+    /// Client RPC partagé par les commandes qui interrogent le réseau.
+    rpc: Rc<dyn WalletRpc>,
 }
 
 impl AppCli {
     pub fn new(config: WalletConfig) -> Self {
-        Self { config }
+        let rpc = Rc::new(SolanaRpc::new(&config.rpc_url, config.commitment));
+        Self { config, rpc }
     }
 
-    pub fn setup_cli(&self) -> Command {
+    /// Sortie des commandes (avec le réseau détecté, une fois la commande exécutée).
+    pub fn output(&self) -> &Output {
+        &self.config.output
+    }
+
+    pub fn setup_cli() -> Command {
         Command::new("Solana Wallet")
             .version("1.0.0")
             .about("Example of a Solana Wallet in Rust")
-            .subcommand(self.configure_generate_seed())
-            .subcommand(self.configure_recover_seed())
-            .subcommand(self.configure_send())
-            .subcommand(self.configure_pubkey())
-            .subcommand(self.configure_balance_by_pubkey())
+            .arg(
+                Arg::new("output")
+                    .long("output")
+                    .help("The output format: text, or a JSON document for scripts (the text then goes to stderr)")
+                    .value_parser(["text", "json"])
+                    .default_value("text")
+                    .global(true),
+            )
+            .arg(
+                Arg::new("profile")
+                    .long("profile")
+                    .help("The configuration profile: devnet, testnet, mainnet, localnet, or a profile of the config file")
+                    .global(true),
+            )
+            .arg(
+                Arg::new("url")
+                    .short('u')
+                    .long("url")
+                    .value_name("URL_OR_MONIKER")
+                    .help("The RPC URL, or a moniker: m (mainnet-beta), d (devnet), t (testnet), l (localhost) - e.g. -um")
+                    .global(true),
+            )
+            .arg(
+                Arg::new("keypair")
+                    .short('k')
+                    .long("keypair")
+                    .value_name("KEYPAIR")
+                    .help("The keypair file (plaintext or encrypted keystore), instead of the default account")
+                    .global(true),
+            )
+            .arg(
+                Arg::new("commitment")
+                    .long("commitment")
+                    .help("The commitment level of the RPC requests")
+                    .value_parser(["processed", "confirmed", "finalized"])
+                    .global(true),
+            )
+            .arg(
+                Arg::new("config")
+                    .long("config")
+                    .value_name("FILE")
+                    .help("The configuration file, instead of ./wallet.toml and the user config file")
+                    .global(true),
+            )
+            .arg(
+                Arg::new("derivations")
+                    .long("derivations")
+                    .value_name("COUNT")
+                    .help("The number of derived keypairs (NB_DERIVATIONS)")
+                    .value_parser(clap::value_parser!(usize))
+                    .global(true),
+            )
+            .arg(
+                Arg::new("allow-mainnet")
+                    .long("allow-mainnet")
+                    .help("Allow commands that move funds on mainnet-beta without typing \"mainnet\" to confirm")
+                    .action(ArgAction::SetTrue)
+                    .global(true),
+            )
+            .subcommand(Self::configure_generate_seed())
+            .subcommand(Self::configure_recover_seed())
+            .subcommand(Self::configure_validate_mnemonic())
+            .subcommand(Self::configure_send())
+            .subcommand(Self::configure_send_batch())
+            .subcommand(Self::configure_tx())
+            .subcommand(Self::configure_nonce())
+            .subcommand(Self::configure_token())
+            .subcommand(Self::configure_stake())
+            .subcommand(Self::configure_pubkey())
+            .subcommand(Self::configure_sign_message())
+            .subcommand(Self::configure_verify_message())
+            .subcommand(Self::configure_balance_by_pubkey())
+            .subcommand(Self::configure_balance())
+            .subcommand(Self::configure_history())
+            .subcommand(Self::configure_accounts())
+            .subcommand(Self::configure_discover())
+            .subcommand(Self::configure_migrate_keystore())
+            .subcommand(Self::configure_config())
     }
 
-    fn configure_generate_seed(&self) -> Command {
-        Command::new("generate_seed").about("Generates a new random mnemonic")
+    fn configure_generate_seed() -> Command {
+        Command::new("generate_seed")
+            .about("Generates a new random mnemonic")
+            .arg(
+                Arg::new("words")
+                    .long("words")
+                    .help("The number of words of the mnemonic (12 words: 128 bits of entropy, 24 words: 256 bits)")
+                    .value_parser(["12", "15", "18", "21", "24"])
+                    .default_value("12"),
+            )
+            .arg(
+                Arg::new("language")
+                    .long("language")
+                    .help("The language of the BIP39 wordlist")
+                    .value_parser(LANGUAGES.map(|(name, _)| name))
+                    .default_value("english"),
+            )
+            .args(Self::derivation_args())
+            .arg(Self::unencrypted_arg())
     }
 
-    fn configure_recover_seed(&self) -> Command {
+    fn configure_recover_seed() -> Command {
         Command::new("recover_seed")
-            .about("Generates a mnemonic from a specified phrase")
+            .about("Generates a mnemonic from a specified phrase (its language is detected)")
+            .arg(
+                Arg::new("PHRASE")
+                    .help("A mnemonic phrase")
+                    .required(true)
+                    .index(1),
+            )
+            .args(Self::derivation_args())
+            .arg(Self::unencrypted_arg())
+    }
+
+    fn configure_validate_mnemonic() -> Command {
+        Command::new("validate_mnemonic")
+            .about("Checks a mnemonic phrase and suggests corrections for mistyped words or an invalid checksum")
             .arg(
                 Arg::new("PHRASE")
                     .help("A mnemonic phrase")
@@ -39,7 +179,29 @@ impl AppCli {
             )
     }
 
-    fn configure_send(&self) -> Command {
+    /// Options communes permettant de choisir le schéma de dérivation des clés.
+    fn derivation_args() -> [Arg; 2] {
+        [
+            Arg::new("scheme")
+                .long("scheme")
+                .help("Derivation scheme: bip44change (default), bip44, deprecated or root")
+                .value_parser(["bip44change", "bip44", "deprecated", "root"])
+                .conflicts_with("derivation-path"),
+            Arg::new("derivation-path")
+                .long("derivation-path")
+                .help("Custom derivation path, with an {account} placeholder (e.g. \"m/44'/501'/{account}'/0'\")"),
+        ]
+    }
+
+    /// Option permettant d'écrire les paires de clés en clair (au format JSON de solana-keygen) au lieu d'un keystore chiffré.
+    fn unencrypted_arg() -> Arg {
+        Arg::new("unencrypted")
+            .long("unencrypted")
+            .help("Store the keypairs unencrypted (solana-keygen JSON format), without a keystore password")
+            .action(ArgAction::SetTrue)
+    }
+
+    fn configure_send() -> Command {
         Command::new("send")
             .about("Send SOL to a specific address")
             .arg(
@@ -47,18 +209,455 @@ impl AppCli {
                     .help("The recipient's public key")
                     .required(true),
             )
+            .args(Self::amount_args())
+            .arg(Self::memo_arg())
+            .arg(Self::yes_arg(
+                "Send without asking for confirmation (for scripts)",
+            ))
+            .arg(Self::nonce_arg())
+            .args(Self::compute_budget_args())
+            .arg(Self::account_arg())
+    }
+
+    fn configure_send_batch() -> Command {
+        Command::new("send_batch")
+            .about("Send SOL to many recipients from a CSV file (recipient,amount[,memo]), several transfers per transaction")
+            .arg(
+                Arg::new("FILE")
+                    .help("The CSV file: one \"recipient,amount[,memo]\" row per payment (header and # comments allowed)")
+                    .required(true),
+            )
+            .arg(
+                Arg::new("sol")
+                    .long("sol")
+                    .help("The amounts are in SOL (up to 9 decimals, e.g. 0.25)")
+                    .action(ArgAction::SetTrue)
+                    .conflicts_with("lamports"),
+            )
+            .arg(
+                Arg::new("lamports")
+                    .long("lamports")
+                    .help("The amounts are in lamports (default)")
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                Arg::new("results")
+                    .long("results")
+                    .value_name("FILE")
+                    .help("The results file, used to resume an interrupted batch (default: <FILE>.results.json)"),
+            )
+            .arg(Self::yes_arg("Send without asking for confirmation (for scripts)"))
+            .arg(Self::account_arg())
+    }
+
+    fn configure_tx() -> Command {
+        Command::new("tx")
+            .about("Builds (online), signs (offline) and broadcasts (online) a transaction in separate steps")
+            .subcommand_required(true)
+            .subcommand(
+                Command::new("build")
+                    .about("Builds an unsigned transaction file (fetches the blockhash online)")
+                    .arg(
+                        Arg::new("RECIPIENT")
+                            .help("The recipient's public key")
+                            .required(true),
+                    )
+                    .args(Self::amount_args())
+                    .arg(Self::memo_arg())
+                    .arg(
+                        Arg::new("outfile")
+                            .short('o')
+                            .long("outfile")
+                            .help("The transaction file to write")
+                            .required(true),
+                    )
+                    .arg(Self::nonce_arg())
+                    .args(Self::compute_budget_args())
+                    .arg(Self::account_arg()),
+            )
+            .subcommand(
+                Command::new("sign")
+                    .about("Signs a transaction file with the wallet keypair (works offline)")
+                    .arg(
+                        Arg::new("FILE")
+                            .help("The transaction file")
+                            .required(true)
+                            .index(1),
+                    )
+                    .arg(
+                        Arg::new("outfile")
+                            .short('o')
+                            .long("outfile")
+                            .help("The signed transaction file to write (default: overwrite FILE)"),
+                    )
+                    .arg(
+                        Arg::new("force")
+                            .long("force")
+                            .help("Sign even if some instructions cannot be decoded (unknown programs)")
+                            .action(ArgAction::SetTrue),
+                    )
+                    .arg(Self::yes_arg("Sign without asking for confirmation (for scripts)"))
+                    .arg(Self::account_arg()),
+            )
+            .subcommand(
+                Command::new("broadcast")
+                    .about("Broadcasts a signed transaction file")
+                    .arg(
+                        Arg::new("FILE")
+                            .help("The transaction file")
+                            .required(true)
+                            .index(1),
+                    ),
+            )
+    }
+
+    fn configure_nonce() -> Command {
+        let nonce_account_arg = Arg::new("NONCE_ACCOUNT")
+            .help("The nonce account public key")
+            .required(true);
+
+        Command::new("nonce")
+            .about("Manages durable nonce accounts (for transactions which do not expire)")
+            .subcommand_required(true)
+            .subcommand(
+                Command::new("create")
+                    .about("Creates a nonce account, paid by the wallet")
+                    .arg(
+                        Arg::new("NONCE_KEYPAIR")
+                            .help("The nonce account keypair file (generated if it does not exist)")
+                            .required(true),
+                    )
+                    .arg(
+                        Arg::new("amount")
+                            .long("amount")
+                            .help("The amount to deposit: in lamports, or in SOL with --sol (default: the rent-exempt minimum)"),
+                    )
+                    .arg(
+                        Arg::new("sol")
+                            .long("sol")
+                            .help("The amount is in SOL")
+                            .action(ArgAction::SetTrue),
+                    )
+                    .arg(
+                        Arg::new("authority")
+                            .long("authority")
+                            .help("The nonce authority public key (default: the wallet public key)"),
+                    )
+                    .arg(Self::account_arg()),
+            )
+            .subcommand(
+                Command::new("show")
+                    .about("Displays a nonce account (authority, current nonce, balance)")
+                    .arg(nonce_account_arg.clone()),
+            )
+            .subcommand(
+                Command::new("advance")
+                    .about("Advances the nonce (invalidates transactions signed with the current nonce)")
+                    .arg(nonce_account_arg.clone())
+                    .arg(Self::account_arg()),
+            )
+            .subcommand(
+                Command::new("withdraw")
+                    .about("Withdraws lamports from a nonce account (ALL closes the account)")
+                    .arg(nonce_account_arg.clone())
+                    .arg(
+                        Arg::new("RECIPIENT")
+                            .help("The recipient's public key")
+                            .required(true),
+                    )
+                    .args(Self::amount_args())
+                    .arg(Self::account_arg()),
+            )
+            .subcommand(
+                Command::new("authorize")
+                    .about("Changes the authority of a nonce account")
+                    .arg(nonce_account_arg)
+                    .arg(
+                        Arg::new("NEW_AUTHORITY")
+                            .help("The new authority public key")
+                            .required(true),
+                    )
+                    .arg(Self::account_arg()),
+            )
+    }
+
+    fn configure_token() -> Command {
+        Command::new("token")
+            .about("Manages SPL tokens (SPL Token and Token-2022)")
+            .subcommand_required(true)
+            .subcommand(
+                Command::new("list")
+                    .about("Lists the token accounts of the wallet (mint, decimals, amount)")
+                    .arg(Self::account_arg()),
+            )
+            .subcommand(
+                Command::new("transfer")
+                    .about("Transfers tokens, creating the recipient's associated token account if it is missing")
+                    .arg(Arg::new("MINT").help("The token mint").required(true))
+                    .arg(
+                        Arg::new("RECIPIENT")
+                            .help("The recipient's wallet public key (not a token account)")
+                            .required(true),
+                    )
+                    .arg(
+                        Arg::new("AMOUNT")
+                            .help("The amount of tokens, with the token decimals (e.g. 1.5), or ALL/max to send the whole token balance")
+                            .required(true),
+                    )
+                    .arg(Self::memo_arg())
+                    .arg(Self::yes_arg("Send without asking for confirmation (for scripts)"))
+                    .arg(Self::account_arg()),
+            )
+            .subcommand(
+                Command::new("close")
+                    .about("Closes an empty token account to reclaim its rent")
+                    .arg(
+                        Arg::new("TOKEN_ACCOUNT")
+                            .help("The token account to close")
+                            .required(true),
+                    )
+                    .arg(Self::yes_arg("Close without asking for confirmation (for scripts)"))
+                    .arg(Self::account_arg()),
+            )
+    }
+
+    fn configure_stake() -> Command {
+        let stake_account_arg = Arg::new("STAKE_ACCOUNT")
+            .help("The stake account public key")
+            .required(true);
+
+        Command::new("stake")
+            .about("Manages native stake accounts (delegation to a validator)")
+            .subcommand_required(true)
+            .subcommand(
+                Command::new("create")
+                    .about("Creates a stake account, paid by the wallet")
+                    .args(Self::amount_args())
+                    .args(Self::stake_address_args())
+                    .group(ArgGroup::new("stake_address").required(true))
+                    .arg(
+                        Arg::new("stake_authority")
+                            .long("stake-authority")
+                            .help("The stake authority: a wallet account (label or index) or a public key (default: the wallet account)"),
+                    )
+                    .arg(
+                        Arg::new("withdraw_authority")
+                            .long("withdraw-authority")
+                            .help("The withdraw authority: a wallet account (label or index) or a public key (default: the wallet account)"),
+                    )
+                    .arg(Self::account_arg()),
+            )
+            .subcommand(
+                Command::new("delegate")
+                    .about("Delegates a stake account to a validator")
+                    .arg(stake_account_arg.clone())
+                    .arg(
+                        Arg::new("VOTE_ACCOUNT")
+                            .help("The vote account of the validator")
+                            .required(true),
+                    )
+                    .arg(Self::stake_signer_arg("stake"))
+                    .arg(Self::account_arg()),
+            )
+            .subcommand(
+                Command::new("show")
+                    .about("Displays a stake account (authorities, activation state, last reward)")
+                    .arg(stake_account_arg.clone()),
+            )
+            .subcommand(
+                Command::new("deactivate")
+                    .about("Deactivates a stake account (withdrawable after the cooldown)")
+                    .arg(stake_account_arg.clone())
+                    .arg(Self::stake_signer_arg("stake"))
+                    .arg(Self::account_arg()),
+            )
+            .subcommand(
+                Command::new("withdraw")
+                    .about("Withdraws lamports from a stake account (ALL withdraws everything which is not staked)")
+                    .arg(stake_account_arg.clone())
+                    .arg(
+                        Arg::new("RECIPIENT")
+                            .help("The recipient's public key")
+                            .required(true),
+                    )
+                    .args(Self::amount_args())
+                    .arg(Self::stake_signer_arg("withdraw"))
+                    .arg(Self::account_arg()),
+            )
+            .subcommand(
+                Command::new("split")
+                    .about("Moves part of a stake account into a new stake account")
+                    .arg(stake_account_arg)
+                    .args(Self::amount_args())
+                    .args(Self::stake_address_args())
+                    .group(ArgGroup::new("stake_address").required(true))
+                    .arg(Self::stake_signer_arg("stake"))
+                    .arg(Self::account_arg()),
+            )
+            .subcommand(
+                Command::new("merge")
+                    .about("Merges a stake account into another one (the source account is closed)")
+                    .arg(
+                        Arg::new("DESTINATION")
+                            .help("The stake account which receives the stake")
+                            .required(true),
+                    )
+                    .arg(
+                        Arg::new("SOURCE")
+                            .help("The stake account merged (and closed)")
+                            .required(true),
+                    )
+                    .arg(Self::stake_signer_arg("stake"))
+                    .arg(Self::account_arg()),
+            )
+    }
+
+    /// Options permettant de choisir l'adresse d'un nouveau compte de stake (paire de clés ou seed).
+    fn stake_address_args() -> [Arg; 2] {
+        [
+            Arg::new("stake_keypair")
+                .long("stake-keypair")
+                .help("The new stake account keypair file (generated if it does not exist)")
+                .group("stake_address"),
+            Arg::new("seed")
+                .long("seed")
+                .help(
+                    "Derive the new stake account address from the wallet public key and this seed",
+                )
+                .group("stake_address"),
+        ]
+    }
+
+    /// Option permettant de signer avec une autre autorité que le compte qui paie les frais.
+    fn stake_signer_arg(kind: &str) -> Arg {
+        Arg::new("authority").long("authority").help(format!(
+            "The wallet account (label or index) which is the {} authority (default: the account paying the fees)",
+            kind
+        ))
+    }
+
+    /// Option permettant d'utiliser un nonce durable à la place d'un blockhash récent.
+    fn nonce_arg() -> Arg {
+        Arg::new("nonce")
+            .long("nonce")
+            .help("Use this durable nonce account instead of a recent blockhash (the transaction does not expire)")
+    }
+
+    /// Option permettant d'ajouter un memo (programme SPL Memo) à un transfert.
+    fn memo_arg() -> Arg {
+        Arg::new("memo")
+            .long("memo")
+            .value_name("TEXT")
+            .help(format!(
+                "Attach a memo to the transfer (SPL Memo program, up to {} bytes), e.g. the deposit reference required by an exchange",
+                MAX_MEMO_LENGTH
+            ))
+    }
+
+    /// Options communes permettant d'ajouter des frais de priorité à une transaction.
+    fn compute_budget_args() -> [Arg; 3] {
+        [
+            Arg::new("priority_fee")
+                .long("priority-fee")
+                .value_name("MICRO_LAMPORTS|auto")
+                .help("Price of a compute unit in micro-lamports, or \"auto\" to estimate it from the recent prioritization fees"),
+            Arg::new("priority_fee_percentile")
+                .long("priority-fee-percentile")
+                .value_name("PERCENTILE")
+                .help("Percentile of the recent prioritization fees used by --priority-fee auto (default: 75)")
+                .value_parser(clap::value_parser!(u8).range(0..=100))
+                .requires("priority_fee"),
+            Arg::new("compute_unit_limit")
+                .long("compute-unit-limit")
+                .value_name("UNITS")
+                .help("Maximum compute units of the transaction (the priority fee is paid on this limit; default: 200000 per instruction)")
+                .value_parser(clap::value_parser!(u32).range(1..=i64::from(MAX_COMPUTE_UNIT_LIMIT))),
+        ]
+    }
+
+    /// Options communes permettant de saisir le montant d'un transfert.
+    fn amount_args() -> [Arg; 3] {
+        [
+            Arg::new("AMOUNT")
+                .help("The amount to send: in lamports (default), in SOL with --sol, or ALL/max to send the whole available balance")
+                .required(true),
+            Arg::new("sol")
+                .long("sol")
+                .help("The amount is in SOL (up to 9 decimals, e.g. 0.25)")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("lamports"),
+            Arg::new("lamports")
+                .long("lamports")
+                .help("The amount is in lamports (default, 1 SOL = 1000000000 lamports)")
+                .action(ArgAction::SetTrue),
+        ]
+    }
+
+    /// Option permettant de ne pas demander de confirmation.
+    fn yes_arg(help: &'static str) -> Arg {
+        Arg::new("yes")
+            .short('y')
+            .long("yes")
+            .help(help)
+            .action(ArgAction::SetTrue)
+    }
+
+    fn configure_pubkey() -> Command {
+        Command::new("pubkey")
+            .about("Displays the public key from the keypair stored in file")
+            .arg(Self::account_arg())
+    }
+
+    fn configure_sign_message() -> Command {
+        Command::new("sign_message")
+            .about("Signs a message with the wallet keypair (e.g. to prove the ownership of an address)")
+            .args(Self::message_args())
+            .arg(
+                Arg::new("encoding")
+                    .long("encoding")
+                    .help("The encoding of the signature")
+                    .value_parser(["base58", "base64"])
+                    .default_value("base58"),
+            )
+            .arg(Self::account_arg())
+    }
+
+    fn configure_verify_message() -> Command {
+        Command::new("verify_message")
+            .about("Verifies the signature of a message")
+            .arg(
+                Arg::new("PUBKEY")
+                    .help("The public key of the signer (or a wallet account)")
+                    .required(true),
+            )
             .arg(
-                Arg::new("AMOUNT")
-                    .help("The amount of SOL to send")
+                Arg::new("SIGNATURE")
+                    .help("The signature (base58 or base64)")
                     .required(true),
             )
+            .args(Self::message_args())
     }
 
-    fn configure_pubkey(&self) -> Command {
-        Command::new("pubkey").about("Displays the public key from the keypair stored in file")
+    /// Options communes donnant le message à signer ou à vérifier, et son format.
+    fn message_args() -> [Arg; 3] {
+        [
+            Arg::new("MESSAGE")
+                .help("The message (text)")
+                .required_unless_present("file")
+                .conflicts_with("file"),
+            Arg::new("file")
+                .long("file")
+                .value_name("FILE")
+                .help("Read the message from a file (any bytes with --format raw)"),
+            Arg::new("format")
+                .long("format")
+                .help("The signed bytes: the Solana off-chain message format (header, version, format byte), the UTF-8 text, or the raw bytes")
+                .value_parser(["offchain", "utf8", "raw"])
+                .default_value("offchain"),
+        ]
     }
 
-    fn configure_balance_by_pubkey(&self) -> Command {
+    fn configure_balance_by_pubkey() -> Command {
         Command::new("balance_by_pubkey")
             .about("Displays the balance for the public key")
             .arg(
@@ -69,52 +668,750 @@ impl AppCli {
             )
     }
 
-    pub fn handle_matches(&self, matches: ArgMatches) {
+    fn configure_balance() -> Command {
+        Command::new("balance")
+            .about("Displays the balance of a wallet account")
+            .arg(Self::account_arg())
+    }
+
+    fn configure_history() -> Command {
+        Command::new("history")
+            .about("Displays the transaction history of an address (SOL and token balance changes)")
+            .arg(
+                Arg::new("ADDRESS")
+                    .help("The address (default: the wallet account)")
+                    .conflicts_with("account"),
+            )
+            .arg(
+                Arg::new("before")
+                    .long("before")
+                    .help("Start before this transaction signature (to get the next page)"),
+            )
+            .arg(
+                Arg::new("until")
+                    .long("until")
+                    .help("Stop at this transaction signature (excluded)"),
+            )
+            .arg(
+                Arg::new("limit")
+                    .long("limit")
+                    .help("The maximum number of transactions")
+                    .value_parser(clap::value_parser!(usize))
+                    .default_value("20"),
+            )
+            .arg(
+                Arg::new("format")
+                    .long("format")
+                    .help("The output format")
+                    .value_parser(["table", "json", "csv"])
+                    .default_value("table"),
+            )
+            .arg(Self::account_arg())
+    }
+
+    fn configure_accounts() -> Command {
+        Command::new("accounts")
+            .about("Manages the wallet accounts (labels, default account, etc.)")
+            .subcommand_required(true)
+            .subcommand(Command::new("list").about("Lists the wallet accounts"))
+            .subcommand(
+                Command::new("add")
+                    .about("Adds an account from a keypair file, or a watch-only public key")
+                    .arg(
+                        Arg::new("LABEL")
+                            .help("The account label")
+                            .required(true)
+                            .index(1),
+                    )
+                    .arg(
+                        // Le fichier de paire de clés à importer est donné par l'option globale "--keypair".
+                        Arg::new("pubkey")
+                            .long("pubkey")
+                            .help("Public key of a watch-only account (or --keypair <FILE> to import a keypair file)")
+                            .conflicts_with("keypair"),
+                    ),
+            )
+            .subcommand(
+                Command::new("rename")
+                    .about("Renames an account")
+                    .arg(
+                        Arg::new("ACCOUNT")
+                            .help("The account label or index")
+                            .required(true)
+                            .index(1),
+                    )
+                    .arg(
+                        Arg::new("NEW_LABEL")
+                            .help("The new label")
+                            .required(true)
+                            .index(2),
+                    ),
+            )
+            .subcommand(
+                Command::new("remove")
+                    .about("Removes an account from the wallet (the keypair file is kept)")
+                    .arg(
+                        Arg::new("ACCOUNT")
+                            .help("The account label or index")
+                            .required(true)
+                            .index(1),
+                    ),
+            )
+            .subcommand(
+                Command::new("default")
+                    .about("Sets the account used by default by send, pubkey and balance")
+                    .arg(
+                        Arg::new("ACCOUNT")
+                            .help("The account label or index")
+                            .required(true)
+                            .index(1),
+                    ),
+            )
+    }
+
+    /// Option commune permettant de choisir le compte du wallet à utiliser.
+    fn account_arg() -> Arg {
+        Arg::new("account")
+            .long("account")
+            .help("The wallet account to use (label or index), instead of the default account")
+    }
+
+    fn configure_discover() -> Command {
+        Command::new("discover")
+            .about("Scans every derivation scheme of a mnemonic and displays the funded accounts")
+            .arg(
+                Arg::new("PHRASE")
+                    .help("A mnemonic phrase")
+                    .required(true)
+                    .index(1),
+            )
+            .arg(
+                Arg::new("gap-limit")
+                    .long("gap-limit")
+                    .help("Number of consecutive empty accounts after which a scheme stops being scanned")
+                    .value_parser(clap::value_parser!(usize))
+                    .default_value("5"),
+            )
+    }
+
+    fn configure_migrate_keystore() -> Command {
+        Command::new("migrate_keystore")
+            .about("Encrypts plaintext keypair files into password-protected keystores")
+            .arg(
+                Arg::new("FILES")
+                    .help("Keypair files to encrypt (default: the main keypair and all the derived keypairs)")
+                    .num_args(0..),
+            )
+    }
+
+    fn configure_config() -> Command {
+        Command::new("config")
+            .about("Show the wallet configuration")
+            .subcommand_required(true)
+            .subcommand(
+                Command::new("show")
+                    .about("Show the resolved configuration, with the source of each value (flag, env, profile or default)"),
+            )
+    }
+
+    /// Retourne les valeurs des options globales données sur la ligne de commande ("--profile", "--url", "--output"...).
+    pub fn config_flags(matches: &ArgMatches) -> ConfigFlags {
+        let given = |name: &str| matches.value_source(name) == Some(ValueSource::CommandLine);
+        let string = |name: &str| matches.get_one::<String>(name).cloned();
+        ConfigFlags {
+            profile: string("profile"),
+            url: string("url").map(|url| moniker_url(&url)),
+            url_profile: string("url")
+                .and_then(|url| moniker_profile(&url))
+                .map(str::to_string),
+            keypair: string("keypair"),
+            commitment: string("commitment"),
+            config: string("config"),
+            derivations: matches.get_one::<usize>("derivations").copied(),
+            allow_mainnet: matches.get_flag("allow-mainnet"),
+            output: matches
+                .get_one::<String>("output")
+                .filter(|_| given("output"))
+                .and_then(|output| OutputFormat::parse(output).ok()),
+        }
+    }
+
+    /// Exécute la commande demandée.
+    ///
+    /// # Returns:
+    /// Retourne l'erreur de la commande (son message commence par l'action qui a échoué, ex : "Failed to send transaction").
+    pub fn handle_matches(&mut self, matches: ArgMatches) -> Result<(), WalletError> {
+        if let Some((name, sub_matches)) = matches.subcommand() {
+            if Self::uses_network(name, sub_matches) {
+                self.print_cluster();
+            }
+        }
+
         match matches.subcommand() {
-            Some(("generate_seed", _)) => self.handle_generate_seed(),
+            Some(("generate_seed", sub_matches)) => self.handle_generate_seed(sub_matches),
             Some(("recover_seed", sub_matches)) => self.handle_recover_seed(sub_matches),
+            Some(("validate_mnemonic", sub_matches)) => self.handle_validate_mnemonic(sub_matches),
             Some(("send", sub_matches)) => self.handle_send(sub_matches),
-            Some(("pubkey", _)) => self.pubkey(),
+            Some(("send_batch", sub_matches)) => self.handle_send_batch(sub_matches),
+            Some(("tx", sub_matches)) => self.handle_tx(sub_matches),
+            Some(("nonce", sub_matches)) => self.handle_nonce(sub_matches),
+            Some(("token", sub_matches)) => self.handle_token(sub_matches),
+            Some(("stake", sub_matches)) => self.handle_stake(sub_matches),
+            Some(("pubkey", sub_matches)) => self.pubkey(sub_matches),
+            Some(("sign_message", sub_matches)) => self.handle_sign_message(sub_matches),
+            Some(("verify_message", sub_matches)) => self.handle_verify_message(sub_matches),
             Some(("balance_by_pubkey", sub_matches)) => self.handle_balance_by_pubkey(sub_matches),
-            _ => println!("Unknown command."),
+            Some(("balance", sub_matches)) => self.handle_balance(sub_matches),
+            Some(("history", sub_matches)) => self.handle_history(sub_matches),
+            Some(("accounts", sub_matches)) => self.handle_accounts(sub_matches),
+            Some(("discover", sub_matches)) => self.handle_discover(sub_matches),
+            Some(("migrate_keystore", sub_matches)) => self.handle_migrate_keystore(sub_matches),
+            Some(("config", sub_matches)) => self.handle_config(sub_matches),
+            _ => Err(WalletError::InvalidInput("Unknown command.".to_string())),
+        }
+    }
+
+    /// Indique si une commande interroge le noeud RPC (les autres fonctionnent hors ligne).
+    fn uses_network(name: &str, sub_matches: &ArgMatches) -> bool {
+        match name {
+            "send" | "send_batch" | "nonce" | "token" | "stake" | "balance_by_pubkey"
+            | "balance" | "history" | "discover" => true,
+            "tx" => !matches!(sub_matches.subcommand_name(), Some("sign")),
+            _ => false,
+        }
+    }
+
+    /// Affiche le réseau du noeud RPC sur la sortie d'erreur (la sortie standard reste réservée au résultat),
+    /// et l'ajoute au document JSON de la commande (clé "cluster").
+    /// Si le réseau ne peut pas être détecté, la commande elle-même signalera l'erreur RPC.
+    fn print_cluster(&mut self) {
+        if let Ok(cluster) = Cluster::detect(self.rpc.as_ref()) {
+            self.config.output.cluster = Some(cluster.to_string());
+            let warning = if cluster.is_mainnet() {
+                " - real funds"
+            } else {
+                ""
+            };
+            eprintln!("Cluster: {} ({}){}", cluster, self.config.rpc_url, warning);
         }
     }
 
-    fn handle_generate_seed(&self) {
-        let wallet_manager = WalletManager::new(self.config.clone());
-        wallet_manager.generate_and_print_random_mnemonic();
+    fn handle_generate_seed(&self, sub_matches: &ArgMatches) -> Result<(), WalletError> {
+        let config = self.config_with_derivation_args(sub_matches)?;
+        let words = self
+            .string_arg(sub_matches, "words")?
+            .parse::<usize>()
+            .map_err(|e| WalletError::InvalidInput(format!("Invalid number of words: {}", e)))?;
+        let language = BipMnemonic::parse_language(&self.string_arg(sub_matches, "language")?)?;
+        WalletManager::new(config, Rc::clone(&self.rpc))
+            .generate_and_print_random_mnemonic(
+                words,
+                language,
+                sub_matches.get_flag("unencrypted"),
+            )
+            .map_err(|e| e.context("Failed to generate seed"))
+    }
+
+    fn handle_recover_seed(&self, sub_matches: &ArgMatches) -> Result<(), WalletError> {
+        let phrase = self.string_arg(sub_matches, "PHRASE")?;
+        let config = self.config_with_derivation_args(sub_matches)?;
+        WalletManager::new(config, Rc::clone(&self.rpc))
+            .generate_and_print_mnemonic_from_phrase(&phrase, sub_matches.get_flag("unencrypted"))
+            .map_err(|e| e.context("Failed to recover seed"))
     }
 
-    fn handle_recover_seed(&self, sub_matches: &ArgMatches) {
-        if let Some(phrase) = sub_matches.get_one::<String>("PHRASE") {
-            let wallet_manager = WalletManager::new(self.config.clone());
-            wallet_manager.generate_and_print_mnemonic_from_phrase(phrase);
+    fn handle_validate_mnemonic(&self, sub_matches: &ArgMatches) -> Result<(), WalletError> {
+        MnemonicManager::new(self.config.clone())
+            .validate(&self.string_arg(sub_matches, "PHRASE")?)
+            .map_err(|e| e.context("Failed to validate mnemonic"))
+    }
+
+    /// Retourne une copie de la configuration, avec le schéma de dérivation choisi via "--scheme" ou "--derivation-path".
+    /// Retourne une erreur si le chemin personnalisé est invalide.
+    fn config_with_derivation_args(
+        &self,
+        sub_matches: &ArgMatches,
+    ) -> Result<WalletConfig, WalletError> {
+        let mut config = self.config.clone();
+
+        let scheme = sub_matches
+            .get_one::<String>("scheme")
+            .or_else(|| sub_matches.get_one::<String>("derivation-path"));
+
+        if let Some(scheme) = scheme {
+            config.derivation_scheme = scheme.parse::<DerivationScheme>().map_err(|e| {
+                WalletError::InvalidInput(format!("Failed to parse derivation scheme: {}", e))
+            })?;
         }
+
+        Ok(config)
     }
 
-    fn pubkey(&self) {
+    fn pubkey(&self, sub_matches: &ArgMatches) -> Result<(), WalletError> {
         let keypair_manager = KeypairManager::new(self.config.clone());
-        keypair_manager.pubkey();
+        keypair_manager.pubkey(self.selected_account(sub_matches))
     }
 
-    fn handle_balance_by_pubkey(&self, sub_matches: &ArgMatches) {
-        if let Some(pubkey) = sub_matches.get_one::<String>("PUBKEY") {
-            let wallet_manager = WalletManager::new(self.config.clone());
-            match wallet_manager.get_balance_by_pubkey(pubkey) {
-                Ok(balance) => {
-                    let sol_value = balance as f64 / 1_000_000_000_f64; // Convertir les lamports en SOL.
-                    println!("Balance: {:.9} SOL ({} lamports)", sol_value, balance);
-                }
-                Err(e) => println!("Failed to retrieve balance: {}", e),
+    fn handle_sign_message(&self, sub_matches: &ArgMatches) -> Result<(), WalletError> {
+        let account_manager = AccountManager::new(self.config.clone());
+        account_manager
+            .config_for_account(self.selected_account(sub_matches))
+            .and_then(|config| {
+                MessageManager::new(config).sign(
+                    &self.message_bytes(sub_matches)?,
+                    MessageFormat::parse(&self.string_arg(sub_matches, "format")?)
+                        .map_err(WalletError::InvalidInput)?,
+                    SignatureEncoding::parse(&self.string_arg(sub_matches, "encoding")?)
+                        .map_err(WalletError::InvalidInput)?,
+                )
+            })
+            .map_err(|e| e.context("Failed to sign message"))
+    }
+
+    fn handle_verify_message(&self, sub_matches: &ArgMatches) -> Result<(), WalletError> {
+        self.authority_pubkey(sub_matches, "PUBKEY")
+            .and_then(|pubkey| {
+                MessageManager::new(self.config.clone()).verify(
+                    &pubkey.ok_or("PUBKEY required")?,
+                    &self.string_arg(sub_matches, "SIGNATURE")?,
+                    &self.message_bytes(sub_matches)?,
+                    MessageFormat::parse(&self.string_arg(sub_matches, "format")?)
+                        .map_err(WalletError::InvalidInput)?,
+                )
+            })
+            .map_err(|e| e.context("Failed to verify message"))
+    }
+
+    /// Retourne les octets du message : le contenu du fichier "--file", sinon le texte "MESSAGE".
+    fn message_bytes(&self, matches: &ArgMatches) -> Result<Vec<u8>, WalletError> {
+        match matches.get_one::<String>("file") {
+            Some(path) => fs::read(path).map_err(|e| {
+                WalletError::File(format!("Failed to read message file {}: {}", path, e))
+            }),
+            None => Ok(self.string_arg(matches, "MESSAGE")?.into_bytes()),
+        }
+    }
+
+    fn handle_balance_by_pubkey(&self, sub_matches: &ArgMatches) -> Result<(), WalletError> {
+        self.print_balance(&self.string_arg(sub_matches, "PUBKEY")?)
+    }
+
+    fn handle_balance(&self, sub_matches: &ArgMatches) -> Result<(), WalletError> {
+        let account_manager = AccountManager::new(self.config.clone());
+        let pubkey = account_manager
+            .pubkey_for_account(self.selected_account(sub_matches))
+            .map_err(|e| e.context("Failed to retrieve balance"))?;
+        self.print_balance(&pubkey.to_string())
+    }
+
+    fn print_balance(&self, pubkey: &str) -> Result<(), WalletError> {
+        let wallet_manager = WalletManager::new(self.config.clone(), Rc::clone(&self.rpc));
+        let balance = wallet_manager
+            .get_balance_by_pubkey(pubkey)
+            .map_err(|e| e.context("Failed to retrieve balance"))?;
+        self.config.output.line(format!(
+            "Balance: {} SOL ({} lamports)",
+            SolAmount::format_sol(balance),
+            balance
+        ));
+        self.config.output.json(json!({
+            "pubkey": pubkey,
+            "balance": SolAmount::json(balance),
+        }));
+        Ok(())
+    }
+
+    fn handle_history(&self, sub_matches: &ArgMatches) -> Result<(), WalletError> {
+        self.history_address(sub_matches)
+            .and_then(|address| {
+                let page = HistoryPage {
+                    before: self.signature_arg(sub_matches, "before")?,
+                    until: self.signature_arg(sub_matches, "until")?,
+                    limit: *sub_matches.get_one::<usize>("limit").unwrap_or(&20),
+                };
+                let format = HistoryFormat::parse(&self.string_arg(sub_matches, "format")?)
+                    .map_err(WalletError::InvalidInput)?;
+                HistoryManager::new(self.config.clone(), Rc::clone(&self.rpc))
+                    .print_history(&address, &page, format)
+            })
+            .map_err(|e| e.context("Failed to retrieve history"))
+    }
+
+    /// Retourne l'adresse de l'historique : l'adresse donnée, sinon celle du compte sélectionné du wallet.
+    fn history_address(&self, matches: &ArgMatches) -> Result<Pubkey, WalletError> {
+        match matches.get_one::<String>("ADDRESS") {
+            Some(address) => self.parse_pubkey(address),
+            None => AccountManager::new(self.config.clone())
+                .pubkey_for_account(self.selected_account(matches)),
+        }
+    }
+
+    fn signature_arg(
+        &self,
+        matches: &ArgMatches,
+        name: &str,
+    ) -> Result<Option<Signature>, WalletError> {
+        matches
+            .get_one::<String>(name)
+            .map(|signature| {
+                Signature::from_str(signature).map_err(|_| {
+                    WalletError::InvalidInput(format!("Invalid signature: {}", signature))
+                })
+            })
+            .transpose()
+    }
+
+    fn handle_accounts(&self, sub_matches: &ArgMatches) -> Result<(), WalletError> {
+        let account_manager = AccountManager::new(self.config.clone());
+        let arg = |matches: &ArgMatches, name: &str| matches.get_one::<String>(name).cloned();
+
+        match sub_matches.subcommand() {
+            Some(("list", _)) => account_manager.list(),
+            Some(("add", matches)) => account_manager.add(
+                &arg(matches, "LABEL").unwrap_or_default(),
+                arg(matches, "keypair").as_deref(),
+                arg(matches, "pubkey").as_deref(),
+            ),
+            Some(("rename", matches)) => account_manager.rename(
+                &arg(matches, "ACCOUNT").unwrap_or_default(),
+                &arg(matches, "NEW_LABEL").unwrap_or_default(),
+            ),
+            Some(("remove", matches)) => {
+                account_manager.remove(&arg(matches, "ACCOUNT").unwrap_or_default())
             }
+            Some(("default", matches)) => {
+                account_manager.set_default(&arg(matches, "ACCOUNT").unwrap_or_default())
+            }
+            _ => Err(WalletError::InvalidInput("Unknown command.".to_string())),
+        }
+    }
+
+    fn handle_send(&self, sub_matches: &ArgMatches) -> Result<(), WalletError> {
+        // Utilise la paire de clés du compte sélectionné ("--account", sinon le compte par défaut du wallet).
+        let account_manager = AccountManager::new(self.config.clone());
+        account_manager
+            .config_for_account(self.selected_account(sub_matches))
+            .and_then(|config| {
+                TransactionManager::new(config, Rc::clone(&self.rpc)).send_transaction(sub_matches)
+            })
+            .map_err(|e| e.context("Failed to send transaction"))?;
+        self.config.output.line("Transaction sent successfully!");
+        Ok(())
+    }
+
+    fn handle_send_batch(&self, sub_matches: &ArgMatches) -> Result<(), WalletError> {
+        let account_manager = AccountManager::new(self.config.clone());
+        account_manager
+            .config_for_account(self.selected_account(sub_matches))
+            .and_then(|config| {
+                BatchManager::new(config, Rc::clone(&self.rpc)).send_batch(
+                    &self.string_arg(sub_matches, "FILE")?,
+                    sub_matches
+                        .get_one::<String>("results")
+                        .map(|path| path.as_str()),
+                    self.amount_unit(sub_matches),
+                    sub_matches.get_flag("yes"),
+                )
+            })
+            .map_err(|e| e.context("Failed to send batch"))
+    }
+
+    fn handle_tx(&self, sub_matches: &ArgMatches) -> Result<(), WalletError> {
+        let account_manager = AccountManager::new(self.config.clone());
+
+        match sub_matches.subcommand() {
+            Some(("build", matches)) => {
+                // Un compte watch-only suffit : la transaction n'est pas signée à cette étape.
+                account_manager
+                    .pubkey_for_account(self.selected_account(matches))
+                    .and_then(|sender_pubkey| {
+                        TransactionManager::new(self.config.clone(), Rc::clone(&self.rpc))
+                            .build_transaction(matches, &sender_pubkey)
+                    })
+                    .map_err(|e| e.context("Failed to build transaction"))
+            }
+            Some(("sign", matches)) => account_manager
+                .config_for_account(self.selected_account(matches))
+                .and_then(|config| {
+                    TransactionManager::new(config, Rc::clone(&self.rpc))
+                        .sign_transaction_file(matches)
+                })
+                .map_err(|e| e.context("Failed to sign transaction")),
+            Some(("broadcast", matches)) => {
+                TransactionManager::new(self.config.clone(), Rc::clone(&self.rpc))
+                    .broadcast_transaction_file(matches)
+                    .map_err(|e| e.context("Failed to broadcast transaction"))
+            }
+            _ => Err(WalletError::InvalidInput("Unknown command.".to_string())),
+        }
+    }
+
+    fn handle_nonce(&self, sub_matches: &ArgMatches) -> Result<(), WalletError> {
+        let Some((name, matches)) = sub_matches.subcommand() else {
+            return Err(WalletError::InvalidInput("Unknown command.".to_string()));
+        };
+
+        // L'affichage ne nécessite aucune paire de clés.
+        if name == "show" {
+            return self
+                .pubkey_arg(matches, "NONCE_ACCOUNT")
+                .and_then(|nonce_account| {
+                    NonceManager::new(self.config.clone(), Rc::clone(&self.rpc))
+                        .show(&nonce_account)
+                })
+                .map_err(|e| e.context("Failed to show nonce account"));
+        }
+
+        // L'autorité (et le payeur des frais) est le compte sélectionné du wallet.
+        AccountManager::new(self.config.clone())
+            .config_for_account(self.selected_account(matches))
+            .and_then(|config| {
+                let nonce_manager = NonceManager::new(config, Rc::clone(&self.rpc));
+                match name {
+                    "create" => nonce_manager.create(
+                        &self.string_arg(matches, "NONCE_KEYPAIR")?,
+                        matches
+                            .get_one::<String>("amount")
+                            .map(|amount| self.parse_amount(amount, matches))
+                            .transpose()?,
+                        matches
+                            .get_one::<String>("authority")
+                            .map(|authority| self.parse_pubkey(authority))
+                            .transpose()?,
+                    ),
+                    "advance" => nonce_manager.advance(&self.pubkey_arg(matches, "NONCE_ACCOUNT")?),
+                    "withdraw" => nonce_manager.withdraw(
+                        &self.pubkey_arg(matches, "NONCE_ACCOUNT")?,
+                        &self.pubkey_arg(matches, "RECIPIENT")?,
+                        self.amount_arg(matches, "AMOUNT")?,
+                    ),
+                    "authorize" => nonce_manager.authorize(
+                        &self.pubkey_arg(matches, "NONCE_ACCOUNT")?,
+                        &self.pubkey_arg(matches, "NEW_AUTHORITY")?,
+                    ),
+                    _ => Err(WalletError::InvalidInput("Unknown command".to_string())),
+                }
+            })
+            .map_err(|e| e.context(&format!("Failed to {} nonce account", name)))
+    }
+
+    fn handle_token(&self, sub_matches: &ArgMatches) -> Result<(), WalletError> {
+        let Some((name, matches)) = sub_matches.subcommand() else {
+            return Err(WalletError::InvalidInput("Unknown command.".to_string()));
+        };
+        let account_manager = AccountManager::new(self.config.clone());
+
+        let result = match name {
+            // Un compte watch-only suffit pour afficher ses tokens.
+            "list" => account_manager
+                .pubkey_for_account(self.selected_account(matches))
+                .and_then(|owner| {
+                    TokenManager::new(self.config.clone(), Rc::clone(&self.rpc)).list(&owner)
+                }),
+            "transfer" => account_manager
+                .config_for_account(self.selected_account(matches))
+                .and_then(|config| {
+                    TokenManager::new(config, Rc::clone(&self.rpc)).transfer(
+                        &self.pubkey_arg(matches, "MINT")?,
+                        &self.pubkey_arg(matches, "RECIPIENT")?,
+                        &self.string_arg(matches, "AMOUNT")?,
+                        matches.get_one::<String>("memo").map(|memo| memo.as_str()),
+                        matches.get_flag("yes"),
+                    )
+                }),
+            "close" => account_manager
+                .config_for_account(self.selected_account(matches))
+                .and_then(|config| {
+                    TokenManager::new(config, Rc::clone(&self.rpc)).close(
+                        &self.pubkey_arg(matches, "TOKEN_ACCOUNT")?,
+                        matches.get_flag("yes"),
+                    )
+                }),
+            _ => Err(WalletError::InvalidInput("Unknown command".to_string())),
+        };
+
+        result.map_err(|e| {
+            let action = match name {
+                "list" => "list token accounts",
+                "transfer" => "transfer tokens",
+                _ => "close token account",
+            };
+            e.context(&format!("Failed to {}", action))
+        })
+    }
+
+    fn handle_stake(&self, sub_matches: &ArgMatches) -> Result<(), WalletError> {
+        let Some((name, matches)) = sub_matches.subcommand() else {
+            return Err(WalletError::InvalidInput("Unknown command.".to_string()));
+        };
+
+        // L'affichage ne nécessite aucune paire de clés.
+        if name == "show" {
+            return self
+                .pubkey_arg(matches, "STAKE_ACCOUNT")
+                .and_then(|stake_account| {
+                    StakeManager::new(self.config.clone(), Rc::clone(&self.rpc), None)
+                        .show(&stake_account)
+                })
+                .map_err(|e| e.context("Failed to show stake account"));
+        }
+
+        // Le compte sélectionné du wallet paie les frais ; l'autorité est un autre compte du wallet ("--authority",
+        // absent de "create" dont les autorités ne signent pas).
+        let account_manager = AccountManager::new(self.config.clone());
+        account_manager
+            .config_for_account(self.selected_account(matches))
+            .and_then(|config| {
+                let authority_keypair_path = matches
+                    .try_get_one::<String>("authority")
+                    .ok()
+                    .flatten()
+                    .map(|authority| {
+                        account_manager
+                            .config_for_account(Some(authority))
+                            .map(|config| config.keypair_path)
+                    })
+                    .transpose()?;
+                let stake_manager =
+                    StakeManager::new(config, Rc::clone(&self.rpc), authority_keypair_path);
+                match name {
+                    "create" => stake_manager.create(
+                        &self.stake_address(matches)?,
+                        self.amount_arg(matches, "AMOUNT")?,
+                        &StakeAuthorities {
+                            staker: self.authority_pubkey(matches, "stake_authority")?,
+                            withdrawer: self.authority_pubkey(matches, "withdraw_authority")?,
+                        },
+                    ),
+                    "delegate" => stake_manager.delegate(
+                        &self.pubkey_arg(matches, "STAKE_ACCOUNT")?,
+                        &self.pubkey_arg(matches, "VOTE_ACCOUNT")?,
+                    ),
+                    "deactivate" => {
+                        stake_manager.deactivate(&self.pubkey_arg(matches, "STAKE_ACCOUNT")?)
+                    }
+                    "withdraw" => stake_manager.withdraw(
+                        &self.pubkey_arg(matches, "STAKE_ACCOUNT")?,
+                        &self.pubkey_arg(matches, "RECIPIENT")?,
+                        self.amount_arg(matches, "AMOUNT")?,
+                    ),
+                    "split" => stake_manager.split(
+                        &self.pubkey_arg(matches, "STAKE_ACCOUNT")?,
+                        &self.stake_address(matches)?,
+                        self.amount_arg(matches, "AMOUNT")?,
+                    ),
+                    "merge" => stake_manager.merge(
+                        &self.pubkey_arg(matches, "DESTINATION")?,
+                        &self.pubkey_arg(matches, "SOURCE")?,
+                    ),
+                    _ => Err(WalletError::InvalidInput("Unknown command".to_string())),
+                }
+            })
+            .map_err(|e| e.context(&format!("Failed to {} stake account", name)))
+    }
+
+    /// Retourne l'adresse du nouveau compte de stake ("--stake-keypair" ou "--seed").
+    fn stake_address(&self, matches: &ArgMatches) -> Result<StakeAddress, WalletError> {
+        if let Some(seed) = matches.get_one::<String>("seed") {
+            return Ok(StakeAddress::Seed(seed.clone()));
         }
+        Ok(StakeAddress::Keypair(
+            self.string_arg(matches, "stake_keypair")?,
+        ))
+    }
+
+    /// Retourne la clé publique d'une autorité : un compte du wallet (label ou index), ou une clé publique.
+    fn authority_pubkey(
+        &self,
+        matches: &ArgMatches,
+        name: &str,
+    ) -> Result<Option<Pubkey>, WalletError> {
+        let Some(authority) = matches.get_one::<String>(name) else {
+            return Ok(None);
+        };
+        AccountManager::new(self.config.clone())
+            .pubkey_for_account(Some(authority))
+            .or_else(|_| Pubkey::from_str(authority))
+            .map(Some)
+            .map_err(|_| {
+                WalletError::InvalidPubkey(format!(
+                    "Unknown wallet account or invalid public key: {}",
+                    authority
+                ))
+            })
+    }
+
+    /// Retourne l'unité du montant ("--sol", sinon lamports).
+    fn amount_unit(&self, matches: &ArgMatches) -> AmountUnit {
+        if matches.get_flag("sol") {
+            AmountUnit::Sol
+        } else {
+            AmountUnit::Lamports
+        }
+    }
+
+    /// Retourne le montant d'un argument, dans l'unité choisie ("--sol", sinon lamports).
+    fn amount_arg(&self, matches: &ArgMatches, name: &str) -> Result<TransferAmount, WalletError> {
+        self.parse_amount(&self.string_arg(matches, name)?, matches)
+    }
+
+    fn parse_amount(
+        &self,
+        amount: &str,
+        matches: &ArgMatches,
+    ) -> Result<TransferAmount, WalletError> {
+        SolAmount::parse(amount, self.amount_unit(matches)).map_err(WalletError::InvalidInput)
+    }
+
+    fn string_arg(&self, matches: &ArgMatches, name: &str) -> Result<String, WalletError> {
+        matches
+            .get_one::<String>(name)
+            .cloned()
+            .ok_or_else(|| WalletError::InvalidInput(format!("{} required", name)))
+    }
+
+    fn pubkey_arg(&self, matches: &ArgMatches, name: &str) -> Result<Pubkey, WalletError> {
+        self.parse_pubkey(&self.string_arg(matches, name)?)
+    }
+
+    fn parse_pubkey(&self, pubkey: &str) -> Result<Pubkey, WalletError> {
+        Pubkey::from_str(pubkey)
+            .map_err(|_| WalletError::InvalidPubkey(format!("Invalid public key: {}", pubkey)))
+    }
+
+    /// Retourne le compte du wallet sélectionné via "--account" (label ou index).
+    fn selected_account<'a>(&self, sub_matches: &'a ArgMatches) -> Option<&'a str> {
+        sub_matches
+            .get_one::<String>("account")
+            .map(|account| account.as_str())
+    }
+
+    fn handle_discover(&self, sub_matches: &ArgMatches) -> Result<(), WalletError> {
+        let phrase = self.string_arg(sub_matches, "PHRASE")?;
+        let gap_limit = *sub_matches.get_one::<usize>("gap-limit").unwrap_or(&5);
+        let discovery_manager = DiscoveryManager::new(self.config.clone(), Rc::clone(&self.rpc));
+        discovery_manager
+            .discover_and_print(&phrase, gap_limit)
+            .map_err(|e| e.context("Failed to discover accounts"))
+    }
+
+    fn handle_migrate_keystore(&self, sub_matches: &ArgMatches) -> Result<(), WalletError> {
+        let files: Vec<String> = sub_matches
+            .get_many::<String>("FILES")
+            .map(|files| files.cloned().collect())
+            .unwrap_or_default();
+        let keypair_manager = KeypairManager::new(self.config.clone());
+        keypair_manager
+            .migrate_keystore(&files)
+            .map_err(|e| e.context("Failed to migrate keystore"))
     }
 
-    fn handle_send(&self, sub_matches: &ArgMatches) {
-        let transaction_manager = TransactionManager::new(self.config.clone());
-        match transaction_manager.send_transaction(sub_matches) {
-            Ok(_) => println!("Transaction sent successfully!"),
-            Err(e) => println!("Failed to send transaction: {}", e),
+    fn handle_config(&self, sub_matches: &ArgMatches) -> Result<(), WalletError> {
+        match sub_matches.subcommand() {
+            Some(("show", _)) => ConfigManager::new(self.config.clone())
+                .show()
+                .map_err(|e| e.context("Failed to show configuration")),
+            _ => Err(WalletError::InvalidInput("Unknown command.".to_string())),
         }
     }
 }
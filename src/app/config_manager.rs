@@ -0,0 +1,96 @@
+use crate::config::wallet_config::WalletConfig;
+use crate::error::WalletError;
+use crate::solana::amount::SolAmount;
+use serde_json::{json, Map, Value};
+
+pub struct ConfigManager {
+    config: WalletConfig,
+}
+
+impl ConfigManager {
+    pub fn new(config: WalletConfig) -> Self {
+        Self { config }
+    }
+
+    /// Affiche la configuration résolue : chaque paramètre avec sa valeur et son origine
+    /// (option, variable d'environnement, profil ou valeur par défaut), et les fichiers de configuration chargés.
+    pub fn show(&self) -> Result<(), WalletError> {
+        let config = &self.config;
+        let files = if config.config_files.is_empty() {
+            "none".to_string()
+        } else {
+            config.config_files.join(", ")
+        };
+        config.output.line(format!("Config files: {}", files));
+
+        let mut settings = Map::new();
+        for (name, value) in self.settings() {
+            let source = config.source(name).to_string();
+            config.output.line(format!(
+                "{:<26} {} [{}]",
+                format!("{}:", name),
+                value.as_deref().unwrap_or("(none)"),
+                source
+            ));
+            settings.insert(
+                name.to_string(),
+                json!({ "value": value, "source": source }),
+            );
+        }
+
+        config.output.json(json!({
+            "config_files": config.config_files,
+            "settings": Value::Object(settings),
+        }));
+        Ok(())
+    }
+
+    /// Retourne les paramètres affichés, avec leur valeur (None si le paramètre n'est pas défini).
+    fn settings(&self) -> Vec<(&'static str, Option<String>)> {
+        let config = &self.config;
+        vec![
+            ("profile", Some(config.profile.clone())),
+            ("rpc_url", Some(config.rpc_url.clone())),
+            ("websocket_url", Some(config.websocket_url.clone())),
+            ("commitment", Some(config.commitment.commitment.to_string())),
+            ("keypair_path", Some(config.keypair_path.clone())),
+            (
+                "keypair_derivations_path",
+                Some(config.keypair_derivations_path.clone()),
+            ),
+            ("default_account", config.default_account.clone()),
+            (
+                "spending_limit_per_transaction",
+                config
+                    .spending_limit_per_transaction
+                    .map(Self::format_limit),
+            ),
+            (
+                "spending_limit_per_day",
+                config.spending_limit_per_day.map(Self::format_limit),
+            ),
+            (
+                "spending_ledger_path",
+                Some(config.spending_ledger_path.clone()),
+            ),
+            ("nb_derivations", Some(config.nb_derivations.to_string())),
+            (
+                "derivation_engine",
+                Some(config.derivation_engine.to_string()),
+            ),
+            (
+                "derivation_scheme",
+                Some(config.derivation_scheme.to_string()),
+            ),
+            (
+                "wallet_registry_path",
+                Some(config.wallet_registry_path.clone()),
+            ),
+            ("output", Some(config.output.to_string())),
+        ]
+    }
+
+    fn format_limit(lamports: u64) -> String {
+        format!("{} SOL", SolAmount::format_sol(lamports))
+    }
+}
@@ -0,0 +1,159 @@
+use crate::bip::derivation_scheme::DerivationScheme;
+use crate::bip::mnemonic::BipMnemonic;
+use crate::bip::passphrase::BipPassphrase;
+use crate::bip::seed::{BipSeed, DerivationEngine};
+use crate::config::wallet_config::WalletConfig;
+use crate::error::WalletError;
+use crate::solana::address::SolanaAddress;
+use crate::solana::amount::SolAmount;
+use crate::solana::balance::SolanaBalance;
+use crate::solana::rpc::WalletRpc;
+use serde_json::json;
+use solana_sdk::signer::Signer;
+use std::rc::Rc;
+
+/// Compte approvisionné trouvé lors d'un scan de découverte.
+pub struct DiscoveredAccount {
+    pub scheme: DerivationScheme,
+    pub engine: DerivationEngine,
+    pub path: String,
+    pub pubkey: String,
+    pub lamports: u64,
+}
+
+pub struct DiscoveryManager {
+    config: WalletConfig,
+    rpc: Rc<dyn WalletRpc>,
+}
+
+impl DiscoveryManager {
+    pub fn new(config: WalletConfig, rpc: Rc<dyn WalletRpc>) -> Self {
+        Self { config, rpc }
+    }
+
+    /// Parcourt tous les schémas de dérivation supportés d'une phrase mnémonique, et affiche les comptes approvisionnés.
+    ///
+    /// # Arguments:
+    /// - phrase - La phrase mnémonique à analyser.
+    /// - gap_limit - Le nombre de comptes vides consécutifs après lequel le scan d'un schéma s'arrête.
+    pub fn discover_and_print(&self, phrase: &str, gap_limit: usize) -> Result<(), WalletError> {
+        let mnemonic = BipMnemonic::get_mnemonic_from_phrase(phrase)?;
+        let passphrase = BipPassphrase::prompt_for_passphrase(&self.config.output)?;
+        let seed = BipSeed::generate_seed(&mnemonic, &passphrase);
+
+        self.config.output.line(format!(
+            "Discovering accounts (gap limit: {})...",
+            gap_limit
+        ));
+
+        let accounts = self.discover(BipSeed::get_seed_bytes(&seed), gap_limit)?;
+        self.config.output.json(json!({
+            "gap_limit": gap_limit,
+            "accounts": accounts
+                .iter()
+                .map(|account| {
+                    json!({
+                        "scheme": account.scheme.name(),
+                        "engine": account.engine.to_string(),
+                        "path": account.path,
+                        "pubkey": account.pubkey,
+                        "balance": SolAmount::json(account.lamports),
+                    })
+                })
+                .collect::<Vec<_>>(),
+        }));
+        if accounts.is_empty() {
+            self.config.output.line("No funded account found.");
+            return Ok(());
+        }
+        self.config.output.line(format!(
+            "{:<12} {:<7} {:<24} {:<44} {:>20}",
+            "SCHEME", "ENGINE", "PATH", "PUBKEY", "LAMPORTS"
+        ));
+        for account in &accounts {
+            self.config.output.line(format!(
+                "{:<12} {:<7} {:<24} {:<44} {:>20}",
+                account.scheme.name(),
+                account.engine,
+                account.path,
+                account.pubkey,
+                account.lamports
+            ));
+        }
+        self.config
+            .output
+            .line(format!("Found {} funded account(s).", accounts.len()));
+        Ok(())
+    }
+
+    /// Dérive les comptes de chaque schéma (et de chaque moteur de dérivation) et interroge leur balance via RPC.
+    /// Pour chaque schéma, le scan s'arrête après "gap_limit" comptes vides consécutifs.
+    ///
+    /// # Arguments:
+    /// - seed_bytes - Les octets de la seed BIP39.
+    /// - gap_limit - Le nombre de comptes vides consécutifs après lequel le scan d'un schéma s'arrête.
+    ///
+    /// # Returns:
+    /// Retourne la liste des comptes ayant une balance non nulle, ou une erreur RPC.
+    pub fn discover(
+        &self,
+        seed_bytes: &[u8],
+        gap_limit: usize,
+    ) -> Result<Vec<DiscoveredAccount>, WalletError> {
+        let solana_balance = SolanaBalance::new(Rc::clone(&self.rpc));
+        let mut accounts = Vec::new();
+
+        for (scheme, engine) in Self::candidates() {
+            let mut consecutive_empty = 0;
+            let mut account = 0;
+
+            while consecutive_empty < gap_limit.max(1) {
+                // Un schéma non dérivable avec ce moteur (ex : niveaux non-hardened avec SLIP-0010) est ignoré.
+                let derived_seed_bytes =
+                    match BipSeed::derive_seed_bytes(seed_bytes, &scheme, account, engine) {
+                        Ok(derived_seed_bytes) => derived_seed_bytes,
+                        Err(_) => break,
+                    };
+                let pubkey = SolanaAddress::generate_keypair(&derived_seed_bytes)?
+                    .pubkey()
+                    .to_string();
+
+                let lamports = solana_balance.get_balance_by_pubkey(&pubkey)?;
+                if lamports > 0 {
+                    consecutive_empty = 0;
+                    accounts.push(DiscoveredAccount {
+                        scheme: scheme.clone(),
+                        engine,
+                        path: scheme.path(account).unwrap_or_else(|| "m".to_string()),
+                        pubkey,
+                        lamports,
+                    });
+                } else {
+                    consecutive_empty += 1;
+                }
+
+                if scheme.is_single_account() {
+                    break;
+                }
+                account += 1;
+            }
+        }
+
+        Ok(accounts)
+    }
+
+    /// Retourne les combinaisons (schéma, moteur) à parcourir.
+    /// La seed racine ne dépend pas du moteur : elle n'est parcourue qu'une seule fois.
+    fn candidates() -> Vec<(DerivationScheme, DerivationEngine)> {
+        let mut candidates = Vec::new();
+        for engine in [DerivationEngine::Slip10, DerivationEngine::LegacyBip32] {
+            for scheme in DerivationScheme::standard_schemes() {
+                if scheme == DerivationScheme::Root && engine != DerivationEngine::Slip10 {
+                    continue;
+                }
+                candidates.push((scheme, engine));
+            }
+        }
+        candidates
+    }
+}
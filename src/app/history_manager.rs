@@ -0,0 +1,160 @@
+use crate::config::wallet_config::WalletConfig;
+use crate::error::WalletError;
+use crate::solana::amount::SolAmount;
+use crate::solana::history::{HistoryEntry, HistoryPage, SolanaHistory};
+use crate::solana::rpc::WalletRpc;
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use std::rc::Rc;
+
+/// Format d'affichage de l'historique.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+impl HistoryFormat {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        match input.to_lowercase().as_str() {
+            "table" => Ok(HistoryFormat::Table),
+            "json" => Ok(HistoryFormat::Json),
+            "csv" => Ok(HistoryFormat::Csv),
+            _ => Err(format!(
+                "Invalid format '{}': expected table, json or csv",
+                input
+            )),
+        }
+    }
+}
+
+pub struct HistoryManager {
+    config: WalletConfig,
+    rpc: Rc<dyn WalletRpc>,
+}
+
+impl HistoryManager {
+    pub fn new(config: WalletConfig, rpc: Rc<dyn WalletRpc>) -> Self {
+        Self { config, rpc }
+    }
+
+    /// Affiche l'historique des transactions d'une adresse, de la plus récente à la plus ancienne.
+    ///
+    /// # Arguments:
+    /// - address - L'adresse consultée.
+    /// - page - Les options de pagination ("before", "until" et "limit").
+    /// - format - Le format d'affichage (tableau, JSON ou CSV).
+    pub fn print_history(
+        &self,
+        address: &Pubkey,
+        page: &HistoryPage,
+        format: HistoryFormat,
+    ) -> Result<(), WalletError> {
+        let entries = SolanaHistory::fetch(self.rpc.as_ref(), address, page)?;
+        self.config.output.json(json!({
+            "address": address.to_string(),
+            "transactions": entries,
+        }));
+
+        match format {
+            HistoryFormat::Json => self
+                .config
+                .output
+                .line(serde_json::to_string_pretty(&entries)?),
+            HistoryFormat::Csv => self.print_csv(&entries),
+            HistoryFormat::Table => {
+                if entries.is_empty() {
+                    self.config
+                        .output
+                        .line(format!("No transaction found for {}", address));
+                    return Ok(());
+                }
+                self.print_table(&entries);
+                if entries.len() == page.limit {
+                    if let Some(last) = entries.last() {
+                        self.config
+                            .output
+                            .line(format!("Next page: --before {}", last.signature));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Affiche une ligne par variation de balance (au moins une ligne par transaction).
+    fn print_table(&self, entries: &[HistoryEntry]) {
+        self.config.output.line(format!(
+            "{:<20} {:<88} {:<44} {:>22} {:<44} {:>11} {:<6} MEMO",
+            "TIME", "SIGNATURE", "COUNTERPARTY", "DELTA", "ASSET", "FEE", "STATUS"
+        ));
+        for entry in entries {
+            for (counterparty, delta, asset) in Self::rows(entry) {
+                self.config.output.line(format!(
+                    "{:<20} {:<88} {:<44} {:>22} {:<44} {:>11} {:<6} {}",
+                    entry.time(),
+                    entry.signature,
+                    counterparty,
+                    delta,
+                    asset,
+                    SolAmount::format_sol(entry.fee),
+                    entry.status(),
+                    entry.memo()
+                ));
+            }
+        }
+    }
+
+    fn print_csv(&self, entries: &[HistoryEntry]) {
+        self.config
+            .output
+            .line("time,signature,slot,counterparty,delta,asset,fee,status,memo");
+        for entry in entries {
+            for (counterparty, delta, asset) in Self::rows(entry) {
+                self.config.output.line(format!(
+                    "{},{},{},{},{},{},{},{},{}",
+                    entry.time(),
+                    entry.signature,
+                    entry.slot,
+                    counterparty,
+                    delta,
+                    asset,
+                    SolAmount::format_sol(entry.fee),
+                    entry.status(),
+                    Self::csv_field(&entry.memo())
+                ));
+            }
+        }
+    }
+
+    /// Entoure une valeur CSV de guillemets si elle contient une virgule, un guillemet ou un retour à la ligne.
+    fn csv_field(value: &str) -> String {
+        if value.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", value.replace('"', "\"\""))
+        } else {
+            value.to_string()
+        }
+    }
+
+    /// Retourne les colonnes (contrepartie, variation, actif) de chaque variation de balance d'une transaction.
+    fn rows(entry: &HistoryEntry) -> Vec<(String, String, String)> {
+        if entry.changes.is_empty() {
+            return vec![("-".to_string(), "-".to_string(), "-".to_string())];
+        }
+        entry
+            .changes
+            .iter()
+            .map(|change| {
+                (
+                    change
+                        .counterparty
+                        .clone()
+                        .unwrap_or_else(|| "-".to_string()),
+                    change.ui_delta(),
+                    change.asset.clone(),
+                )
+            })
+            .collect()
+    }
+}
@@ -1,6 +1,9 @@
+use crate::app::account_manager::AccountManager;
 use crate::config::wallet_config::WalletConfig;
-use crate::solana::address::SolanaAddress;
-use solana_sdk::signer::Signer;
+use crate::error::WalletError;
+use crate::solana::keystore::SolanaKeystore;
+use serde_json::json;
+use std::fs;
 
 pub struct KeypairManager {
     config: WalletConfig,
@@ -12,17 +15,109 @@ impl KeypairManager {
     }
 
     /// Récupère et affiche la clé publique à partir d'une paire de clés stockée localement dans un fichier.
-    pub fn pubkey(&self) {
-        // Obtient le chemin d'accès au fichier où la paire de clés est stockée.
-        let keypair_path = &self.config.keypair_path;
-
-        // Tente de lire la paire de clés à partir du fichier spécifié (en utilisant le chemin obtenu précédemment).
-        // La fonction "read_keypair_from_file" gère le chargement et la désérialisation de la paire de clés à partir du fichier.
-        // Ok: En cas de succès, extrait la clé publique de la paire de clés et l'affiche.
-        // Err: En cas d'échec, affiche une erreur indiquant que la lecture a échoué.
-        match SolanaAddress::read_keypair_from_file(keypair_path) {
-            Ok(keypair) => println!("Solana Public Key: {}", keypair.pubkey()),
-            Err(e) => println!("Failed to read key pair from file: {}", e),
+    ///
+    /// # Arguments:
+    /// - account - Le label ou l'index du compte du wallet (par défaut : le compte par défaut du wallet, sinon `KEYPAIR_PATH`).
+    pub fn pubkey(&self, account: Option<&str>) -> Result<(), WalletError> {
+        let account_manager = AccountManager::new(self.config.clone());
+
+        // Tente de lire la clé publique du compte sélectionné (depuis le fichier de sa paire de clés).
+        // La fonction "read_pubkey" gère les fichiers en clair comme les keystores chiffrés
+        // (dont la clé publique est lisible sans mot de passe).
+        // Ok: En cas de succès, affiche la clé publique.
+        // Err: En cas d'échec, retourne une erreur indiquant que la lecture a échoué.
+        let pubkey = account_manager
+            .pubkey_for_account(account)
+            .map_err(|e| e.context("Failed to read key pair from file"))?;
+        self.config
+            .output
+            .line(format!("Solana Public Key: {}", pubkey));
+
+        // La clé publique d'un keystore chiffré n'est authentifiée qu'au déchiffrement de sa clé privée.
+        let keypair_path = match account_manager.resolve(account)? {
+            Some(account) => account.keypair_path,
+            None => Some(self.config.keypair_path.clone()),
+        };
+        let verified = !keypair_path
+            .as_deref()
+            .is_some_and(SolanaKeystore::is_encrypted_file);
+        if !verified {
+            self.config.output.line("Note: read from an encrypted keystore, this address is unverified until the keystore is unlocked (a tampered file could show another address).");
+        }
+        self.config
+            .output
+            .json(json!({ "pubkey": pubkey.to_string(), "verified": verified }));
+        Ok(())
+    }
+
+    /// Chiffre les fichiers de paires de clés en clair (format JSON de solana-keygen) au format keystore.
+    ///
+    /// # Arguments:
+    /// - files - Les fichiers à migrer. Si la liste est vide, migre la paire de clés principale et toutes les paires de clés dérivées.
+    ///
+    /// # Returns:
+    /// Retourne une erreur si le mot de passe est invalide, ou si au moins un fichier n'a pas pu être migré.
+    pub fn migrate_keystore(&self, files: &[String]) -> Result<(), WalletError> {
+        let files = if files.is_empty() {
+            self.stored_keypair_files()
+        } else {
+            files.to_vec()
+        };
+
+        let password = SolanaKeystore::new_password("Enter new keystore password:")?;
+        if password.is_empty() {
+            return Err(WalletError::InvalidInput(
+                "the password cannot be empty".to_string(),
+            ));
+        }
+
+        // Chaque fichier est migré indépendamment : un échec n'interrompt pas la migration des suivants.
+        let mut failures = 0;
+        let mut migrated = Vec::new();
+        for file in &files {
+            match SolanaKeystore::migrate_file(file, &password) {
+                Ok(true) => {
+                    self.config.output.line(format!("Encrypted: {}", file));
+                    migrated.push(json!({ "file": file, "status": "encrypted" }));
+                }
+                Ok(false) => {
+                    self.config
+                        .output
+                        .line(format!("Already encrypted: {}", file));
+                    migrated.push(json!({ "file": file, "status": "already_encrypted" }));
+                }
+                Err(e) => {
+                    eprintln!("Failed to migrate {}: {}", file, e);
+                    failures += 1;
+                }
+            }
+        }
+        if failures > 0 {
+            return Err(WalletError::KeyIo(format!(
+                "{} of {} file(s) could not be migrated",
+                failures,
+                files.len()
+            )));
         }
+        self.config.output.json(json!({ "files": migrated }));
+        Ok(())
+    }
+
+    /// Retourne la paire de clés principale et les paires de clés dérivées présentes sur le disque.
+    fn stored_keypair_files(&self) -> Vec<String> {
+        let mut files = vec![self.config.keypair_path.clone()];
+
+        if let Ok(entries) = fs::read_dir(&self.config.keypair_derivations_path) {
+            let mut derived: Vec<String> = entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+                .map(|path| path.to_string_lossy().to_string())
+                .collect();
+            derived.sort();
+            files.extend(derived);
+        }
+
+        files
     }
 }
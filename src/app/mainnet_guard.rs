@@ -0,0 +1,41 @@
+use crate::config::wallet_config::WalletConfig;
+use crate::error::WalletError;
+use crate::solana::cluster::Cluster;
+use crate::solana::rpc::WalletRpc;
+
+/// Protège les commandes qui déplacent des fonds sur mainnet-beta : sauf avec "--allow-mainnet",
+/// l'utilisateur doit taper "mainnet" avant l'envoi de la transaction.
+pub struct MainnetGuard<'a> {
+    config: &'a WalletConfig,
+    rpc: &'a dyn WalletRpc,
+}
+
+impl<'a> MainnetGuard<'a> {
+    pub fn new(config: &'a WalletConfig, rpc: &'a dyn WalletRpc) -> Self {
+        Self { config, rpc }
+    }
+
+    /// Vérifie que l'action peut être exécutée sur le réseau du noeud RPC.
+    ///
+    /// # Arguments:
+    /// - action - L'action protégée, pour la question (ex : "send a transaction").
+    ///
+    /// # Returns:
+    /// Retourne une erreur `Cancelled` si l'utilisateur n'a pas tapé "mainnet",
+    /// ou l'erreur RPC si le réseau ne peut pas être détecté (dans le doute, rien n'est envoyé).
+    pub fn check(&self, action: &str) -> Result<(), WalletError> {
+        if !Cluster::detect(self.rpc)?.is_mainnet() || self.config.allow_mainnet {
+            return Ok(());
+        }
+        let answer = self.config.output.prompt(&format!(
+            "You are about to {} on mainnet-beta, with real funds. Type \"mainnet\" to continue (or use --allow-mainnet):",
+            action
+        ));
+        if answer != "mainnet" {
+            return Err(WalletError::Cancelled(
+                "Mainnet operation cancelled".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+use crate::config::wallet_config::WalletConfig;
+use crate::error::WalletError;
+use crate::solana::keystore::SolanaKeystore;
+use crate::solana::message::{MessageFormat, SignatureEncoding, SolanaMessage};
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+
+pub struct MessageManager {
+    config: WalletConfig,
+}
+
+impl MessageManager {
+    pub fn new(config: WalletConfig) -> Self {
+        Self { config }
+    }
+
+    /// Signe un message avec la paire de clés du wallet (ex : pour prouver la possession d'une adresse),
+    /// et affiche la clé publique et la signature.
+    ///
+    /// # Arguments:
+    /// - message - Les octets du message.
+    /// - format - Le format des octets signés (brut, UTF-8, ou message hors chaîne de Solana).
+    /// - encoding - L'encodage de la signature affichée (base58 ou base64).
+    pub fn sign(
+        &self,
+        message: &[u8],
+        format: MessageFormat,
+        encoding: SignatureEncoding,
+    ) -> Result<(), WalletError> {
+        let keypair = SolanaKeystore::read_keypair(&self.config.keypair_path)
+            .map_err(|e| e.context("Failed to read keypair from file"))?;
+        let signature =
+            SolanaMessage::sign(&keypair, message, format).map_err(WalletError::InvalidInput)?;
+
+        self.config
+            .output
+            .line(format!("Public key: {}", keypair.pubkey()));
+        self.config.output.line(format!(
+            "Format:     {}",
+            SolanaMessage::describe(message, format)
+        ));
+        self.config
+            .output
+            .line(format!("Signature:  {}", encoding.encode(&signature)));
+        self.config.output.json(json!({
+            "pubkey": keypair.pubkey().to_string(),
+            "format": SolanaMessage::describe(message, format),
+            "signature": encoding.encode(&signature),
+        }));
+        Ok(())
+    }
+
+    /// Vérifie la signature d'un message par une clé publique.
+    ///
+    /// # Arguments:
+    /// - pubkey - La clé publique du signataire annoncé.
+    /// - signature - La signature (base58 ou base64).
+    /// - message - Les octets du message.
+    /// - format - Le format des octets signés.
+    ///
+    /// # Returns:
+    /// Retourne une erreur si la signature est invalide.
+    pub fn verify(
+        &self,
+        pubkey: &Pubkey,
+        signature: &str,
+        message: &[u8],
+        format: MessageFormat,
+    ) -> Result<(), WalletError> {
+        let signature =
+            SolanaMessage::decode_signature(signature).map_err(WalletError::InvalidInput)?;
+        if !SolanaMessage::verify(pubkey, &signature, message, format)
+            .map_err(WalletError::InvalidInput)?
+        {
+            return Err(format!(
+                "Invalid signature: the {} message was not signed by {}",
+                format, pubkey
+            )
+            .into());
+        }
+        self.config.output.line(format!(
+            "Valid signature: the {} message was signed by {}",
+            SolanaMessage::describe(message, format),
+            pubkey
+        ));
+        self.config.output.json(json!({
+            "valid": true,
+            "pubkey": pubkey.to_string(),
+            "format": SolanaMessage::describe(message, format),
+        }));
+        Ok(())
+    }
+}
@@ -1,8 +1,13 @@
+use crate::bip::mnemonic::BipMnemonic;
 use crate::bip::passphrase::BipPassphrase;
 use crate::bip::seed::BipSeed;
 use crate::config::wallet_config::WalletConfig;
+use crate::config::wallet_registry::WalletRegistry;
+use crate::error::WalletError;
 use crate::solana::address::SolanaAddress;
+use crate::solana::keystore::SolanaKeystore;
 use bip39::Mnemonic;
+use serde_json::{json, Value};
 use solana_sdk::signer::Signer;
 
 pub struct MnemonicManager {
@@ -16,60 +21,173 @@ impl MnemonicManager {
 
     /// Traite une mnémonique pour générer et afficher la clé publique correspondante, en prenant en compte les dérivations spécifiées.
     /// Cette fonction sert de point central pour la création de clés Solana à partir d'une phrase mnémonique.
-    pub fn process_mnemonic(&self, mnemonic: &Mnemonic) {
+    ///
+    /// # Arguments:
+    /// - mnemonic - La phrase mnémonique.
+    /// - unencrypted - Écrit les paires de clés en clair (option "--unencrypted"), sans demander de mot de passe de keystore.
+    pub fn process_mnemonic(
+        &self,
+        mnemonic: &Mnemonic,
+        unencrypted: bool,
+    ) -> Result<(), WalletError> {
         // Demande à l'utilisateur d'entrer une passphrase optionnelle qui sera utilisée lors de la génération de la seed.
         // (laisser vide pour ne pas utiliser de passphrase)
-        let passphrase = BipPassphrase::prompt_for_passphrase();
+        let passphrase = BipPassphrase::prompt_for_passphrase(&self.config.output)?;
 
         // Génère une seed en format hexadécimal à partir de la phrase mnémonique et de la passphrase.
         // Cette seed de portefeuille HD (Hiérarchiquement Déterministe) permettra de produire une suite cohérente de clés dérivées.
         let seed = BipSeed::generate_seed(mnemonic, &passphrase);
-        println!("Seed: {:X}", seed);
+        self.config.output.line(format!("Seed: {:X}", seed));
+
+        // Demande le mot de passe du keystore qui chiffrera les paires de clés écrites sur le disque.
+        // Un mot de passe vide (ex : entrée standard vide dans un script) est refusé : les paires de clés
+        // ne sont écrites en clair, au format JSON de solana-keygen, qu'avec "--unencrypted".
+        let password = if unencrypted {
+            self.config
+                .output
+                .line("Warning: keypairs will be stored unencrypted.");
+            String::new()
+        } else {
+            let password = SolanaKeystore::new_password("Enter keystore password:")?;
+            if password.is_empty() {
+                return Err(WalletError::InvalidInput(
+                    "the keystore password cannot be empty (use --unencrypted to store keypairs unencrypted)".to_string(),
+                ));
+            }
+            password
+        };
 
         // Convertit la seed en un tableau de bytes bruts, qui servira de base pour la génération de clés dérivées.
         let seed_bytes = BipSeed::get_seed_bytes(&seed);
-        self.handle_key_derivation(seed_bytes);
+        let accounts = self.handle_key_derivation(seed_bytes, &password)?;
+
+        self.config.output.json(json!({
+            "mnemonic": BipMnemonic::get_mnemonic_to_str(mnemonic),
+            "language": BipMnemonic::language_name(mnemonic.language()),
+            "seed": format!("{:X}", seed),
+            "derivation_scheme": self.config.derivation_scheme.to_string(),
+            "encrypted": !password.is_empty(),
+            "accounts": accounts,
+        }));
+        Ok(())
     }
 
-    /// Gère la dérivation de clés et leur enregistrement.
-    fn handle_key_derivation(&self, seed_bytes: &[u8]) {
-        // Récupère le nombre de dérivations souhaitées (est de 1 par défaut).
-        let nb_derivations = self.config.nb_derivations;
+    /// Vérifie une phrase mnémonique, sans dériver ni enregistrer de clés.
+    ///
+    /// # Returns:
+    /// Retourne une erreur `InvalidMnemonic` qui liste les mots inconnus (avec les mots les plus proches)
+    /// et les corrections d'un seul mot qui donnent un checksum valide.
+    pub fn validate(&self, phrase: &str) -> Result<(), WalletError> {
+        let mnemonic = BipMnemonic::get_mnemonic_from_phrase(phrase)?;
+        let language = BipMnemonic::language_name(mnemonic.language());
+        let words = mnemonic.phrase().split_whitespace().count();
+
+        self.config
+            .output
+            .line(format!("Valid mnemonic: {} ({} words)", language, words));
+        self.config.output.json(json!({
+            "valid": true,
+            "language": language,
+            "words": words,
+        }));
+        Ok(())
+    }
 
-        // Nb de public keys = 1 (la seed originale) + le dérivations souhaitées.
+    /// Gère la dérivation de clés et leur enregistrement, en suivant le schéma de dérivation configuré.
+    ///
+    /// # Returns:
+    /// Retourne les comptes dérivés (pour la sortie JSON).
+    fn handle_key_derivation(
+        &self,
+        seed_bytes: &[u8],
+        password: &str,
+    ) -> Result<Vec<Value>, WalletError> {
+        let scheme = &self.config.derivation_scheme;
+        self.config
+            .output
+            .line(format!("Derivation scheme: {}", scheme));
+
+        // Récupère le nombre de dérivations souhaitées (est de 0 par défaut).
+        // Un schéma à compte unique (seed racine, chemin sans "{account}") ne produit qu'une seule paire de clés.
+        let nb_derivations = if scheme.is_single_account() {
+            0
+        } else {
+            self.config.nb_derivations
+        };
+
+        // Nb de public keys = 1 (le compte 0) + le dérivations souhaitées.
         let nb_pubkeys = 1 + nb_derivations;
 
+        // Charge l'index du wallet, dans lequel chaque paire de clés dérivée sera enregistrée (label, chemin, etc.).
+        let registry_path = &self.config.wallet_registry_path;
+        let mut registry = WalletRegistry::load(registry_path)
+            .map_err(|e| WalletError::Account(format!("Failed to load wallet registry: {}", e)))?;
+
         // Gère les dérivations multiples pour générer plusieurs paires de clés.
-        for index in 0..nb_pubkeys {
-            self.derive_and_store_keypair(seed_bytes, index);
-        }
+        let accounts = (0..nb_pubkeys)
+            .map(|index| self.derive_and_store_keypair(seed_bytes, index, password, &mut registry))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        registry
+            .save(registry_path)
+            .map_err(|e| WalletError::Account(format!("Failed to save wallet registry: {}", e)))?;
+        Ok(accounts)
     }
 
-    /// Dérive et stocke une paire de clés.
-    fn derive_and_store_keypair(&self, seed_bytes: &[u8], index: usize) {
+    /// Dérive et stocke une paire de clés (chiffrée si un mot de passe de keystore est donné), puis l'enregistre dans l'index du wallet.
+    fn derive_and_store_keypair(
+        &self,
+        seed_bytes: &[u8],
+        index: usize,
+        password: &str,
+        registry: &mut WalletRegistry,
+    ) -> Result<Value, WalletError> {
         // Dériver la seed pour chaque index spécifié.
-        match BipSeed::derive_seed_bytes(seed_bytes, index) {
-            Ok(derived_seed_bytes) => {
-                // Génerer une paire de clés (clé publique et clé privée) à partir de la seed en bytes.
-                // Puis écrire cette paire de clés dans un fichier JSON.
-                let keypair = SolanaAddress::generate_keypair(&derived_seed_bytes);
-
-                let keypair_path = self.derive_keypair_path(index);
-                SolanaAddress::write_keypair(&keypair, &keypair_path);
-
-                // Affiche la clé publique (qui dans le cas de Solana, est également utilisée comme adresse publique du wallet).
-                if index == 0 {
-                    println!("Solana Public Key: {}", keypair.pubkey());
-                } else {
-                    println!(
-                        "Solana Public Key (derivation {}): {}",
-                        index,
-                        keypair.pubkey()
-                    );
-                }
-            }
-            Err(e) => println!("Error deriving seed bytes: {}", e),
+        let derived_seed_bytes = BipSeed::derive_seed_bytes(
+            seed_bytes,
+            &self.config.derivation_scheme,
+            index,
+            self.config.derivation_engine,
+        )
+        .map_err(|e| WalletError::KeyIo(format!("Error deriving seed bytes: {}", e)))?;
+
+        // Génerer une paire de clés (clé publique et clé privée) à partir de la seed en bytes.
+        // Puis écrire cette paire de clés dans un fichier JSON.
+        let keypair = SolanaAddress::generate_keypair(&derived_seed_bytes)?;
+
+        let keypair_path = self.derive_keypair_path(index);
+        if password.is_empty() {
+            SolanaAddress::write_keypair(&keypair, &keypair_path)?;
+        } else {
+            SolanaKeystore::write_encrypted_keypair(&keypair, &keypair_path, password)
+                .map_err(|e| e.context(&format!("Failed to write keystore {}", keypair_path)))?;
+        }
+
+        registry.upsert_derived(
+            &format!("account-{}", index),
+            &keypair.pubkey().to_string(),
+            self.config.derivation_scheme.path(index),
+            &keypair_path,
+        );
+
+        // Affiche la clé publique (qui dans le cas de Solana, est également utilisée comme adresse publique du wallet).
+        if index == 0 {
+            self.config
+                .output
+                .line(format!("Solana Public Key: {}", keypair.pubkey()));
+        } else {
+            self.config.output.line(format!(
+                "Solana Public Key (derivation {}): {}",
+                index,
+                keypair.pubkey()
+            ));
         }
+        Ok(json!({
+            "index": index,
+            "derivation_path": self.config.derivation_scheme.path(index),
+            "pubkey": keypair.pubkey().to_string(),
+            "keypair_path": keypair_path,
+        }))
     }
 
     /// Construit le chemin du fichier de la paire de clés en fonction de l'index de dérivation.
@@ -1,5 +1,16 @@
+pub mod account_manager;
+pub mod batch_manager;
 pub mod cli;
+pub mod config_manager;
+pub mod discovery_manager;
+pub mod history_manager;
 pub mod keypair_manager;
+pub mod mainnet_guard;
+pub mod message_manager;
 pub mod mnemonic_manager;
+pub mod nonce_manager;
+pub mod spending_limits;
+pub mod stake_manager;
+pub mod token_manager;
 pub mod transaction_manager;
 pub mod wallet_manager;
@@ -0,0 +1,277 @@
+use crate::app::mainnet_guard::MainnetGuard;
+use crate::app::spending_limits::SpendingLimits;
+use crate::config::wallet_config::WalletConfig;
+use crate::error::WalletError;
+use crate::solana::amount::{SolAmount, TransferAmount};
+use crate::solana::keystore::SolanaKeystore;
+use crate::solana::nonce::{DurableNonce, SolanaNonce};
+use crate::solana::rpc::WalletRpc;
+use crate::solana::transaction::SolanaTransaction;
+use serde_json::json;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, write_keypair_file, Keypair, Signature, Signer};
+use solana_sdk::system_instruction;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+pub struct NonceManager {
+    config: WalletConfig,
+    rpc: Rc<dyn WalletRpc>,
+}
+
+impl NonceManager {
+    pub fn new(config: WalletConfig, rpc: Rc<dyn WalletRpc>) -> Self {
+        Self { config, rpc }
+    }
+
+    /// Crée un compte nonce durable, payé par la paire de clés du wallet.
+    ///
+    /// # Arguments:
+    /// - nonce_keypair_path - Le fichier de la paire de clés du compte nonce (généré s'il n'existe pas).
+    /// - amount - Le montant déposé sur le compte nonce (par défaut : le minimum pour être exempté de rente).
+    /// - authority - L'autorité du nonce (par défaut : la clé publique du wallet).
+    pub fn create(
+        &self,
+        nonce_keypair_path: &str,
+        amount: Option<TransferAmount>,
+        authority: Option<Pubkey>,
+    ) -> Result<(), WalletError> {
+        let payer = self.read_wallet_keypair()?;
+        let nonce_keypair = self.nonce_keypair(nonce_keypair_path)?;
+        let authority = authority.unwrap_or_else(|| payer.pubkey());
+
+        let minimum_balance = SolanaNonce::minimum_balance(self.rpc.as_ref())?;
+        let lamports = match amount {
+            None => minimum_balance,
+            Some(TransferAmount::Lamports(lamports)) if lamports >= minimum_balance => lamports,
+            Some(TransferAmount::Lamports(lamports)) => {
+                return Err(WalletError::InvalidInput(format!(
+                "A nonce account needs at least {} lamports to be rent-exempt ({} lamports given)",
+                minimum_balance, lamports
+            )))
+            }
+            Some(TransferAmount::All) => {
+                return Err(WalletError::InvalidInput(
+                    "ALL is not supported when creating a nonce account".to_string(),
+                ))
+            }
+        };
+
+        let instructions = system_instruction::create_nonce_account(
+            &payer.pubkey(),
+            &nonce_keypair.pubkey(),
+            &authority,
+            lamports,
+        );
+        let spending_limits = SpendingLimits::new(self.config.clone());
+        spending_limits.check(&[lamports])?;
+        let signature = self.send(&instructions, &[&payer, &nonce_keypair])?;
+        spending_limits.record(lamports, &signature.to_string())?;
+
+        self.config.output.line(format!(
+            "Nonce account created: {} (authority: {}, balance: {} SOL)",
+            nonce_keypair.pubkey(),
+            authority,
+            SolAmount::format_sol(lamports)
+        ));
+        self.config
+            .output
+            .line(format!("Transaction signature: {}", signature));
+        self.config.output.json(json!({
+            "nonce_account": nonce_keypair.pubkey().to_string(),
+            "authority": authority.to_string(),
+            "balance": SolAmount::json(lamports),
+            "signature": signature.to_string(),
+        }));
+        Ok(())
+    }
+
+    /// Affiche l'état d'un compte nonce durable.
+    pub fn show(&self, nonce_account: &Pubkey) -> Result<(), WalletError> {
+        let nonce = SolanaNonce::fetch(self.rpc.as_ref(), nonce_account)?;
+
+        self.config
+            .output
+            .line(format!("Nonce account:          {}", nonce.account));
+        self.config
+            .output
+            .line(format!("Authority:              {}", nonce.authority));
+        self.config
+            .output
+            .line(format!("Nonce:                  {}", nonce.blockhash));
+        self.config.output.line(format!(
+            "Lamports per signature: {}",
+            nonce.lamports_per_signature
+        ));
+        self.config.output.line(format!(
+            "Balance:                {} SOL ({} lamports)",
+            SolAmount::format_sol(nonce.balance),
+            nonce.balance
+        ));
+        self.config.output.json(json!({
+            "nonce_account": nonce.account.to_string(),
+            "authority": nonce.authority.to_string(),
+            "nonce": nonce.blockhash.to_string(),
+            "lamports_per_signature": nonce.lamports_per_signature,
+            "balance": SolAmount::json(nonce.balance),
+        }));
+        Ok(())
+    }
+
+    /// Avance la valeur d'un nonce (ce qui invalide les transactions signées avec sa valeur actuelle).
+    pub fn advance(&self, nonce_account: &Pubkey) -> Result<(), WalletError> {
+        let (authority, nonce) = self.authority_for(nonce_account)?;
+
+        let instruction =
+            system_instruction::advance_nonce_account(&nonce.account, &nonce.authority);
+        let signature = self.send(&[instruction], &[&authority])?;
+
+        self.config
+            .output
+            .line(format!("Nonce account {} advanced", nonce.account));
+        self.config
+            .output
+            .line(format!("Transaction signature: {}", signature));
+        self.config.output.json(json!({
+            "nonce_account": nonce.account.to_string(),
+            "signature": signature.to_string(),
+        }));
+        Ok(())
+    }
+
+    /// Retire des lamports d'un compte nonce ("ALL" ferme le compte en retirant toute sa balance).
+    pub fn withdraw(
+        &self,
+        nonce_account: &Pubkey,
+        recipient: &Pubkey,
+        amount: TransferAmount,
+    ) -> Result<(), WalletError> {
+        let (authority, nonce) = self.authority_for(nonce_account)?;
+        let lamports = match amount {
+            TransferAmount::Lamports(lamports) => lamports,
+            TransferAmount::All => nonce.balance,
+        };
+
+        let instruction = system_instruction::withdraw_nonce_account(
+            &nonce.account,
+            &nonce.authority,
+            recipient,
+            lamports,
+        );
+        let spending_limits = SpendingLimits::new(self.config.clone());
+        spending_limits.check(&[lamports])?;
+        let signature = self.send(&[instruction], &[&authority])?;
+        spending_limits.record(lamports, &signature.to_string())?;
+
+        self.config.output.line(format!(
+            "Withdrew {} SOL ({} lamports) from nonce account {} to {}",
+            SolAmount::format_sol(lamports),
+            lamports,
+            nonce.account,
+            recipient
+        ));
+        self.config
+            .output
+            .line(format!("Transaction signature: {}", signature));
+        self.config.output.json(json!({
+            "nonce_account": nonce.account.to_string(),
+            "recipient": recipient.to_string(),
+            "amount": SolAmount::json(lamports),
+            "signature": signature.to_string(),
+        }));
+        Ok(())
+    }
+
+    /// Change l'autorité d'un compte nonce.
+    pub fn authorize(
+        &self,
+        nonce_account: &Pubkey,
+        new_authority: &Pubkey,
+    ) -> Result<(), WalletError> {
+        let (authority, nonce) = self.authority_for(nonce_account)?;
+
+        let instruction = system_instruction::authorize_nonce_account(
+            &nonce.account,
+            &nonce.authority,
+            new_authority,
+        );
+        let signature = self.send(&[instruction], &[&authority])?;
+
+        self.config.output.line(format!(
+            "Nonce account {} authority changed from {} to {}",
+            nonce.account, nonce.authority, new_authority
+        ));
+        self.config
+            .output
+            .line(format!("Transaction signature: {}", signature));
+        self.config.output.json(json!({
+            "nonce_account": nonce.account.to_string(),
+            "previous_authority": nonce.authority.to_string(),
+            "authority": new_authority.to_string(),
+            "signature": signature.to_string(),
+        }));
+        Ok(())
+    }
+
+    /// Récupère un compte nonce, et vérifie que la paire de clés du wallet en est l'autorité.
+    fn authority_for(
+        &self,
+        nonce_account: &Pubkey,
+    ) -> Result<(Keypair, DurableNonce), WalletError> {
+        let nonce = SolanaNonce::fetch(self.rpc.as_ref(), nonce_account)?;
+        let authority = self.read_wallet_keypair()?;
+        if authority.pubkey() != nonce.authority {
+            return Err(WalletError::Account(format!(
+                "The authority of nonce account {} is {}, not this wallet ({})",
+                nonce.account,
+                nonce.authority,
+                authority.pubkey()
+            )));
+        }
+        Ok((authority, nonce))
+    }
+
+    /// Signe et envoie une transaction du compte nonce (après confirmation sur mainnet-beta).
+    fn send(
+        &self,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<Signature, WalletError> {
+        MainnetGuard::new(&self.config, self.rpc.as_ref()).check("send a nonce transaction")?;
+        SolanaTransaction::send_instructions(self.rpc.as_ref(), instructions, signers)
+    }
+
+    fn read_wallet_keypair(&self) -> Result<Keypair, WalletError> {
+        SolanaKeystore::read_keypair(&self.config.keypair_path)
+            .map_err(|e| e.context("Failed to read keypair from file"))
+    }
+
+    /// Lit la paire de clés du compte nonce, ou la génère (et l'enregistre) si le fichier n'existe pas.
+    fn nonce_keypair(&self, file_path: &str) -> Result<Keypair, WalletError> {
+        if Path::new(file_path).exists() {
+            return read_keypair_file(file_path).map_err(|e| {
+                WalletError::KeyIo(format!(
+                    "Failed to read nonce keypair from {}: {}",
+                    file_path, e
+                ))
+            });
+        }
+
+        if let Some(parent) = Path::new(file_path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let keypair = Keypair::new();
+        write_keypair_file(&keypair, file_path).map_err(|e| {
+            WalletError::KeyIo(format!(
+                "Failed to write nonce keypair to {}: {}",
+                file_path, e
+            ))
+        })?;
+        self.config
+            .output
+            .line(format!("Nonce account keypair written to {}", file_path));
+        Ok(keypair)
+    }
+}
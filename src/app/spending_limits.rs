@@ -0,0 +1,118 @@
+use crate::config::spending_ledger::SpendingLedger;
+use crate::config::wallet_config::WalletConfig;
+use crate::error::WalletError;
+use crate::solana::amount::SolAmount;
+
+/// Plafonds de dépense du profil actif ("spending_limit_per_transaction" et "spending_limit_per_day").
+///
+/// Les montants envoyés sont comptés dans le fichier des dépenses du jour (UTC), par profil.
+pub struct SpendingLimits {
+    config: WalletConfig,
+}
+
+impl SpendingLimits {
+    pub fn new(config: WalletConfig) -> Self {
+        Self { config }
+    }
+
+    /// Vérifie qu'un envoi respecte les plafonds du profil, avant toute signature.
+    ///
+    /// # Arguments:
+    /// - transactions - Le montant (en lamports) transféré par chaque transaction de l'envoi.
+    ///
+    /// # Returns:
+    /// Retourne une erreur `SpendingLimit` si une transaction, ou le total envoyé aujourd'hui, dépasse son plafond.
+    pub fn check(&self, transactions: &[u64]) -> Result<(), WalletError> {
+        let sol = |lamports: u64| format!("{} SOL", SolAmount::format_sol(lamports));
+
+        if let Some(limit) = self.config.spending_limit_per_transaction {
+            if let Some(lamports) = transactions
+                .iter()
+                .copied()
+                .find(|&lamports| lamports > limit)
+            {
+                return Err(WalletError::SpendingLimit(format!(
+                    "{} exceeds the spending limit per transaction of profile {} ({})",
+                    sol(lamports),
+                    self.config.profile,
+                    sol(limit)
+                )));
+            }
+        }
+
+        if let Some(limit) = self.config.spending_limit_per_day {
+            let spent = SpendingLedger::load(&self.config.spending_ledger_path)?
+                .spent(&self.config.profile, &SpendingLedger::today());
+            let total = transactions
+                .iter()
+                .fold(spent, |total, &lamports| total.saturating_add(lamports));
+            if total > limit {
+                return Err(WalletError::SpendingLimit(format!(
+                    "{} would exceed the spending limit per day of profile {} ({}, {} already sent today)",
+                    sol(total - spent),
+                    self.config.profile,
+                    sol(limit),
+                    sol(spent)
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Compte une transaction envoyée dans le total du jour (uniquement si le profil a un plafond par jour).
+    ///
+    /// # Arguments:
+    /// - lamports - Le montant transféré par la transaction.
+    /// - signature - La signature de la transaction.
+    pub fn record(&self, lamports: u64, signature: &str) -> Result<(), WalletError> {
+        if self.config.spending_limit_per_day.is_none() {
+            return Ok(());
+        }
+        let path = &self.config.spending_ledger_path;
+        let mut ledger = SpendingLedger::load(path)?;
+        ledger.record(
+            &self.config.profile,
+            &SpendingLedger::today(),
+            lamports,
+            signature,
+        );
+        ledger.save(path).map_err(|e| {
+            e.context(&format!(
+                "Transaction {} sent, but failed to record it in {}",
+                signature, path
+            ))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_check_and_record() {
+        let ledger_path = "./storage/tests/spending_temp_test_limits.json";
+        fs::remove_file(ledger_path).ok();
+
+        let mut config = WalletConfig::new().unwrap();
+        config.profile = "mainnet".to_string();
+        config.spending_ledger_path = ledger_path.to_string();
+        config.spending_limit_per_transaction = Some(100);
+        config.spending_limit_per_day = Some(150);
+        let limits = SpendingLimits::new(config);
+
+        // Plafond par transaction.
+        assert!(limits.check(&[100, 50]).is_ok());
+        let error = limits.check(&[101]).unwrap_err();
+        assert_eq!(error.exit_code(), 12);
+
+        // Plafond par jour : les transactions envoyées sont comptées.
+        assert!(limits.check(&[100, 100]).is_err());
+        limits.record(100, "first").unwrap();
+        assert!(limits.check(&[50]).is_ok());
+        assert!(limits.check(&[51]).is_err());
+
+        fs::remove_file(ledger_path).ok();
+    }
+}
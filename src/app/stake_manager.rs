@@ -0,0 +1,609 @@
+use crate::app::mainnet_guard::MainnetGuard;
+use crate::app::spending_limits::SpendingLimits;
+use crate::config::wallet_config::WalletConfig;
+use crate::error::WalletError;
+use crate::solana::amount::{SolAmount, TransferAmount};
+use crate::solana::keystore::SolanaKeystore;
+use crate::solana::rpc::WalletRpc;
+use crate::solana::stake::{SolanaStake, StakeAccount};
+use crate::solana::transaction::SolanaTransaction;
+use serde_json::{json, Value};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, write_keypair_file, Keypair, Signature, Signer};
+use solana_sdk::stake::{
+    self, instruction as stake_instruction,
+    state::{Authorized, Lockup},
+};
+use solana_sdk::system_instruction;
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+/// Adresse d'un nouveau compte de stake : une paire de clés dédiée, ou une adresse dérivée du wallet avec une seed.
+pub enum StakeAddress {
+    /// Fichier de la paire de clés du compte (généré s'il n'existe pas).
+    Keypair(String),
+    /// Seed de l'adresse, dérivée de la clé publique du wallet ("create_with_seed").
+    Seed(String),
+}
+
+/// Autorités d'un nouveau compte de stake (par défaut : la clé publique du wallet).
+#[derive(Default)]
+pub struct StakeAuthorities {
+    pub staker: Option<Pubkey>,
+    pub withdrawer: Option<Pubkey>,
+}
+
+pub struct StakeManager {
+    /// Configuration du compte du wallet qui paie les frais.
+    config: WalletConfig,
+    rpc: Rc<dyn WalletRpc>,
+    /// Paire de clés de l'autorité qui signe les opérations (par défaut : celle du wallet).
+    authority_keypair_path: Option<String>,
+}
+
+impl StakeManager {
+    pub fn new(
+        config: WalletConfig,
+        rpc: Rc<dyn WalletRpc>,
+        authority_keypair_path: Option<String>,
+    ) -> Self {
+        Self {
+            config,
+            rpc,
+            authority_keypair_path,
+        }
+    }
+
+    /// Crée un compte de stake, payé par la paire de clés du wallet.
+    ///
+    /// # Arguments:
+    /// - address - La paire de clés du compte de stake, ou la seed de son adresse.
+    /// - amount - Le montant déposé sur le compte de stake.
+    /// - authorities - Les autorités de stake et de retrait (par défaut : la clé publique du wallet).
+    pub fn create(
+        &self,
+        address: &StakeAddress,
+        amount: TransferAmount,
+        authorities: &StakeAuthorities,
+    ) -> Result<(), WalletError> {
+        let payer = self.read_wallet_keypair()?;
+        let authorized = Authorized {
+            staker: authorities.staker.unwrap_or_else(|| payer.pubkey()),
+            withdrawer: authorities.withdrawer.unwrap_or_else(|| payer.pubkey()),
+        };
+
+        let minimum_balance = SolanaStake::minimum_balance(self.rpc.as_ref())?;
+        let lamports = match amount {
+            TransferAmount::Lamports(lamports) if lamports >= minimum_balance => lamports,
+            TransferAmount::Lamports(lamports) => {
+                return Err(WalletError::InvalidInput(format!(
+                "A stake account needs at least {} lamports to be rent-exempt ({} lamports given)",
+                minimum_balance, lamports
+            )))
+            }
+            TransferAmount::All => {
+                return Err(WalletError::InvalidInput(
+                    "ALL is not supported when creating a stake account".to_string(),
+                ))
+            }
+        };
+
+        let (stake_address, stake_keypair) = self.new_stake_address(&payer, address)?;
+        let instructions = match address {
+            StakeAddress::Keypair(_) => stake_instruction::create_account(
+                &payer.pubkey(),
+                &stake_address,
+                &authorized,
+                &Lockup::default(),
+                lamports,
+            ),
+            StakeAddress::Seed(seed) => stake_instruction::create_account_with_seed(
+                &payer.pubkey(),
+                &stake_address,
+                &payer.pubkey(),
+                seed,
+                &authorized,
+                &Lockup::default(),
+                lamports,
+            ),
+        };
+        let spending_limits = SpendingLimits::new(self.config.clone());
+        spending_limits.check(&[lamports])?;
+        let signature = self.send(&instructions, &payer, stake_keypair.as_ref())?;
+        spending_limits.record(lamports, &signature.to_string())?;
+
+        self.config.output.line(format!(
+            "Stake account created: {} (stake authority: {}, withdraw authority: {}, balance: {} SOL)",
+            stake_address,
+            authorized.staker,
+            authorized.withdrawer,
+            SolAmount::format_sol(lamports)
+        ));
+        self.config
+            .output
+            .line(format!("Transaction signature: {}", signature));
+        self.config.output.json(json!({
+            "stake_account": stake_address.to_string(),
+            "stake_authority": authorized.staker.to_string(),
+            "withdraw_authority": authorized.withdrawer.to_string(),
+            "balance": SolAmount::json(lamports),
+            "signature": signature.to_string(),
+        }));
+        Ok(())
+    }
+
+    /// Délègue un compte de stake à un validateur (son compte de vote).
+    pub fn delegate(
+        &self,
+        stake_account: &Pubkey,
+        vote_account: &Pubkey,
+    ) -> Result<(), WalletError> {
+        let (authority, account) = self.stake_authority_for(stake_account)?;
+        let rpc = self.rpc.as_ref();
+        SolanaStake::check_vote_account(rpc, vote_account)?;
+
+        let stake = account.balance.saturating_sub(account.rent_exempt_reserve);
+        let minimum_delegation = SolanaStake::minimum_delegation(rpc)?;
+        if stake < minimum_delegation {
+            return Err(WalletError::InsufficientFunds(format!(
+                "Stake account {} can delegate {} lamports, below the minimum delegation ({} lamports)",
+                stake_account, stake, minimum_delegation
+            )));
+        }
+
+        let instruction =
+            stake_instruction::delegate_stake(stake_account, &authority.pubkey(), vote_account);
+        let signature = self.send(&[instruction], &authority, None)?;
+
+        self.config.output.line(format!(
+            "Stake account {} delegated to {} ({} SOL, active from the next epoch)",
+            stake_account,
+            vote_account,
+            SolAmount::format_sol(stake)
+        ));
+        self.config
+            .output
+            .line(format!("Transaction signature: {}", signature));
+        self.config.output.json(json!({
+            "stake_account": stake_account.to_string(),
+            "vote_account": vote_account.to_string(),
+            "stake": SolAmount::json(stake),
+            "signature": signature.to_string(),
+        }));
+        Ok(())
+    }
+
+    /// Affiche un compte de stake : autorités, état d'activation et dernière récompense.
+    pub fn show(&self, stake_account: &Pubkey) -> Result<(), WalletError> {
+        let rpc = self.rpc.as_ref();
+        let account = SolanaStake::fetch(rpc, stake_account)?;
+        let activation = SolanaStake::activation(rpc, &account)?;
+
+        self.config
+            .output
+            .line(format!("Stake account:       {}", account.address));
+        self.config.output.line(format!(
+            "Balance:             {} SOL ({} lamports)",
+            SolAmount::format_sol(account.balance),
+            account.balance
+        ));
+        self.config.output.line(format!(
+            "Rent-exempt reserve: {} SOL",
+            SolAmount::format_sol(account.rent_exempt_reserve)
+        ));
+        self.config.output.line(format!(
+            "Stake authority:     {}",
+            account.authorized.staker
+        ));
+        self.config.output.line(format!(
+            "Withdraw authority:  {}",
+            account.authorized.withdrawer
+        ));
+        if account.lockup != Lockup::default() {
+            self.config.output.line(format!(
+                "Lockup:              until epoch {} / unix timestamp {} (custodian: {})",
+                account.lockup.epoch, account.lockup.unix_timestamp, account.lockup.custodian
+            ));
+        }
+        self.config
+            .output
+            .line(format!("State:               {}", activation.state));
+
+        let mut document = json!({
+            "stake_account": account.address.to_string(),
+            "balance": SolAmount::json(account.balance),
+            "rent_exempt_reserve": SolAmount::json(account.rent_exempt_reserve),
+            "stake_authority": account.authorized.staker.to_string(),
+            "withdraw_authority": account.authorized.withdrawer.to_string(),
+            "lockup": json!({
+                "epoch": account.lockup.epoch,
+                "unix_timestamp": account.lockup.unix_timestamp,
+                "custodian": account.lockup.custodian.to_string(),
+            }),
+            "state": activation.state.to_string(),
+            "current_epoch": activation.epoch,
+            "delegation": Value::Null,
+            "last_reward": Value::Null,
+        });
+        let Some(delegation) = account.delegation else {
+            self.config.output.json(document);
+            return Ok(());
+        };
+        self.config
+            .output
+            .line(format!("Delegated to:        {}", delegation.voter_pubkey));
+        self.config.output.line(format!(
+            "Delegated stake:     {} SOL",
+            SolAmount::format_sol(delegation.stake)
+        ));
+        self.config.output.line(format!(
+            "Active stake:        {} SOL",
+            SolAmount::format_sol(activation.effective)
+        ));
+        if activation.activating > 0 {
+            self.config.output.line(format!(
+                "Activating stake:    {} SOL",
+                SolAmount::format_sol(activation.activating)
+            ));
+        }
+        if activation.deactivating > 0 {
+            self.config.output.line(format!(
+                "Deactivating stake:  {} SOL",
+                SolAmount::format_sol(activation.deactivating)
+            ));
+        }
+        self.config.output.line(format!(
+            "Activation epoch:    {}",
+            delegation.activation_epoch
+        ));
+        if delegation.deactivation_epoch != u64::MAX {
+            self.config.output.line(format!(
+                "Deactivation epoch:  {}",
+                delegation.deactivation_epoch
+            ));
+        }
+        self.config
+            .output
+            .line(format!("Current epoch:       {}", activation.epoch));
+
+        let last_reward = SolanaStake::last_reward(rpc, stake_account)?;
+        match &last_reward {
+            Some(reward) => self.config.output.line(format!(
+                "Last reward:         {} SOL (epoch {}, commission {}%)",
+                SolAmount::format_sol(reward.amount),
+                reward.epoch,
+                reward.commission.unwrap_or_default()
+            )),
+            None => self.config.output.line("Last reward:         none"),
+        }
+
+        document["delegation"] = json!({
+            "vote_account": delegation.voter_pubkey.to_string(),
+            "stake": SolAmount::json(delegation.stake),
+            "active": SolAmount::json(activation.effective),
+            "activating": SolAmount::json(activation.activating),
+            "deactivating": SolAmount::json(activation.deactivating),
+            "activation_epoch": delegation.activation_epoch,
+            "deactivation_epoch": (delegation.deactivation_epoch != u64::MAX)
+                .then_some(delegation.deactivation_epoch),
+        });
+        document["last_reward"] = match last_reward {
+            Some(reward) => json!({
+                "amount": SolAmount::json(reward.amount),
+                "epoch": reward.epoch,
+                "commission": reward.commission,
+            }),
+            None => Value::Null,
+        };
+        self.config.output.json(document);
+        Ok(())
+    }
+
+    /// Désactive un compte de stake : il devient retirable à la fin de la période de refroidissement.
+    pub fn deactivate(&self, stake_account: &Pubkey) -> Result<(), WalletError> {
+        let (authority, account) = self.stake_authority_for(stake_account)?;
+        if account.delegation.is_none() {
+            return Err(WalletError::InvalidInput(format!(
+                "Stake account {} is not delegated",
+                stake_account
+            )));
+        }
+
+        let instruction = stake_instruction::deactivate_stake(stake_account, &authority.pubkey());
+        let signature = self.send(&[instruction], &authority, None)?;
+
+        self.config.output.line(format!(
+            "Stake account {} deactivated (withdrawable after the cooldown)",
+            stake_account
+        ));
+        self.config
+            .output
+            .line(format!("Transaction signature: {}", signature));
+        self.config.output.json(json!({
+            "stake_account": stake_account.to_string(),
+            "signature": signature.to_string(),
+        }));
+        Ok(())
+    }
+
+    /// Retire des lamports d'un compte de stake ("ALL" retire tout le montant retirable).
+    pub fn withdraw(
+        &self,
+        stake_account: &Pubkey,
+        recipient: &Pubkey,
+        amount: TransferAmount,
+    ) -> Result<(), WalletError> {
+        let authority = self.read_authority_keypair()?;
+        let account = SolanaStake::fetch(self.rpc.as_ref(), stake_account)?;
+        Self::check_authority(
+            "withdraw",
+            &account,
+            &account.authorized.withdrawer,
+            &authority,
+        )?;
+
+        let activation = SolanaStake::activation(self.rpc.as_ref(), &account)?;
+        let withdrawable = account.withdrawable(&activation);
+        let lamports = match amount {
+            TransferAmount::Lamports(lamports) => lamports,
+            TransferAmount::All => withdrawable,
+        };
+        if lamports == 0 || lamports > withdrawable {
+            return Err(WalletError::InsufficientFunds(format!(
+                "Only {} SOL can be withdrawn from stake account {} ({} SOL still staked: deactivate it and wait for the cooldown)",
+                SolAmount::format_sol(withdrawable),
+                stake_account,
+                SolAmount::format_sol(activation.staked())
+            )));
+        }
+
+        let instruction = stake_instruction::withdraw(
+            stake_account,
+            &authority.pubkey(),
+            recipient,
+            lamports,
+            None,
+        );
+        let spending_limits = SpendingLimits::new(self.config.clone());
+        spending_limits.check(&[lamports])?;
+        let signature = self.send(&[instruction], &authority, None)?;
+        spending_limits.record(lamports, &signature.to_string())?;
+
+        self.config.output.line(format!(
+            "Withdrew {} SOL ({} lamports) from stake account {} to {}",
+            SolAmount::format_sol(lamports),
+            lamports,
+            stake_account,
+            recipient
+        ));
+        self.config
+            .output
+            .line(format!("Transaction signature: {}", signature));
+        self.config.output.json(json!({
+            "stake_account": stake_account.to_string(),
+            "recipient": recipient.to_string(),
+            "amount": SolAmount::json(lamports),
+            "signature": signature.to_string(),
+        }));
+        Ok(())
+    }
+
+    /// Sépare une partie d'un compte de stake dans un nouveau compte (avec la même délégation).
+    ///
+    /// Le nouveau compte est d'abord approvisionné de sa réserve de rente, payée par le wallet.
+    pub fn split(
+        &self,
+        stake_account: &Pubkey,
+        address: &StakeAddress,
+        amount: TransferAmount,
+    ) -> Result<(), WalletError> {
+        let payer = self.read_wallet_keypair()?;
+        let (authority, account) = self.stake_authority_for(stake_account)?;
+        let lamports = match amount {
+            TransferAmount::Lamports(lamports) if lamports < account.balance => lamports,
+            TransferAmount::Lamports(_) => {
+                return Err(WalletError::InsufficientFunds(format!(
+                    "Stake account {} only holds {} SOL",
+                    stake_account,
+                    SolAmount::format_sol(account.balance)
+                )))
+            }
+            TransferAmount::All => return Err(
+                "ALL is not supported when splitting a stake account: merge the accounts instead"
+                    .into(),
+            ),
+        };
+
+        let (split_address, split_keypair) = self.new_stake_address(&payer, address)?;
+        let rent_exempt_reserve = SolanaStake::minimum_balance(self.rpc.as_ref())?;
+        let mut instructions = vec![system_instruction::transfer(
+            &payer.pubkey(),
+            &split_address,
+            rent_exempt_reserve,
+        )];
+        instructions.extend(match address {
+            StakeAddress::Keypair(_) => stake_instruction::split(
+                stake_account,
+                &authority.pubkey(),
+                lamports,
+                &split_address,
+            ),
+            StakeAddress::Seed(seed) => stake_instruction::split_with_seed(
+                stake_account,
+                &authority.pubkey(),
+                lamports,
+                &split_address,
+                &payer.pubkey(),
+                seed,
+            ),
+        });
+
+        let signature = self.send(&instructions, &authority, split_keypair.as_ref())?;
+
+        self.config.output.line(format!(
+            "Split {} SOL from stake account {} into {} (plus its rent-exempt reserve of {} SOL, paid by the wallet)",
+            SolAmount::format_sol(lamports),
+            stake_account,
+            split_address,
+            SolAmount::format_sol(rent_exempt_reserve)
+        ));
+        self.config
+            .output
+            .line(format!("Transaction signature: {}", signature));
+        self.config.output.json(json!({
+            "stake_account": stake_account.to_string(),
+            "split_stake_account": split_address.to_string(),
+            "amount": SolAmount::json(lamports),
+            "rent_exempt_reserve": SolAmount::json(rent_exempt_reserve),
+            "signature": signature.to_string(),
+        }));
+        Ok(())
+    }
+
+    /// Fusionne un compte de stake dans un autre (le compte source est fermé).
+    pub fn merge(&self, destination: &Pubkey, source: &Pubkey) -> Result<(), WalletError> {
+        if destination == source {
+            return Err(WalletError::InvalidInput(
+                "Cannot merge a stake account into itself".to_string(),
+            ));
+        }
+        let (authority, _) = self.stake_authority_for(destination)?;
+        self.stake_authority_for(source)?;
+
+        let instructions = stake_instruction::merge(destination, source, &authority.pubkey());
+        let signature = self.send(&instructions, &authority, None)?;
+
+        self.config.output.line(format!(
+            "Stake account {} merged into {}",
+            source, destination
+        ));
+        self.config
+            .output
+            .line(format!("Transaction signature: {}", signature));
+        self.config.output.json(json!({
+            "stake_account": destination.to_string(),
+            "merged": source.to_string(),
+            "signature": signature.to_string(),
+        }));
+        Ok(())
+    }
+
+    /// Récupère un compte de stake, et vérifie que la paire de clés de l'autorité en est l'autorité de stake.
+    fn stake_authority_for(
+        &self,
+        stake_account: &Pubkey,
+    ) -> Result<(Keypair, StakeAccount), WalletError> {
+        let authority = self.read_authority_keypair()?;
+        let account = SolanaStake::fetch(self.rpc.as_ref(), stake_account)?;
+        Self::check_authority("stake", &account, &account.authorized.staker, &authority)?;
+        Ok((authority, account))
+    }
+
+    fn check_authority(
+        kind: &str,
+        account: &StakeAccount,
+        expected: &Pubkey,
+        authority: &Keypair,
+    ) -> Result<(), WalletError> {
+        if authority.pubkey() != *expected {
+            return Err(WalletError::Account(format!(
+                "The {} authority of stake account {} is {}, not {}",
+                kind,
+                account.address,
+                expected,
+                authority.pubkey()
+            )));
+        }
+        Ok(())
+    }
+
+    /// Signe et envoie une transaction payée par le wallet, avec les signataires supplémentaires nécessaires
+    /// (après confirmation sur mainnet-beta).
+    fn send(
+        &self,
+        instructions: &[Instruction],
+        signer: &Keypair,
+        stake_keypair: Option<&Keypair>,
+    ) -> Result<Signature, WalletError> {
+        let payer = self.read_wallet_keypair()?;
+        let mut signers = vec![&payer];
+        if signer.pubkey() != payer.pubkey() {
+            signers.push(signer);
+        }
+        if let Some(stake_keypair) = stake_keypair {
+            signers.push(stake_keypair);
+        }
+        MainnetGuard::new(&self.config, self.rpc.as_ref()).check("send a stake transaction")?;
+        SolanaTransaction::send_instructions(self.rpc.as_ref(), instructions, &signers)
+    }
+
+    /// Retourne l'adresse d'un nouveau compte de stake, et sa paire de clés (None pour une adresse dérivée d'une seed).
+    fn new_stake_address(
+        &self,
+        payer: &Keypair,
+        address: &StakeAddress,
+    ) -> Result<(Pubkey, Option<Keypair>), WalletError> {
+        match address {
+            StakeAddress::Keypair(file_path) => {
+                let keypair = self.stake_keypair(file_path)?;
+                Ok((keypair.pubkey(), Some(keypair)))
+            }
+            StakeAddress::Seed(seed) => {
+                let address =
+                    Pubkey::create_with_seed(&payer.pubkey(), seed, &stake::program::id())
+                        .map_err(|e| {
+                            WalletError::InvalidInput(format!("Invalid seed '{}': {}", seed, e))
+                        })?;
+                self.config.output.line(format!(
+                    "Stake account address: {} (seed \"{}\", base {})",
+                    address,
+                    seed,
+                    payer.pubkey()
+                ));
+                Ok((address, None))
+            }
+        }
+    }
+
+    fn read_wallet_keypair(&self) -> Result<Keypair, WalletError> {
+        SolanaKeystore::read_keypair(&self.config.keypair_path)
+            .map_err(|e| e.context("Failed to read keypair from file"))
+    }
+
+    fn read_authority_keypair(&self) -> Result<Keypair, WalletError> {
+        match &self.authority_keypair_path {
+            Some(keypair_path) => SolanaKeystore::read_keypair(keypair_path)
+                .map_err(|e| e.context("Failed to read authority keypair from file")),
+            None => self.read_wallet_keypair(),
+        }
+    }
+
+    /// Lit la paire de clés d'un compte de stake, ou la génère (et l'enregistre) si le fichier n'existe pas.
+    fn stake_keypair(&self, file_path: &str) -> Result<Keypair, WalletError> {
+        if Path::new(file_path).exists() {
+            return read_keypair_file(file_path).map_err(|e| {
+                WalletError::KeyIo(format!(
+                    "Failed to read stake keypair from {}: {}",
+                    file_path, e
+                ))
+            });
+        }
+
+        if let Some(parent) = Path::new(file_path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let keypair = Keypair::new();
+        write_keypair_file(&keypair, file_path).map_err(|e| {
+            WalletError::KeyIo(format!(
+                "Failed to write stake keypair to {}: {}",
+                file_path, e
+            ))
+        })?;
+        self.config
+            .output
+            .line(format!("Stake account keypair written to {}", file_path));
+        Ok(keypair)
+    }
+}
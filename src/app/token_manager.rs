@@ -0,0 +1,257 @@
+use crate::app::mainnet_guard::MainnetGuard;
+use crate::app::spending_limits::SpendingLimits;
+use crate::config::wallet_config::WalletConfig;
+use crate::error::WalletError;
+use crate::solana::amount::SolAmount;
+use crate::solana::keystore::SolanaKeystore;
+use crate::solana::memo::SolanaMemo;
+use crate::solana::rpc::WalletRpc;
+use crate::solana::token::{SolanaToken, TokenAmount};
+use crate::solana::transaction::SolanaTransaction;
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signer;
+use std::rc::Rc;
+
+pub struct TokenManager {
+    config: WalletConfig,
+    rpc: Rc<dyn WalletRpc>,
+}
+
+impl TokenManager {
+    pub fn new(config: WalletConfig, rpc: Rc<dyn WalletRpc>) -> Self {
+        Self { config, rpc }
+    }
+
+    /// Affiche les comptes de tokens (SPL Token et Token-2022) d'un wallet.
+    pub fn list(&self, owner: &Pubkey) -> Result<(), WalletError> {
+        let accounts = SolanaToken::accounts(self.rpc.as_ref(), owner)?;
+        self.config.output.json(json!({
+            "owner": owner.to_string(),
+            "accounts": accounts.iter().map(|account| json!({
+                "address": account.address.to_string(),
+                "mint": account.mint.to_string(),
+                "program": account.program.name(),
+                "decimals": account.decimals,
+                "amount": account.amount.to_string(),
+                "ui_amount": account.ui_amount(),
+            })).collect::<Vec<_>>(),
+        }));
+        if accounts.is_empty() {
+            self.config
+                .output
+                .line(format!("No token account found for {}", owner));
+            return Ok(());
+        }
+
+        self.config.output.line(format!(
+            "{:<44} {:<44} {:<10} {:<8} AMOUNT",
+            "TOKEN ACCOUNT", "MINT", "PROGRAM", "DECIMALS"
+        ));
+        for account in &accounts {
+            self.config.output.line(format!(
+                "{:<44} {:<44} {:<10} {:<8} {}",
+                account.address.to_string(),
+                account.mint.to_string(),
+                account.program.name(),
+                account.decimals,
+                account.ui_amount()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Transfère des tokens ("transfer_checked") vers le compte associé du destinataire,
+    /// en le créant s'il n'existe pas (sa réserve de rente est payée par l'expéditeur).
+    ///
+    /// # Arguments:
+    /// - mint - Le mint du token.
+    /// - recipient - L'adresse du wallet destinataire (pas celle de son compte de token).
+    /// - amount - Le montant avec ses décimales (ex : "1.5"), ou "ALL" / "max".
+    /// - memo - Le memo ajouté au transfert (optionnel).
+    /// - yes - Envoie sans demander de confirmation.
+    pub fn transfer(
+        &self,
+        mint: &Pubkey,
+        recipient: &Pubkey,
+        amount: &str,
+        memo: Option<&str>,
+        yes: bool,
+    ) -> Result<(), WalletError> {
+        if let Some(memo) = memo {
+            SolanaMemo::validate(memo).map_err(WalletError::InvalidInput)?;
+        }
+        let sender = SolanaKeystore::read_keypair(&self.config.keypair_path)
+            .map_err(|e| e.context("Failed to read keypair from file"))?;
+        let rpc = self.rpc.as_ref();
+
+        let mint = SolanaToken::mint(rpc, mint)?;
+        let source_address = SolanaToken::associated_address(&sender.pubkey(), &mint);
+        let source = SolanaToken::account(rpc, &source_address)?.ok_or_else(|| {
+            format!(
+                "No token account for mint {} (expected {})",
+                mint.address, source_address
+            )
+        })?;
+
+        let units =
+            match TokenAmount::parse(amount, mint.decimals).map_err(WalletError::InvalidInput)? {
+                TokenAmount::Units(units) => units,
+                TokenAmount::All if source.amount > 0 => source.amount,
+                TokenAmount::All => {
+                    return Err(WalletError::InsufficientFunds(
+                        "Token balance is zero: nothing to send".to_string(),
+                    ))
+                }
+            };
+        if units > source.amount {
+            return Err(WalletError::InsufficientFunds(format!(
+                "Insufficient token balance: {} available, {} needed",
+                source.ui_amount(),
+                SolAmount::format_decimal(units, mint.decimals)
+            )));
+        }
+
+        // Le destinataire est un wallet : envoyer vers l'ATA d'un compte de token rendrait les tokens inaccessibles.
+        if SolanaToken::account(rpc, recipient).is_ok_and(|account| account.is_some()) {
+            return Err(WalletError::InvalidInput(format!(
+                "{} is a token account: give the wallet address of the recipient",
+                recipient
+            )));
+        }
+        let destination_address = SolanaToken::associated_address(recipient, &mint);
+        let create_destination = SolanaToken::account(rpc, &destination_address)?.is_none();
+
+        self.config.output.line("Token transfer preview:");
+        self.config.output.line(format!(
+            "  Mint:          {} ({})",
+            mint.address,
+            mint.program.name()
+        ));
+        self.config.output.line(format!(
+            "  From:          {} (owner: {})",
+            source_address,
+            sender.pubkey()
+        ));
+        self.config.output.line(format!(
+            "  To:            {} (owner: {})",
+            destination_address, recipient
+        ));
+        self.config.output.line(format!(
+            "  Amount:        {} ({} base units)",
+            SolAmount::format_decimal(units, mint.decimals),
+            units
+        ));
+        if let Some(memo) = memo {
+            self.config
+                .output
+                .line(format!("  Memo:          {}", memo));
+        }
+        self.config.output.line(format!(
+            "  Balance after: {}",
+            SolAmount::format_decimal(source.amount - units, mint.decimals)
+        ));
+        if create_destination {
+            self.config.output.line("Note: the recipient has no token account for this mint: it will be created (you pay its rent-exempt reserve).");
+        }
+        // Seuls les transferts de wrapped SOL déplacent des lamports, comptés dans les plafonds de dépense.
+        let lamports = if mint.is_native() { units } else { 0 };
+        let spending_limits = SpendingLimits::new(self.config.clone());
+        spending_limits.check(&[lamports])?;
+        MainnetGuard::new(&self.config, rpc).check("transfer tokens")?;
+        if !yes && !self.config.output.confirm("Send this transaction? [y/N]:") {
+            return Err(WalletError::Cancelled("Transaction cancelled".to_string()));
+        }
+
+        let mut instructions = SolanaToken::transfer_instructions(
+            &sender.pubkey(),
+            recipient,
+            &mint,
+            units,
+            create_destination,
+        )?;
+        if let Some(memo) = memo {
+            instructions.push(SolanaMemo::instruction(memo, &sender.pubkey()));
+        }
+        let signature = SolanaTransaction::send_instructions(rpc, &instructions, &[&sender])?;
+        if lamports > 0 {
+            spending_limits.record(lamports, &signature.to_string())?;
+        }
+        self.config
+            .output
+            .line(format!("Transaction signature: {}", signature));
+        self.config.output.json(json!({
+            "signature": signature.to_string(),
+            "mint": mint.address.to_string(),
+            "from": source_address.to_string(),
+            "to": destination_address.to_string(),
+            "recipient": recipient.to_string(),
+            "amount": units.to_string(),
+            "ui_amount": SolAmount::format_decimal(units, mint.decimals),
+            "created_recipient_account": create_destination,
+            "memo": memo,
+        }));
+        Ok(())
+    }
+
+    /// Ferme un compte de token vide, pour récupérer sa réserve de rente.
+    ///
+    /// # Arguments:
+    /// - address - L'adresse du compte de token.
+    /// - yes - Ferme sans demander de confirmation.
+    pub fn close(&self, address: &Pubkey, yes: bool) -> Result<(), WalletError> {
+        let owner = SolanaKeystore::read_keypair(&self.config.keypair_path)
+            .map_err(|e| e.context("Failed to read keypair from file"))?;
+
+        let account = SolanaToken::account(self.rpc.as_ref(), address)?
+            .ok_or_else(|| format!("Token account {} not found", address))?;
+        if account.owner != owner.pubkey() {
+            return Err(WalletError::Account(format!(
+                "Token account {} is owned by {}, not by this wallet ({})",
+                address,
+                account.owner,
+                owner.pubkey()
+            )));
+        }
+        if account.amount > 0 {
+            return Err(WalletError::InvalidInput(format!(
+                "Token account {} still holds {} tokens: transfer them before closing it",
+                address,
+                account.ui_amount()
+            )));
+        }
+
+        self.config.output.line(format!(
+            "Closing token account {} (mint: {}, program: {}): its rent-exempt reserve goes back to {}",
+            address,
+            account.mint,
+            account.program.name(),
+            owner.pubkey()
+        ));
+        MainnetGuard::new(&self.config, self.rpc.as_ref()).check("close a token account")?;
+        if !yes
+            && !self
+                .config
+                .output
+                .confirm("Close this token account? [y/N]:")
+        {
+            return Err(WalletError::Cancelled("Transaction cancelled".to_string()));
+        }
+
+        let instruction = SolanaToken::close_instruction(&account)?;
+        let signature =
+            SolanaTransaction::send_instructions(self.rpc.as_ref(), &[instruction], &[&owner])?;
+        self.config
+            .output
+            .line(format!("Token account {} closed", address));
+        self.config
+            .output
+            .line(format!("Transaction signature: {}", signature));
+        self.config.output.json(json!({
+            "closed": address.to_string(),
+            "mint": account.mint.to_string(),
+            "signature": signature.to_string(),
+        }));
+        Ok(())
+    }
+}
@@ -1,17 +1,33 @@
+use crate::app::mainnet_guard::MainnetGuard;
+use crate::app::spending_limits::SpendingLimits;
 use crate::config::wallet_config::WalletConfig;
-use crate::solana::transaction::SolanaTransaction;
+use crate::error::WalletError;
+use crate::solana::amount::{AmountUnit, SolAmount, TransferAmount};
+use crate::solana::compute_budget::{
+    ComputeBudget, PriorityFee, SolanaComputeBudget, DEFAULT_PRIORITY_FEE_PERCENTILE,
+};
+use crate::solana::keystore::SolanaKeystore;
+use crate::solana::memo::SolanaMemo;
+use crate::solana::nonce::{DurableNonce, SolanaNonce};
+use crate::solana::rpc::WalletRpc;
+use crate::solana::transaction::{SolanaTransaction, TransferPreview};
+use crate::solana::transaction_file::TransactionFile;
 use clap::ArgMatches;
-use solana_sdk::{pubkey::Pubkey, signature::read_keypair_file};
+use serde_json::json;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use std::rc::Rc;
 use std::str::FromStr;
 
 pub struct TransactionManager {
     config: WalletConfig,
+    rpc: Rc<dyn WalletRpc>,
 }
 
 impl TransactionManager {
     /// Crée une nouvelle instance de TransactionManager.
-    pub fn new(config: WalletConfig) -> Self {
-        Self { config }
+    pub fn new(config: WalletConfig, rpc: Rc<dyn WalletRpc>) -> Self {
+        Self { config, rpc }
     }
 
     /// Envoie des lamports (unité de SOL) à une adresse spécifiée en utilisant la clé privée du wallet.
@@ -22,25 +38,411 @@ impl TransactionManager {
     /// # Returns:
     /// - Ok(()) - Si la transaction est envoyée avec succès.
     /// - Err(e) - Si une erreur se produit lors de la lecture de la clé, la conversion des arguments, ou l'envoi de la transaction.
-    pub fn send_transaction(&self, matches: &ArgMatches) -> Result<(), Box<dyn std::error::Error>> {
-        // Lecture du fichier contenant la clé privée du sender.
-        let sender_keypair = read_keypair_file(&self.config.keypair_path)
-            .map_err(|_| "Failed to read keypair from file")?;
+    pub fn send_transaction(&self, matches: &ArgMatches) -> Result<(), WalletError> {
+        // Lecture du fichier contenant la clé privée du sender (déverrouillage du keystore s'il est chiffré).
+        let sender_keypair = SolanaKeystore::read_keypair(&self.config.keypair_path)
+            .map_err(|e| e.context("Failed to read keypair from file"))?;
 
-        // Extraction et validation de l'adresse publique du destinataire.
-        let recipient_pubkey = self.get_pubkey_from_matches(matches)?;
+        // Avec un nonce durable, l'avancement du nonce doit être signé par son autorité : ici, l'expéditeur.
+        let nonce = self.get_nonce_from_matches(matches)?;
+        if let Some(nonce) = nonce.as_ref() {
+            if nonce.authority != sender_keypair.pubkey() {
+                return Err(WalletError::Account(format!(
+                    "The authority of nonce account {} is {}, not the sender: use \"tx build\" and \"tx sign\" to sign with the nonce authority",
+                    nonce.account, nonce.authority
+                )));
+            }
+        }
 
-        // Extraction et validation du montant à envoyer.
-        let amount = self.get_amount_from_matches(matches)?;
+        // Résumé de la transaction, plafonds de dépense du profil, puis confirmation (sauf avec "--yes").
+        let preview = self.prepare_transfer(matches, &sender_keypair.pubkey(), nonce.as_ref())?;
+        let spending_limits = SpendingLimits::new(self.config.clone());
+        spending_limits.check(&[preview.lamports])?;
+        MainnetGuard::new(&self.config, self.rpc.as_ref()).check("send a transaction")?;
+        if !matches.get_flag("yes") && !self.config.output.confirm("Send this transaction? [y/N]:")
+        {
+            return Err(WalletError::Cancelled("Transaction cancelled".to_string()));
+        }
 
         // Envoi via le réseau Solana.
-        SolanaTransaction::send_lamports(
-            &self.config.rpc_url,
+        let signature = SolanaTransaction::send_lamports(
+            self.rpc.as_ref(),
             &sender_keypair,
+            &preview.recipient,
+            preview.lamports,
+            nonce.as_ref(),
+            &preview.compute_budget,
+            preview.memo.as_deref(),
+        )?;
+        spending_limits.record(preview.lamports, &signature.to_string())?;
+
+        self.config
+            .output
+            .line(format!("Transaction signature: {}", signature));
+        // Les frais sont déterminés par le message : ce sont ceux estimés dans le résumé.
+        self.config.output.line(format!(
+            "Total fee: {} SOL ({} lamports, including {} lamports of priority fee)",
+            SolAmount::format_sol(preview.fee),
+            preview.fee,
+            preview.priority_fee
+        ));
+        self.config.output.json(json!({
+            "signature": signature.to_string(),
+            "from": preview.sender.to_string(),
+            "to": preview.recipient.to_string(),
+            "amount": SolAmount::json(preview.lamports),
+            "fee": SolAmount::json(preview.fee),
+            "priority_fee": SolAmount::json(preview.priority_fee),
+            "memo": preview.memo,
+            "nonce_account": nonce.map(|nonce| nonce.account.to_string()),
+        }));
+        Ok(())
+    }
+
+    /// Construit une transaction de transfert non signée ("tx build", en ligne), et l'enregistre dans un fichier.
+    /// La transaction pourra ensuite être signée hors ligne ("tx sign"), puis diffusée ("tx broadcast").
+    ///
+    /// # Arguments:
+    /// - matches - Arguments de ligne de commande traités, fournissant le destinataire, le montant et le fichier.
+    /// - sender_pubkey - La clé publique de l'expéditeur (qui paie aussi les frais).
+    pub fn build_transaction(
+        &self,
+        matches: &ArgMatches,
+        sender_pubkey: &Pubkey,
+    ) -> Result<(), WalletError> {
+        let file_path = matches
+            .get_one::<String>("outfile")
+            .ok_or("Output file required")?;
+
+        let nonce = self.get_nonce_from_matches(matches)?;
+        let preview = self.prepare_transfer(matches, sender_pubkey, nonce.as_ref())?;
+        let transaction = SolanaTransaction::build_transfer(
+            self.rpc.as_ref(),
+            sender_pubkey,
+            &preview.recipient,
+            preview.lamports,
+            nonce.as_ref(),
+            &preview.compute_budget,
+            preview.memo.as_deref(),
+        )?;
+
+        let transaction_file = TransactionFile::new(&transaction)?;
+        transaction_file.save(file_path)?;
+
+        self.print_transaction_file(file_path, &transaction_file)?;
+        self.config
+            .output
+            .line(format!("Unsigned transaction written to {}", file_path));
+        match nonce {
+            Some(_) => self.config.output.line("Note: the transaction uses a durable nonce: it stays valid until the nonce is advanced."),
+            None => self.config.output.line("Note: the blockhash expires after about 60-90 seconds: sign and broadcast the transaction before it expires (or use --nonce)."),
+        }
+        self.config.output.json(json!({
+            "file": file_path,
+            "summary": transaction_file.summary()?,
+        }));
+        Ok(())
+    }
+
+    /// Signe un fichier de transaction avec la paire de clés du wallet ("tx sign", sans accès au réseau).
+    ///
+    /// # Arguments:
+    /// - matches - Arguments de ligne de commande traités, fournissant le fichier de transaction.
+    pub fn sign_transaction_file(&self, matches: &ArgMatches) -> Result<(), WalletError> {
+        let file_path = matches
+            .get_one::<String>("FILE")
+            .ok_or("Transaction file required")?;
+        let output_path = matches.get_one::<String>("outfile").unwrap_or(file_path);
+
+        let mut transaction_file = TransactionFile::load(file_path)?;
+        let mut transaction = transaction_file.transaction()?;
+        self.print_transaction_file(file_path, &transaction_file)?;
+
+        // Une instruction qui ne peut pas être décodée ne peut pas être relue : elle n'est signée qu'avec "--force".
+        let summary = transaction_file.summary()?;
+        let undecoded = summary.undecoded_instructions();
+        if !undecoded.is_empty() && !matches.get_flag("force") {
+            let programs: Vec<&str> = undecoded
+                .iter()
+                .map(|instruction| instruction.program_id.as_str())
+                .collect();
+            return Err(WalletError::InvalidInput(format!(
+                "The transaction has {} instruction(s) which cannot be decoded (program {}): check them and use --force to sign anyway",
+                undecoded.len(),
+                programs.join(", ")
+            )));
+        }
+
+        let keypair = SolanaKeystore::read_keypair(&self.config.keypair_path)
+            .map_err(|e| e.context("Failed to read keypair from file"))?;
+        if !transaction
+            .message
+            .signer_keys()
+            .contains(&&keypair.pubkey())
+        {
+            return Err(WalletError::Account(format!(
+                "Keypair {} is not a required signer of this transaction",
+                keypair.pubkey()
+            )));
+        }
+
+        if !matches.get_flag("yes") && !self.config.output.confirm("Sign this transaction? [y/N]:")
+        {
+            return Err(WalletError::Cancelled("Signature cancelled".to_string()));
+        }
+
+        let blockhash = transaction.message.recent_blockhash;
+        transaction.try_partial_sign(&[&keypair], blockhash)?;
+        transaction_file.set_transaction(&transaction)?;
+        transaction_file.save(output_path)?;
+
+        self.config.output.line(format!(
+            "Transaction signed by {}, written to {}",
+            keypair.pubkey(),
+            output_path
+        ));
+        self.config.output.json(json!({
+            "file": output_path,
+            "signer": keypair.pubkey().to_string(),
+            "summary": transaction_file.summary()?,
+        }));
+        Ok(())
+    }
+
+    /// Diffuse un fichier de transaction entièrement signée ("tx broadcast").
+    ///
+    /// # Arguments:
+    /// - matches - Arguments de ligne de commande traités, fournissant le fichier de transaction.
+    pub fn broadcast_transaction_file(&self, matches: &ArgMatches) -> Result<(), WalletError> {
+        let file_path = matches
+            .get_one::<String>("FILE")
+            .ok_or("Transaction file required")?;
+
+        let transaction_file = TransactionFile::load(file_path)?;
+        let transaction = transaction_file.transaction()?;
+        self.print_transaction_file(file_path, &transaction_file)?;
+
+        if !transaction.is_signed() {
+            return Err(WalletError::Transaction(
+                "Transaction is not fully signed (run \"tx sign\" first)".to_string(),
+            ));
+        }
+        transaction.verify().map_err(|_| {
+            WalletError::Transaction("Transaction has an invalid signature".to_string())
+        })?;
+
+        // Le SOL déplacé par la transaction (transferts, créations de comptes, retraits) compte dans les plafonds de dépense du profil.
+        let lamports = transaction_file.summary()?.lamports();
+        let spending_limits = SpendingLimits::new(self.config.clone());
+        spending_limits.check(&[lamports])?;
+        MainnetGuard::new(&self.config, self.rpc.as_ref()).check("broadcast a transaction")?;
+
+        let signature = SolanaTransaction::broadcast(self.rpc.as_ref(), &transaction)?;
+        spending_limits.record(lamports, &signature.to_string())?;
+        self.config
+            .output
+            .line(format!("Transaction signature: {}", signature));
+        self.config.output.json(json!({
+            "file": file_path,
+            "signature": signature.to_string(),
+            "summary": transaction_file.summary()?,
+        }));
+        Ok(())
+    }
+
+    /// Extrait le destinataire et le montant d'un transfert, affiche son résumé et vérifie la balance de l'expéditeur.
+    ///
+    /// # Arguments:
+    /// - matches - Arguments de ligne de commande pour l'opération de transaction.
+    /// - sender_pubkey - La clé publique de l'expéditeur.
+    /// - nonce - Le compte nonce durable utilisé par la transaction (optionnel).
+    ///
+    /// # Returns:
+    /// Retourne le résumé du transfert, ou une erreur si les arguments sont invalides ou si la balance est insuffisante.
+    fn prepare_transfer(
+        &self,
+        matches: &ArgMatches,
+        sender_pubkey: &Pubkey,
+        nonce: Option<&DurableNonce>,
+    ) -> Result<TransferPreview, WalletError> {
+        // Extraction et validation de l'adresse publique du destinataire.
+        let recipient_pubkey = self.get_pubkey_from_matches(matches)?;
+
+        // Frais de priorité ("--priority-fee" / "--compute-unit-limit"), estimés sur les comptes modifiés en mode "auto".
+        let mut writable_accounts = vec![*sender_pubkey, recipient_pubkey];
+        writable_accounts.extend(nonce.map(|nonce| nonce.account));
+        let compute_budget = self.get_compute_budget_from_matches(matches, &writable_accounts)?;
+
+        // Memo optionnel ("--memo"), ajouté au transfert par une instruction SPL Memo.
+        let memo = self.get_memo_from_matches(matches)?;
+
+        // Extraction et validation du montant à envoyer ("ALL" / "max" : toute la balance disponible).
+        let amount = match self.get_amount_from_matches(matches)? {
+            TransferAmount::Lamports(lamports) => lamports,
+            TransferAmount::All => {
+                let transferable = SolanaTransaction::transferable_balance(
+                    self.rpc.as_ref(),
+                    sender_pubkey,
+                    &recipient_pubkey,
+                    nonce,
+                    &compute_budget,
+                    memo.as_deref(),
+                )?;
+                self.config.output.line(format!(
+                    "Sending all available funds: {} SOL ({} lamports), keeping {} lamports for the fee and {} lamports of rent-exempt reserve (balance: {} lamports)",
+                    SolAmount::format_sol(transferable.lamports),
+                    transferable.lamports,
+                    transferable.fee,
+                    transferable.rent_reserve,
+                    transferable.balance
+                ));
+                transferable.lamports
+            }
+        };
+
+        // Résumé de la transaction.
+        let preview = SolanaTransaction::preview_transfer(
+            self.rpc.as_ref(),
+            sender_pubkey,
             &recipient_pubkey,
             amount,
-        )
-        .map_err(Into::into)
+            nonce,
+            &compute_budget,
+            memo.as_deref(),
+        )?;
+        self.print_preview(&preview);
+        if preview.balance_after().is_none() {
+            return Err(WalletError::InsufficientFunds(format!(
+                "Insufficient balance: {} lamports available, {} lamports needed (amount + fee)",
+                preview.balance,
+                u128::from(preview.lamports) + u128::from(preview.fee)
+            )));
+        }
+        Ok(preview)
+    }
+
+    /// Affiche le résumé d'un fichier de transaction (recalculé à partir de la transaction elle-même).
+    fn print_transaction_file(
+        &self,
+        file_path: &str,
+        transaction_file: &TransactionFile,
+    ) -> Result<(), WalletError> {
+        let summary = transaction_file.summary()?;
+        let signed = summary
+            .signers
+            .iter()
+            .filter(|signer| signer.signed)
+            .count();
+
+        self.config.output.line(format!(
+            "Transaction file: {} (version {})",
+            file_path, transaction_file.version
+        ));
+        self.config
+            .output
+            .line(format!("  Fee payer:     {}", summary.fee_payer));
+        self.config
+            .output
+            .line(format!("  Instructions:  {}", summary.instructions.len()));
+        for (index, instruction) in summary.instructions.iter().enumerate() {
+            let program = match &instruction.program {
+                Some(name) => format!("{} ({})", name, instruction.program_id),
+                None => format!("Unknown program {}", instruction.program_id),
+            };
+            self.config
+                .output
+                .line(format!("    #{} {}", index + 1, program));
+            self.config.output.line(format!(
+                "       {}",
+                instruction
+                    .description
+                    .as_deref()
+                    .unwrap_or("Warning: this instruction cannot be decoded")
+            ));
+            self.config.output.line(format!(
+                "       Accounts: {}",
+                instruction.accounts.join(", ")
+            ));
+        }
+        self.config.output.line(format!(
+            "  Estimated fee: {} SOL ({} lamports)",
+            SolAmount::format_sol(summary.fee),
+            summary.fee
+        ));
+        match &summary.nonce_account {
+            Some(nonce_account) => self.config.output.line(format!(
+                "  Durable nonce: {} (nonce: {})",
+                nonce_account, summary.blockhash
+            )),
+            None => self
+                .config
+                .output
+                .line(format!("  Blockhash:     {}", summary.blockhash)),
+        }
+        self.config.output.line(format!(
+            "  Signatures:    {}/{}",
+            signed,
+            summary.signers.len()
+        ));
+        for signer in &summary.signers {
+            self.config.output.line(format!(
+                "    {}: {}",
+                signer.pubkey,
+                if signer.signed { "signed" } else { "missing" }
+            ));
+        }
+        Ok(())
+    }
+
+    /// Affiche le résumé d'un transfert et ses avertissements.
+    fn print_preview(&self, preview: &TransferPreview) {
+        let sol = |lamports: u64| {
+            format!(
+                "{} SOL ({} lamports)",
+                SolAmount::format_sol(lamports),
+                lamports
+            )
+        };
+
+        self.config.output.line("Transaction preview:");
+        self.config
+            .output
+            .line(format!("  From:          {}", preview.sender));
+        self.config
+            .output
+            .line(format!("  To:            {}", preview.recipient));
+        self.config
+            .output
+            .line(format!("  Amount:        {}", sol(preview.lamports)));
+        if let Some(memo) = &preview.memo {
+            self.config
+                .output
+                .line(format!("  Memo:          {}", memo));
+        }
+        self.config
+            .output
+            .line(format!("  Estimated fee: {}", sol(preview.fee)));
+        if let Some(unit_price) = preview.compute_budget.unit_price {
+            self.config.output.line(format!(
+                "  Priority fee:  {} ({} micro-lamports x {} compute units, included in the fee)",
+                sol(preview.priority_fee),
+                unit_price,
+                preview.compute_unit_limit
+            ));
+        }
+        match preview.balance_after() {
+            Some(balance_after) => self
+                .config
+                .output
+                .line(format!("  Balance after: {}", sol(balance_after))),
+            None => self.config.output.line(format!(
+                "  Balance after: insufficient (balance: {})",
+                sol(preview.balance)
+            )),
+        }
+        for warning in &preview.warnings {
+            self.config.output.line(format!("Warning: {}", warning));
+        }
     }
 
     /// Extrait l'adresse publique du destinataire à partir des arguments de ligne de commande.
@@ -49,33 +451,119 @@ impl TransactionManager {
     /// - matches - Arguments de ligne de commande pour l'opération de transaction.
     ///
     /// # Returns:
-    /// - Result<Pubkey, Box<dyn std::error::Error>> - Sui est l'adresse publique du destinataire si l'extraction est réussie.
-    fn get_pubkey_from_matches(
-        &self,
-        matches: &ArgMatches,
-    ) -> Result<Pubkey, Box<dyn std::error::Error>> {
+    /// - Result<Pubkey, WalletError> - Sui est l'adresse publique du destinataire si l'extraction est réussie.
+    fn get_pubkey_from_matches(&self, matches: &ArgMatches) -> Result<Pubkey, WalletError> {
         let recipient = matches
             .get_one::<String>("RECIPIENT")
             .ok_or("Recipient required")?;
-        Pubkey::from_str(recipient).map_err(|_| "Invalid public key format".into())
+        Pubkey::from_str(recipient)
+            .map_err(|_| WalletError::InvalidPubkey("Invalid public key format".to_string()))
     }
 
-    /// Extrait le montant des lamports à envoyer à partir des arguments de ligne de commande.
+    /// Récupère le compte nonce durable donné par "--nonce" (optionnel), et affiche sa valeur.
     ///
     /// Arguments:
     /// - matches - Arguments de ligne de commande pour l'opération de transaction.
     ///
     /// # Returns:
-    /// - Result<u64, Box<dyn std::error::Error>> - Qui est le montant des lamports si l'extraction est réussie.
-    fn get_amount_from_matches(
+    /// - Result<Option<DurableNonce>, WalletError> - Qui est l'état du compte nonce, ou None sans "--nonce".
+    fn get_nonce_from_matches(
         &self,
         matches: &ArgMatches,
-    ) -> Result<u64, Box<dyn std::error::Error>> {
+    ) -> Result<Option<DurableNonce>, WalletError> {
+        let Some(nonce_account) = matches.get_one::<String>("nonce") else {
+            return Ok(None);
+        };
+        let nonce_account = Pubkey::from_str(nonce_account).map_err(|_| {
+            WalletError::InvalidPubkey("Invalid nonce account public key".to_string())
+        })?;
+
+        let nonce = SolanaNonce::fetch(self.rpc.as_ref(), &nonce_account)?;
+        self.config.output.line(format!(
+            "Using durable nonce account {} (nonce: {}, authority: {})",
+            nonce.account, nonce.blockhash, nonce.authority
+        ));
+        Ok(Some(nonce))
+    }
+
+    /// Récupère le memo donné par "--memo" (optionnel), et vérifie sa longueur.
+    ///
+    /// Arguments:
+    /// - matches - Arguments de ligne de commande pour l'opération de transaction.
+    ///
+    /// # Returns:
+    /// - Result<Option<String>, WalletError> - Qui est le memo, ou None sans "--memo".
+    fn get_memo_from_matches(&self, matches: &ArgMatches) -> Result<Option<String>, WalletError> {
+        let Some(memo) = matches.get_one::<String>("memo") else {
+            return Ok(None);
+        };
+        SolanaMemo::validate(memo).map_err(WalletError::InvalidInput)?;
+        Ok(Some(memo.clone()))
+    }
+
+    /// Construit les instructions "ComputeBudget" à partir de "--priority-fee" et "--compute-unit-limit".
+    /// En mode "auto", le prix est estimé à partir des frais de priorité récents des comptes modifiés.
+    ///
+    /// Arguments:
+    /// - matches - Arguments de ligne de commande pour l'opération de transaction.
+    /// - writable_accounts - Les comptes modifiés par la transaction.
+    ///
+    /// # Returns:
+    /// - Result<ComputeBudget, WalletError> - Qui est le budget de calcul de la transaction (vide par défaut).
+    fn get_compute_budget_from_matches(
+        &self,
+        matches: &ArgMatches,
+        writable_accounts: &[Pubkey],
+    ) -> Result<ComputeBudget, WalletError> {
+        let unit_limit = matches.get_one::<u32>("compute_unit_limit").copied();
+        let unit_price = match matches.get_one::<String>("priority_fee") {
+            None => None,
+            Some(priority_fee) => {
+                match PriorityFee::parse(priority_fee).map_err(WalletError::InvalidInput)? {
+                    PriorityFee::MicroLamports(micro_lamports) => Some(micro_lamports),
+                    PriorityFee::Auto => {
+                        let percentile = matches
+                            .get_one::<u8>("priority_fee_percentile")
+                            .copied()
+                            .unwrap_or(DEFAULT_PRIORITY_FEE_PERCENTILE);
+                        let recent = SolanaComputeBudget::recent_priority_fee(
+                            self.rpc.as_ref(),
+                            writable_accounts,
+                            percentile,
+                        )?;
+                        self.config.output.line(format!(
+                        "Priority fee (auto): {} micro-lamports per compute unit (percentile {} of the last {} slots)",
+                        recent.micro_lamports, recent.percentile, recent.slots
+                    ));
+                        Some(recent.micro_lamports)
+                    }
+                }
+            }
+        };
+
+        Ok(ComputeBudget {
+            unit_limit,
+            unit_price,
+        })
+    }
+
+    /// Extrait le montant à envoyer à partir des arguments de ligne de commande.
+    /// Le montant est en lamports par défaut (ou avec "--lamports"), et en SOL avec "--sol".
+    ///
+    /// Arguments:
+    /// - matches - Arguments de ligne de commande pour l'opération de transaction.
+    ///
+    /// # Returns:
+    /// - Result<TransferAmount, WalletError> - Qui est le montant (en lamports, ou "ALL") si l'extraction est réussie.
+    fn get_amount_from_matches(&self, matches: &ArgMatches) -> Result<TransferAmount, WalletError> {
         let amount_str = matches
             .get_one::<String>("AMOUNT")
             .ok_or("Amount required")?;
-        amount_str
-            .parse::<u64>()
-            .map_err(|_| "Amount needs to be a number".into())
+        let unit = if matches.get_flag("sol") {
+            AmountUnit::Sol
+        } else {
+            AmountUnit::Lamports
+        };
+        SolAmount::parse(amount_str, unit).map_err(WalletError::InvalidInput)
     }
 }
@@ -1,51 +1,86 @@
 use crate::app::mnemonic_manager::MnemonicManager;
 use crate::bip::mnemonic::BipMnemonic;
 use crate::config::wallet_config::WalletConfig;
+use crate::error::WalletError;
 use crate::solana::balance::SolanaBalance;
+use crate::solana::rpc::WalletRpc;
+use bip39::{Language, Mnemonic};
+use std::rc::Rc;
 
 pub struct WalletManager {
     config: WalletConfig,
+    rpc: Rc<dyn WalletRpc>,
 }
 
 impl WalletManager {
-    pub fn new(config: WalletConfig) -> Self {
-        Self { config }
+    pub fn new(config: WalletConfig, rpc: Rc<dyn WalletRpc>) -> Self {
+        Self { config, rpc }
     }
 
-    /// Génère une phrase mnémonique aléatoire de 12 mots et affiche la clé publique Solana correspondante.
+    /// Génère une phrase mnémonique aléatoire et affiche la clé publique Solana correspondante.
     /// Cette fonction est typiquement utilisée pour la création initiale d'un portefeuille.
-    pub fn generate_and_print_random_mnemonic(&self) {
+    ///
+    /// # Arguments:
+    /// - words - Le nombre de mots de la phrase (12, 15, 18, 21 ou 24).
+    /// - language - La langue de la liste de mots BIP39.
+    /// - unencrypted - Écrit les paires de clés en clair, sans mot de passe de keystore.
+    pub fn generate_and_print_random_mnemonic(
+        &self,
+        words: usize,
+        language: Language,
+        unencrypted: bool,
+    ) -> Result<(), WalletError> {
         let mnemonic_manager = MnemonicManager::new(self.config.clone());
 
         // Créer une nouvelle phrase mnémonique générée aléatoirement (en suivant le standard BIP39).
-        // Il s'agit d'une mnémonique de 12 mots, ce qui est un standard commun pour de nombreux portefeuilles.
-        let mnemonic = BipMnemonic::generate_mnemonic();
+        // 12 mots (128 bits d'entropie) par défaut, ce qui est un standard commun pour de nombreux portefeuilles.
+        let mnemonic = BipMnemonic::generate_mnemonic(words, language)?;
 
         // Convertit la mnémonique en une chaîne de caractères (pour pouvoir l'afficher et l'utiliser ultérieurement).
         // Cette phrase est utilisée pour générer une seed et peut être utilisée pour la récupération d'un portefeuille.
         let phrase = BipMnemonic::get_mnemonic_to_str(&mnemonic);
 
-        println!("BIP39 Mnemonic (random phrase): {}", phrase);
+        self.config
+            .output
+            .line(format!("BIP39 Mnemonic (random phrase): {}", phrase));
+        self.print_language(&mnemonic);
 
-        mnemonic_manager.process_mnemonic(&mnemonic);
+        mnemonic_manager.process_mnemonic(&mnemonic, unencrypted)
     }
 
     /// Génère une phrase mnémonique à partir d'une phrase donnée (12/24/Etc. mots) et affiche la clé publique Solana correspondante.
     /// Cette méthode permet d'utiliser une phrase existante pour récupérer ou accéder à un portefeuille.
-    pub fn generate_and_print_mnemonic_from_phrase(&self, phrase: &str) {
+    pub fn generate_and_print_mnemonic_from_phrase(
+        &self,
+        phrase: &str,
+        unencrypted: bool,
+    ) -> Result<(), WalletError> {
         let mnemonic_manager = MnemonicManager::new(self.config.clone());
 
-        println!("BIP39 Mnemonic (given phrase): {}", phrase);
+        self.config
+            .output
+            .line(format!("BIP39 Mnemonic (given phrase): {}", phrase));
 
         // Convertit la chaîne de caractères fournie en une structure mnémonique valide.
         // Cette étape vérifie que la phrase correspond aux critères du standard BIP39 et qu'elle peut être utilisée pour générer une seed.
-        let mnemonic = BipMnemonic::get_mnemonic_from_phrase(phrase);
+        // La langue de la phrase est détectée à partir de ses mots.
+        let mnemonic = BipMnemonic::get_mnemonic_from_phrase(phrase)?;
+        self.print_language(&mnemonic);
 
-        mnemonic_manager.process_mnemonic(&mnemonic);
+        mnemonic_manager.process_mnemonic(&mnemonic, unencrypted)
     }
 
-    pub fn get_balance_by_pubkey(&self, pubkey: &str) -> Result<u64, Box<dyn std::error::Error>> {
-        let solana_balance = SolanaBalance::new(self.config.clone());
+    /// Affiche la langue et le nombre de mots d'une phrase mnémonique.
+    fn print_language(&self, mnemonic: &Mnemonic) {
+        self.config.output.line(format!(
+            "Mnemonic language: {} ({} words)",
+            BipMnemonic::language_name(mnemonic.language()),
+            mnemonic.phrase().split_whitespace().count()
+        ));
+    }
+
+    pub fn get_balance_by_pubkey(&self, pubkey: &str) -> Result<u64, WalletError> {
+        let solana_balance = SolanaBalance::new(Rc::clone(&self.rpc));
         solana_balance.get_balance_by_pubkey(pubkey)
     }
 }
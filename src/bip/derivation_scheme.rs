@@ -0,0 +1,178 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Placeholder remplacé par l'index du compte dans un chemin de dérivation personnalisé.
+pub const ACCOUNT_PLACEHOLDER: &str = "{account}";
+
+/// Schéma de dérivation utilisé pour obtenir les clés d'un compte à partir de la seed.
+///
+/// SLIP44: 501 = Solana Coin (SOL Symbol).
+///
+/// Le chemin de dérivation utilisé dans BIP44 suit cette structure :
+/// m / purpose' / coin_type' / account' / change'
+/// # Résumé :
+/// - m : Est la "master key", la racine de toutes les dérivations.
+/// - purpose : Est une constante fixée à 44' (pour indiquer qu'on suit la norme BIP44).
+/// - coin_type : Est une constante (integer), définie pour chaque crypto-monnaie.
+/// - account : Account index (incrémenté pour chaque nouveau compte).
+/// - change (0 ou 1) : 0' pour sous-niveau hardened fixe (structure HD Trezor).
+///
+/// Note :
+/// Solana utilise Ed25519 (et non secp256k1 comme BTC/ETH).
+/// Ed25519 (SLIP-0010) exige que tous les niveaux soient hardened (').
+/// Les niveaux non-hardened (change/address_index classiques de BIP44) ne sont pas supportés avec SLIP-0010,
+/// le schéma "deprecated" n'est donc dérivable qu'avec le moteur "legacy".
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum DerivationScheme {
+    /// bip44Change grouping : Style Trezor (avec sous-niveau) - "m/44'/501'/{account}'/0'".
+    #[default]
+    Bip44Change,
+    /// bip44 grouping : Style Ledger (simple) - "m/44'/501'/{account}'".
+    Bip44,
+    /// Deprecated derivation paths (Non-hardened) - "m/44'/501'/0'/0/{account}".
+    Deprecated,
+    /// Aucune dérivation : la clé privée correspond aux 32 premiers octets de la seed.
+    Root,
+    /// Chemin personnalisé (ex : "m/44'/501'/{account}'/0'/0'").
+    Custom(String),
+}
+
+impl DerivationScheme {
+    /// Retourne les schémas standards (utile pour parcourir tous les chemins possibles d'une phrase).
+    pub fn standard_schemes() -> Vec<DerivationScheme> {
+        vec![
+            DerivationScheme::Bip44Change,
+            DerivationScheme::Bip44,
+            DerivationScheme::Deprecated,
+            DerivationScheme::Root,
+        ]
+    }
+
+    /// Retourne le nom court du schéma (tel qu'accepté par l'option "--scheme").
+    pub fn name(&self) -> &str {
+        match self {
+            DerivationScheme::Bip44Change => "bip44change",
+            DerivationScheme::Bip44 => "bip44",
+            DerivationScheme::Deprecated => "deprecated",
+            DerivationScheme::Root => "root",
+            DerivationScheme::Custom(_) => "custom",
+        }
+    }
+
+    /// Retourne le modèle de chemin du schéma (avec le placeholder "{account}"), ou None pour la seed racine.
+    pub fn template(&self) -> Option<String> {
+        match self {
+            DerivationScheme::Bip44Change => Some("m/44'/501'/{account}'/0'".to_string()),
+            DerivationScheme::Bip44 => Some("m/44'/501'/{account}'".to_string()),
+            DerivationScheme::Deprecated => Some("m/44'/501'/0'/0/{account}".to_string()),
+            DerivationScheme::Root => None,
+            DerivationScheme::Custom(template) => Some(template.clone()),
+        }
+    }
+
+    /// Construit le chemin de dérivation d'un compte.
+    ///
+    /// # Arguments:
+    /// - account - L'index du compte à substituer au placeholder "{account}".
+    ///
+    /// # Returns:
+    /// Retourne le chemin complet, ou None si le schéma n'utilise pas de dérivation (seed racine).
+    pub fn path(&self, account: usize) -> Option<String> {
+        self.template()
+            .map(|template| template.replace(ACCOUNT_PLACEHOLDER, &account.to_string()))
+    }
+
+    /// Indique si le schéma ne produit qu'un seul compte (seed racine ou chemin sans placeholder).
+    pub fn is_single_account(&self) -> bool {
+        match self.template() {
+            Some(template) => !template.contains(ACCOUNT_PLACEHOLDER),
+            None => true,
+        }
+    }
+}
+
+impl FromStr for DerivationScheme {
+    type Err = String;
+
+    /// Accepte le nom d'un schéma standard, ou un chemin personnalisé commençant par "m".
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        match value.to_lowercase().as_str() {
+            "bip44change" | "trezor" => Ok(DerivationScheme::Bip44Change),
+            "bip44" | "ledger" => Ok(DerivationScheme::Bip44),
+            "deprecated" => Ok(DerivationScheme::Deprecated),
+            "root" => Ok(DerivationScheme::Root),
+            _ if value == "m" || value.starts_with("m/") => {
+                // Valide le chemin en remplaçant le placeholder par un index quelconque.
+                let sample = value.replace(ACCOUNT_PLACEHOLDER, "0");
+                let is_valid = sample.split('/').skip(1).all(|segment| {
+                    segment
+                        .trim_end_matches(['\'', 'h', 'H'])
+                        .parse::<u32>()
+                        .is_ok()
+                });
+                if is_valid {
+                    Ok(DerivationScheme::Custom(value.to_string()))
+                } else {
+                    Err(format!("Invalid derivation path '{}'", value))
+                }
+            }
+            _ => Err(format!(
+                "Unknown derivation scheme '{}' (expected bip44change, bip44, deprecated, root or a path like \"m/44'/501'/{{account}}'/0'\")",
+                value
+            )),
+        }
+    }
+}
+
+impl fmt::Display for DerivationScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.template() {
+            Some(template) => write!(f, "{} ({})", self.name(), template),
+            None => write!(f, "{} (no derivation)", self.name()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_scheme_paths() {
+        assert_eq!(
+            DerivationScheme::Bip44Change.path(3).unwrap(),
+            "m/44'/501'/3'/0'"
+        );
+        assert_eq!(DerivationScheme::Bip44.path(3).unwrap(), "m/44'/501'/3'");
+        assert_eq!(
+            DerivationScheme::Deprecated.path(3).unwrap(),
+            "m/44'/501'/0'/0/3"
+        );
+        assert_eq!(DerivationScheme::Root.path(3), None);
+    }
+
+    #[test]
+    fn test_parse_schemes() {
+        assert_eq!(
+            "ledger".parse::<DerivationScheme>().unwrap(),
+            DerivationScheme::Bip44
+        );
+        assert_eq!(
+            "ROOT".parse::<DerivationScheme>().unwrap(),
+            DerivationScheme::Root
+        );
+
+        let custom = "m/44'/501'/{account}'/0'/0'"
+            .parse::<DerivationScheme>()
+            .unwrap();
+        assert_eq!(custom.path(7).unwrap(), "m/44'/501'/7'/0'/0'");
+        assert!(!custom.is_single_account());
+
+        let fixed = "m/44'/501'/5'".parse::<DerivationScheme>().unwrap();
+        assert!(fixed.is_single_account());
+
+        assert!("m/44'/abc'".parse::<DerivationScheme>().is_err());
+        assert!("phantom".parse::<DerivationScheme>().is_err());
+    }
+}
@@ -1,12 +1,38 @@
+use crate::bip::mnemonic_validator::MnemonicValidator;
+use crate::error::WalletError;
 use bip39::{Language, Mnemonic, MnemonicType};
+use unicode_normalization::UnicodeNormalization;
+
+/// Langues des listes de mots BIP39, avec leur nom pour l'option "--language".
+/// L'ordre est celui de la détection : l'anglais d'abord, puis les autres langues.
+pub const LANGUAGES: [(&str, Language); 8] = [
+    ("english", Language::English),
+    ("chinese-simplified", Language::ChineseSimplified),
+    ("chinese-traditional", Language::ChineseTraditional),
+    ("french", Language::French),
+    ("italian", Language::Italian),
+    ("japanese", Language::Japanese),
+    ("korean", Language::Korean),
+    ("spanish", Language::Spanish),
+];
 
 pub struct BipMnemonic {}
 
 impl BipMnemonic {
-    /// Génère une phrase mnémonique (12 mots aléatoires) selon le standard BIP39.
+    /// Génère une phrase mnémonique aléatoire selon le standard BIP39.
     /// Cette phrase est utilisée pour sécuriser et restaurer des portefeuilles cryptographiques.
-    pub fn generate_mnemonic() -> Mnemonic {
-        Mnemonic::new(MnemonicType::Words12, Language::English)
+    ///
+    /// # Arguments:
+    /// - words - Le nombre de mots (12, 15, 18, 21 ou 24) : plus il y en a, plus l'entropie est grande.
+    /// - language - La langue de la liste de mots.
+    pub fn generate_mnemonic(words: usize, language: Language) -> Result<Mnemonic, WalletError> {
+        let mnemonic_type = MnemonicType::for_word_count(words).map_err(|_| {
+            WalletError::InvalidInput(format!(
+                "Invalid number of words: {} (expected 12, 15, 18, 21 or 24)",
+                words
+            ))
+        })?;
+        Ok(Mnemonic::new(mnemonic_type, language))
     }
 
     /// Récupère et retourne la phrase mnémonique sous forme de chaîne de caractères.
@@ -16,10 +42,99 @@ impl BipMnemonic {
     }
 
     /// Crée une phrase mnémonique à partir d'une phrase spécifique donnée (12/24/Etc. mots).
-    /// La phrase doit correspondre aux critères du standard BIP39 et être en anglais.
-    /// Retourne un objet Mnemonic si la phrase est valide selon BIP39, sinon une erreur est déclenchée.
-    pub fn get_mnemonic_from_phrase(phrase: &str) -> Mnemonic {
-        Mnemonic::from_phrase(phrase, Language::English)
-            .expect("Failed to get mnemonic from phrase")
+    /// La phrase doit correspondre aux critères du standard BIP39. Sa langue est détectée :
+    /// c'est la première langue dont la liste contient tous les mots, et dont le checksum est valide.
+    /// Retourne un objet Mnemonic si la phrase est valide selon BIP39, sinon une erreur
+    /// qui liste les mots inconnus et les corrections possibles.
+    pub fn get_mnemonic_from_phrase(phrase: &str) -> Result<Mnemonic, WalletError> {
+        let report = MnemonicValidator::validate(phrase);
+        if !report.is_valid() {
+            return Err(WalletError::InvalidMnemonic(format!(
+                "Invalid mnemonic phrase: {}",
+                report.problems().join("; ")
+            )));
+        }
+        Mnemonic::from_phrase(&report.words.join(" "), report.language)
+            .map_err(|e| WalletError::InvalidMnemonic(format!("Invalid mnemonic phrase: {}", e)))
+    }
+
+    /// Retourne la langue correspondant à un nom de l'option "--language" (ex : "french").
+    pub fn parse_language(name: &str) -> Result<Language, WalletError> {
+        LANGUAGES
+            .iter()
+            .find(|(language_name, _)| language_name.eq_ignore_ascii_case(name))
+            .map(|(_, language)| *language)
+            .ok_or_else(|| {
+                let names: Vec<&str> = LANGUAGES.iter().map(|(name, _)| *name).collect();
+                WalletError::InvalidInput(format!(
+                    "Unknown language '{}': expected {}",
+                    name,
+                    names.join(", ")
+                ))
+            })
+    }
+
+    /// Retourne le nom d'une langue (celui de l'option "--language").
+    pub fn language_name(language: Language) -> &'static str {
+        LANGUAGES
+            .iter()
+            .find(|(_, candidate)| *candidate == language)
+            .map(|(name, _)| *name)
+            .unwrap_or("english")
+    }
+
+    /// Découpe une phrase en mots, en minuscules et normalisés (NFKD) comme les listes de mots BIP39.
+    pub fn normalize_words(phrase: &str) -> Vec<String> {
+        phrase
+            .split_whitespace()
+            .map(|word| word.to_lowercase().nfkd().collect())
+            .collect()
+    }
+
+    /// Indique si un mot (normalisé) appartient à la liste de mots d'une langue.
+    pub fn is_word(word: &str, language: Language) -> bool {
+        language.wordmap().get_bits(word).is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_mnemonic() {
+        for words in [12, 15, 18, 21, 24] {
+            let mnemonic = BipMnemonic::generate_mnemonic(words, Language::Japanese).unwrap();
+            assert_eq!(mnemonic.phrase().split_whitespace().count(), words);
+            assert_eq!(mnemonic.language(), Language::Japanese);
+        }
+        assert!(BipMnemonic::generate_mnemonic(13, Language::English).is_err());
+    }
+
+    #[test]
+    fn test_detect_language() {
+        for (name, language) in LANGUAGES {
+            let mnemonic = BipMnemonic::generate_mnemonic(24, language).unwrap();
+            let recovered = BipMnemonic::get_mnemonic_from_phrase(mnemonic.phrase()).unwrap();
+            assert_eq!(recovered.language(), language, "{}", name);
+            assert_eq!(recovered.entropy(), mnemonic.entropy());
+            assert_eq!(BipMnemonic::parse_language(name).unwrap(), language);
+            assert_eq!(BipMnemonic::language_name(language), name);
+        }
+
+        // Majuscules et accents composés (NFC) sont acceptés.
+        let phrase = "Abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        assert_eq!(
+            BipMnemonic::get_mnemonic_from_phrase(phrase)
+                .unwrap()
+                .language(),
+            Language::English
+        );
+        let french = BipMnemonic::generate_mnemonic(12, Language::French).unwrap();
+        let composed: String = french.phrase().nfc().collect();
+        assert!(BipMnemonic::get_mnemonic_from_phrase(&composed).is_ok());
+
+        assert!(BipMnemonic::get_mnemonic_from_phrase("not a mnemonic").is_err());
+        assert!(BipMnemonic::parse_language("klingon").is_err());
     }
 }
@@ -0,0 +1,370 @@
+use crate::bip::mnemonic::{BipMnemonic, LANGUAGES};
+use bip39::{Language, Mnemonic, MnemonicType};
+use std::cmp::Reverse;
+
+/// Nombre maximum de mots proposés pour un mot inconnu.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Distance d'édition maximale entre un mot inconnu et un mot proposé.
+const MAX_DISTANCE: usize = 2;
+
+/// Longueur du préfixe qui identifie un mot (les 4 premières lettres suffisent dans la liste anglaise).
+const PREFIX_LENGTH: usize = 4;
+
+/// Nombre maximum de corrections du checksum affichées (les plus proches des mots saisis).
+const MAX_CHECKSUM_FIXES: usize = 5;
+
+/// Un mot de la phrase absent de la liste de mots de la langue détectée.
+pub struct UnknownWord {
+    /// La position du mot dans la phrase (à partir de 1).
+    pub position: usize,
+    pub word: String,
+    /// Les mots de la liste les plus proches, du plus probable au moins probable.
+    pub suggestions: Vec<String>,
+}
+
+/// Le remplacement d'un mot de la phrase qui donne un checksum valide.
+pub struct WordFix {
+    /// La position du mot remplacé (à partir de 1).
+    pub position: usize,
+    pub word: String,
+    pub replacement: String,
+}
+
+/// Le résultat de la vérification d'une phrase mnémonique.
+pub struct MnemonicReport {
+    /// La langue détectée (celle dont la liste contient le plus de mots de la phrase).
+    pub language: Language,
+    /// Les mots de la phrase, en minuscules et normalisés.
+    pub words: Vec<String>,
+    pub unknown_words: Vec<UnknownWord>,
+    pub checksum_valid: bool,
+    /// Les remplacements d'un seul mot qui donnent un checksum valide, du plus proche au plus éloigné du mot saisi.
+    /// Calculés uniquement si la phrase est invalide et qu'au plus un mot est inconnu.
+    pub checksum_fixes: Vec<WordFix>,
+}
+
+impl MnemonicReport {
+    /// Indique si la phrase est une phrase mnémonique BIP39 valide.
+    pub fn is_valid(&self) -> bool {
+        self.checksum_valid
+    }
+
+    /// Indique si le nombre de mots est accepté par BIP39 (12, 15, 18, 21 ou 24).
+    pub fn word_count_valid(&self) -> bool {
+        MnemonicType::for_word_count(self.words.len()).is_ok()
+    }
+
+    /// Décrit les problèmes de la phrase, un par élément (vide si la phrase est valide).
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        let language = BipMnemonic::language_name(self.language);
+
+        if !self.word_count_valid() {
+            problems.push(format!(
+                "expected 12, 15, 18, 21 or 24 words, got {}",
+                self.words.len()
+            ));
+        }
+
+        for unknown in &self.unknown_words {
+            let suggestions = if unknown.suggestions.is_empty() {
+                "no close match".to_string()
+            } else {
+                format!("did you mean: {}?", unknown.suggestions.join(", "))
+            };
+            problems.push(format!(
+                "word {} \"{}\" is not in the {} wordlist ({})",
+                unknown.position, unknown.word, language, suggestions
+            ));
+        }
+
+        match self.unknown_words.as_slice() {
+            [] if self.word_count_valid() && !self.checksum_valid => {
+                let fixes = self.describe_checksum_fixes(|fix| {
+                    format!(
+                        "word {} \"{}\" -> \"{}\"",
+                        fix.position, fix.word, fix.replacement
+                    )
+                });
+                problems.push(match fixes {
+                    Some(fixes) => format!(
+                        "invalid checksum (single-word corrections with a valid checksum: {})",
+                        fixes
+                    ),
+                    None => "invalid checksum (no single-word correction gives a valid checksum)"
+                        .to_string(),
+                });
+            }
+            [unknown] => {
+                if let Some(fixes) = self.describe_checksum_fixes(|fix| fix.replacement.clone()) {
+                    problems.push(format!(
+                        "replacements of word {} with a valid checksum: {}",
+                        unknown.position, fixes
+                    ));
+                }
+            }
+            _ => {}
+        }
+        problems
+    }
+
+    /// Décrit les corrections du checksum les plus proches, suivies de leur nombre total s'il y en a d'autres.
+    fn describe_checksum_fixes(&self, describe: impl Fn(&WordFix) -> String) -> Option<String> {
+        if self.checksum_fixes.is_empty() {
+            return None;
+        }
+        let fixes: Vec<String> = self
+            .checksum_fixes
+            .iter()
+            .take(MAX_CHECKSUM_FIXES)
+            .map(describe)
+            .collect();
+        let more = if self.checksum_fixes.len() > fixes.len() {
+            format!(", ... ({} in total)", self.checksum_fixes.len())
+        } else {
+            String::new()
+        };
+        Some(format!("{}{}", fixes.join(", "), more))
+    }
+}
+
+pub struct MnemonicValidator {}
+
+impl MnemonicValidator {
+    /// Vérifie une phrase mnémonique et, si elle est invalide, cherche comment la corriger :
+    /// les mots inconnus sont rapprochés des mots de la liste (distance d'édition, préfixe de 4 lettres),
+    /// et les remplacements d'un seul mot qui donnent un checksum valide sont listés.
+    pub fn validate(phrase: &str) -> MnemonicReport {
+        let words = BipMnemonic::normalize_words(phrase);
+        let joined = words.join(" ");
+
+        // Une langue qui contient tous les mots avec un checksum valide, sinon celle qui en contient le plus
+        // (la première dans l'ordre de détection en cas d'égalité).
+        let languages = LANGUAGES.map(|(_, language)| language);
+        let known = |language: Language| {
+            words
+                .iter()
+                .filter(|word| BipMnemonic::is_word(word, language))
+                .count()
+        };
+        if let Some(language) = languages
+            .iter()
+            .copied()
+            .find(|language| Mnemonic::from_phrase(&joined, *language).is_ok())
+        {
+            return MnemonicReport {
+                language,
+                words,
+                unknown_words: Vec::new(),
+                checksum_valid: true,
+                checksum_fixes: Vec::new(),
+            };
+        }
+        let language = languages
+            .iter()
+            .copied()
+            .min_by_key(|language| Reverse(known(*language)))
+            .unwrap_or(Language::English);
+
+        let wordlist = Self::wordlist(language);
+        let unknown_words: Vec<UnknownWord> = words
+            .iter()
+            .enumerate()
+            .filter(|(_, word)| !BipMnemonic::is_word(word, language))
+            .map(|(index, word)| UnknownWord {
+                position: index + 1,
+                word: word.clone(),
+                suggestions: Self::suggestions(word, &wordlist),
+            })
+            .collect();
+
+        // Avec plusieurs mots inconnus, une correction d'un seul mot ne peut pas suffire.
+        let positions: Vec<usize> = match unknown_words.as_slice() {
+            [] => (0..words.len()).collect(),
+            [unknown] => vec![unknown.position - 1],
+            _ => Vec::new(),
+        };
+        let checksum_fixes = if MnemonicType::for_word_count(words.len()).is_ok() {
+            Self::checksum_fixes(&words, &positions, language, &wordlist)
+        } else {
+            Vec::new()
+        };
+
+        MnemonicReport {
+            language,
+            words,
+            unknown_words,
+            checksum_valid: false,
+            checksum_fixes,
+        }
+    }
+
+    /// Retourne les 2048 mots de la liste d'une langue, dans l'ordre de leurs indices BIP39.
+    fn wordlist(language: Language) -> Vec<&'static str> {
+        let wordlist = language.wordlist();
+        (0..2048u16)
+            .map(|bits| wordlist.get_word(bits.into()))
+            .collect()
+    }
+
+    /// Propose les mots de la liste les plus proches d'un mot inconnu : d'abord le seul mot qui commence
+    /// par les mêmes 4 lettres, puis les mots à la plus petite distance d'édition.
+    fn suggestions(word: &str, wordlist: &[&str]) -> Vec<String> {
+        let mut suggestions = Vec::new();
+
+        let prefix: String = word.chars().take(PREFIX_LENGTH).collect();
+        if prefix.chars().count() == PREFIX_LENGTH {
+            let matches: Vec<&&str> = wordlist
+                .iter()
+                .filter(|candidate| candidate.chars().take(PREFIX_LENGTH).eq(prefix.chars()))
+                .collect();
+            if let [unique] = matches.as_slice() {
+                suggestions.push(unique.to_string());
+            }
+        }
+
+        // Une distance égale à la longueur du mot (ex : un seul idéogramme) ne rapproche de rien.
+        let length = word.chars().count();
+        let mut close: Vec<(usize, &str)> = wordlist
+            .iter()
+            .map(|candidate| (Self::edit_distance(word, candidate), *candidate))
+            .filter(|(distance, _)| *distance <= MAX_DISTANCE && *distance < length)
+            .collect();
+        close.sort();
+        for (_, candidate) in close {
+            if suggestions.len() == MAX_SUGGESTIONS {
+                break;
+            }
+            if !suggestions.iter().any(|suggestion| suggestion == candidate) {
+                suggestions.push(candidate.to_string());
+            }
+        }
+        suggestions
+    }
+
+    /// Essaie chaque mot de la liste aux positions données (à partir de 0), et retourne les remplacements
+    /// qui donnent un checksum valide, triés par distance d'édition avec le mot saisi.
+    fn checksum_fixes(
+        words: &[String],
+        positions: &[usize],
+        language: Language,
+        wordlist: &[&'static str],
+    ) -> Vec<WordFix> {
+        let mut fixes: Vec<(usize, WordFix)> = Vec::new();
+        for &index in positions {
+            let mut candidate = words.to_vec();
+            for replacement in wordlist {
+                if *replacement == words[index] {
+                    continue;
+                }
+                candidate[index] = replacement.to_string();
+                if Mnemonic::from_phrase(&candidate.join(" "), language).is_ok() {
+                    fixes.push((
+                        Self::edit_distance(&words[index], replacement),
+                        WordFix {
+                            position: index + 1,
+                            word: words[index].clone(),
+                            replacement: replacement.to_string(),
+                        },
+                    ));
+                }
+            }
+        }
+        // Le tri est stable : à distance égale, l'ordre des positions puis de la liste est conservé.
+        fixes.sort_by_key(|(distance, _)| *distance);
+        fixes.into_iter().map(|(_, fix)| fix).collect()
+    }
+
+    /// Calcule la distance de Levenshtein (insertions, suppressions, substitutions) entre deux mots.
+    fn edit_distance(a: &str, b: &str) -> usize {
+        let b: Vec<char> = b.chars().collect();
+        let mut previous: Vec<usize> = (0..=b.len()).collect();
+        for (i, ca) in a.chars().enumerate() {
+            let mut current = vec![i + 1; b.len() + 1];
+            for (j, cb) in b.iter().enumerate() {
+                let substitution = previous[j] + usize::from(ca != *cb);
+                current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+            }
+            previous = current;
+        }
+        previous[b.len()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHRASE: &str =
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(MnemonicValidator::edit_distance("abandon", "abandon"), 0);
+        assert_eq!(MnemonicValidator::edit_distance("abandn", "abandon"), 1);
+        assert_eq!(MnemonicValidator::edit_distance("kitten", "sitting"), 3);
+        assert_eq!(MnemonicValidator::edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_validate_unknown_words() {
+        assert!(MnemonicValidator::validate(PHRASE).is_valid());
+
+        // Faute de frappe et mot tronqué : suggestions par distance d'édition et par préfixe.
+        let report = MnemonicValidator::validate(&PHRASE.replacen("abandon", "abandn", 1));
+        assert!(!report.is_valid());
+        assert_eq!(report.language, Language::English);
+        assert_eq!(report.unknown_words.len(), 1);
+        assert_eq!(report.unknown_words[0].position, 1);
+        assert_eq!(report.unknown_words[0].suggestions[0], "abandon");
+        assert!(report
+            .checksum_fixes
+            .iter()
+            .any(|fix| fix.position == 1 && fix.replacement == "abandon"));
+
+        let report = MnemonicValidator::validate(&PHRASE.replace("about", "abou"));
+        assert_eq!(report.unknown_words[0].suggestions[0], "about");
+        let report = MnemonicValidator::validate(&PHRASE.replace("about", "scorpio"));
+        assert_eq!(report.unknown_words[0].suggestions[0], "scorpion");
+
+        let problems = report.problems();
+        assert!(problems[0].contains(
+            "word 12 \"scorpio\" is not in the english wordlist (did you mean: scorpion"
+        ));
+    }
+
+    #[test]
+    fn test_validate_checksum() {
+        // "above" est un mot de la liste, mais le checksum n'est plus valide.
+        let report = MnemonicValidator::validate(&PHRASE.replace("about", "above"));
+        assert!(!report.is_valid());
+        assert!(report.unknown_words.is_empty());
+        assert!(report
+            .checksum_fixes
+            .iter()
+            .any(|fix| fix.position == 12 && fix.replacement == "about"));
+        let distances: Vec<usize> = report
+            .checksum_fixes
+            .iter()
+            .map(|fix| MnemonicValidator::edit_distance(&fix.word, &fix.replacement))
+            .collect();
+        assert!(distances.windows(2).all(|pair| pair[0] <= pair[1]));
+        for fix in &report.checksum_fixes {
+            let mut words = report.words.clone();
+            words[fix.position - 1] = fix.replacement.clone();
+            assert!(Mnemonic::from_phrase(&words.join(" "), Language::English).is_ok());
+        }
+        assert!(report.problems()[0].starts_with(
+            "invalid checksum (single-word corrections with a valid checksum: word 12 \"above\" ->"
+        ));
+
+        // Nombre de mots invalide : pas de correction du checksum.
+        let report = MnemonicValidator::validate("abandon abandon about");
+        assert!(report.checksum_fixes.is_empty());
+        assert_eq!(
+            report.problems(),
+            vec!["expected 12, 15, 18, 21 or 24 words, got 3"]
+        );
+    }
+}
@@ -1,3 +1,6 @@
+pub mod derivation_scheme;
 pub mod mnemonic;
+pub mod mnemonic_validator;
 pub mod passphrase;
 pub mod seed;
+pub mod slip10;
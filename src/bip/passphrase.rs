@@ -1,3 +1,5 @@
+use crate::config::output::Output;
+use crate::error::WalletError;
 use std::io;
 
 pub struct BipPassphrase {}
@@ -5,15 +7,16 @@ pub struct BipPassphrase {}
 impl BipPassphrase {
     /// Demande à l'utilisateur d'entrer une passphrase optionnelle pour renforcer la sécurité de la seed.
     /// Laisser vide si aucune passphrase n'est souhaitée.
-    pub fn prompt_for_passphrase() -> String {
-        println!("Enter passphrase (optional):");
+    /// La question est affichée selon le format de sortie (sur la sortie d'erreur en JSON).
+    pub fn prompt_for_passphrase(output: &Output) -> Result<String, WalletError> {
+        output.line("Enter passphrase (optional):");
 
         let mut passphrase = String::new();
         io::stdin()
             .read_line(&mut passphrase)
-            .expect("Failed to read passphrase");
+            .map_err(|e| format!("Failed to read passphrase: {}", e))?;
 
         // Nettoyer et retourner la passphrase saisie, en éliminant les espaces avant et après.
-        passphrase.trim().to_string()
+        Ok(passphrase.trim().to_string())
     }
 }
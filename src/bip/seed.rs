@@ -1,7 +1,44 @@
-use bip32::{DerivationPath, Error as Bip32Error, XPrv};
+use crate::bip::derivation_scheme::DerivationScheme;
+use crate::bip::slip10::Slip10;
+use crate::error::WalletError;
+use bip32::{DerivationPath, XPrv};
 use bip39::{Mnemonic, Seed};
+use std::fmt;
 use std::str::FromStr;
 
+/// Moteur de dérivation utilisé pour obtenir les clés privées à partir de la seed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DerivationEngine {
+    /// SLIP-0010 Ed25519 : compatible avec Phantom, Solflare et "solana-keygen".
+    Slip10,
+    /// Ancienne dérivation BIP32 secp256k1 (conservée pour accéder aux wallets déjà créés avec ce moteur).
+    LegacyBip32,
+}
+
+impl FromStr for DerivationEngine {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_lowercase().as_str() {
+            "slip10" => Ok(DerivationEngine::Slip10),
+            "legacy" | "bip32" => Ok(DerivationEngine::LegacyBip32),
+            _ => Err(format!(
+                "Unknown derivation engine '{}' (expected 'slip10' or 'legacy')",
+                value
+            )),
+        }
+    }
+}
+
+impl fmt::Display for DerivationEngine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DerivationEngine::Slip10 => write!(f, "slip10"),
+            DerivationEngine::LegacyBip32 => write!(f, "legacy"),
+        }
+    }
+}
+
 pub struct BipSeed {}
 
 impl BipSeed {
@@ -28,38 +65,53 @@ impl BipSeed {
         seed.as_bytes()
     }
 
-    /// Dérive la seed (seed_bytes param) pour générer différentes clés privées en fonction de l'index spécifié (index param).
-    /// Utilise le chemin de dérivation BIP44 spécifique à Solana.
+    /// Dérive la seed (seed_bytes param) pour générer différentes clés privées en fonction de l'index de compte spécifié (account param).
+    /// Le chemin de dérivation est déterminé par le schéma choisi (BIP44 spécifique à Solana par défaut).
     ///
     /// # Arguments:
     /// - seed_bytes - Les octets de la seed à partir de laquelle les clés seront dérivées.
-    /// - index - L'index de dérivation utilisé pour générer différentes clés privées.
+    /// - scheme - Le schéma de dérivation (Trezor, Ledger, deprecated, seed racine ou chemin personnalisé).
+    /// - account - L'index du compte utilisé pour générer différentes clés privées.
+    /// - engine - Le moteur de dérivation (SLIP-0010 par défaut, ou l'ancien moteur BIP32).
     ///
     /// # Returns:
     /// Retourne un vecteur contenant les octets de la clé privée dérivée. Ou retourne une erreur en cas de problème lors de la dérivation.
-    pub fn derive_seed_bytes(seed_bytes: &[u8], index: usize) -> Result<Vec<u8>, Bip32Error> {
-        // SLIP44: 501 = Solana Coin (SOL Symbol).
-        // Format: "m/44'/501'/{index}'/0'" (Style Trezor)
-        //
-        // Le chemin de dérivation utilisé dans BIP44 suit cette structure :
-        // m / purpose' / coin_type' / account' / change'
-        // # Résumé :
-        // - m : Est la "master key", la racine de toutes les dérivations.
-        // - purpose : Est une constante fixée à 44' (pour indiquer qu'on suit la norme BIP44).
-        // - coin_type : Est une constante (integer), définie pour chaque crypto-monnaie.
-        // - account : Account index (incrémenté pour chaque nouveau compte).
-        // - change (0 ou 1) : 0' pour sous-niveau hardened fixe (structure HD Trezor).
-        //
-        // Note :
-        // Solana utilise Ed25519 (et non secp256k1 comme BTC/ETH).
-        // Ed25519 (SLIP-0010) exige que tous les niveaux soient hardened (').
-        // Les niveaux non-hardened (change/address_index classiques de BIP44) ne sont pas supportés avec Solana,
-        // d'où l'utilisation de /0' au lieu de /0/0.
+    pub fn derive_seed_bytes(
+        seed_bytes: &[u8],
+        scheme: &DerivationScheme,
+        account: usize,
+        engine: DerivationEngine,
+    ) -> Result<Vec<u8>, WalletError> {
+        // Sans chemin (seed racine), la clé privée correspond directement aux 32 premiers octets de la seed.
+        let path = match scheme.path(account) {
+            Some(path) => path,
+            None => return Ok(seed_bytes[..32].to_vec()),
+        };
 
-        //let path = format!("m/44'/501'/0'/0/{}", index); // Deprecated derivation paths (Non-hardened invalide pour Solana).
-        //let path = format!("m/44'/501'/{}'", index); // bip44 grouping : Style Ledger (simple).
-        let path = format!("m/44'/501'/{}'/0'", index); // bip44Change grouping : Style Trezor (avec sous-niveau).
-        let derivation_path = DerivationPath::from_str(&path)?;
+        match engine {
+            DerivationEngine::Slip10 => Ok(Slip10::derive_path(seed_bytes, &path)
+                .map_err(|e| e.to_string())?
+                .private_key
+                .to_vec()),
+            DerivationEngine::LegacyBip32 => {
+                Ok(Self::derive_legacy_bip32(seed_bytes, &path).map_err(|e| e.to_string())?)
+            }
+        }
+    }
+
+    /// Dérive une clé privée avec l'ancien moteur BIP32 (courbe secp256k1).
+    ///
+    /// Attention : ce moteur ne correspond pas à ce que produisent Phantom, Solflare ou "solana-keygen".
+    /// Il n'est conservé que pour retrouver les clés des wallets créés avant l'adoption de SLIP-0010.
+    ///
+    /// # Arguments:
+    /// - seed_bytes - Les octets de la seed à partir de laquelle les clés seront dérivées.
+    /// - path - Le chemin de dérivation complet.
+    ///
+    /// # Returns:
+    /// Retourne les octets de la clé privée dérivée, ou une erreur BIP32.
+    fn derive_legacy_bip32(seed_bytes: &[u8], path: &str) -> Result<Vec<u8>, bip32::Error> {
+        let derivation_path = DerivationPath::from_str(path)?;
 
         // Créer une clé privée étendue à partir des octets de la seed.
         let mut master_xprv = XPrv::new(seed_bytes)?;
@@ -0,0 +1,283 @@
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+use std::fmt;
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// Clé HMAC utilisée par SLIP-0010 pour générer la clé maître de la courbe Ed25519.
+const ED25519_CURVE_SEED: &[u8] = b"ed25519 seed";
+
+/// Bit de poids fort indiquant un index "hardened" (').
+pub const HARDENED_OFFSET: u32 = 0x8000_0000;
+
+/// Erreurs pouvant survenir lors d'une dérivation SLIP-0010.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Slip10Error {
+    /// Le chemin de dérivation n'a pas pu être interprété.
+    InvalidPath(String),
+    /// Ed25519 (SLIP-0010) n'autorise que les dérivations hardened.
+    NonHardenedIndex(u32),
+}
+
+impl fmt::Display for Slip10Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Slip10Error::InvalidPath(path) => write!(f, "Invalid derivation path: {}", path),
+            Slip10Error::NonHardenedIndex(index) => write!(
+                f,
+                "Non-hardened index {} is not supported by SLIP-0010 ed25519 derivation",
+                index
+            ),
+        }
+    }
+}
+
+impl std::error::Error for Slip10Error {}
+
+/// Clé étendue SLIP-0010 : une clé privée Ed25519 (32 octets) et son chain code (32 octets).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Slip10ExtendedKey {
+    pub private_key: [u8; 32],
+    pub chain_code: [u8; 32],
+}
+
+pub struct Slip10 {}
+
+impl Slip10 {
+    /// Génère la clé maître Ed25519 à partir des octets de la seed (HMAC-SHA512 avec la clé "ed25519 seed").
+    ///
+    /// # Arguments:
+    /// - seed_bytes - Les octets de la seed BIP39.
+    ///
+    /// # Returns:
+    /// Retourne la clé étendue maître (racine "m" de toutes les dérivations).
+    pub fn master_key(seed_bytes: &[u8]) -> Slip10ExtendedKey {
+        Self::hmac_split(ED25519_CURVE_SEED, &[seed_bytes])
+    }
+
+    /// Dérive une clé enfant (hardened uniquement) à partir d'une clé parente.
+    ///
+    /// # Arguments:
+    /// - parent - La clé étendue parente.
+    /// - index - L'index de l'enfant, qui doit avoir le bit hardened (HARDENED_OFFSET) positionné.
+    ///
+    /// # Returns:
+    /// Retourne la clé étendue enfant, ou une erreur si l'index n'est pas hardened.
+    pub fn derive_child(
+        parent: &Slip10ExtendedKey,
+        index: u32,
+    ) -> Result<Slip10ExtendedKey, Slip10Error> {
+        if index < HARDENED_OFFSET {
+            return Err(Slip10Error::NonHardenedIndex(index));
+        }
+
+        // Data = 0x00 || clé privée parente (32 octets) || index (big-endian, 4 octets).
+        Ok(Self::hmac_split(
+            &parent.chain_code,
+            &[&[0u8], &parent.private_key, &index.to_be_bytes()],
+        ))
+    }
+
+    /// Dérive une clé en suivant un chemin complet (ex : "m/44'/501'/0'/0'").
+    ///
+    /// # Arguments:
+    /// - seed_bytes - Les octets de la seed BIP39.
+    /// - path - Le chemin de dérivation, dont tous les niveaux doivent être hardened.
+    ///
+    /// # Returns:
+    /// Retourne la clé étendue correspondant au dernier niveau du chemin.
+    pub fn derive_path(seed_bytes: &[u8], path: &str) -> Result<Slip10ExtendedKey, Slip10Error> {
+        Self::parse_path(path)?
+            .into_iter()
+            .try_fold(Self::master_key(seed_bytes), |key, index| {
+                Self::derive_child(&key, index)
+            })
+    }
+
+    /// Interprète un chemin de dérivation textuel en une liste d'index (le bit hardened étant inclus).
+    ///
+    /// # Arguments:
+    /// - path - Le chemin de dérivation, qui doit commencer par "m".
+    ///
+    /// # Returns:
+    /// Retourne la liste des index de chaque niveau.
+    pub fn parse_path(path: &str) -> Result<Vec<u32>, Slip10Error> {
+        let invalid = || Slip10Error::InvalidPath(path.to_string());
+
+        let mut segments = path.trim().split('/');
+        if segments.next() != Some("m") {
+            return Err(invalid());
+        }
+
+        segments
+            .map(|segment| {
+                let (number, hardened) = match segment.strip_suffix(['\'', 'h', 'H']) {
+                    Some(number) => (number, true),
+                    None => (segment, false),
+                };
+                let index = number.parse::<u32>().map_err(|_| invalid())?;
+                if index >= HARDENED_OFFSET {
+                    return Err(invalid());
+                }
+                Ok(if hardened {
+                    index | HARDENED_OFFSET
+                } else {
+                    index
+                })
+            })
+            .collect()
+    }
+
+    /// Calcule HMAC-SHA512(key, data) et sépare le résultat en (clé privée, chain code).
+    fn hmac_split(key: &[u8], data: &[&[u8]]) -> Slip10ExtendedKey {
+        let mut mac = HmacSha512::new_from_slice(key).expect("HMAC accepts keys of any size");
+        for chunk in data {
+            mac.update(chunk);
+        }
+        let result = mac.finalize().into_bytes();
+
+        let mut private_key = [0u8; 32];
+        let mut chain_code = [0u8; 32];
+        private_key.copy_from_slice(&result[..32]);
+        chain_code.copy_from_slice(&result[32..]);
+
+        Slip10ExtendedKey {
+            private_key,
+            chain_code,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bip39::{Language, Mnemonic, Seed};
+    use solana_sdk::derivation_path::DerivationPath;
+    use solana_sdk::signature::{keypair_from_seed, keypair_from_seed_and_derivation_path};
+    use solana_sdk::signer::Signer;
+
+    fn hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    fn unhex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn assert_vector(seed: &str, path: &str, chain_code: &str, private_key: &str) {
+        let key = Slip10::derive_path(&unhex(seed), path).unwrap();
+        assert_eq!(hex(&key.chain_code), chain_code, "chain code for {}", path);
+        assert_eq!(
+            hex(&key.private_key),
+            private_key,
+            "private key for {}",
+            path
+        );
+    }
+
+    #[test]
+    fn test_slip10_vector_1() {
+        // https://github.com/satoshilabs/slips/blob/master/slip-0010.md (Test vector 1 for ed25519).
+        let seed = "000102030405060708090a0b0c0d0e0f";
+
+        assert_vector(
+            seed,
+            "m",
+            "90046a93de5380a72b5e45010748567d5ea02bbf6522f979e05c0d8d8ca9fffb",
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7",
+        );
+        assert_vector(
+            seed,
+            "m/0'",
+            "8b59aa11380b624e81507a27fedda59fea6d0b779a778918a2fd3590e16e9c69",
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3",
+        );
+        assert_vector(
+            seed,
+            "m/0'/1'",
+            "a320425f77d1b5c2505a6b1b27382b37368ee640e3557c315416801243552f14",
+            "b1d0bad404bf35da785a64ca1ac54b2617211d2777696fbffaf208f746ae84f2",
+        );
+        assert_vector(
+            seed,
+            "m/0'/1'/2'/2'/1000000000'",
+            "68789923a0cac2cd5a29172a475fe9e0fb14cd6adb5ad98a3fa70333e7afa230",
+            "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793",
+        );
+    }
+
+    #[test]
+    fn test_slip10_vector_2() {
+        // https://github.com/satoshilabs/slips/blob/master/slip-0010.md (Test vector 2 for ed25519).
+        let seed = "fffcf9f6f3f0edeae7e4e1dedbd8d5d2cfccc9c6c3c0bdbab7b4b1aeaba8a5a29f9c999693908d8a8784817e7b7875726f6c696663605d5a5754514e4b484542";
+
+        assert_vector(
+            seed,
+            "m",
+            "ef70a74db9c3a5af931b5fe73ed8e1a53464133654fd55e7a66f8570b8e33c3b",
+            "171cb88b1b3c1db25add599712e36245d75bc65a1a5c9e18d76f9f2b1eab4012",
+        );
+        assert_vector(
+            seed,
+            "m/0'",
+            "0b78a3226f915c082bf118f83618a618ab6dec793752624cbeb622acb562862d",
+            "1559eb2bbec5790b0c65d8693e4d0875b1747f4970ae8b650486ed7470845635",
+        );
+    }
+
+    #[test]
+    fn test_non_hardened_index_is_rejected() {
+        let seed = unhex("000102030405060708090a0b0c0d0e0f");
+        assert_eq!(
+            Slip10::derive_path(&seed, "m/44'/501'/0'/0"),
+            Err(Slip10Error::NonHardenedIndex(0))
+        );
+    }
+
+    #[test]
+    fn test_invalid_paths_are_rejected() {
+        assert!(Slip10::parse_path("44'/501'").is_err());
+        assert!(Slip10::parse_path("m/abc'").is_err());
+        assert!(Slip10::parse_path("m/2147483648'").is_err());
+        assert_eq!(
+            Slip10::parse_path("m/44'/501h/0H").unwrap(),
+            vec![44 | HARDENED_OFFSET, 501 | HARDENED_OFFSET, HARDENED_OFFSET]
+        );
+    }
+
+    #[test]
+    fn test_matches_phantom_and_solana_keygen() {
+        // Adresse affichée par Phantom / Solflare / "solana-keygen recover 'prompt://?key=0/0'" pour cette phrase.
+        let mnemonic = Mnemonic::from_phrase(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            Language::English,
+        )
+        .unwrap();
+        let seed = Seed::new(&mnemonic, "");
+
+        let key = Slip10::derive_path(seed.as_bytes(), "m/44'/501'/0'/0'").unwrap();
+        let keypair = keypair_from_seed(&key.private_key).unwrap();
+        assert_eq!(
+            keypair.pubkey().to_string(),
+            "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk"
+        );
+
+        // Vérifie aussi la cohérence avec l'implémentation de "solana-sdk" (utilisée par solana-keygen).
+        for account in 0..3 {
+            let path = format!("m/44'/501'/{}'/0'", account);
+            let key = Slip10::derive_path(seed.as_bytes(), &path).unwrap();
+            let expected = keypair_from_seed_and_derivation_path(
+                seed.as_bytes(),
+                Some(DerivationPath::new_bip44(Some(account), Some(0))),
+            )
+            .unwrap();
+            assert_eq!(
+                keypair_from_seed(&key.private_key).unwrap().pubkey(),
+                expected.pubkey()
+            );
+        }
+    }
+}
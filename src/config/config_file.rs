@@ -0,0 +1,383 @@
+use crate::config::wallet_config::{ConfigSource, WalletConfig};
+use crate::error::WalletError;
+use crate::solana::amount::SolAmount;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Fichier de configuration local au projet (dans le dossier courant), prioritaire sur celui de l'utilisateur.
+pub const PROJECT_CONFIG_FILE: &str = "wallet.toml";
+
+/// Variable d'environnement donnant le fichier de configuration à utiliser à la place des fichiers par défaut.
+pub const CONFIG_FILE_ENV: &str = "WALLET_CONFIG";
+
+/// Profil utilisé si aucun n'est choisi (ni option, ni variable d'environnement, ni clé "profile" d'un fichier).
+pub const DEFAULT_PROFILE: &str = "devnet";
+
+/// Profils intégrés, avec l'URL RPC de leur réseau. Un fichier de configuration peut les compléter ou en définir d'autres.
+const BUILTIN_PROFILES: [(&str, &str); 4] = [
+    ("devnet", "https://api.devnet.solana.com"),
+    ("testnet", "https://api.testnet.solana.com"),
+    ("mainnet", "https://api.mainnet-beta.solana.com"),
+    ("localnet", "http://localhost:8899"),
+];
+
+/// Retourne le profil intégré d'un moniker de réseau, comme la CLI Solana ("m" ou "mainnet-beta", "d" ou "devnet",
+/// "t" ou "testnet", "l" ou "localhost"), ou de l'URL RPC d'un profil intégré. None pour toute autre URL.
+pub fn moniker_profile(input: &str) -> Option<&'static str> {
+    let profile = match input {
+        "m" | "mainnet-beta" | "mainnet" => "mainnet",
+        "d" | "devnet" => "devnet",
+        "t" | "testnet" => "testnet",
+        "l" | "localhost" | "localnet" => "localnet",
+        _ => {
+            return BUILTIN_PROFILES
+                .iter()
+                .find(|(_, rpc_url)| *rpc_url == input.trim_end_matches('/'))
+                .map(|(name, _)| *name)
+        }
+    };
+    Some(profile)
+}
+
+/// Retourne l'URL RPC d'un moniker de réseau (voir `moniker_profile`). Toute autre valeur est considérée
+/// comme une URL et retournée telle quelle.
+pub fn moniker_url(input: &str) -> String {
+    moniker_profile(input)
+        .and_then(|profile| BUILTIN_PROFILES.iter().find(|(name, _)| *name == profile))
+        .map(|(_, rpc_url)| rpc_url.to_string())
+        .unwrap_or_else(|| input.to_string())
+}
+
+/// Paramètres d'un profil (section "[profiles.<nom>]" d'un fichier de configuration). Tous sont optionnels.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileConfig {
+    pub rpc_url: Option<String>,
+    /// URL WebSocket du noeud (par défaut, déduite de l'URL RPC).
+    pub websocket_url: Option<String>,
+    /// Niveau de confirmation des requêtes : processed, confirmed ou finalized.
+    pub commitment: Option<String>,
+    /// Dossier des paires de clés du profil : la paire de clés principale "id.json", et les paires de clés dérivées "derived/".
+    pub keystore: Option<String>,
+    /// Compte du wallet utilisé lorsque "--account" n'est pas donné (label ou index).
+    pub default_account: Option<String>,
+    /// Montant maximal (en SOL) envoyé par une transaction.
+    pub spending_limit_per_transaction: Option<String>,
+    /// Montant maximal (en SOL) envoyé par jour (UTC).
+    pub spending_limit_per_day: Option<String>,
+}
+
+/// Contenu d'un fichier de configuration TOML.
+///
+/// ```toml
+/// profile = "devnet"
+///
+/// [profiles.devnet]
+/// commitment = "confirmed"
+/// keystore = "./storage/devnet"
+/// ```
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    /// Profil actif.
+    pub profile: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+}
+
+impl ConfigFile {
+    /// Lit et valide un fichier de configuration.
+    pub fn read(file_path: &str) -> Result<Self, WalletError> {
+        let content = fs::read_to_string(file_path).map_err(|e| {
+            WalletError::File(format!("Failed to read config file {}: {}", file_path, e))
+        })?;
+        let config: ConfigFile = toml::from_str(&content)
+            .map_err(|e| WalletError::File(format!("Invalid config file {}: {}", file_path, e)))?;
+
+        for (name, profile) in &config.profiles {
+            let invalid = |e: String| {
+                WalletError::File(format!(
+                    "Invalid config file {} (profile {}): {}",
+                    file_path, name, e
+                ))
+            };
+            if let Some(commitment) = &profile.commitment {
+                WalletConfig::parse_commitment(commitment).map_err(invalid)?;
+            }
+            for limit in [
+                &profile.spending_limit_per_transaction,
+                &profile.spending_limit_per_day,
+            ]
+            .into_iter()
+            .flatten()
+            {
+                SolAmount::parse_sol(limit)
+                    .map_err(|e| invalid(format!("Invalid spending limit: {}", e)))?;
+            }
+        }
+        Ok(config)
+    }
+}
+
+/// Fichiers de configuration chargés, du plus prioritaire au moins prioritaire.
+#[derive(Clone, Debug, Default)]
+pub struct ConfigFiles {
+    files: Vec<(String, ConfigFile)>,
+}
+
+impl ConfigFiles {
+    /// Charge les fichiers de configuration : le fichier donné (qui doit exister), sinon ceux des emplacements par défaut
+    /// qui existent (voir `default_paths`).
+    pub fn load(file_path: Option<&str>) -> Result<Self, WalletError> {
+        let paths = match file_path {
+            Some(file_path) => vec![PathBuf::from(file_path)],
+            None => Self::default_paths()
+                .into_iter()
+                .filter(|path| path.exists())
+                .collect(),
+        };
+
+        let files = paths
+            .iter()
+            .map(|path| {
+                let path = path.to_string_lossy().to_string();
+                ConfigFile::read(&path).map(|file| (path, file))
+            })
+            .collect::<Result<Vec<_>, WalletError>>()?;
+        Ok(Self { files })
+    }
+
+    /// Crée la liste à partir de fichiers déjà lus, du plus prioritaire au moins prioritaire.
+    #[cfg(test)]
+    pub fn from_files(files: Vec<(String, ConfigFile)>) -> Self {
+        Self { files }
+    }
+
+    /// Retourne les emplacements par défaut, du plus prioritaire au moins prioritaire :
+    /// "./wallet.toml", puis "config.toml" dans le dossier de configuration de l'utilisateur
+    /// (ex : "~/.config/rust-solana-wallet/config.toml" sous Linux).
+    pub fn default_paths() -> Vec<PathBuf> {
+        let mut paths = vec![PathBuf::from(".").join(PROJECT_CONFIG_FILE)];
+        if let Some(config_dir) = dirs::config_dir() {
+            paths.push(config_dir.join("rust-solana-wallet").join("config.toml"));
+        }
+        paths
+    }
+
+    /// Retourne les chemins des fichiers chargés.
+    pub fn paths(&self) -> Vec<String> {
+        self.files.iter().map(|(path, _)| path.clone()).collect()
+    }
+
+    /// Retourne le profil actif défini par le fichier le plus prioritaire (clé "profile"), avec ce fichier.
+    pub fn active_profile(&self) -> Option<(String, ConfigSource)> {
+        self.files.iter().find_map(|(path, file)| {
+            file.profile
+                .clone()
+                .map(|profile| (profile, ConfigSource::File(path.clone())))
+        })
+    }
+
+    /// Retourne les noms des profils disponibles : les profils intégrés, puis ceux définis par les fichiers.
+    pub fn profile_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = BUILTIN_PROFILES
+            .iter()
+            .map(|(name, _)| name.to_string())
+            .collect();
+        for (_, file) in &self.files {
+            for name in file.profiles.keys() {
+                if !names.contains(name) {
+                    names.push(name.clone());
+                }
+            }
+        }
+        names
+    }
+
+    /// Retourne la valeur d'un paramètre d'un profil, avec son origine : celle du fichier le plus prioritaire
+    /// qui la définit, sinon celle du profil intégré.
+    ///
+    /// # Arguments:
+    /// - profile - Le nom du profil.
+    /// - field - Le paramètre, lu dans la section du profil.
+    pub fn profile_value(
+        &self,
+        profile: &str,
+        field: fn(&ProfileConfig) -> Option<&String>,
+    ) -> Option<(String, ConfigSource)> {
+        let from_files = self.files.iter().find_map(|(path, file)| {
+            file.profiles.get(profile).and_then(field).map(|value| {
+                (
+                    value.clone(),
+                    ConfigSource::Profile {
+                        name: profile.to_string(),
+                        file: Some(path.clone()),
+                    },
+                )
+            })
+        });
+
+        from_files.or_else(|| {
+            let builtin = Self::builtin_profile(profile)?;
+            field(&builtin).map(|value| {
+                (
+                    value.clone(),
+                    ConfigSource::Profile {
+                        name: profile.to_string(),
+                        file: None,
+                    },
+                )
+            })
+        })
+    }
+
+    fn builtin_profile(name: &str) -> Option<ProfileConfig> {
+        BUILTIN_PROFILES
+            .iter()
+            .find(|(builtin, _)| *builtin == name)
+            .map(|(_, rpc_url)| ProfileConfig {
+                rpc_url: Some(rpc_url.to_string()),
+                ..ProfileConfig::default()
+            })
+    }
+}
+
+/// Retourne le chemin d'un fichier dans le dossier "keystore" d'un profil.
+pub fn keystore_path(keystore: &str, file_name: &str) -> String {
+    Path::new(keystore)
+        .join(file_name)
+        .to_string_lossy()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROJECT_FILE: &str = r#"
+profile = "mainnet"
+
+[profiles.mainnet]
+rpc_url = "https://mainnet.example.com"
+keystore = "./storage/mainnet"
+"#;
+
+    const USER_FILE: &str = r#"
+profile = "devnet"
+
+[profiles.mainnet]
+rpc_url = "https://ignored.example.com"
+commitment = "finalized"
+default_account = "cold"
+
+[profiles.staging]
+rpc_url = "https://staging.example.com"
+"#;
+
+    fn rpc_url(profile: &ProfileConfig) -> Option<&String> {
+        profile.rpc_url.as_ref()
+    }
+
+    fn commitment(profile: &ProfileConfig) -> Option<&String> {
+        profile.commitment.as_ref()
+    }
+
+    fn files() -> ConfigFiles {
+        ConfigFiles::from_files(vec![
+            (
+                "./wallet.toml".to_string(),
+                toml::from_str(PROJECT_FILE).unwrap(),
+            ),
+            (
+                "~/.config/rust-solana-wallet/config.toml".to_string(),
+                toml::from_str(USER_FILE).unwrap(),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_project_file_overrides_user_file() {
+        let files = files();
+        assert_eq!(
+            files.active_profile(),
+            Some((
+                "mainnet".to_string(),
+                ConfigSource::File("./wallet.toml".to_string())
+            ))
+        );
+
+        assert_eq!(
+            files.profile_value("mainnet", rpc_url),
+            Some((
+                "https://mainnet.example.com".to_string(),
+                ConfigSource::Profile {
+                    name: "mainnet".to_string(),
+                    file: Some("./wallet.toml".to_string()),
+                }
+            ))
+        );
+
+        // Les paramètres absents du fichier du projet viennent du fichier de l'utilisateur.
+        assert_eq!(
+            files.profile_value("mainnet", commitment).unwrap().0,
+            "finalized"
+        );
+    }
+
+    #[test]
+    fn test_builtin_profiles() {
+        let files = files();
+        assert_eq!(
+            files.profile_value("localnet", rpc_url),
+            Some((
+                "http://localhost:8899".to_string(),
+                ConfigSource::Profile {
+                    name: "localnet".to_string(),
+                    file: None,
+                }
+            ))
+        );
+        assert_eq!(files.profile_value("unknown", rpc_url), None);
+        assert_eq!(
+            files.profile_names(),
+            vec!["devnet", "testnet", "mainnet", "localnet", "staging"]
+        );
+    }
+
+    #[test]
+    fn test_moniker_url() {
+        assert_eq!(moniker_url("m"), "https://api.mainnet-beta.solana.com");
+        assert_eq!(moniker_url("devnet"), "https://api.devnet.solana.com");
+        assert_eq!(moniker_url("t"), "https://api.testnet.solana.com");
+        assert_eq!(moniker_url("l"), "http://localhost:8899");
+        assert_eq!(
+            moniker_url("https://rpc.example.com"),
+            "https://rpc.example.com"
+        );
+
+        assert_eq!(moniker_profile("m"), Some("mainnet"));
+        assert_eq!(
+            moniker_profile("https://api.mainnet-beta.solana.com/"),
+            Some("mainnet")
+        );
+        assert_eq!(moniker_profile("d"), Some("devnet"));
+        assert_eq!(moniker_profile("https://rpc.example.com"), None);
+    }
+
+    #[test]
+    fn test_invalid_files() {
+        assert!(toml::from_str::<ConfigFile>("[profiles.devnet]\nrpc = \"typo\"").is_err());
+
+        let file_path = "./storage/tests/config_temp_test_invalid.toml";
+        fs::write(file_path, "[profiles.devnet]\ncommitment = \"fast\"\n").unwrap();
+        let error = ConfigFiles::load(Some(file_path)).unwrap_err();
+        fs::remove_file(file_path).ok();
+        assert_eq!(error.exit_code(), 11);
+        assert!(error.message().contains("profile devnet"));
+
+        // Un fichier donné explicitement doit exister.
+        assert!(ConfigFiles::load(Some("./storage/tests/missing.toml")).is_err());
+    }
+}
@@ -1 +1,5 @@
+pub mod config_file;
+pub mod output;
+pub mod spending_ledger;
 pub mod wallet_config;
+pub mod wallet_registry;
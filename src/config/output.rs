@@ -0,0 +1,158 @@
+use crate::error::WalletError;
+use serde_json::{json, Value};
+use std::fmt;
+use std::io;
+
+/// Format de sortie des commandes, choisi avec l'option globale "--output".
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Texte lisible (par défaut).
+    #[default]
+    Text,
+    /// Un seul document JSON sur la sortie standard (résultat ou erreur), pour les scripts.
+    /// Le texte lisible (résumés, avertissements, questions) passe alors sur la sortie d'erreur.
+    Json,
+}
+
+impl OutputFormat {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        match input.to_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(format!(
+                "Invalid output format '{}': expected text or json",
+                input
+            )),
+        }
+    }
+
+    /// Affiche une ligne de texte : sur la sortie standard en mode texte, sur la sortie d'erreur en mode JSON.
+    pub fn line(&self, text: impl fmt::Display) {
+        match self {
+            OutputFormat::Text => println!("{}", text),
+            OutputFormat::Json => eprintln!("{}", text),
+        }
+    }
+
+    /// Affiche le document JSON résultat d'une commande (uniquement en mode JSON).
+    pub fn json(&self, value: Value) {
+        if *self == OutputFormat::Json {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&value).unwrap_or_default()
+            );
+        }
+    }
+
+    /// Affiche une erreur : son message sur la sortie d'erreur en mode texte,
+    /// un document JSON `{"error": {...}}` sur la sortie standard en mode JSON.
+    pub fn error(&self, error: &WalletError) {
+        match self {
+            OutputFormat::Text => eprintln!("{}", error),
+            OutputFormat::Json => self.json(Self::error_document(error)),
+        }
+    }
+
+    fn error_document(error: &WalletError) -> Value {
+        json!({
+            "error": {
+                "kind": error.kind(),
+                "code": error.exit_code(),
+                "message": error.message(),
+            }
+        })
+    }
+
+    /// Demande une confirmation (y/N) à l'utilisateur. Toute autre réponse que "y" / "yes" annule.
+    pub fn confirm(&self, prompt: &str) -> bool {
+        matches!(self.prompt(prompt).to_lowercase().as_str(), "y" | "yes")
+    }
+
+    /// Pose une question à l'utilisateur et retourne sa réponse, sans les espaces autour
+    /// (une réponse vide si l'entrée standard est fermée ou illisible).
+    pub fn prompt(&self, prompt: &str) -> String {
+        self.line(prompt);
+
+        let mut answer = String::new();
+        if io::stdin().read_line(&mut answer).is_err() {
+            return String::new();
+        }
+        answer.trim().to_string()
+    }
+}
+
+/// Sortie d'une commande : son format, et le réseau du noeud RPC pour les commandes qui l'interrogent.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Output {
+    pub format: OutputFormat,
+    /// Réseau détecté (ex : "devnet"), ajouté au document JSON sous la clé "cluster".
+    pub cluster: Option<String>,
+}
+
+impl Output {
+    pub fn new(format: OutputFormat) -> Self {
+        Self {
+            format,
+            cluster: None,
+        }
+    }
+
+    /// Voir `OutputFormat::line`.
+    pub fn line(&self, text: impl fmt::Display) {
+        self.format.line(text)
+    }
+
+    /// Affiche le document JSON résultat d'une commande (uniquement en mode JSON), avec le réseau détecté.
+    pub fn json(&self, mut value: Value) {
+        if let (Some(cluster), Value::Object(document)) = (&self.cluster, &mut value) {
+            document.insert("cluster".to_string(), Value::String(cluster.clone()));
+        }
+        self.format.json(value)
+    }
+
+    /// Voir `OutputFormat::error` (le document JSON contient aussi le réseau détecté).
+    pub fn error(&self, error: &WalletError) {
+        match self.format {
+            OutputFormat::Text => self.format.error(error),
+            OutputFormat::Json => self.json(OutputFormat::error_document(error)),
+        }
+    }
+
+    /// Voir `OutputFormat::confirm`.
+    pub fn confirm(&self, prompt: &str) -> bool {
+        self.format.confirm(prompt)
+    }
+
+    /// Voir `OutputFormat::prompt`.
+    pub fn prompt(&self, prompt: &str) -> String {
+        self.format.prompt(prompt)
+    }
+}
+
+impl fmt::Display for Output {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.format.fmt(f)
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OutputFormat::Text => write!(f, "text"),
+            OutputFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(OutputFormat::parse("json"), Ok(OutputFormat::Json));
+        assert_eq!(OutputFormat::parse("TEXT"), Ok(OutputFormat::Text));
+        assert!(OutputFormat::parse("yaml").is_err());
+        assert_eq!(OutputFormat::default(), OutputFormat::Text);
+    }
+}
@@ -0,0 +1,112 @@
+use crate::error::WalletError;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Version actuelle du format du fichier des dépenses.
+pub const LEDGER_VERSION: u32 = 1;
+
+/// Montant envoyé par une transaction, compté dans le plafond par jour de son profil.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SpendingEntry {
+    pub profile: String,
+    /// Jour de l'envoi (UTC), au format "YYYY-MM-DD".
+    pub date: String,
+    pub lamports: u64,
+    pub signature: String,
+}
+
+/// Montants envoyés aujourd'hui, stockés dans un fichier JSON (`SPENDING_LEDGER_PATH`).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SpendingLedger {
+    pub version: u32,
+    pub entries: Vec<SpendingEntry>,
+}
+
+impl Default for SpendingLedger {
+    fn default() -> Self {
+        Self {
+            version: LEDGER_VERSION,
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl SpendingLedger {
+    /// Charge les dépenses depuis un fichier. Retourne un fichier vide s'il n'existe pas encore.
+    pub fn load(file_path: &str) -> Result<Self, WalletError> {
+        if !Path::new(file_path).exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(file_path)?;
+        let ledger: SpendingLedger = serde_json::from_str(&content)?;
+        if ledger.version != LEDGER_VERSION {
+            return Err(format!("Unsupported spending ledger version: {}", ledger.version).into());
+        }
+        Ok(ledger)
+    }
+
+    /// Enregistre les dépenses dans un fichier (en créant les dossiers parents).
+    pub fn save(&self, file_path: &str) -> Result<(), WalletError> {
+        if let Some(parent) = Path::new(file_path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(file_path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Retourne le jour courant (UTC), au format "YYYY-MM-DD".
+    pub fn today() -> String {
+        Utc::now().format("%Y-%m-%d").to_string()
+    }
+
+    /// Retourne le montant (en lamports) envoyé par un profil un jour donné.
+    pub fn spent(&self, profile: &str, date: &str) -> u64 {
+        self.entries
+            .iter()
+            .filter(|entry| entry.profile == profile && entry.date == date)
+            .fold(0u64, |total, entry| total.saturating_add(entry.lamports))
+    }
+
+    /// Ajoute un envoi. Les envois des jours précédents, qui ne comptent plus, sont supprimés.
+    ///
+    /// # Arguments:
+    /// - profile - Le profil de configuration de l'envoi.
+    /// - date - Le jour de l'envoi (UTC).
+    /// - lamports - Le montant envoyé.
+    /// - signature - La signature de la transaction.
+    pub fn record(&mut self, profile: &str, date: &str, lamports: u64, signature: &str) {
+        self.entries.retain(|entry| entry.date == date);
+        self.entries.push(SpendingEntry {
+            profile: profile.to_string(),
+            date: date.to_string(),
+            lamports,
+            signature: signature.to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spent_and_record() {
+        let mut ledger = SpendingLedger::default();
+        ledger.record("mainnet", "2026-01-01", 5, "old");
+        ledger.record("mainnet", "2026-01-02", 10, "a");
+        ledger.record("mainnet", "2026-01-02", 20, "b");
+        ledger.record("devnet", "2026-01-02", 40, "c");
+
+        // Seuls les envois du jour sont conservés, et chaque profil a son propre total.
+        assert_eq!(ledger.entries.len(), 3);
+        assert_eq!(ledger.spent("mainnet", "2026-01-02"), 30);
+        assert_eq!(ledger.spent("devnet", "2026-01-02"), 40);
+        assert_eq!(ledger.spent("mainnet", "2026-01-01"), 0);
+
+        // La date du jour est au format "YYYY-MM-DD".
+        assert_eq!(SpendingLedger::today().len(), 10);
+    }
+}
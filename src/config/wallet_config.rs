@@ -1,55 +1,481 @@
+use crate::bip::derivation_scheme::DerivationScheme;
+use crate::bip::seed::DerivationEngine;
+use crate::config::config_file::{
+    keystore_path, ConfigFiles, ProfileConfig, CONFIG_FILE_ENV, DEFAULT_PROFILE,
+};
+use crate::config::output::{Output, OutputFormat};
+use crate::error::WalletError;
+use crate::solana::amount::SolAmount;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::collections::BTreeMap;
 use std::env;
+use std::fmt;
 
 #[cfg(test)]
 use serial_test::serial;
 
+/// Origine de la valeur d'un paramètre de la configuration (voir "config show").
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConfigSource {
+    /// Option de la ligne de commande (ex : "--profile").
+    Flag(&'static str),
+    /// Variable d'environnement.
+    Env(&'static str),
+    /// Profil d'un fichier de configuration, ou profil intégré (file: None).
+    Profile { name: String, file: Option<String> },
+    /// Clé d'un fichier de configuration en dehors des profils (ex : le profil actif).
+    File(String),
+    /// Valeur par défaut.
+    Default,
+}
+
+impl fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigSource::Flag(flag) => write!(f, "flag {}", flag),
+            ConfigSource::Env(var) => write!(f, "env {}", var),
+            ConfigSource::Profile {
+                name,
+                file: Some(file),
+            } => write!(f, "profile {} ({})", name, file),
+            ConfigSource::Profile { name, file: None } => {
+                write!(f, "profile {} (built-in)", name)
+            }
+            ConfigSource::File(file) => write!(f, "file {}", file),
+            ConfigSource::Default => write!(f, "default"),
+        }
+    }
+}
+
+/// Valeurs données par les options globales de la ligne de commande, prioritaires sur tout le reste.
+#[derive(Clone, Debug, Default)]
+pub struct ConfigFlags {
+    pub profile: Option<String>,
+    /// URL RPC ("--url", monikers déjà convertis en URL).
+    pub url: Option<String>,
+    /// Profil intégré du réseau de "--url" (ex : "mainnet" pour "-um") : il est choisi à la place du profil
+    /// des variables d'environnement et des fichiers, pour que ses plafonds de dépense s'appliquent.
+    pub url_profile: Option<String>,
+    pub keypair: Option<String>,
+    pub commitment: Option<String>,
+    /// Fichier de configuration ("--config"), à la place de `WALLET_CONFIG` et des fichiers par défaut.
+    pub config: Option<String>,
+    pub derivations: Option<usize>,
+    pub output: Option<OutputFormat>,
+    pub allow_mainnet: bool,
+}
+
 #[derive(Clone)] // Cette ligne est utile pour implémenter automatiquement Clone.
 pub struct WalletConfig {
+    pub profile: String,
     pub keypair_path: String,
     pub keypair_derivations_path: String,
     pub nb_derivations: usize,
     pub rpc_url: String,
+    pub websocket_url: String,
+    pub commitment: CommitmentConfig,
+    pub default_account: Option<String>,
+    /// Plafonds de dépense du profil (en lamports) : par transaction, et par jour (UTC).
+    pub spending_limit_per_transaction: Option<u64>,
+    pub spending_limit_per_day: Option<u64>,
+    pub spending_ledger_path: String,
+    pub derivation_engine: DerivationEngine,
+    pub derivation_scheme: DerivationScheme,
+    pub wallet_registry_path: String,
+    pub output: Output,
+    pub allow_mainnet: bool,
+    /// Fichiers de configuration chargés, du plus prioritaire au moins prioritaire.
+    pub config_files: Vec<String>,
+    /// Origine des paramètres qui ne viennent pas de leur valeur par défaut.
+    sources: BTreeMap<&'static str, ConfigSource>,
 }
 
-impl Default for WalletConfig {
-    fn default() -> Self {
-        Self::new()
-    }
-}
-
+/// Chaque paramètre prend la première valeur valide parmi : l'option de la ligne de commande, la variable
+/// d'environnement, le profil actif (fichiers de configuration, puis profil intégré), et la valeur par défaut.
+///
 /// # Assignments:
 ///
+/// - profile:
+///   Le profil actif : "--profile", le profil intégré du réseau de "--url" (ex : "mainnet" pour "-um"), la variable d'environnement
+///   `WALLET_PROFILE`, la clé "profile" d'un fichier de configuration, sinon "devnet". Les profils intégrés sont devnet, testnet, mainnet et localnet.
+///
 /// - nb_derivations:
-///   Récupère le nombre de dérivations à effectuer à partir de la variable d'environnement `NB_DERIVATIONS`.
+///   Récupère le nombre de dérivations à effectuer à partir de "--derivations", sinon de la variable d'environnement `NB_DERIVATIONS`.
 ///   Retourne 1 par défaut si la variable n'est pas définie ou si sa valeur n'est pas un entier valide.
 ///
 /// - keypair_path:
-///   Récupère le chemin d'accès au fichier où la paire de clés principale est stockée à partir de la variable d'environnement `KEYPAIR_PATH`.
-///   Retourne un chemin par défaut si la variable d'environnement n'est pas définie.
+///   Récupère le chemin d'accès au fichier où la paire de clés principale est stockée à partir de "--keypair", sinon de la variable d'environnement `KEYPAIR_PATH`,
+///   sinon "id.json" dans le dossier "keystore" du profil.
+///   Retourne un chemin par défaut si aucun n'est défini.
 ///
 /// - keypair_derivations_path:
-///   Récupère le chemin d'accès au dossier où les paires de clés dérivées sont stockées, spécifié par la variable d'environnement `KEYPAIR_DERIVATIONS_PATH`.
-///   Retourne un chemin par défaut si la variable d'environnement n'est pas définie.
+///   Récupère le chemin d'accès au dossier où les paires de clés dérivées sont stockées, spécifié par la variable d'environnement `KEYPAIR_DERIVATIONS_PATH`,
+///   sinon "derived" dans le dossier "keystore" du profil.
+///   Retourne un chemin par défaut si aucun n'est défini.
 ///
 /// - rpc_url:
-///   Récupère l'URL du serveur RPC pour l'accès au réseau Solana à partir de la variable d'environnement `RPC_URL`.
-///   Retourne "https://api.devnet.solana.com" par défaut, indiquant que le réseau Testnet est utilisé si la variable d'environnement n'est pas définie.
-
+///   Récupère l'URL du serveur RPC pour l'accès au réseau Solana à partir de "--url" (ou d'un moniker : "-um", "-ud", "-ut", "-ul"),
+///   sinon de la variable d'environnement `RPC_URL`, sinon celle du profil.
+///   Retourne "https://api.devnet.solana.com" (le profil devnet) par défaut.
+///
+/// - websocket_url:
+///   Récupère l'URL WebSocket du noeud à partir de la variable d'environnement `WEBSOCKET_URL`, sinon celle du profil.
+///   Par défaut, ou si l'URL RPC ne vient pas du profil, elle est déduite de l'URL RPC (comme la CLI Solana).
+///
+/// - commitment:
+///   Récupère le niveau de confirmation des requêtes (processed, confirmed ou finalized) à partir de "--commitment", sinon de la variable d'environnement `COMMITMENT`,
+///   sinon celui du profil. Retourne "finalized" par défaut. Une valeur invalide de `COMMITMENT` est une erreur.
+///
+/// - default_account:
+///   Récupère le compte du wallet utilisé lorsque "--account" n'est pas donné à partir de la variable d'environnement `WALLET_ACCOUNT`,
+///   sinon celui du profil. Par défaut, c'est le compte par défaut de l'index du wallet ("accounts default").
+///
+/// - spending_limit_per_transaction / spending_limit_per_day:
+///   Plafonds de dépense en SOL du profil (clés "spending_limit_per_transaction" et "spending_limit_per_day").
+///   Aucun plafond par défaut.
+///
+/// - spending_ledger_path:
+///   Récupère le chemin d'accès au fichier des montants envoyés aujourd'hui (pour le plafond par jour) à partir de la variable
+///   d'environnement `SPENDING_LEDGER_PATH`. Retourne un chemin par défaut si la variable d'environnement n'est pas définie.
+///
+/// - derivation_engine:
+///   Récupère le moteur de dérivation des clés à partir de la variable d'environnement `DERIVATION_ENGINE` ("slip10" ou "legacy").
+///   Retourne SLIP-0010 par défaut (compatible Phantom/Solflare/solana-keygen) si la variable n'est pas définie.
+///
+/// - derivation_scheme:
+///   Récupère le schéma de dérivation à partir de la variable d'environnement `DERIVATION_PATH`
+///   (bip44change, bip44, deprecated, root, ou un chemin personnalisé avec le placeholder "{account}").
+///   Retourne "bip44change" ("m/44'/501'/{account}'/0'") par défaut si la variable n'est pas définie.
+///   Une valeur invalide de ces deux variables est une erreur : les clés seraient dérivées avec un autre chemin que celui demandé.
+///
+/// - wallet_registry_path:
+///   Récupère le chemin d'accès au fichier d'index des comptes du wallet (labels, chemins de dérivation, etc.) à partir de la variable d'environnement `WALLET_REGISTRY_PATH`.
+///   Retourne un chemin par défaut si la variable d'environnement n'est pas définie.
+///
+/// - output:
+///   Format de sortie des commandes : texte par défaut, ou JSON avec l'option globale "--output json".
+///
+/// - allow_mainnet:
+///   Option globale "--allow-mainnet" : les commandes qui déplacent des fonds sur mainnet-beta ne demandent pas de taper "mainnet".
 impl WalletConfig {
-    /// Charge la configuration depuis les variables d'environnement ou utilise les valeurs par défaut.
-    pub fn new() -> Self {
-        Self {
-            nb_derivations: env::var("NB_DERIVATIONS")
-                .unwrap_or_else(|_| "0".to_string()) // Utilise "1" comme valeur par défaut si la variable n'est pas définie.
-                .parse::<usize>() // Tente de convertir la chaîne de caractères en un entier de type usize.
-                .unwrap_or(0), // Retourne 1 si la conversion échoue ou si la valeur convertie n'est pas un nombre.
-            keypair_path: env::var("KEYPAIR_PATH")
-                .unwrap_or_else(|_| "./storage/keypair/id.json".to_string()),
-            keypair_derivations_path: env::var("KEYPAIR_DERIVATIONS_PATH")
-                .unwrap_or_else(|_| "./storage/keypair/derived".to_string()),
-            rpc_url:
-                env::var("RPC_URL") // Lire l'URL RPC de l'environnement
-                    .unwrap_or_else(|_| "https://api.devnet.solana.com".to_string()), // URL par défaut pointant vers le Testnet de Solana.
+    /// Charge la configuration depuis les variables d'environnement ou utilise les valeurs par défaut
+    /// (sans fichier de configuration ni option de la ligne de commande). La commande utilise `load`.
+    ///
+    /// # Returns:
+    /// Retourne une erreur `Config` si une variable d'environnement est invalide (voir `check_env`).
+    #[cfg(test)]
+    pub fn new() -> Result<Self, WalletError> {
+        Self::resolve(&ConfigFlags::default(), &ConfigFiles::default())
+    }
+
+    /// Charge la configuration complète : options de la ligne de commande, variables d'environnement,
+    /// puis fichiers de configuration (celui de "--config" ou de `WALLET_CONFIG`, sinon "./wallet.toml" et celui de l'utilisateur).
+    ///
+    /// # Returns:
+    /// Retourne une erreur si un fichier de configuration est invalide, si le profil choisi n'existe pas,
+    /// si "--profile" et "--url" désignent deux profils intégrés différents,
+    /// ou si une variable d'environnement est invalide (voir `check_env`).
+    pub fn load(flags: &ConfigFlags) -> Result<Self, WalletError> {
+        if let (Some(profile), Some(url_profile)) = (&flags.profile, &flags.url_profile) {
+            if profile != url_profile {
+                return Err(WalletError::InvalidInput(format!(
+                    "--url selects the {} network, but --profile is {}: use \"--profile {}\", or drop --url to use the rpc_url of the profile",
+                    url_profile, profile, url_profile
+                )));
+            }
+        }
+
+        let file_path = flags
+            .config
+            .clone()
+            .or_else(|| env::var(CONFIG_FILE_ENV).ok());
+        let files = ConfigFiles::load(file_path.as_deref())?;
+        let config = Self::resolve(flags, &files)?;
+
+        let profiles = files.profile_names();
+        if !profiles.contains(&config.profile) {
+            return Err(WalletError::InvalidInput(format!(
+                "Unknown profile '{}' (available profiles: {})",
+                config.profile,
+                profiles.join(", ")
+            )));
+        }
+        Ok(config)
+    }
+
+    /// Vérifie les variables d'environnement dont une faute de frappe ne doit pas retomber silencieusement
+    /// sur une autre valeur : les clés dérivées (`DERIVATION_ENGINE`, `DERIVATION_PATH`) et le niveau de
+    /// confirmation attendu des transactions (`COMMITMENT`).
+    fn check_env() -> Result<(), WalletError> {
+        let check = |var: &str, parse: fn(&str) -> Result<(), String>| match env::var(var) {
+            Ok(value) => parse(&value)
+                .map_err(|e| WalletError::Config(format!("Invalid {} '{}': {}", var, value, e))),
+            Err(_) => Ok(()),
+        };
+        check("DERIVATION_ENGINE", |value| {
+            value.parse::<DerivationEngine>().map(|_| ())
+        })?;
+        check("DERIVATION_PATH", |value| {
+            value.parse::<DerivationScheme>().map(|_| ())
+        })?;
+        check("COMMITMENT", |value| {
+            Self::parse_commitment(value).map(|_| ())
+        })
+    }
+
+    /// Retourne l'origine de la valeur d'un paramètre (ex : "rpc_url").
+    pub fn source(&self, name: &str) -> ConfigSource {
+        self.sources
+            .get(name)
+            .cloned()
+            .unwrap_or(ConfigSource::Default)
+    }
+
+    /// Convertit un niveau de confirmation ("processed", "confirmed" ou "finalized").
+    pub fn parse_commitment(input: &str) -> Result<CommitmentConfig, String> {
+        match input.to_lowercase().as_str() {
+            "processed" => Ok(CommitmentConfig::processed()),
+            "confirmed" => Ok(CommitmentConfig::confirmed()),
+            "finalized" => Ok(CommitmentConfig::finalized()),
+            _ => Err(format!(
+                "Invalid commitment '{}': expected processed, confirmed or finalized",
+                input
+            )),
+        }
+    }
+
+    fn resolve(flags: &ConfigFlags, files: &ConfigFiles) -> Result<Self, WalletError> {
+        Self::check_env()?;
+
+        let mut layers = Layers {
+            files,
+            profile: String::new(),
+            sources: BTreeMap::new(),
+        };
+
+        let mut profile_layers = vec![
+            (flags.profile.clone(), ConfigSource::Flag("--profile")),
+            (flags.url_profile.clone(), ConfigSource::Flag("--url")),
+            Layers::env("WALLET_PROFILE"),
+        ];
+        if let Some((profile, source)) = files.active_profile() {
+            profile_layers.push((Some(profile), source));
+        }
+        layers.profile = layers
+            .pick("profile", profile_layers, |profile| {
+                Some(profile.to_string())
+            })
+            .unwrap_or_else(|| DEFAULT_PROFILE.to_string());
+
+        let keystore: fn(&ProfileConfig) -> Option<&String> = |profile| profile.keystore.as_ref();
+
+        let rpc_url = layers
+            .pick(
+                "rpc_url",
+                vec![
+                    (flags.url.clone(), ConfigSource::Flag("--url")),
+                    Layers::env("RPC_URL"),
+                    layers.profile_layer(|profile| profile.rpc_url.as_ref()),
+                ],
+                |url| Some(url.to_string()),
+            )
+            .unwrap_or_else(|| "https://api.devnet.solana.com".to_string());
+        // L'URL WebSocket d'un profil n'est utilisée qu'avec l'URL RPC de ce profil.
+        let websocket_layers = match layers.sources.get("rpc_url") {
+            Some(ConfigSource::Profile { .. }) | None => vec![
+                Layers::env("WEBSOCKET_URL"),
+                layers.profile_layer(|profile| profile.websocket_url.as_ref()),
+            ],
+            Some(_) => vec![Layers::env("WEBSOCKET_URL")],
+        };
+
+        Ok(Self {
+            nb_derivations: layers
+                .pick(
+                    "nb_derivations",
+                    vec![
+                        (
+                            flags.derivations.map(|derivations| derivations.to_string()),
+                            ConfigSource::Flag("--derivations"),
+                        ),
+                        Layers::env("NB_DERIVATIONS"),
+                    ],
+                    |value| value.parse::<usize>().ok(), // Tente de convertir la chaîne de caractères en un entier de type usize.
+                )
+                .unwrap_or(0), // Retourne 0 si la variable n'est pas définie ou si sa valeur n'est pas un nombre.
+            keypair_path: layers
+                .pick(
+                    "keypair_path",
+                    vec![
+                        (flags.keypair.clone(), ConfigSource::Flag("--keypair")),
+                        Layers::env("KEYPAIR_PATH"),
+                        layers.profile_layer(keystore),
+                    ],
+                    |path| Some(path.to_string()),
+                )
+                .map(|path| layers.keystore_file(path, "keypair_path", "id.json"))
+                .unwrap_or_else(|| "./storage/keypair/id.json".to_string()),
+            keypair_derivations_path: layers
+                .pick(
+                    "keypair_derivations_path",
+                    vec![
+                        Layers::env("KEYPAIR_DERIVATIONS_PATH"),
+                        layers.profile_layer(keystore),
+                    ],
+                    |path| Some(path.to_string()),
+                )
+                .map(|path| layers.keystore_file(path, "keypair_derivations_path", "derived"))
+                .unwrap_or_else(|| "./storage/keypair/derived".to_string()),
+            websocket_url: layers
+                .pick("websocket_url", websocket_layers, |url| {
+                    Some(url.to_string())
+                })
+                .unwrap_or_else(|| Self::websocket_url(&rpc_url)),
+            rpc_url,
+            commitment: layers
+                .pick(
+                    "commitment",
+                    vec![
+                        (flags.commitment.clone(), ConfigSource::Flag("--commitment")),
+                        Layers::env("COMMITMENT"),
+                        layers.profile_layer(|profile| profile.commitment.as_ref()),
+                    ],
+                    |commitment| Self::parse_commitment(commitment).ok(),
+                )
+                .unwrap_or_else(CommitmentConfig::finalized),
+            default_account: layers.pick(
+                "default_account",
+                vec![
+                    Layers::env("WALLET_ACCOUNT"),
+                    layers.profile_layer(|profile| profile.default_account.as_ref()),
+                ],
+                |account| Some(account.to_string()),
+            ),
+            spending_limit_per_transaction: layers.pick(
+                "spending_limit_per_transaction",
+                vec![
+                    layers.profile_layer(|profile| profile.spending_limit_per_transaction.as_ref())
+                ],
+                |limit| SolAmount::parse_sol(limit).ok(),
+            ),
+            spending_limit_per_day: layers.pick(
+                "spending_limit_per_day",
+                vec![layers.profile_layer(|profile| profile.spending_limit_per_day.as_ref())],
+                |limit| SolAmount::parse_sol(limit).ok(),
+            ),
+            spending_ledger_path: layers
+                .pick(
+                    "spending_ledger_path",
+                    vec![Layers::env("SPENDING_LEDGER_PATH")],
+                    |path| Some(path.to_string()),
+                )
+                .unwrap_or_else(|| "./storage/spending.json".to_string()),
+            derivation_engine: layers
+                .pick(
+                    "derivation_engine",
+                    vec![Layers::env("DERIVATION_ENGINE")],
+                    |engine| engine.parse::<DerivationEngine>().ok(),
+                )
+                .unwrap_or(DerivationEngine::Slip10),
+            derivation_scheme: layers
+                .pick(
+                    "derivation_scheme",
+                    vec![Layers::env("DERIVATION_PATH")],
+                    |scheme| scheme.parse::<DerivationScheme>().ok(),
+                )
+                .unwrap_or_default(),
+            wallet_registry_path: layers
+                .pick(
+                    "wallet_registry_path",
+                    vec![Layers::env("WALLET_REGISTRY_PATH")],
+                    |path| Some(path.to_string()),
+                )
+                .unwrap_or_else(|| "./storage/wallet.json".to_string()),
+            output: layers
+                .pick(
+                    "output",
+                    vec![(
+                        flags.output.map(|output| output.to_string()),
+                        ConfigSource::Flag("--output"),
+                    )],
+                    |output| OutputFormat::parse(output).ok(),
+                )
+                .map(Output::new)
+                .unwrap_or_default(),
+            allow_mainnet: flags.allow_mainnet,
+            profile: layers.profile,
+            config_files: files.paths(),
+            sources: layers.sources,
+        })
+    }
+
+    /// Déduit l'URL WebSocket d'une URL RPC, comme la CLI Solana : "http(s)" devient "ws(s)",
+    /// et le port (s'il est donné) est incrémenté (ex : 8899 -> 8900 pour un validateur local).
+    fn websocket_url(rpc_url: &str) -> String {
+        let (scheme, rest) = match rpc_url.split_once("://") {
+            Some(("https", rest)) => ("wss", rest),
+            Some(("http", rest)) => ("ws", rest),
+            _ => return rpc_url.to_string(),
+        };
+        let (host, path) = match rest.find('/') {
+            Some(index) => rest.split_at(index),
+            None => (rest, ""),
+        };
+        let host = match host.rsplit_once(':') {
+            Some((name, port)) => match port.parse::<u16>() {
+                Ok(port) => format!("{}:{}", name, port.saturating_add(1)),
+                Err(_) => host.to_string(),
+            },
+            None => host.to_string(),
+        };
+        format!("{}://{}{}", scheme, host, path)
+    }
+}
+
+/// Couches de la configuration en cours de résolution, et origine des valeurs retenues.
+struct Layers<'a> {
+    files: &'a ConfigFiles,
+    profile: String,
+    sources: BTreeMap<&'static str, ConfigSource>,
+}
+
+impl Layers<'_> {
+    fn env(var: &'static str) -> (Option<String>, ConfigSource) {
+        (env::var(var).ok(), ConfigSource::Env(var))
+    }
+
+    fn profile_layer(
+        &self,
+        field: fn(&ProfileConfig) -> Option<&String>,
+    ) -> (Option<String>, ConfigSource) {
+        match self.files.profile_value(&self.profile, field) {
+            Some((value, source)) => (Some(value), source),
+            None => (None, ConfigSource::Default),
+        }
+    }
+
+    /// Retourne la première valeur valide des couches (de la plus prioritaire à la moins prioritaire),
+    /// et enregistre son origine. Retourne None si aucune couche ne donne de valeur valide.
+    fn pick<T>(
+        &mut self,
+        name: &'static str,
+        layers: Vec<(Option<String>, ConfigSource)>,
+        parse: impl Fn(&str) -> Option<T>,
+    ) -> Option<T> {
+        for (value, source) in layers {
+            if let Some(value) = value.as_deref().and_then(&parse) {
+                self.sources.insert(name, source);
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    /// Un dossier "keystore" de profil désigne un fichier de ce dossier ; un chemin venant d'ailleurs est gardé tel quel.
+    fn keystore_file(&self, path: String, name: &str, file_name: &str) -> String {
+        match self.sources.get(name) {
+            Some(ConfigSource::Profile { .. }) => keystore_path(&path, file_name),
+            _ => path,
         }
     }
 }
@@ -64,6 +490,13 @@ mod tests {
         env::remove_var("KEYPAIR_PATH");
         env::remove_var("KEYPAIR_DERIVATIONS_PATH");
         env::remove_var("RPC_URL");
+        env::remove_var("DERIVATION_ENGINE");
+        env::remove_var("DERIVATION_PATH");
+        env::remove_var("WALLET_REGISTRY_PATH");
+        env::remove_var("WALLET_PROFILE");
+        env::remove_var("WEBSOCKET_URL");
+        env::remove_var("COMMITMENT");
+        env::remove_var("WALLET_ACCOUNT");
     }
 
     fn teardown() {
@@ -75,11 +508,14 @@ mod tests {
     fn test_default_values() {
         setup();
 
-        let config = WalletConfig::new();
+        let config = WalletConfig::new().unwrap();
         assert_eq!(config.nb_derivations, 0);
         assert_eq!(config.keypair_path, "./storage/keypair/id.json");
         assert_eq!(config.keypair_derivations_path, "./storage/keypair/derived");
         assert_eq!(config.rpc_url, "https://api.devnet.solana.com");
+        assert_eq!(config.derivation_engine, DerivationEngine::Slip10);
+        assert_eq!(config.derivation_scheme, DerivationScheme::Bip44Change);
+        assert_eq!(config.wallet_registry_path, "./storage/wallet.json");
 
         teardown();
     }
@@ -96,8 +532,11 @@ mod tests {
             "./storage/custom/keypair/derived",
         );
         env::set_var("RPC_URL", "https://custom.rpc.url");
+        env::set_var("DERIVATION_ENGINE", "legacy");
+        env::set_var("DERIVATION_PATH", "m/44'/501'/{account}'/0'/0'");
+        env::set_var("WALLET_REGISTRY_PATH", "./storage/custom/wallet.json");
 
-        let config = WalletConfig::new();
+        let config = WalletConfig::new().unwrap();
         assert_eq!(config.nb_derivations, 5);
         assert_eq!(config.keypair_path, "./storage/custom/keypair/id.json");
         assert_eq!(
@@ -105,6 +544,12 @@ mod tests {
             "./storage/custom/keypair/derived"
         );
         assert_eq!(config.rpc_url, "https://custom.rpc.url");
+        assert_eq!(config.derivation_engine, DerivationEngine::LegacyBip32);
+        assert_eq!(
+            config.derivation_scheme,
+            DerivationScheme::Custom("m/44'/501'/{account}'/0'/0'".to_string())
+        );
+        assert_eq!(config.wallet_registry_path, "./storage/custom/wallet.json");
 
         teardown();
     }
@@ -116,9 +561,149 @@ mod tests {
 
         env::set_var("NB_DERIVATIONS", "not_a_number");
 
-        let config = WalletConfig::new();
+        let config = WalletConfig::new().unwrap();
         assert_eq!(config.nb_derivations, 0); // Doit être par défaut à 0 s'il n'est pas valide.
 
         teardown();
     }
+
+    #[test]
+    #[serial]
+    fn test_invalid_env() {
+        setup();
+
+        // Une valeur invalide est une erreur, que la configuration vienne de "new" ou de "load".
+        env::set_var("DERIVATION_PATH", "m/44'/501'/{acount}'/0'");
+        assert!(matches!(WalletConfig::new(), Err(WalletError::Config(_))));
+        assert!(matches!(
+            WalletConfig::load(&ConfigFlags::default()),
+            Err(WalletError::Config(_))
+        ));
+
+        env::remove_var("DERIVATION_PATH");
+        env::set_var("DERIVATION_ENGINE", "slip-10");
+        assert!(matches!(
+            WalletConfig::load(&ConfigFlags::default()),
+            Err(WalletError::Config(_))
+        ));
+
+        env::remove_var("DERIVATION_ENGINE");
+        env::set_var("COMMITMENT", "not_a_commitment");
+        let error = WalletConfig::new().err().unwrap();
+        assert_eq!(error.exit_code(), 13);
+        assert!(error
+            .message()
+            .starts_with("Invalid COMMITMENT 'not_a_commitment'"));
+
+        env::remove_var("COMMITMENT");
+        teardown();
+    }
+
+    #[test]
+    #[serial]
+    fn test_profile_layers() {
+        setup();
+
+        let files = ConfigFiles::from_files(vec![(
+            "./wallet.toml".to_string(),
+            toml::from_str(
+                "profile = \"localnet\"\n\n\
+                 [profiles.localnet]\n\
+                 commitment = \"processed\"\n\
+                 keystore = \"./storage/localnet\"\n\
+                 default_account = \"savings\"\n",
+            )
+            .unwrap(),
+        )]);
+
+        let config = WalletConfig::resolve(&ConfigFlags::default(), &files).unwrap();
+        assert_eq!(config.profile, "localnet");
+        assert_eq!(
+            config.source("profile"),
+            ConfigSource::File("./wallet.toml".to_string())
+        );
+        assert_eq!(config.rpc_url, "http://localhost:8899");
+        assert_eq!(config.websocket_url, "ws://localhost:8900");
+        assert_eq!(config.commitment, CommitmentConfig::processed());
+        assert_eq!(config.keypair_path, "./storage/localnet/id.json");
+        assert_eq!(
+            config.keypair_derivations_path,
+            "./storage/localnet/derived"
+        );
+        assert_eq!(config.default_account.as_deref(), Some("savings"));
+        assert_eq!(config.source("nb_derivations"), ConfigSource::Default);
+
+        // Les variables d'environnement sont prioritaires sur le profil, et les options sur tout le reste.
+        env::set_var("KEYPAIR_PATH", "./storage/custom/id.json");
+        env::set_var("WALLET_PROFILE", "testnet");
+        env::set_var("NB_DERIVATIONS", "2");
+        let flags = ConfigFlags {
+            profile: Some("mainnet".to_string()),
+            derivations: Some(4),
+            output: Some(OutputFormat::Json),
+            ..ConfigFlags::default()
+        };
+        let config = WalletConfig::resolve(&flags, &files).unwrap();
+        assert_eq!(config.profile, "mainnet");
+        assert_eq!(config.source("profile"), ConfigSource::Flag("--profile"));
+        assert_eq!(config.rpc_url, "https://api.mainnet-beta.solana.com");
+        assert_eq!(config.keypair_path, "./storage/custom/id.json");
+        assert_eq!(
+            config.source("keypair_path"),
+            ConfigSource::Env("KEYPAIR_PATH")
+        );
+        assert_eq!(config.commitment, CommitmentConfig::finalized());
+        assert_eq!(config.default_account, None);
+        assert_eq!(config.output.format, OutputFormat::Json);
+        assert_eq!(config.nb_derivations, 4);
+        assert_eq!(
+            config.source("nb_derivations"),
+            ConfigSource::Flag("--derivations")
+        );
+
+        // "--url" remplace l'URL RPC, et l'URL WebSocket du profil qui va avec.
+        let flags = ConfigFlags {
+            url: Some("http://127.0.0.1:9000".to_string()),
+            keypair: Some("./storage/flag/id.json".to_string()),
+            commitment: Some("confirmed".to_string()),
+            ..ConfigFlags::default()
+        };
+        let config = WalletConfig::resolve(&flags, &files).unwrap();
+        assert_eq!(config.rpc_url, "http://127.0.0.1:9000");
+        assert_eq!(config.websocket_url, "ws://127.0.0.1:9001");
+        assert_eq!(config.keypair_path, "./storage/flag/id.json");
+        assert_eq!(config.commitment, CommitmentConfig::confirmed());
+
+        // Un moniker ("-um") choisit aussi le profil de son réseau, avec ses plafonds de dépense.
+        let flags = ConfigFlags {
+            url: Some("https://api.mainnet-beta.solana.com".to_string()),
+            url_profile: Some("mainnet".to_string()),
+            ..ConfigFlags::default()
+        };
+        let config = WalletConfig::resolve(&flags, &files).unwrap();
+        assert_eq!(config.profile, "mainnet");
+        assert_eq!(config.source("profile"), ConfigSource::Flag("--url"));
+        let flags = ConfigFlags {
+            profile: Some("devnet".to_string()),
+            ..flags
+        };
+        assert!(matches!(
+            WalletConfig::load(&flags),
+            Err(WalletError::InvalidInput(_))
+        ));
+
+        teardown();
+    }
+
+    #[test]
+    fn test_websocket_url() {
+        assert_eq!(
+            WalletConfig::websocket_url("https://api.devnet.solana.com"),
+            "wss://api.devnet.solana.com"
+        );
+        assert_eq!(
+            WalletConfig::websocket_url("http://127.0.0.1:8899/rpc"),
+            "ws://127.0.0.1:8900/rpc"
+        );
+    }
 }
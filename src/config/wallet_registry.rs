@@ -0,0 +1,362 @@
+use crate::error::WalletError;
+use chrono::{SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Version actuelle du format du fichier d'index du wallet.
+pub const REGISTRY_VERSION: u32 = 1;
+
+/// Origine d'un compte du wallet.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountSource {
+    /// Dérivé de la phrase mnémonique ("generate_seed" / "recover_seed").
+    Derived,
+    /// Importé depuis un fichier de paire de clés existant.
+    Imported,
+    /// Clé publique seule (aucune clé privée sur cette machine).
+    WatchOnly,
+}
+
+impl fmt::Display for AccountSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccountSource::Derived => write!(f, "derived"),
+            AccountSource::Imported => write!(f, "imported"),
+            AccountSource::WatchOnly => write!(f, "watch-only"),
+        }
+    }
+}
+
+/// Compte enregistré dans le wallet.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WalletAccount {
+    /// Index stable du compte (ne change pas lorsque d'autres comptes sont supprimés).
+    pub index: usize,
+    pub label: String,
+    pub pubkey: String,
+    /// Chemin de dérivation (uniquement pour les comptes dérivés, None pour la seed racine).
+    pub derivation_path: Option<String>,
+    /// Fichier de la paire de clés (None pour un compte watch-only).
+    pub keypair_path: Option<String>,
+    pub source: AccountSource,
+    pub created_at: String,
+}
+
+/// Index des comptes du wallet, stocké dans un fichier JSON (`WALLET_REGISTRY_PATH`).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct WalletRegistry {
+    pub version: u32,
+    /// Index du compte utilisé par défaut par "send", "pubkey" et "balance".
+    pub default_account: Option<usize>,
+    pub accounts: Vec<WalletAccount>,
+}
+
+impl Default for WalletRegistry {
+    fn default() -> Self {
+        Self {
+            version: REGISTRY_VERSION,
+            default_account: None,
+            accounts: Vec::new(),
+        }
+    }
+}
+
+impl WalletRegistry {
+    /// Charge l'index du wallet depuis un fichier. Retourne un index vide si le fichier n'existe pas encore.
+    pub fn load(file_path: &str) -> Result<Self, WalletError> {
+        if !Path::new(file_path).exists() {
+            return Ok(Self::default());
+        }
+
+        let content = fs::read_to_string(file_path)?;
+        let registry: WalletRegistry = serde_json::from_str(&content)?;
+        if registry.version != REGISTRY_VERSION {
+            return Err(
+                format!("Unsupported wallet registry version: {}", registry.version).into(),
+            );
+        }
+        Ok(registry)
+    }
+
+    /// Enregistre l'index du wallet dans un fichier (en créant les dossiers parents).
+    pub fn save(&self, file_path: &str) -> Result<(), WalletError> {
+        if let Some(parent) = Path::new(file_path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(file_path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Recherche un compte par son label ou par son index.
+    ///
+    /// # Arguments:
+    /// - selector - Le label du compte, ou son index.
+    pub fn find(&self, selector: &str) -> Option<&WalletAccount> {
+        match selector.parse::<usize>() {
+            Ok(index) => self.accounts.iter().find(|account| account.index == index),
+            Err(_) => self
+                .accounts
+                .iter()
+                .find(|account| account.label == selector),
+        }
+    }
+
+    /// Retourne le compte par défaut, s'il a été défini.
+    pub fn default_account(&self) -> Option<&WalletAccount> {
+        self.default_account
+            .and_then(|index| self.accounts.iter().find(|account| account.index == index))
+    }
+
+    /// Ajoute un compte au wallet.
+    ///
+    /// # Arguments:
+    /// - label - Le label du compte (unique, et qui ne doit pas être un nombre pour ne pas être confondu avec un index).
+    /// - pubkey - La clé publique du compte.
+    /// - derivation_path - Le chemin de dérivation (comptes dérivés uniquement).
+    /// - keypair_path - Le fichier de la paire de clés (None pour un compte watch-only).
+    /// - source - L'origine du compte.
+    ///
+    /// # Returns:
+    /// Retourne l'index du compte ajouté, ou une erreur si le label ou la clé publique est déjà utilisé.
+    pub fn add(
+        &mut self,
+        label: &str,
+        pubkey: &str,
+        derivation_path: Option<String>,
+        keypair_path: Option<String>,
+        source: AccountSource,
+    ) -> Result<usize, String> {
+        self.validate_label(label)?;
+        if let Some(account) = self
+            .accounts
+            .iter()
+            .find(|account| account.pubkey == pubkey)
+        {
+            return Err(format!(
+                "Public key {} is already registered as '{}'",
+                pubkey, account.label
+            ));
+        }
+
+        let index = self.next_index();
+        self.accounts.push(WalletAccount {
+            index,
+            label: label.to_string(),
+            pubkey: pubkey.to_string(),
+            derivation_path,
+            keypair_path,
+            source,
+            created_at: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        });
+        Ok(index)
+    }
+
+    /// Enregistre un compte dérivé. Si un compte utilise déjà le même fichier de paire de clés,
+    /// il est mis à jour (le fichier vient d'être réécrit), en conservant son label et son index.
+    ///
+    /// # Returns:
+    /// Retourne l'index du compte enregistré.
+    pub fn upsert_derived(
+        &mut self,
+        default_label: &str,
+        pubkey: &str,
+        derivation_path: Option<String>,
+        keypair_path: &str,
+    ) -> usize {
+        // Une autre entrée pour la même clé publique (ex : importée) est remplacée par le compte dérivé.
+        self.accounts.retain(|account| {
+            account.pubkey != pubkey || account.keypair_path.as_deref() == Some(keypair_path)
+        });
+
+        let created_at = Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true);
+        if let Some(account) = self
+            .accounts
+            .iter_mut()
+            .find(|account| account.keypair_path.as_deref() == Some(keypair_path))
+        {
+            account.pubkey = pubkey.to_string();
+            account.derivation_path = derivation_path;
+            account.source = AccountSource::Derived;
+            account.created_at = created_at;
+            return account.index;
+        }
+
+        let label = self.unique_label(default_label);
+        let index = self.next_index();
+        self.accounts.push(WalletAccount {
+            index,
+            label,
+            pubkey: pubkey.to_string(),
+            derivation_path,
+            keypair_path: Some(keypair_path.to_string()),
+            source: AccountSource::Derived,
+            created_at,
+        });
+        index
+    }
+
+    /// Renomme un compte.
+    pub fn rename(&mut self, selector: &str, new_label: &str) -> Result<(), String> {
+        let index = self.require(selector)?.index;
+        if self.find(new_label).map(|account| account.index) != Some(index) {
+            self.validate_label(new_label)?;
+        }
+
+        if let Some(account) = self
+            .accounts
+            .iter_mut()
+            .find(|account| account.index == index)
+        {
+            account.label = new_label.to_string();
+        }
+        Ok(())
+    }
+
+    /// Supprime un compte de l'index (le fichier de la paire de clés n'est pas supprimé).
+    ///
+    /// # Returns:
+    /// Retourne le compte supprimé.
+    pub fn remove(&mut self, selector: &str) -> Result<WalletAccount, String> {
+        let index = self.require(selector)?.index;
+        if self.default_account == Some(index) {
+            self.default_account = None;
+        }
+
+        let position = self
+            .accounts
+            .iter()
+            .position(|account| account.index == index)
+            .expect("account exists");
+        Ok(self.accounts.remove(position))
+    }
+
+    /// Définit le compte par défaut.
+    pub fn set_default(&mut self, selector: &str) -> Result<&WalletAccount, String> {
+        let index = self.require(selector)?.index;
+        self.default_account = Some(index);
+        self.require(selector)
+    }
+
+    /// Recherche un compte, ou retourne une erreur explicite s'il n'existe pas.
+    pub fn require(&self, selector: &str) -> Result<&WalletAccount, String> {
+        self.find(selector)
+            .ok_or_else(|| format!("Account '{}' not found in wallet", selector))
+    }
+
+    fn validate_label(&self, label: &str) -> Result<(), String> {
+        if label.trim().is_empty() {
+            return Err("Account label cannot be empty".to_string());
+        }
+        if label.parse::<usize>().is_ok() {
+            return Err(format!(
+                "Account label '{}' cannot be a number (numbers select accounts by index)",
+                label
+            ));
+        }
+        if self.accounts.iter().any(|account| account.label == label) {
+            return Err(format!("Account label '{}' is already used", label));
+        }
+        Ok(())
+    }
+
+    fn unique_label(&self, label: &str) -> String {
+        let mut candidate = label.to_string();
+        let mut suffix = 2;
+        while self.validate_label(&candidate).is_err() {
+            candidate = format!("{}-{}", label, suffix);
+            suffix += 1;
+        }
+        candidate
+    }
+
+    fn next_index(&self) -> usize {
+        self.accounts
+            .iter()
+            .map(|account| account.index + 1)
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUBKEY_1: &str = "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk";
+    const PUBKEY_2: &str = "Hh8QwFUA6MtVu1qAoq12ucvFHNwCcVTV7hpWjeY1Hztb";
+    const PUBKEY_3: &str = "GjJyeC1r2RgkuoCWMyPYkCWSGSGLcz266EaAkLA27AhL";
+
+    #[test]
+    fn test_add_find_rename_remove() {
+        let mut registry = WalletRegistry::default();
+
+        let index = registry
+            .add(
+                "savings",
+                PUBKEY_1,
+                None,
+                Some("./id.json".to_string()),
+                AccountSource::Imported,
+            )
+            .unwrap();
+        assert_eq!(index, 0);
+        registry
+            .add("cold", PUBKEY_2, None, None, AccountSource::WatchOnly)
+            .unwrap();
+
+        // Recherche par label ou par index.
+        assert_eq!(registry.find("savings").unwrap().pubkey, PUBKEY_1);
+        assert_eq!(registry.find("1").unwrap().label, "cold");
+        assert!(registry.find("unknown").is_none());
+
+        // Labels uniques et non numériques, clés publiques uniques.
+        assert!(registry
+            .add("savings", PUBKEY_3, None, None, AccountSource::WatchOnly)
+            .is_err());
+        assert!(registry
+            .add("42", PUBKEY_3, None, None, AccountSource::WatchOnly)
+            .is_err());
+        assert!(registry
+            .add("other", PUBKEY_1, None, None, AccountSource::WatchOnly)
+            .is_err());
+
+        registry.rename("savings", "treasury").unwrap();
+        registry.set_default("treasury").unwrap();
+        assert_eq!(registry.default_account().unwrap().pubkey, PUBKEY_1);
+
+        // La suppression du compte par défaut réinitialise le compte par défaut, sans réutiliser son index.
+        registry.remove("0").unwrap();
+        assert!(registry.default_account().is_none());
+        let index = registry
+            .add("new", PUBKEY_1, None, None, AccountSource::WatchOnly)
+            .unwrap();
+        assert_eq!(index, 2);
+    }
+
+    #[test]
+    fn test_upsert_derived_replaces_account_with_same_keypair_path() {
+        let mut registry = WalletRegistry::default();
+
+        let first = registry.upsert_derived(
+            "account-0",
+            PUBKEY_1,
+            Some("m/44'/501'/0'/0'".to_string()),
+            "./id.json",
+        );
+        registry.rename("account-0", "main").unwrap();
+
+        // Une nouvelle dérivation dans le même fichier met à jour le compte existant (label et index conservés).
+        let second = registry.upsert_derived(
+            "account-0",
+            PUBKEY_2,
+            Some("m/44'/501'/0'/0'".to_string()),
+            "./id.json",
+        );
+        assert_eq!(first, second);
+        assert_eq!(registry.accounts.len(), 1);
+        assert_eq!(registry.find("main").unwrap().pubkey, PUBKEY_2);
+    }
+}
@@ -0,0 +1,220 @@
+use solana_client::client_error::ClientError;
+use solana_sdk::program_error::ProgramError;
+use solana_sdk::pubkey::ParsePubkeyError;
+use solana_sdk::signature::ParseSignatureError;
+use solana_sdk::signer::SignerError;
+use std::fmt;
+use std::io;
+
+/// Erreur du wallet, retournée par toutes les commandes.
+/// Chaque variante correspond à un code de sortie distinct du processus (voir `exit_code`),
+/// afin que les scripts puissent réagir selon le type d'échec.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WalletError {
+    /// Argument invalide (montant, option, memo, fichier CSV...).
+    InvalidInput(String),
+    /// Phrase mnémonique invalide (mot inconnu, mauvais nombre de mots, checksum).
+    InvalidMnemonic(String),
+    /// Clé publique invalide.
+    InvalidPubkey(String),
+    /// Lecture ou écriture d'une paire de clés ou d'un keystore (fichier absent, mauvais mot de passe...).
+    KeyIo(String),
+    /// Compte du wallet inconnu, watch-only, ou index des comptes illisible.
+    Account(String),
+    /// Erreur de communication avec le noeud RPC.
+    Rpc(String),
+    /// Balance insuffisante pour l'opération (montant et frais).
+    InsufficientFunds(String),
+    /// Transaction refusée ou échouée sur le réseau.
+    Transaction(String),
+    /// Opération annulée par l'utilisateur.
+    Cancelled(String),
+    /// Fichier (CSV, transaction, résultats...) illisible ou invalide.
+    File(String),
+    /// Plafond de dépense du profil dépassé (par transaction ou par jour).
+    SpendingLimit(String),
+    /// Configuration invalide (valeur d'une variable d'environnement).
+    Config(String),
+    /// Autre erreur.
+    Other(String),
+}
+
+impl WalletError {
+    /// Retourne le code de sortie du processus correspondant à l'erreur (toujours non nul).
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            WalletError::Other(_) => 1,
+            WalletError::InvalidInput(_) => 2,
+            WalletError::InvalidMnemonic(_) => 3,
+            WalletError::InvalidPubkey(_) => 4,
+            WalletError::KeyIo(_) => 5,
+            WalletError::Account(_) => 6,
+            WalletError::Rpc(_) => 7,
+            WalletError::InsufficientFunds(_) => 8,
+            WalletError::Transaction(_) => 9,
+            WalletError::Cancelled(_) => 10,
+            WalletError::File(_) => 11,
+            WalletError::SpendingLimit(_) => 12,
+            WalletError::Config(_) => 13,
+        }
+    }
+
+    /// Retourne le type de l'erreur, stable pour les scripts (ex : "insufficient_funds").
+    pub fn kind(&self) -> &'static str {
+        match self {
+            WalletError::InvalidInput(_) => "invalid_input",
+            WalletError::InvalidMnemonic(_) => "invalid_mnemonic",
+            WalletError::InvalidPubkey(_) => "invalid_pubkey",
+            WalletError::KeyIo(_) => "key_io",
+            WalletError::Account(_) => "account",
+            WalletError::Rpc(_) => "rpc",
+            WalletError::InsufficientFunds(_) => "insufficient_funds",
+            WalletError::Transaction(_) => "transaction",
+            WalletError::Cancelled(_) => "cancelled",
+            WalletError::File(_) => "file",
+            WalletError::SpendingLimit(_) => "spending_limit",
+            WalletError::Config(_) => "config",
+            WalletError::Other(_) => "other",
+        }
+    }
+
+    /// Retourne le message de l'erreur.
+    pub fn message(&self) -> &str {
+        match self {
+            WalletError::InvalidInput(message)
+            | WalletError::InvalidMnemonic(message)
+            | WalletError::InvalidPubkey(message)
+            | WalletError::KeyIo(message)
+            | WalletError::Account(message)
+            | WalletError::Rpc(message)
+            | WalletError::InsufficientFunds(message)
+            | WalletError::Transaction(message)
+            | WalletError::Cancelled(message)
+            | WalletError::File(message)
+            | WalletError::SpendingLimit(message)
+            | WalletError::Config(message)
+            | WalletError::Other(message) => message,
+        }
+    }
+
+    /// Préfixe le message de l'erreur (ex : "Failed to send transaction: ..."), sans changer sa variante.
+    pub fn context(self, context: &str) -> Self {
+        let wrap = |message: String| format!("{}: {}", context, message);
+        match self {
+            WalletError::InvalidInput(message) => WalletError::InvalidInput(wrap(message)),
+            WalletError::InvalidMnemonic(message) => WalletError::InvalidMnemonic(wrap(message)),
+            WalletError::InvalidPubkey(message) => WalletError::InvalidPubkey(wrap(message)),
+            WalletError::KeyIo(message) => WalletError::KeyIo(wrap(message)),
+            WalletError::Account(message) => WalletError::Account(wrap(message)),
+            WalletError::Rpc(message) => WalletError::Rpc(wrap(message)),
+            WalletError::InsufficientFunds(message) => {
+                WalletError::InsufficientFunds(wrap(message))
+            }
+            WalletError::Transaction(message) => WalletError::Transaction(wrap(message)),
+            WalletError::Cancelled(message) => WalletError::Cancelled(wrap(message)),
+            WalletError::File(message) => WalletError::File(wrap(message)),
+            WalletError::SpendingLimit(message) => WalletError::SpendingLimit(wrap(message)),
+            WalletError::Config(message) => WalletError::Config(wrap(message)),
+            WalletError::Other(message) => WalletError::Other(wrap(message)),
+        }
+    }
+}
+
+impl fmt::Display for WalletError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl std::error::Error for WalletError {}
+
+impl From<String> for WalletError {
+    fn from(message: String) -> Self {
+        WalletError::Other(message)
+    }
+}
+
+impl From<&str> for WalletError {
+    fn from(message: &str) -> Self {
+        WalletError::Other(message.to_string())
+    }
+}
+
+impl From<io::Error> for WalletError {
+    fn from(e: io::Error) -> Self {
+        WalletError::File(e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for WalletError {
+    fn from(e: serde_json::Error) -> Self {
+        WalletError::File(e.to_string())
+    }
+}
+
+impl From<bincode::Error> for WalletError {
+    fn from(e: bincode::Error) -> Self {
+        WalletError::File(e.to_string())
+    }
+}
+
+impl From<base64::DecodeError> for WalletError {
+    fn from(e: base64::DecodeError) -> Self {
+        WalletError::InvalidInput(e.to_string())
+    }
+}
+
+/// Une transaction refusée par le réseau (simulation ou exécution) est une erreur de transaction, et non une erreur RPC.
+impl From<ClientError> for WalletError {
+    fn from(e: ClientError) -> Self {
+        match e.get_transaction_error() {
+            Some(_) => WalletError::Transaction(e.to_string()),
+            None => WalletError::Rpc(e.to_string()),
+        }
+    }
+}
+
+impl From<ParsePubkeyError> for WalletError {
+    fn from(e: ParsePubkeyError) -> Self {
+        WalletError::InvalidPubkey(e.to_string())
+    }
+}
+
+impl From<ParseSignatureError> for WalletError {
+    fn from(e: ParseSignatureError) -> Self {
+        WalletError::InvalidInput(e.to_string())
+    }
+}
+
+impl From<SignerError> for WalletError {
+    fn from(e: SignerError) -> Self {
+        WalletError::Transaction(e.to_string())
+    }
+}
+
+impl From<ProgramError> for WalletError {
+    fn from(e: ProgramError) -> Self {
+        WalletError::Other(e.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_keeps_exit_code() {
+        let error = WalletError::InsufficientFunds("Insufficient balance".to_string())
+            .context("Failed to send transaction");
+        assert_eq!(
+            error.to_string(),
+            "Failed to send transaction: Insufficient balance"
+        );
+        assert_eq!(error.exit_code(), 8);
+        assert_eq!(WalletError::from("oops").exit_code(), 1);
+        assert_eq!(
+            WalletError::from(io::Error::new(io::ErrorKind::NotFound, "missing")).exit_code(),
+            11
+        );
+    }
+}
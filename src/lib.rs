@@ -1,4 +1,5 @@
 pub mod app;
 pub mod bip;
 pub mod config;
+pub mod error;
 pub mod solana;
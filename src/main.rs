@@ -1,11 +1,13 @@
 mod app;
 mod bip;
 mod config;
+mod error;
 mod solana;
 
 use app::cli::AppCli;
 use config::wallet_config::WalletConfig;
 use std::env;
+use std::process;
 
 use dotenv::dotenv;
 
@@ -15,12 +17,20 @@ fn main() {
         dotenv().ok();
     }
 
-    // Charger la configuration du wallet (selon les données du fichier .env).
-    let config = WalletConfig::new();
+    let matches = AppCli::setup_cli().get_matches();
 
-    let app_cli = AppCli::new(config.clone());
+    // Charger la configuration du wallet : options globales ("--profile", "--output"), variables d'environnement
+    // (fichier .env), puis profil des fichiers de configuration.
+    let flags = AppCli::config_flags(&matches);
+    let config = WalletConfig::load(&flags).unwrap_or_else(|e| {
+        flags.output.unwrap_or_default().error(&e);
+        process::exit(e.exit_code());
+    });
+    let mut app_cli = AppCli::new(config);
 
-    let matches = app_cli.setup_cli().get_matches();
-
-    app_cli.handle_matches(matches);
+    // En cas d'échec, affiche l'erreur et termine avec le code de sortie correspondant (voir `WalletError::exit_code`).
+    if let Err(e) = app_cli.handle_matches(matches) {
+        app_cli.output().error(&e);
+        process::exit(e.exit_code());
+    }
 }
@@ -1,3 +1,4 @@
+use crate::error::WalletError;
 use solana_sdk::signature::{keypair_from_seed, write_keypair_file};
 use solana_sdk::signer::keypair::Keypair;
 use std::fs;
@@ -12,9 +13,10 @@ impl SolanaAddress {
     /// - seed_bytes - Les octets de la seed à partir de laquelle la paire de clés est générée.
     ///
     /// # Returns:
-    /// Retourne la paire de clés générée ou interrompt l'exécution en cas d'erreur.
-    pub fn generate_keypair(seed_bytes: &[u8]) -> Keypair {
-        keypair_from_seed(seed_bytes).expect("Failed to generate keypair to file")
+    /// Retourne la paire de clés générée, ou une erreur si la seed est trop courte.
+    pub fn generate_keypair(seed_bytes: &[u8]) -> Result<Keypair, WalletError> {
+        keypair_from_seed(seed_bytes)
+            .map_err(|e| WalletError::KeyIo(format!("Failed to generate keypair: {}", e)))
     }
 
     /// Écrit la paire de clés générée dans un fichier.
@@ -25,8 +27,12 @@ impl SolanaAddress {
     /// - keypair - La paire de clés à écrire.
     /// - file_path - Le chemin du fichier où écrire la paire de clés.
     ///   @note Ce fichier peut être utilisé pour stocker de manière sécurisée la paire de clés ou pour l'importer dans d'autres applications ou services compatibles avec Solana.
-    pub fn write_keypair(keypair: &Keypair, file_path: &str) {
-        write_keypair_file(keypair, file_path).expect("Failed to write keypair to file");
+    pub fn write_keypair(keypair: &Keypair, file_path: &str) -> Result<(), WalletError> {
+        write_keypair_file(keypair, file_path)
+            .map(|_| ())
+            .map_err(|e| {
+                WalletError::KeyIo(format!("Failed to write keypair to {}: {}", file_path, e))
+            })
     }
 
     /// Lit une paire de clés à partir d'un fichier et la retourne.
@@ -0,0 +1,222 @@
+use serde_json::{json, Value};
+
+/// Nombre de décimales d'un SOL (1 lamport = 0.000000001 SOL).
+pub const SOL_DECIMALS: usize = 9;
+
+/// Unité dans laquelle un montant est saisi.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum AmountUnit {
+    /// Montant entier en lamports (unité par défaut, pour rester compatible avec les anciennes commandes).
+    #[default]
+    Lamports,
+    /// Montant décimal en SOL (jusqu'à 9 décimales).
+    Sol,
+}
+
+/// Montant d'un transfert.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransferAmount {
+    /// Montant exact en lamports.
+    Lamports(u64),
+    /// Toute la balance disponible ("ALL" / "max"), moins les frais et la réserve de rente.
+    All,
+}
+
+pub struct SolAmount {}
+
+impl SolAmount {
+    /// Analyse un montant saisi par l'utilisateur, sans passer par des flottants (aucun arrondi).
+    ///
+    /// # Arguments:
+    /// - input - Le montant saisi : un nombre, ou "ALL" / "max" pour envoyer toute la balance disponible.
+    /// - unit - L'unité du montant (lamports ou SOL).
+    ///
+    /// # Returns:
+    /// Retourne le montant, ou une erreur si le montant est invalide, trop précis, nul ou trop grand.
+    pub fn parse(input: &str, unit: AmountUnit) -> Result<TransferAmount, String> {
+        let input = input.trim();
+        if input.eq_ignore_ascii_case("all") || input.eq_ignore_ascii_case("max") {
+            return Ok(TransferAmount::All);
+        }
+
+        let lamports = match unit {
+            AmountUnit::Lamports => Self::parse_lamports(input)?,
+            AmountUnit::Sol => Self::parse_sol(input)?,
+        };
+        if lamports == 0 {
+            return Err("Amount must be greater than zero".to_string());
+        }
+        Ok(TransferAmount::Lamports(lamports))
+    }
+
+    /// Analyse un montant entier en lamports.
+    pub fn parse_lamports(input: &str) -> Result<u64, String> {
+        if input.contains('.') {
+            return Err(format!(
+                "Invalid amount '{}': lamports cannot have decimals (use --sol to send a decimal SOL amount)",
+                input
+            ));
+        }
+        Self::parse_digits(input, input)
+    }
+
+    /// Analyse un montant décimal en SOL, et le convertit en lamports.
+    ///
+    /// Exemples : "1" = 1_000_000_000 lamports, "0.5" = 500_000_000 lamports, ".000000001" = 1 lamport.
+    pub fn parse_sol(input: &str) -> Result<u64, String> {
+        let fractional_part = input.split_once('.').map_or("", |(_, fraction)| fraction);
+        if fractional_part.len() > SOL_DECIMALS {
+            return Err(format!(
+                "Invalid amount '{}': SOL amounts have at most {} decimals (1 lamport = 0.000000001 SOL)",
+                input, SOL_DECIMALS
+            ));
+        }
+        Self::parse_decimal(input, SOL_DECIMALS as u8)
+    }
+
+    /// Analyse un montant décimal ayant au plus "decimals" décimales (ex : un montant de tokens SPL),
+    /// et le convertit en unités de base (ex : "1.5" avec 6 décimales = 1_500_000).
+    pub fn parse_decimal(input: &str, decimals: u8) -> Result<u64, String> {
+        let decimals = usize::from(decimals);
+        let (integer_part, fractional_part) = input.split_once('.').unwrap_or((input, ""));
+        if integer_part.is_empty() && fractional_part.is_empty() {
+            return Err(format!("Invalid amount '{}': expected a number", input));
+        }
+        if fractional_part.len() > decimals {
+            return Err(format!(
+                "Invalid amount '{}': at most {} decimals are allowed",
+                input, decimals
+            ));
+        }
+
+        let integer = if integer_part.is_empty() {
+            0
+        } else {
+            Self::parse_digits(integer_part, input)?
+        };
+        let fraction = if fractional_part.is_empty() {
+            0
+        } else {
+            // Complète la partie décimale : "5" avec 9 décimales => "500000000".
+            let padded = format!("{:0<width$}", fractional_part, width = decimals);
+            Self::parse_digits(&padded, input)?
+        };
+
+        u32::try_from(decimals)
+            .ok()
+            .and_then(|decimals| 10u64.checked_pow(decimals))
+            .and_then(|unit| integer.checked_mul(unit))
+            .and_then(|amount| amount.checked_add(fraction))
+            .ok_or_else(|| format!("Invalid amount '{}': amount is too large", input))
+    }
+
+    /// Formate un montant en lamports en SOL, avec ses 9 décimales (ex : 1500000000 => "1.500000000").
+    pub fn format_sol(lamports: u64) -> String {
+        Self::format_decimal(lamports, SOL_DECIMALS as u8)
+    }
+
+    /// Retourne un montant pour la sortie JSON : `{"lamports": 1500000000, "sol": "1.500000000"}`.
+    /// Le montant en SOL est une chaîne, pour ne perdre aucune décimale.
+    pub fn json(lamports: u64) -> Value {
+        json!({ "lamports": lamports, "sol": Self::format_sol(lamports) })
+    }
+
+    /// Formate un montant en unités de base avec toutes ses décimales (ex : 1500000 avec 6 décimales => "1.500000").
+    pub fn format_decimal(amount: u64, decimals: u8) -> String {
+        if decimals == 0 {
+            return amount.to_string();
+        }
+        // Au-delà de 38 décimales, 10^decimals dépasse u128 : la partie entière est alors nulle.
+        let unit = 10u128.checked_pow(u32::from(decimals)).unwrap_or(u128::MAX);
+        format!(
+            "{}.{:0width$}",
+            u128::from(amount) / unit,
+            u128::from(amount) % unit,
+            width = usize::from(decimals)
+        )
+    }
+
+    /// Analyse une suite de chiffres décimaux (sans signe, ni espace, ni séparateur).
+    fn parse_digits(digits: &str, input: &str) -> Result<u64, String> {
+        if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return Err(format!("Invalid amount '{}': expected a number", input));
+        }
+        digits
+            .parse::<u64>()
+            .map_err(|_| format!("Invalid amount '{}': amount is too large", input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sol_is_exact() {
+        assert_eq!(SolAmount::parse_sol("1"), Ok(1_000_000_000));
+        assert_eq!(SolAmount::parse_sol("0.1"), Ok(100_000_000));
+        assert_eq!(SolAmount::parse_sol("1.5"), Ok(1_500_000_000));
+        assert_eq!(SolAmount::parse_sol(".000000001"), Ok(1));
+        assert_eq!(SolAmount::parse_sol("2."), Ok(2_000_000_000));
+        // 0.3 n'est pas représentable exactement en flottant, mais l'est en lamports.
+        assert_eq!(SolAmount::parse_sol("0.3"), Ok(300_000_000));
+        assert_eq!(SolAmount::parse_sol("18446744073.709551615"), Ok(u64::MAX));
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_amounts() {
+        // Trop de décimales.
+        assert!(SolAmount::parse_sol("0.0000000001").is_err());
+        // Dépassement de capacité.
+        assert!(SolAmount::parse_sol("18446744073.709551616").is_err());
+        assert!(SolAmount::parse_lamports("18446744073709551616").is_err());
+        // Formats invalides.
+        for input in ["", ".", "-1", "+1", "1e9", "1,5", "1.2.3", "abc", " 1"] {
+            assert!(SolAmount::parse_sol(input).is_err(), "{}", input);
+        }
+        // Les lamports sont entiers.
+        assert!(SolAmount::parse_lamports("1.5").is_err());
+        // Montant nul.
+        assert!(SolAmount::parse("0", AmountUnit::Lamports).is_err());
+        assert!(SolAmount::parse("0.0", AmountUnit::Sol).is_err());
+    }
+
+    #[test]
+    fn test_parse_units_and_all() {
+        assert_eq!(
+            SolAmount::parse("2000000", AmountUnit::Lamports),
+            Ok(TransferAmount::Lamports(2_000_000))
+        );
+        assert_eq!(
+            SolAmount::parse("0.002", AmountUnit::Sol),
+            Ok(TransferAmount::Lamports(2_000_000))
+        );
+        assert_eq!(
+            SolAmount::parse("ALL", AmountUnit::Sol),
+            Ok(TransferAmount::All)
+        );
+        assert_eq!(
+            SolAmount::parse("max", AmountUnit::Lamports),
+            Ok(TransferAmount::All)
+        );
+    }
+
+    #[test]
+    fn test_decimal_amounts() {
+        assert_eq!(SolAmount::parse_decimal("1.5", 6), Ok(1_500_000));
+        assert_eq!(SolAmount::parse_decimal("42", 0), Ok(42));
+        assert!(SolAmount::parse_decimal("0.5", 0).is_err());
+        assert!(SolAmount::parse_decimal("0.0000001", 6).is_err());
+        assert_eq!(SolAmount::format_decimal(1_500_000, 6), "1.500000");
+        assert_eq!(SolAmount::format_decimal(42, 0), "42");
+        assert_eq!(SolAmount::format_decimal(1, 2), "0.01");
+    }
+
+    #[test]
+    fn test_format_sol() {
+        assert_eq!(SolAmount::format_sol(0), "0.000000000");
+        assert_eq!(SolAmount::format_sol(1_500_000_000), "1.500000000");
+        assert_eq!(SolAmount::format_sol(5_910_000), "0.005910000");
+        assert_eq!(SolAmount::format_sol(u64::MAX), "18446744073.709551615");
+    }
+}
@@ -1,16 +1,17 @@
-use crate::config::wallet_config::WalletConfig;
-use solana_client::rpc_client::RpcClient;
+use crate::error::WalletError;
+use crate::solana::rpc::WalletRpc;
 use solana_sdk::pubkey::Pubkey;
+use std::rc::Rc;
 use std::str::FromStr;
 
 pub struct SolanaBalance {
-    config: WalletConfig,
+    rpc: Rc<dyn WalletRpc>,
 }
 
 impl SolanaBalance {
-    /// Crée une nouvelle instance de `SolanaBalance` avec une URL RPC spécifiée.
-    pub fn new(config: WalletConfig) -> Self {
-        Self { config }
+    /// Crée une nouvelle instance de `SolanaBalance` avec le client RPC du réseau Solana.
+    pub fn new(rpc: Rc<dyn WalletRpc>) -> Self {
+        Self { rpc }
     }
 
     /// Retourne la balance en SOL pour une clé publique donnée.
@@ -21,9 +22,8 @@ impl SolanaBalance {
     /// # Returns:
     /// - Ok(balance) - Si la requête est réussie.
     /// - Err(e) - Si une erreur se produit lors de la récupération de la balance.
-    pub fn get_balance_by_pubkey(&self, pubkey: &str) -> Result<u64, Box<dyn std::error::Error>> {
+    pub fn get_balance_by_pubkey(&self, pubkey: &str) -> Result<u64, WalletError> {
         let pubkey = Pubkey::from_str(pubkey)?;
-        let client = RpcClient::new(&self.config.rpc_url);
-        client.get_balance(&pubkey).map_err(Into::into)
+        self.rpc.get_balance(&pubkey)
     }
 }
@@ -0,0 +1,292 @@
+use crate::error::WalletError;
+use crate::solana::amount::{AmountUnit, SolAmount, TransferAmount};
+use crate::solana::memo::SolanaMemo;
+use crate::solana::rpc::WalletRpc;
+use solana_sdk::{
+    hash::Hash, message::Message, packet::PACKET_DATA_SIZE, pubkey::Pubkey, signature::Signature,
+    system_instruction, transaction::Transaction,
+};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Paiement d'un fichier CSV de paiements groupés.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BatchPayment {
+    /// Numéro de la ligne dans le fichier CSV (à partir de 1).
+    pub line: usize,
+    pub recipient: Pubkey,
+    /// Montant du paiement (en lamports).
+    pub lamports: u64,
+    pub memo: Option<String>,
+}
+
+/// État d'une transaction d'un lot, d'après le réseau.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BatchTransactionStatus {
+    /// Transaction exécutée avec succès : ses paiements ont été effectués.
+    Confirmed,
+    /// Transaction exécutée en erreur : aucun de ses paiements n'a été effectué.
+    Failed(String),
+    /// Transaction inconnue du réseau.
+    NotFound,
+}
+
+pub struct SolanaBatch {}
+
+impl SolanaBatch {
+    /// Lit les paiements d'un fichier CSV : une ligne "recipient,amount[,memo]" par paiement.
+    /// Les lignes vides, les commentaires ("#") et une ligne d'en-tête ("recipient,...") sont ignorés.
+    /// Le memo peut contenir des virgules, et être entouré de guillemets.
+    ///
+    /// # Arguments:
+    /// - content - Le contenu du fichier CSV.
+    /// - unit - L'unité des montants (lamports ou SOL).
+    ///
+    /// # Returns:
+    /// Retourne les paiements, ou la liste de toutes les lignes invalides (une erreur par ligne).
+    pub fn parse_csv(content: &str, unit: AmountUnit) -> Result<Vec<BatchPayment>, Vec<String>> {
+        let mut payments = Vec::new();
+        let mut errors = Vec::new();
+
+        for (index, row) in content.lines().enumerate() {
+            let row = row.trim();
+            if row.is_empty() || row.starts_with('#') {
+                continue;
+            }
+            let mut columns = row.splitn(3, ',').map(str::trim);
+            let recipient = columns.next().unwrap_or_default();
+            if payments.is_empty()
+                && errors.is_empty()
+                && recipient.eq_ignore_ascii_case("recipient")
+            {
+                continue;
+            }
+
+            match Self::parse_row(recipient, columns.next(), columns.next(), unit) {
+                Ok((recipient, lamports, memo)) => payments.push(BatchPayment {
+                    line: index + 1,
+                    recipient,
+                    lamports,
+                    memo,
+                }),
+                Err(e) => errors.push(format!("Line {}: {}", index + 1, e)),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(payments)
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Répartit les paiements en transactions : chaque transaction contient autant de paiements consécutifs
+    /// que possible, sans dépasser la taille maximale d'une transaction (PACKET_DATA_SIZE).
+    ///
+    /// # Returns:
+    /// Retourne les paiements de chaque transaction, ou une erreur si un paiement ne tient pas seul dans une transaction.
+    pub fn pack<'a>(
+        sender: &Pubkey,
+        payments: &'a [BatchPayment],
+    ) -> Result<Vec<Vec<&'a BatchPayment>>, String> {
+        let mut batches: Vec<Vec<&BatchPayment>> = Vec::new();
+        let mut current: Vec<&BatchPayment> = Vec::new();
+
+        for payment in payments {
+            current.push(payment);
+            if Self::transaction_size(sender, &current) <= PACKET_DATA_SIZE {
+                continue;
+            }
+            current.pop();
+            if current.is_empty() {
+                return Err(format!(
+                    "Line {}: the payment does not fit in a transaction (memo too long)",
+                    payment.line
+                ));
+            }
+            batches.push(std::mem::replace(&mut current, vec![payment]));
+        }
+        if !current.is_empty() {
+            batches.push(current);
+        }
+        Ok(batches)
+    }
+
+    /// Crée le message (sans blockhash) d'une transaction de paiements, payée par l'expéditeur.
+    /// Chaque paiement est un transfert de SOL, suivi de l'instruction SPL Memo de son memo.
+    pub fn message(sender: &Pubkey, payments: &[&BatchPayment]) -> Message {
+        let mut instructions = Vec::new();
+        for payment in payments {
+            instructions.push(system_instruction::transfer(
+                sender,
+                &payment.recipient,
+                payment.lamports,
+            ));
+            if let Some(memo) = &payment.memo {
+                instructions.push(SolanaMemo::instruction(memo, sender));
+            }
+        }
+        Message::new(&instructions, Some(sender))
+    }
+
+    /// Retourne les paiements inférieurs à la réserve d'exemption de rente d'un compte système vers un compte
+    /// qui n'existe pas encore : le réseau refuserait leur transaction, avec les autres paiements qu'elle contient.
+    /// Chaque paiement est vérifié seul, car deux paiements vers le même destinataire peuvent être dans deux transactions.
+    pub fn payments_below_rent<'a>(
+        rpc: &dyn WalletRpc,
+        payments: &'a [BatchPayment],
+        rent_reserve: u64,
+    ) -> Result<Vec<&'a BatchPayment>, WalletError> {
+        let mut below_rent = Vec::new();
+        let mut missing_accounts: HashMap<Pubkey, bool> = HashMap::new();
+        for payment in payments
+            .iter()
+            .filter(|payment| payment.lamports < rent_reserve)
+        {
+            let is_missing = match missing_accounts.get(&payment.recipient) {
+                Some(is_missing) => *is_missing,
+                None => {
+                    let is_missing = rpc.get_account(&payment.recipient)?.is_none();
+                    missing_accounts.insert(payment.recipient, is_missing);
+                    is_missing
+                }
+            };
+            if is_missing {
+                below_rent.push(payment);
+            }
+        }
+        Ok(below_rent)
+    }
+
+    /// Estime les frais cumulés de plusieurs messages (en lamports).
+    pub fn estimate_fees(rpc: &dyn WalletRpc, messages: &[Message]) -> Result<u64, WalletError> {
+        let (blockhash, _) = rpc.get_latest_blockhash()?;
+        let mut fees = 0u64;
+        for message in messages {
+            let mut message = message.clone();
+            message.recent_blockhash = blockhash;
+            fees = fees.saturating_add(rpc.get_fee_for_message(&message)?);
+        }
+        Ok(fees)
+    }
+
+    /// Retourne le dernier blockhash du réseau, et la dernière hauteur de bloc à laquelle il reste valide.
+    pub fn latest_blockhash(rpc: &dyn WalletRpc) -> Result<(Hash, u64), WalletError> {
+        rpc.get_latest_blockhash()
+    }
+
+    /// Retourne la hauteur de bloc actuelle du réseau.
+    pub fn block_height(rpc: &dyn WalletRpc) -> Result<u64, WalletError> {
+        rpc.get_block_height()
+    }
+
+    /// Recherche l'état d'une transaction (y compris dans l'historique du noeud).
+    pub fn transaction_status(
+        rpc: &dyn WalletRpc,
+        signature: &Signature,
+    ) -> Result<BatchTransactionStatus, WalletError> {
+        Ok(match rpc.get_signature_status(signature)? {
+            None => BatchTransactionStatus::NotFound,
+            Some(status) => match status.err {
+                None => BatchTransactionStatus::Confirmed,
+                Some(e) => BatchTransactionStatus::Failed(e.to_string()),
+            },
+        })
+    }
+
+    fn parse_row(
+        recipient: &str,
+        amount: Option<&str>,
+        memo: Option<&str>,
+        unit: AmountUnit,
+    ) -> Result<(Pubkey, u64, Option<String>), String> {
+        let recipient = Pubkey::from_str(recipient)
+            .map_err(|_| format!("Invalid public key: {}", recipient))?;
+
+        let amount = amount
+            .filter(|amount| !amount.is_empty())
+            .ok_or("Missing amount")?;
+        let lamports = match SolAmount::parse(amount, unit)? {
+            TransferAmount::Lamports(lamports) => lamports,
+            TransferAmount::All => {
+                return Err("ALL/max is not allowed in a batch: give an exact amount".to_string())
+            }
+        };
+
+        let memo = memo.map(Self::unquote).filter(|memo| !memo.is_empty());
+        if let Some(memo) = &memo {
+            SolanaMemo::validate(memo)?;
+        }
+        Ok((recipient, lamports, memo))
+    }
+
+    /// Retire les guillemets entourant une valeur CSV ("" représente un guillemet).
+    fn unquote(value: &str) -> String {
+        match value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+        {
+            Some(value) => value.replace("\"\"", "\""),
+            None => value.to_string(),
+        }
+    }
+
+    /// Taille (en octets) de la transaction signée d'un lot de paiements.
+    fn transaction_size(sender: &Pubkey, payments: &[&BatchPayment]) -> usize {
+        let transaction = Transaction::new_unsigned(Self::message(sender, payments));
+        bincode::serialized_size(&transaction).map_or(usize::MAX, |size| size as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_csv() {
+        let recipient = Pubkey::new_unique();
+        let content = format!(
+            "recipient,amount,memo\n# Contributors\n{r},1.5\n\n{r}, 0.25 ,\"Invoice #42, May\"\n",
+            r = recipient
+        );
+        let payments = SolanaBatch::parse_csv(&content, AmountUnit::Sol).unwrap();
+        assert_eq!(payments.len(), 2);
+        assert_eq!(payments[0].line, 3);
+        assert_eq!(payments[0].lamports, 1_500_000_000);
+        assert_eq!(payments[0].memo, None);
+        assert_eq!(payments[1].line, 5);
+        assert_eq!(payments[1].lamports, 250_000_000);
+        assert_eq!(payments[1].memo.as_deref(), Some("Invoice #42, May"));
+
+        // Toutes les lignes invalides sont signalées.
+        let content = format!("not-a-key,10\n{r},ALL\n{r}\n{r},0.5\n{r},10", r = recipient);
+        let errors = SolanaBatch::parse_csv(&content, AmountUnit::Lamports).unwrap_err();
+        assert_eq!(errors.len(), 4);
+        assert_eq!(errors[0], "Line 1: Invalid public key: not-a-key");
+        assert!(errors[1].starts_with("Line 2: ALL/max is not allowed"));
+        assert_eq!(errors[2], "Line 3: Missing amount");
+        assert!(errors[3].starts_with("Line 4: Invalid amount '0.5'"));
+    }
+
+    #[test]
+    fn test_pack() {
+        let sender = Pubkey::new_unique();
+        let payments: Vec<BatchPayment> = (0..50)
+            .map(|line| BatchPayment {
+                line,
+                recipient: Pubkey::new_unique(),
+                lamports: 1_000,
+                memo: None,
+            })
+            .collect();
+
+        let batches = SolanaBatch::pack(&sender, &payments).unwrap();
+        assert!(batches.len() > 1);
+        assert_eq!(batches.iter().map(Vec::len).sum::<usize>(), 50);
+        for batch in &batches {
+            assert!(SolanaBatch::transaction_size(&sender, batch) <= PACKET_DATA_SIZE);
+        }
+        // Les paiements restent dans l'ordre du fichier.
+        assert_eq!(batches[1][0].line, batches[0].len());
+    }
+}
@@ -0,0 +1,222 @@
+use crate::error::WalletError;
+use crate::solana::batch::BatchPayment;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Version actuelle du format des fichiers de résultats des paiements groupés.
+pub const BATCH_FILE_VERSION: u32 = 1;
+
+/// État d'un paiement dans le fichier de résultats.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PaymentStatus {
+    /// Paiement pas encore envoyé (ou dont la transaction a échoué ou expiré) : il sera envoyé.
+    Unsent,
+    /// Transaction signée, peut-être diffusée : son état doit être vérifié avant tout nouvel envoi.
+    Pending,
+    /// Paiement effectué (transaction confirmée) : il ne sera plus jamais envoyé.
+    Sent,
+}
+
+/// Résultat d'un paiement.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PaymentResult {
+    pub line: usize,
+    pub recipient: String,
+    pub lamports: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    pub status: PaymentStatus,
+    /// Signature de la transaction du paiement (une transaction contient plusieurs paiements).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// Dernière hauteur de bloc à laquelle la transaction peut encore être incluse (expiration de son blockhash).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_valid_block_height: Option<u64>,
+    /// Erreur de la dernière transaction du paiement.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Fichier de résultats d'un envoi groupé ("send_batch"), enregistré après chaque changement d'état.
+///
+/// Une transaction est enregistrée comme "pending" (avec sa signature) avant d'être diffusée : après une interruption,
+/// la commande vérifie l'état de ces transactions sur le réseau au lieu de renvoyer leurs paiements.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchFile {
+    pub version: u32,
+    pub sender: String,
+    /// Empreinte SHA-256 du fichier CSV : un fichier de résultats ne peut pas être repris avec un autre CSV.
+    pub csv_sha256: String,
+    pub payments: Vec<PaymentResult>,
+    /// Montant transféré (en lamports) par chaque transaction en attente, par signature :
+    /// il compte dans le plafond de dépense du jour lorsque sa confirmation est constatée à la reprise.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub transactions: BTreeMap<String, u64>,
+}
+
+impl BatchFile {
+    /// Crée le fichier de résultats d'un CSV, avec tous ses paiements à envoyer.
+    pub fn new(sender: &str, csv_content: &str, payments: &[BatchPayment]) -> Self {
+        Self {
+            version: BATCH_FILE_VERSION,
+            sender: sender.to_string(),
+            csv_sha256: Self::digest(csv_content),
+            payments: payments
+                .iter()
+                .map(|payment| PaymentResult {
+                    line: payment.line,
+                    recipient: payment.recipient.to_string(),
+                    lamports: payment.lamports,
+                    memo: payment.memo.clone(),
+                    status: PaymentStatus::Unsent,
+                    signature: None,
+                    last_valid_block_height: None,
+                    error: None,
+                })
+                .collect(),
+            transactions: BTreeMap::new(),
+        }
+    }
+
+    /// Charge un fichier de résultats (None s'il n'existe pas), en vérifiant sa version.
+    pub fn load(file_path: &str) -> Result<Option<Self>, WalletError> {
+        if !Path::new(file_path).exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(file_path).map_err(|e| {
+            WalletError::File(format!("Failed to read results file {}: {}", file_path, e))
+        })?;
+        let file: BatchFile = serde_json::from_str(&content)
+            .map_err(|e| WalletError::File(format!("Invalid results file {}: {}", file_path, e)))?;
+        if file.version != BATCH_FILE_VERSION {
+            return Err(WalletError::File(format!(
+                "Unsupported results file version: {}",
+                file.version
+            )));
+        }
+        Ok(Some(file))
+    }
+
+    /// Enregistre le fichier de résultats. Il est écrit dans un fichier temporaire puis renommé :
+    /// une interruption pendant l'écriture ne peut pas le corrompre.
+    pub fn save(&self, file_path: &str) -> Result<(), WalletError> {
+        if let Some(parent) = Path::new(file_path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let temp_path = format!("{}.tmp", file_path);
+        fs::write(&temp_path, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&temp_path, file_path)?;
+        Ok(())
+    }
+
+    /// Vérifie que le fichier de résultats a été créé pour ce CSV et cet expéditeur.
+    pub fn check(&self, sender: &str, csv_content: &str) -> Result<(), String> {
+        if self.csv_sha256 != Self::digest(csv_content) {
+            return Err(
+                "the CSV file was modified since the batch was started (use another --results file for a new batch)"
+                    .to_string(),
+            );
+        }
+        if self.sender != sender {
+            return Err(format!(
+                "the batch was started by another sender ({})",
+                self.sender
+            ));
+        }
+        Ok(())
+    }
+
+    /// Retourne les signatures distinctes des transactions en attente, avec leur dernière hauteur de bloc valide.
+    pub fn pending_transactions(&self) -> Vec<(String, u64)> {
+        let mut transactions: Vec<(String, u64)> = Vec::new();
+        for payment in &self.payments {
+            if payment.status != PaymentStatus::Pending {
+                continue;
+            }
+            if let Some(signature) = &payment.signature {
+                if !transactions.iter().any(|(known, _)| known == signature) {
+                    transactions.push((
+                        signature.clone(),
+                        payment.last_valid_block_height.unwrap_or(u64::MAX),
+                    ));
+                }
+            }
+        }
+        transactions
+    }
+
+    /// Retourne le montant transféré (en lamports) par une transaction en attente. Pour un fichier
+    /// qui ne l'a pas enregistré, c'est la somme des paiements de la transaction.
+    pub fn transaction_lamports(&self, signature: &str) -> u64 {
+        self.transactions
+            .get(signature)
+            .copied()
+            .unwrap_or_else(|| {
+                self.payments
+                    .iter()
+                    .filter(|payment| payment.signature.as_deref() == Some(signature))
+                    .fold(0u64, |total, payment| {
+                        total.saturating_add(payment.lamports)
+                    })
+            })
+    }
+
+    /// Change l'état des paiements d'une transaction (qui n'est alors plus en attente).
+    ///
+    /// # Returns:
+    /// Retourne le nombre de paiements modifiés.
+    pub fn set_transaction_status(
+        &mut self,
+        signature: &str,
+        status: PaymentStatus,
+        error: Option<String>,
+    ) -> usize {
+        self.transactions.remove(signature);
+        let mut count = 0;
+        for payment in &mut self.payments {
+            if payment.signature.as_deref() == Some(signature) {
+                payment.status = status;
+                payment.error = error.clone();
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Marque les paiements des lignes données comme en attente de la transaction "signature",
+    /// qui transfère "lamports" au total.
+    pub fn set_pending(
+        &mut self,
+        lines: &[usize],
+        signature: &str,
+        lamports: u64,
+        last_valid_block_height: u64,
+    ) {
+        self.transactions.insert(signature.to_string(), lamports);
+        for payment in &mut self.payments {
+            if lines.contains(&payment.line) {
+                payment.status = PaymentStatus::Pending;
+                payment.signature = Some(signature.to_string());
+                payment.last_valid_block_height = Some(last_valid_block_height);
+                payment.error = None;
+            }
+        }
+    }
+
+    /// Retourne les lignes des paiements dans un état donné.
+    pub fn lines_with_status(&self, status: PaymentStatus) -> Vec<usize> {
+        self.payments
+            .iter()
+            .filter(|payment| payment.status == status)
+            .map(|payment| payment.line)
+            .collect()
+    }
+
+    fn digest(content: &str) -> String {
+        format!("{:x}", Sha256::digest(content.as_bytes()))
+    }
+}
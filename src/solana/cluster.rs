@@ -0,0 +1,89 @@
+use crate::error::WalletError;
+use crate::solana::rpc::WalletRpc;
+use solana_sdk::hash::Hash;
+use std::fmt;
+use std::str::FromStr;
+
+/// Hash du bloc genesis de mainnet-beta.
+pub const MAINNET_BETA_GENESIS_HASH: &str = "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d";
+/// Hash du bloc genesis de devnet.
+pub const DEVNET_GENESIS_HASH: &str = "EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG";
+/// Hash du bloc genesis de testnet.
+pub const TESTNET_GENESIS_HASH: &str = "4uhcVJyU9pJkvQyS88uRDiswHXSCkY3zQawwpjk2NsNY";
+
+/// Réseau Solana, identifié par le hash de son bloc genesis (et non par l'URL du noeud RPC, qui peut être quelconque).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Cluster {
+    MainnetBeta,
+    Devnet,
+    Testnet,
+    /// Autre réseau (ex : validateur local), avec le hash de son bloc genesis.
+    Unknown(Hash),
+}
+
+impl Cluster {
+    /// Retourne le réseau correspondant au hash d'un bloc genesis.
+    pub fn from_genesis_hash(genesis_hash: &Hash) -> Self {
+        let known = |hash: &str| Hash::from_str(hash).ok().as_ref() == Some(genesis_hash);
+        if known(MAINNET_BETA_GENESIS_HASH) {
+            Cluster::MainnetBeta
+        } else if known(DEVNET_GENESIS_HASH) {
+            Cluster::Devnet
+        } else if known(TESTNET_GENESIS_HASH) {
+            Cluster::Testnet
+        } else {
+            Cluster::Unknown(*genesis_hash)
+        }
+    }
+
+    /// Interroge le noeud RPC pour identifier son réseau.
+    ///
+    /// # Arguments:
+    /// - rpc - Le client RPC du réseau Solana.
+    pub fn detect(rpc: &dyn WalletRpc) -> Result<Self, WalletError> {
+        let genesis_hash = rpc
+            .get_genesis_hash()
+            .map_err(|e| e.context("Failed to detect the cluster"))?;
+        Ok(Self::from_genesis_hash(&genesis_hash))
+    }
+
+    /// Indique si le réseau est mainnet-beta (des fonds réels).
+    pub fn is_mainnet(&self) -> bool {
+        *self == Cluster::MainnetBeta
+    }
+}
+
+impl fmt::Display for Cluster {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Cluster::MainnetBeta => write!(f, "mainnet-beta"),
+            Cluster::Devnet => write!(f, "devnet"),
+            Cluster::Testnet => write!(f, "testnet"),
+            Cluster::Unknown(genesis_hash) => write!(f, "unknown (genesis hash {})", genesis_hash),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solana::mock_rpc::MockRpc;
+
+    #[test]
+    fn test_detect() {
+        let rpc = MockRpc::new();
+        assert_eq!(
+            Cluster::detect(&rpc).unwrap(),
+            Cluster::Unknown(MockRpc::genesis_hash())
+        );
+
+        let mainnet = Hash::from_str(MAINNET_BETA_GENESIS_HASH).unwrap();
+        assert_eq!(Cluster::from_genesis_hash(&mainnet), Cluster::MainnetBeta);
+        assert!(Cluster::from_genesis_hash(&mainnet).is_mainnet());
+        assert_eq!(
+            Cluster::from_genesis_hash(&Hash::from_str(DEVNET_GENESIS_HASH).unwrap()).to_string(),
+            "devnet"
+        );
+        assert!(!Cluster::Testnet.is_mainnet());
+    }
+}
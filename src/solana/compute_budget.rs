@@ -0,0 +1,245 @@
+use crate::error::WalletError;
+use crate::solana::rpc::WalletRpc;
+use solana_sdk::{
+    borsh1::try_from_slice_unchecked,
+    compute_budget::{self, ComputeBudgetInstruction},
+    instruction::Instruction,
+    message::Message,
+    pubkey::Pubkey,
+};
+
+/// Percentile des frais de priorité récents utilisé par défaut en mode "auto".
+pub const DEFAULT_PRIORITY_FEE_PERCENTILE: u8 = 75;
+/// Limite de calcul appliquée par le runtime à chaque instruction, sans instruction "SetComputeUnitLimit".
+pub const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+/// Limite de calcul maximale d'une transaction.
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+/// Frais de base d'une transaction, par signature requise (en lamports).
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+
+/// Frais de priorité demandés avec "--priority-fee".
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PriorityFee {
+    /// Prix fixe d'une unité de calcul (en micro-lamports).
+    MicroLamports(u64),
+    /// Prix estimé à partir des frais de priorité récents ("getRecentPrioritizationFees").
+    Auto,
+}
+
+impl PriorityFee {
+    /// Parse un prix en micro-lamports par unité de calcul, ou "auto".
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let input = input.trim();
+        if input.eq_ignore_ascii_case("auto") {
+            return Ok(PriorityFee::Auto);
+        }
+        input
+            .parse::<u64>()
+            .map(PriorityFee::MicroLamports)
+            .map_err(|_| {
+                format!(
+                    "Invalid priority fee '{}': expected a number of micro-lamports per compute unit, or \"auto\"",
+                    input
+                )
+            })
+    }
+}
+
+/// Instructions "ComputeBudget" ajoutées à une transaction.
+///
+/// Sans limite explicite, le runtime applique 200 000 unités par instruction : c'est sur cette
+/// limite que les frais de priorité sont payés, d'où l'intérêt de "--compute-unit-limit".
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ComputeBudget {
+    /// Nombre maximal d'unités de calcul de la transaction.
+    pub unit_limit: Option<u32>,
+    /// Prix d'une unité de calcul (en micro-lamports).
+    pub unit_price: Option<u64>,
+}
+
+impl ComputeBudget {
+    /// Retourne les instructions "ComputeBudget" à ajouter à la transaction (aucune par défaut).
+    pub fn instructions(&self) -> Vec<Instruction> {
+        let mut instructions = Vec::new();
+        if let Some(unit_limit) = self.unit_limit {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_limit(unit_limit));
+        }
+        if let Some(unit_price) = self.unit_price.filter(|price| *price > 0) {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_price(unit_price));
+        }
+        instructions
+    }
+
+    /// Relit les instructions "ComputeBudget" d'un message (la dernière l'emporte, comme pour le runtime).
+    pub fn from_message(message: &Message) -> Self {
+        let mut budget = ComputeBudget::default();
+        for instruction in &message.instructions {
+            if message
+                .account_keys
+                .get(usize::from(instruction.program_id_index))
+                != Some(&compute_budget::id())
+            {
+                continue;
+            }
+            match try_from_slice_unchecked::<ComputeBudgetInstruction>(&instruction.data) {
+                Ok(ComputeBudgetInstruction::SetComputeUnitLimit(units)) => {
+                    budget.unit_limit = Some(units.min(MAX_COMPUTE_UNIT_LIMIT))
+                }
+                Ok(ComputeBudgetInstruction::SetComputeUnitPrice(micro_lamports)) => {
+                    budget.unit_price = Some(micro_lamports)
+                }
+                _ => {}
+            }
+        }
+        budget
+    }
+
+    /// Calcule les frais d'un message sans accès au réseau : frais de base par signature,
+    /// plus les frais de priorité de ses propres instructions "ComputeBudget".
+    pub fn fee_for_message(message: &Message) -> u64 {
+        let signatures = u64::from(message.header.num_required_signatures);
+        signatures
+            .saturating_mul(LAMPORTS_PER_SIGNATURE)
+            .saturating_add(Self::from_message(message).priority_fee(message))
+    }
+
+    /// Retourne la limite d'unités de calcul d'un message : la limite explicite,
+    /// sinon celle appliquée par le runtime (200 000 unités par instruction, hors "ComputeBudget").
+    pub fn unit_limit_for(&self, message: &Message) -> u32 {
+        self.unit_limit.unwrap_or_else(|| {
+            let instructions = message
+                .instructions
+                .iter()
+                .filter(|instruction| {
+                    message
+                        .account_keys
+                        .get(instruction.program_id_index as usize)
+                        != Some(&compute_budget::id())
+                })
+                .count() as u32;
+            instructions
+                .saturating_mul(DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT)
+                .min(MAX_COMPUTE_UNIT_LIMIT)
+        })
+    }
+
+    /// Retourne les frais de priorité d'un message (en lamports) : prix × limite d'unités de calcul, arrondi au supérieur.
+    pub fn priority_fee(&self, message: &Message) -> u64 {
+        let unit_price = u128::from(self.unit_price.unwrap_or(0));
+        let micro_lamports = unit_price * u128::from(self.unit_limit_for(message));
+        let lamports = micro_lamports.div_ceil(MICRO_LAMPORTS_PER_LAMPORT);
+        u64::try_from(lamports).unwrap_or(u64::MAX)
+    }
+}
+
+/// Prix estimé à partir des frais de priorité récents.
+pub struct RecentPriorityFee {
+    /// Prix d'une unité de calcul (en micro-lamports).
+    pub micro_lamports: u64,
+    pub percentile: u8,
+    /// Nombre de slots récents pris en compte.
+    pub slots: usize,
+}
+
+pub struct SolanaComputeBudget {}
+
+impl SolanaComputeBudget {
+    /// Estime le prix d'une unité de calcul à partir des frais payés dans les slots récents
+    /// par les transactions qui écrivent dans les mêmes comptes.
+    ///
+    /// # Arguments:
+    /// - rpc - Le client RPC du réseau Solana.
+    /// - writable_accounts - Les comptes modifiés par la transaction.
+    /// - percentile - Le percentile des frais récents à retenir (0 à 100).
+    pub fn recent_priority_fee(
+        rpc: &dyn WalletRpc,
+        writable_accounts: &[Pubkey],
+        percentile: u8,
+    ) -> Result<RecentPriorityFee, WalletError> {
+        let mut fees = rpc
+            .get_recent_prioritization_fees(writable_accounts)
+            .map_err(|e| e.context("Failed to fetch recent prioritization fees"))?;
+
+        Ok(RecentPriorityFee {
+            micro_lamports: Self::percentile(&mut fees, percentile),
+            percentile,
+            slots: fees.len(),
+        })
+    }
+
+    /// Retourne le percentile (méthode du rang le plus proche) d'une liste de frais, ou 0 si elle est vide.
+    fn percentile(fees: &mut [u64], percentile: u8) -> u64 {
+        if fees.is_empty() {
+            return 0;
+        }
+        fees.sort_unstable();
+        let rank = (usize::from(percentile.min(100)) * fees.len()).div_ceil(100);
+        fees[rank.saturating_sub(1)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::system_instruction;
+
+    #[test]
+    fn test_parse_priority_fee() {
+        assert_eq!(PriorityFee::parse("auto"), Ok(PriorityFee::Auto));
+        assert_eq!(PriorityFee::parse("AUTO"), Ok(PriorityFee::Auto));
+        assert_eq!(
+            PriorityFee::parse("1000"),
+            Ok(PriorityFee::MicroLamports(1000))
+        );
+        assert!(PriorityFee::parse("-1").is_err());
+        assert!(PriorityFee::parse("0.5").is_err());
+    }
+
+    #[test]
+    fn test_priority_fee() {
+        let payer = Pubkey::new_unique();
+        let transfer = system_instruction::transfer(&payer, &Pubkey::new_unique(), 1);
+        let message = |budget: &ComputeBudget| {
+            let mut instructions = budget.instructions();
+            instructions.push(transfer.clone());
+            Message::new(&instructions, Some(&payer))
+        };
+
+        // Sans limite : 200 000 unités pour l'unique instruction de transfert (hors "ComputeBudget").
+        let budget = ComputeBudget {
+            unit_limit: None,
+            unit_price: Some(10_000),
+        };
+        assert_eq!(budget.unit_limit_for(&message(&budget)), 200_000);
+        assert_eq!(budget.priority_fee(&message(&budget)), 2_000);
+
+        // Arrondi au lamport supérieur.
+        let budget = ComputeBudget {
+            unit_limit: Some(450),
+            unit_price: Some(1_001),
+        };
+        assert_eq!(budget.instructions().len(), 2);
+        assert_eq!(budget.priority_fee(&message(&budget)), 1);
+
+        assert!(ComputeBudget::default().instructions().is_empty());
+        assert_eq!(ComputeBudget::default().priority_fee(&message(&budget)), 0);
+
+        // Relecture des instructions d'un message (ex : un fichier de transaction à signer).
+        assert_eq!(ComputeBudget::from_message(&message(&budget)), budget);
+        assert_eq!(
+            ComputeBudget::fee_for_message(&message(&budget)),
+            LAMPORTS_PER_SIGNATURE + 1
+        );
+    }
+
+    #[test]
+    fn test_percentile() {
+        let mut fees = vec![500, 0, 100, 300, 200];
+        assert_eq!(SolanaComputeBudget::percentile(&mut fees, 0), 0);
+        assert_eq!(SolanaComputeBudget::percentile(&mut fees, 50), 200);
+        assert_eq!(SolanaComputeBudget::percentile(&mut fees, 75), 300);
+        assert_eq!(SolanaComputeBudget::percentile(&mut fees, 100), 500);
+        assert_eq!(SolanaComputeBudget::percentile(&mut [], 75), 0);
+    }
+}
@@ -0,0 +1,402 @@
+use crate::error::WalletError;
+use crate::solana::amount::SolAmount;
+use crate::solana::memo::SolanaMemo;
+use crate::solana::rpc::WalletRpc;
+use chrono::{DateTime, SecondsFormat};
+use serde::Serialize;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, UiLoadedAddresses, UiTransactionTokenBalance,
+};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+/// Nombre maximal de signatures retournées par un appel à "getSignaturesForAddress".
+const SIGNATURES_PAGE_SIZE: usize = 1000;
+/// Nom de l'actif des variations de balance en SOL (les tokens sont identifiés par leur mint).
+pub const SOL_ASSET: &str = "SOL";
+
+/// Variation de la balance d'un actif (SOL ou token SPL) pour l'adresse consultée.
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct BalanceChange {
+    /// "SOL", ou l'adresse du mint du token.
+    pub asset: String,
+    /// Variation en unités de base (lamports pour SOL), hors frais de transaction.
+    pub delta: i128,
+    pub decimals: u8,
+    /// Compte dont la balance a varié en sens inverse (l'expéditeur ou le destinataire), s'il est identifiable.
+    pub counterparty: Option<String>,
+}
+
+impl BalanceChange {
+    /// Retourne la variation avec ses décimales et son signe (ex : "+1.500000000").
+    pub fn ui_delta(&self) -> String {
+        let sign = if self.delta < 0 { "-" } else { "+" };
+        let amount = u64::try_from(self.delta.unsigned_abs()).unwrap_or(u64::MAX);
+        format!(
+            "{}{}",
+            sign,
+            SolAmount::format_decimal(amount, self.decimals)
+        )
+    }
+}
+
+/// Transaction de l'historique d'une adresse.
+#[derive(Clone, Debug, Serialize, PartialEq, Eq)]
+pub struct HistoryEntry {
+    pub signature: String,
+    pub slot: u64,
+    /// Date du bloc (Unix timestamp), si le noeud la connaît.
+    pub block_time: Option<i64>,
+    /// Frais payés par l'adresse (0 si elle n'est pas le payeur des frais).
+    pub fee: u64,
+    /// Erreur de la transaction, ou None si elle a réussi.
+    pub error: Option<String>,
+    pub changes: Vec<BalanceChange>,
+    /// Memos (SPL Memo) de la transaction.
+    pub memos: Vec<String>,
+}
+
+impl HistoryEntry {
+    /// Retourne la date du bloc au format RFC 3339 (UTC), ou "-" si elle est inconnue.
+    pub fn time(&self) -> String {
+        self.block_time
+            .and_then(|time| DateTime::from_timestamp(time, 0))
+            .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true))
+            .unwrap_or_else(|| "-".to_string())
+    }
+
+    /// Retourne les memos de la transaction, séparés par "; " (vide sans memo).
+    pub fn memo(&self) -> String {
+        self.memos.join("; ")
+    }
+
+    pub fn status(&self) -> &'static str {
+        if self.error.is_some() {
+            "failed"
+        } else {
+            "ok"
+        }
+    }
+}
+
+/// Options de pagination de l'historique.
+#[derive(Clone, Debug, Default)]
+pub struct HistoryPage {
+    /// Commence avant cette signature (exclue) : la page suivante d'un historique.
+    pub before: Option<Signature>,
+    /// S'arrête à cette signature (exclue).
+    pub until: Option<Signature>,
+    /// Nombre maximal de transactions.
+    pub limit: usize,
+}
+
+pub struct SolanaHistory {}
+
+impl SolanaHistory {
+    /// Retourne les dernières transactions d'une adresse (de la plus récente à la plus ancienne),
+    /// avec les variations de balance (SOL et tokens SPL) de cette adresse.
+    ///
+    /// # Arguments:
+    /// - rpc - Le client RPC du réseau Solana.
+    /// - address - L'adresse consultée.
+    /// - page - Les options de pagination ("before", "until" et "limit").
+    pub fn fetch(
+        rpc: &dyn WalletRpc,
+        address: &Pubkey,
+        page: &HistoryPage,
+    ) -> Result<Vec<HistoryEntry>, WalletError> {
+        // "getSignaturesForAddress" retourne au plus 1000 signatures par appel : on pagine avec "before".
+        let mut signatures = Vec::new();
+        let mut before = page.before;
+        while signatures.len() < page.limit {
+            let limit = (page.limit - signatures.len()).min(SIGNATURES_PAGE_SIZE);
+            let statuses = rpc
+                .get_signatures_for_address(address, before, page.until, limit)
+                .map_err(|e| {
+                    e.context(&format!("Failed to fetch the signatures of {}", address))
+                })?;
+            let last_page = statuses.len() < limit;
+            for status in statuses {
+                let signature = Signature::from_str(&status.signature)?;
+                before = Some(signature);
+                signatures.push(signature);
+            }
+            if last_page {
+                break;
+            }
+        }
+
+        signatures
+            .iter()
+            .map(|signature| {
+                let transaction = rpc.get_transaction(signature).map_err(|e| {
+                    e.context(&format!("Failed to fetch transaction {}", signature))
+                })?;
+                Self::decode(address, &transaction)
+            })
+            .collect()
+    }
+
+    /// Décode une transaction : frais, statut, et variations de balance de l'adresse consultée.
+    pub fn decode(
+        address: &Pubkey,
+        transaction: &EncodedConfirmedTransactionWithStatusMeta,
+    ) -> Result<HistoryEntry, WalletError> {
+        let decoded = transaction
+            .transaction
+            .transaction
+            .decode()
+            .ok_or("Unsupported transaction encoding")?;
+        let signature = decoded
+            .signatures
+            .first()
+            .ok_or("Transaction without signature")?
+            .to_string();
+        let meta = transaction
+            .transaction
+            .meta
+            .as_ref()
+            .ok_or_else(|| format!("Transaction {} has no status metadata", signature))?;
+
+        // Comptes de la transaction : statiques, puis chargés depuis des tables d'adresses (transactions v0).
+        let mut account_keys: Vec<String> = decoded
+            .message
+            .static_account_keys()
+            .iter()
+            .map(Pubkey::to_string)
+            .collect();
+        let loaded_addresses: Option<UiLoadedAddresses> = meta.loaded_addresses.clone().into();
+        if let Some(loaded) = loaded_addresses {
+            account_keys.extend(loaded.writable);
+            account_keys.extend(loaded.readonly);
+        }
+
+        let address = address.to_string();
+        let fee_payer = account_keys.first() == Some(&address);
+        let fee = if fee_payer { meta.fee } else { 0 };
+
+        let mut changes = Vec::new();
+
+        // Variation en SOL, hors frais (payés par le premier compte).
+        let sol_deltas: Vec<i128> = meta
+            .pre_balances
+            .iter()
+            .zip(&meta.post_balances)
+            .enumerate()
+            .map(|(index, (pre, post))| {
+                let fee = if index == 0 { meta.fee } else { 0 };
+                i128::from(*post) - i128::from(*pre) + i128::from(fee)
+            })
+            .collect();
+        if let Some(index) = account_keys.iter().position(|key| *key == address) {
+            let delta = sol_deltas.get(index).copied().unwrap_or(0);
+            if delta != 0 {
+                let counterparty = Self::counterparty(delta, index, &sol_deltas)
+                    .and_then(|index| account_keys.get(index).cloned());
+                changes.push(BalanceChange {
+                    asset: SOL_ASSET.to_string(),
+                    delta,
+                    decimals: 9,
+                    counterparty,
+                });
+            }
+        }
+
+        // Variations des tokens SPL dont l'adresse est propriétaire, par mint.
+        let pre_token_balances: Vec<UiTransactionTokenBalance> =
+            Option::from(meta.pre_token_balances.clone()).unwrap_or_default();
+        let post_token_balances: Vec<UiTransactionTokenBalance> =
+            Option::from(meta.post_token_balances.clone()).unwrap_or_default();
+        let mut token_deltas: BTreeMap<String, (u8, BTreeMap<String, i128>)> = BTreeMap::new();
+        for (balances, sign) in [(&pre_token_balances, -1), (&post_token_balances, 1)] {
+            for balance in balances {
+                let owner: Option<String> = Option::from(balance.owner.clone());
+                let owner = owner.unwrap_or_else(|| {
+                    account_keys
+                        .get(usize::from(balance.account_index))
+                        .cloned()
+                        .unwrap_or_default()
+                });
+                let amount: i128 = balance.ui_token_amount.amount.parse().map_err(|_| {
+                    format!("Invalid token amount: {}", balance.ui_token_amount.amount)
+                })?;
+                let (_, owners) = token_deltas
+                    .entry(balance.mint.clone())
+                    .or_insert((balance.ui_token_amount.decimals, BTreeMap::new()));
+                *owners.entry(owner).or_default() += sign * amount;
+            }
+        }
+        for (mint, (decimals, owners)) in token_deltas {
+            let Some(delta) = owners.get(&address).copied().filter(|delta| *delta != 0) else {
+                continue;
+            };
+            let counterparty = owners
+                .iter()
+                .filter(|(owner, owner_delta)| {
+                    **owner != address && owner_delta.signum() == -delta.signum()
+                })
+                .max_by_key(|(_, owner_delta)| owner_delta.abs())
+                .map(|(owner, _)| owner.clone());
+            changes.push(BalanceChange {
+                asset: mint,
+                delta,
+                decimals,
+                counterparty,
+            });
+        }
+
+        Ok(HistoryEntry {
+            signature,
+            slot: transaction.slot,
+            block_time: transaction.block_time,
+            fee,
+            error: meta.err.as_ref().map(ToString::to_string),
+            changes,
+            memos: SolanaMemo::decode(
+                decoded.message.static_account_keys(),
+                decoded.message.instructions(),
+            ),
+        })
+    }
+
+    /// Retourne l'index du compte dont la balance a le plus varié en sens inverse de celle de l'adresse.
+    fn counterparty(delta: i128, index: usize, deltas: &[i128]) -> Option<usize> {
+        deltas
+            .iter()
+            .enumerate()
+            .filter(|(other, other_delta)| {
+                *other != index && other_delta.signum() == -delta.signum()
+            })
+            .max_by_key(|(_, other_delta)| other_delta.abs())
+            .map(|(other, _)| other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use solana_sdk::{
+        hash::Hash, message::Message, signature::Keypair, signer::Signer, system_instruction,
+        transaction::Transaction,
+    };
+
+    /// Transaction confirmée (encodée en base64) avec ses métadonnées.
+    fn confirmed_transaction(
+        transaction: &Transaction,
+        meta: serde_json::Value,
+    ) -> EncodedConfirmedTransactionWithStatusMeta {
+        use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+        let data = BASE64.encode(bincode::serialize(transaction).unwrap());
+        serde_json::from_value(json!({
+            "slot": 42,
+            "blockTime": 1_700_000_000,
+            "transaction": [data, "base64"],
+            "meta": meta,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_decode_sol_transfer() {
+        let sender = Keypair::new();
+        let recipient = Pubkey::new_unique();
+        let message = Message::new(
+            &[system_instruction::transfer(
+                &sender.pubkey(),
+                &recipient,
+                1_000_000,
+            )],
+            Some(&sender.pubkey()),
+        );
+        let transaction = Transaction::new(&[&sender], message, Hash::default());
+        let transaction = confirmed_transaction(
+            &transaction,
+            json!({
+                "err": null,
+                "status": { "Ok": null },
+                "fee": 5000,
+                "preBalances": [10_000_000, 0, 1],
+                "postBalances": [8_995_000, 1_000_000, 1],
+            }),
+        );
+
+        let sent = SolanaHistory::decode(&sender.pubkey(), &transaction).unwrap();
+        assert_eq!(sent.fee, 5000);
+        assert_eq!(sent.status(), "ok");
+        assert_eq!(sent.time(), "2023-11-14T22:13:20Z");
+        assert_eq!(
+            sent.changes,
+            vec![BalanceChange {
+                asset: SOL_ASSET.to_string(),
+                delta: -1_000_000,
+                decimals: 9,
+                counterparty: Some(recipient.to_string()),
+            }]
+        );
+        assert_eq!(sent.changes[0].ui_delta(), "-0.001000000");
+
+        // Le destinataire ne paie pas les frais.
+        let received = SolanaHistory::decode(&recipient, &transaction).unwrap();
+        assert_eq!(received.fee, 0);
+        assert_eq!(received.changes[0].ui_delta(), "+0.001000000");
+        assert_eq!(
+            received.changes[0].counterparty,
+            Some(sender.pubkey().to_string())
+        );
+    }
+
+    #[test]
+    fn test_decode_token_transfer() {
+        let sender = Keypair::new();
+        let recipient = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let (source, destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let message = Message::new(
+            &[system_instruction::transfer(&sender.pubkey(), &source, 0)],
+            Some(&sender.pubkey()),
+        );
+        let mut transaction = Transaction::new_unsigned(message);
+        transaction.message.account_keys.push(destination);
+        transaction.sign(&[&sender], Hash::default());
+
+        let token_balance = |index: u8, owner: &Pubkey, amount: u64| {
+            json!({
+                "accountIndex": index,
+                "mint": mint.to_string(),
+                "owner": owner.to_string(),
+                "uiTokenAmount": {
+                    "amount": amount.to_string(),
+                    "decimals": 6,
+                    "uiAmount": null,
+                    "uiAmountString": amount.to_string()
+                }
+            })
+        };
+        let transaction = confirmed_transaction(
+            &transaction,
+            json!({
+                "err": { "InstructionError": [0, { "Custom": 1 }] },
+                "status": { "Err": { "InstructionError": [0, { "Custom": 1 }] } },
+                "fee": 5000,
+                "preBalances": [10_000_000, 2_039_280, 1, 2_039_280],
+                "postBalances": [9_995_000, 2_039_280, 1, 2_039_280],
+                "preTokenBalances": [token_balance(1, &sender.pubkey(), 2_500_000), token_balance(3, &recipient, 0)],
+                "postTokenBalances": [token_balance(1, &sender.pubkey(), 1_000_000), token_balance(3, &recipient, 1_500_000)],
+            }),
+        );
+
+        let entry = SolanaHistory::decode(&sender.pubkey(), &transaction).unwrap();
+        assert_eq!(entry.status(), "failed");
+        assert_eq!(
+            entry.changes,
+            vec![BalanceChange {
+                asset: mint.to_string(),
+                delta: -1_500_000,
+                decimals: 6,
+                counterparty: Some(recipient.to_string()),
+            }]
+        );
+    }
+}
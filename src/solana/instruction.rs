@@ -0,0 +1,365 @@
+use crate::solana::amount::SolAmount;
+use crate::solana::memo::SolanaMemo;
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    borsh1::try_from_slice_unchecked,
+    compute_budget::{self, ComputeBudgetInstruction},
+    instruction::CompiledInstruction,
+    pubkey::Pubkey,
+    stake::{self, instruction::StakeInstruction},
+    system_instruction::SystemInstruction,
+    system_program,
+};
+use spl_token_2022::instruction::TokenInstruction;
+
+/// Instruction d'une transaction, décodée pour être relue avant la signature.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InstructionSummary {
+    pub program_id: String,
+    /// Nom du programme, s'il est connu du wallet (ex : "System Program").
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub program: Option<String>,
+    /// Comptes de l'instruction, dans l'ordre.
+    pub accounts: Vec<String>,
+    /// Description de l'instruction, ou None si elle n'a pas pu être décodée.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// SOL déplacé par l'instruction (transfert, création de compte, retrait), en lamports.
+    #[serde(default)]
+    pub lamports: u64,
+}
+
+impl InstructionSummary {
+    /// Indique si l'instruction a été décodée.
+    pub fn is_decoded(&self) -> bool {
+        self.description.is_some()
+    }
+}
+
+/// Description décodée d'une instruction, et le SOL qu'elle déplace.
+struct Decoded {
+    description: String,
+    lamports: u64,
+}
+
+impl Decoded {
+    fn new(description: String) -> Self {
+        Self {
+            description,
+            lamports: 0,
+        }
+    }
+
+    fn moving(description: String, lamports: u64) -> Self {
+        Self {
+            description,
+            lamports,
+        }
+    }
+}
+
+pub struct SolanaInstruction {}
+
+impl SolanaInstruction {
+    /// Décode une instruction compilée : programme, comptes et, pour les programmes connus, sa description.
+    ///
+    /// # Arguments:
+    /// - account_keys - Les comptes du message.
+    /// - instruction - L'instruction compilée.
+    pub fn summarize(
+        account_keys: &[Pubkey],
+        instruction: &CompiledInstruction,
+    ) -> InstructionSummary {
+        let key = |index: &u8| {
+            account_keys
+                .get(usize::from(*index))
+                .map(Pubkey::to_string)
+                .unwrap_or_else(|| format!("<invalid account index {}>", index))
+        };
+        let program_id = account_keys
+            .get(usize::from(instruction.program_id_index))
+            .copied()
+            .unwrap_or_default();
+        let accounts: Vec<String> = instruction.accounts.iter().map(key).collect();
+        let decoded = Self::decode(&program_id, &accounts, &instruction.data);
+
+        InstructionSummary {
+            program_id: program_id.to_string(),
+            program: Self::program_name(&program_id).map(str::to_string),
+            accounts,
+            lamports: decoded.as_ref().map_or(0, |decoded| decoded.lamports),
+            description: decoded.map(|decoded| decoded.description),
+        }
+    }
+
+    /// Retourne le nom d'un programme connu du wallet.
+    pub fn program_name(program_id: &Pubkey) -> Option<&'static str> {
+        let name = match *program_id {
+            id if id == system_program::id() => "System Program",
+            id if id == compute_budget::id() => "Compute Budget Program",
+            id if id == stake::program::id() => "Stake Program",
+            id if id == spl_token::id() => "Token Program",
+            id if id == spl_token_2022::id() => "Token-2022 Program",
+            id if id == spl_associated_token_account::id() => "Associated Token Account Program",
+            id if id == spl_memo::id() || id == spl_memo::v1::id() => "Memo Program",
+            _ => return None,
+        };
+        Some(name)
+    }
+
+    /// Décode les données d'une instruction d'un programme connu (None si le programme ou les données sont inconnus).
+    fn decode(program_id: &Pubkey, accounts: &[String], data: &[u8]) -> Option<Decoded> {
+        let account = |index: usize| {
+            accounts
+                .get(index)
+                .cloned()
+                .unwrap_or_else(|| "<missing account>".to_string())
+        };
+        let sol = |lamports: u64| {
+            format!(
+                "{} SOL ({} lamports)",
+                SolAmount::format_sol(lamports),
+                lamports
+            )
+        };
+
+        if *program_id == system_program::id() {
+            let decoded = match bincode::deserialize::<SystemInstruction>(data).ok()? {
+                SystemInstruction::Transfer { lamports } => Decoded::moving(
+                    format!(
+                        "Transfer {} from {} to {}",
+                        sol(lamports),
+                        account(0),
+                        account(1)
+                    ),
+                    lamports,
+                ),
+                SystemInstruction::TransferWithSeed { lamports, .. } => Decoded::moving(
+                    format!(
+                        "Transfer {} from {} to {}",
+                        sol(lamports),
+                        account(0),
+                        account(2)
+                    ),
+                    lamports,
+                ),
+                SystemInstruction::CreateAccount {
+                    lamports,
+                    space,
+                    owner,
+                }
+                | SystemInstruction::CreateAccountWithSeed {
+                    lamports,
+                    space,
+                    owner,
+                    ..
+                } => Decoded::moving(
+                    format!(
+                        "Create account {} with {} ({} bytes, owner {}), funded by {}",
+                        account(1),
+                        sol(lamports),
+                        space,
+                        owner,
+                        account(0)
+                    ),
+                    lamports,
+                ),
+                SystemInstruction::WithdrawNonceAccount(lamports) => Decoded::moving(
+                    format!(
+                        "Withdraw {} from nonce account {} to {}",
+                        sol(lamports),
+                        account(0),
+                        account(1)
+                    ),
+                    lamports,
+                ),
+                SystemInstruction::Assign { owner }
+                | SystemInstruction::AssignWithSeed { owner, .. } => Decoded::new(format!(
+                    "Assign account {} to program {}",
+                    account(0),
+                    owner
+                )),
+                SystemInstruction::AdvanceNonceAccount => {
+                    Decoded::new(format!("Advance nonce account {}", account(0)))
+                }
+                SystemInstruction::AuthorizeNonceAccount(authority) => Decoded::new(format!(
+                    "Set the authority of nonce account {} to {}",
+                    account(0),
+                    authority
+                )),
+                other => Decoded::new(format!("{:?}", other)),
+            };
+            return Some(decoded);
+        }
+
+        if *program_id == compute_budget::id() {
+            let decoded = match try_from_slice_unchecked::<ComputeBudgetInstruction>(data).ok()? {
+                ComputeBudgetInstruction::SetComputeUnitLimit(units) => {
+                    format!("Set compute unit limit to {}", units)
+                }
+                ComputeBudgetInstruction::SetComputeUnitPrice(micro_lamports) => format!(
+                    "Set compute unit price to {} micro-lamports",
+                    micro_lamports
+                ),
+                other => format!("{:?}", other),
+            };
+            return Some(Decoded::new(decoded));
+        }
+
+        if *program_id == stake::program::id() {
+            let decoded = match bincode::deserialize::<StakeInstruction>(data).ok()? {
+                StakeInstruction::Withdraw(lamports) => Decoded::moving(
+                    format!(
+                        "Withdraw {} from stake account {} to {}",
+                        sol(lamports),
+                        account(0),
+                        account(1)
+                    ),
+                    lamports,
+                ),
+                StakeInstruction::Split(lamports) => Decoded::new(format!(
+                    "Split {} from stake account {} into {}",
+                    sol(lamports),
+                    account(0),
+                    account(1)
+                )),
+                other => Decoded::new(format!("{:?} (stake account {})", other, account(0))),
+            };
+            return Some(decoded);
+        }
+
+        if *program_id == spl_token::id() || *program_id == spl_token_2022::id() {
+            let decoded = match TokenInstruction::unpack(data).ok()? {
+                // Instruction dépréciée, mais encore valide (et utilisée) sur le réseau.
+                #[allow(deprecated)]
+                TokenInstruction::Transfer { amount } => format!(
+                    "Transfer {} token base units from {} to {}",
+                    amount,
+                    account(0),
+                    account(1)
+                ),
+                TokenInstruction::TransferChecked { amount, decimals } => format!(
+                    "Transfer {} tokens (mint {}) from {} to {}",
+                    SolAmount::format_decimal(amount, decimals),
+                    account(1),
+                    account(0),
+                    account(2)
+                ),
+                TokenInstruction::CloseAccount => format!(
+                    "Close token account {} (rent to {})",
+                    account(0),
+                    account(1)
+                ),
+                other => format!("{:?} (account {})", other, account(0)),
+            };
+            return Some(Decoded::new(decoded));
+        }
+
+        if *program_id == spl_associated_token_account::id() {
+            let idempotent = match data {
+                [] | [0] => "",
+                [1] => " (if missing)",
+                _ => return None,
+            };
+            return Some(Decoded::new(format!(
+                "Create associated token account {} for {} (mint {}){}",
+                account(1),
+                account(2),
+                account(3),
+                idempotent
+            )));
+        }
+
+        if *program_id == spl_memo::id() || *program_id == spl_memo::v1::id() {
+            return Some(Decoded::new(format!(
+                "Memo: {}",
+                SolanaMemo::decode_data(data)
+            )));
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solana::compute_budget::ComputeBudget;
+    use solana_sdk::{instruction::AccountMeta, message::Message, system_instruction};
+
+    fn summarize(message: &Message) -> Vec<InstructionSummary> {
+        message
+            .instructions
+            .iter()
+            .map(|instruction| SolanaInstruction::summarize(&message.account_keys, instruction))
+            .collect()
+    }
+
+    #[test]
+    fn test_summarize_known_programs() {
+        let payer = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let budget = ComputeBudget {
+            unit_limit: Some(1_000),
+            unit_price: Some(50),
+        };
+        let mut instructions = budget.instructions();
+        instructions.push(system_instruction::transfer(&payer, &recipient, 42));
+        instructions.push(system_instruction::assign(&payer, &Pubkey::new_unique()));
+        instructions.push(SolanaMemo::instruction("Invoice #42", &payer));
+        let summaries = summarize(&Message::new(&instructions, Some(&payer)));
+
+        assert!(summaries.iter().all(InstructionSummary::is_decoded));
+        assert_eq!(
+            summaries[0].description.as_deref(),
+            Some("Set compute unit limit to 1000")
+        );
+        assert_eq!(
+            summaries[1].description.as_deref(),
+            Some("Set compute unit price to 50 micro-lamports")
+        );
+        assert_eq!(
+            summaries[2].description,
+            Some(format!(
+                "Transfer 0.000000042 SOL (42 lamports) from {} to {}",
+                payer, recipient
+            ))
+        );
+        assert_eq!(summaries[2].lamports, 42);
+        assert_eq!(summaries[2].program.as_deref(), Some("System Program"));
+        assert!(summaries[3]
+            .description
+            .as_deref()
+            .unwrap()
+            .starts_with(&format!("Assign account {} to program", payer)));
+        assert_eq!(summaries[3].lamports, 0);
+        assert_eq!(
+            summaries[4].description.as_deref(),
+            Some("Memo: Invoice #42")
+        );
+    }
+
+    #[test]
+    fn test_summarize_unknown_program() {
+        let payer = Pubkey::new_unique();
+        let program_id = Pubkey::new_unique();
+        let instruction = solana_sdk::instruction::Instruction::new_with_bytes(
+            program_id,
+            &[1, 2, 3],
+            vec![AccountMeta::new(payer, true)],
+        );
+        let summaries = summarize(&Message::new(&[instruction], Some(&payer)));
+        assert!(!summaries[0].is_decoded());
+        assert_eq!(summaries[0].program, None);
+        assert_eq!(summaries[0].program_id, program_id.to_string());
+        assert_eq!(summaries[0].accounts, vec![payer.to_string()]);
+
+        // Données invalides pour un programme connu : l'instruction n'est pas décodée.
+        let instruction = solana_sdk::instruction::Instruction::new_with_bytes(
+            system_program::id(),
+            &[255, 255, 255, 255],
+            vec![AccountMeta::new(payer, true)],
+        );
+        assert!(!summarize(&Message::new(&[instruction], Some(&payer)))[0].is_decoded());
+    }
+}
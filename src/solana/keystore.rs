@@ -0,0 +1,389 @@
+use crate::error::WalletError;
+use crate::solana::address::SolanaAddress;
+use aes_gcm_siv::aead::{Aead, NewAead, Payload};
+use aes_gcm_siv::{Aes256GcmSiv, Key, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use rand::rngs::OsRng;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::keypair::Keypair;
+use solana_sdk::signer::Signer;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::str::FromStr;
+
+/// Version actuelle du format de keystore chiffré.
+pub const KEYSTORE_VERSION: u32 = 1;
+
+/// Variable d'environnement permettant de fournir le mot de passe du keystore sans prompt (scripts, tests).
+pub const KEYSTORE_PASSWORD_ENV: &str = "KEYSTORE_PASSWORD";
+
+const KDF_NAME: &str = "scrypt";
+const CIPHER_NAME: &str = "aes-256-gcm-siv";
+const SALT_LEN: usize = 32;
+const NONCE_LEN: usize = 12;
+
+/// Bornes des paramètres scrypt acceptés à la lecture d'un keystore : ils viennent du fichier,
+/// et des valeurs extrêmes (fichier altéré) demanderaient des téraoctets de mémoire.
+const MAX_LOG_N: u8 = 20;
+const MAX_R_P: u64 = 32;
+/// Mémoire utilisée par scrypt (128 * r * 2^log_n octets) : 1 Gio au plus (128 Mio avec les paramètres recommandés).
+const MAX_MEMORY: u64 = 1 << 30;
+
+/// Paramètres de la fonction de dérivation de clé (mot de passe -> clé de chiffrement).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KdfParams {
+    pub name: String,
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+    pub salt: String,
+}
+
+/// Paramètres du chiffrement authentifié.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CipherParams {
+    pub name: String,
+    pub nonce: String,
+}
+
+/// Enveloppe JSON versionnée d'un keystore chiffré.
+/// La clé publique est stockée en clair (et authentifiée lors du déchiffrement) pour pouvoir être affichée sans mot de passe.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct KeystoreEnvelope {
+    pub version: u32,
+    pub pubkey: String,
+    pub kdf: KdfParams,
+    pub cipher: CipherParams,
+    pub ciphertext: String,
+}
+
+pub struct SolanaKeystore {}
+
+impl SolanaKeystore {
+    /// Chiffre une paire de clés avec un mot de passe.
+    /// La clé de chiffrement est dérivée du mot de passe avec scrypt, puis la clé privée est chiffrée avec AES-256-GCM-SIV.
+    ///
+    /// # Arguments:
+    /// - keypair - La paire de clés à chiffrer.
+    /// - password - Le mot de passe du keystore.
+    ///
+    /// # Returns:
+    /// Retourne l'enveloppe JSON du keystore, ou une erreur en cas d'échec du chiffrement.
+    pub fn encrypt(keypair: &Keypair, password: &str) -> Result<KeystoreEnvelope, WalletError> {
+        let mut salt = [0u8; SALT_LEN];
+        let mut nonce = [0u8; NONCE_LEN];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+
+        let kdf = KdfParams {
+            name: KDF_NAME.to_string(),
+            log_n: scrypt::Params::RECOMMENDED_LOG_N,
+            r: scrypt::Params::RECOMMENDED_R,
+            p: scrypt::Params::RECOMMENDED_P,
+            salt: BASE64.encode(salt),
+        };
+        let pubkey = keypair.pubkey().to_string();
+
+        let cipher = Self::cipher(password, &kdf)?;
+        let ciphertext = cipher
+            .encrypt(
+                &Nonce::from(nonce),
+                Payload {
+                    msg: &keypair.to_bytes(),
+                    aad: pubkey.as_bytes(),
+                },
+            )
+            .map_err(|_| "Failed to encrypt keypair")?;
+
+        Ok(KeystoreEnvelope {
+            version: KEYSTORE_VERSION,
+            pubkey,
+            kdf,
+            cipher: CipherParams {
+                name: CIPHER_NAME.to_string(),
+                nonce: BASE64.encode(nonce),
+            },
+            ciphertext: BASE64.encode(ciphertext),
+        })
+    }
+
+    /// Déchiffre une enveloppe de keystore.
+    ///
+    /// # Arguments:
+    /// - envelope - L'enveloppe JSON du keystore.
+    /// - password - Le mot de passe du keystore.
+    ///
+    /// # Returns:
+    /// Retourne la paire de clés, ou une erreur si le mot de passe est incorrect ou si le fichier a été altéré.
+    pub fn decrypt(envelope: &KeystoreEnvelope, password: &str) -> Result<Keypair, WalletError> {
+        if envelope.version != KEYSTORE_VERSION {
+            return Err(format!("Unsupported keystore version: {}", envelope.version).into());
+        }
+        if envelope.kdf.name != KDF_NAME || envelope.cipher.name != CIPHER_NAME {
+            return Err(format!(
+                "Unsupported keystore algorithms: {} / {}",
+                envelope.kdf.name, envelope.cipher.name
+            )
+            .into());
+        }
+
+        let nonce: [u8; NONCE_LEN] = BASE64
+            .decode(&envelope.cipher.nonce)?
+            .try_into()
+            .map_err(|_| "Invalid keystore nonce")?;
+        let ciphertext = BASE64.decode(&envelope.ciphertext)?;
+
+        let cipher = Self::cipher(password, &envelope.kdf)?;
+        let secret = cipher
+            .decrypt(
+                &Nonce::from(nonce),
+                Payload {
+                    msg: &ciphertext,
+                    aad: envelope.pubkey.as_bytes(),
+                },
+            )
+            .map_err(|_| "Failed to decrypt keystore (wrong password or corrupted file)")?;
+
+        let keypair =
+            Keypair::from_bytes(&secret).map_err(|e| WalletError::KeyIo(e.to_string()))?;
+        if keypair.pubkey().to_string() != envelope.pubkey {
+            return Err("Keystore public key does not match its secret key".into());
+        }
+        Ok(keypair)
+    }
+
+    /// Indique si le contenu d'un fichier de paire de clés est un keystore chiffré (objet JSON) et non un tableau d'octets en clair.
+    pub fn is_encrypted(content: &str) -> bool {
+        content.trim_start().starts_with('{')
+    }
+
+    /// Indique si un fichier de paire de clés est un keystore chiffré (false si le fichier ne peut pas être lu).
+    pub fn is_encrypted_file(file_path: &str) -> bool {
+        fs::read_to_string(file_path).is_ok_and(|content| Self::is_encrypted(&content))
+    }
+
+    /// Chiffre et écrit une paire de clés dans un fichier (permissions 0o600 sur les systèmes Unix).
+    ///
+    /// # Arguments:
+    /// - keypair - La paire de clés à écrire.
+    /// - file_path - Le chemin du fichier où écrire le keystore.
+    /// - password - Le mot de passe du keystore.
+    pub fn write_encrypted_keypair(
+        keypair: &Keypair,
+        file_path: &str,
+        password: &str,
+    ) -> Result<(), WalletError> {
+        Self::key_io(|| {
+            let envelope = Self::encrypt(keypair, password)?;
+            let json = serde_json::to_string_pretty(&envelope)?;
+            Self::write_private_file(file_path, &json)?;
+            Ok(())
+        })
+    }
+
+    /// Lit une paire de clés depuis un fichier, qu'il soit chiffré (keystore) ou en clair (tableau JSON d'octets).
+    /// Pour un keystore chiffré, le mot de passe est lu depuis "KEYSTORE_PASSWORD" ou demandé à l'utilisateur.
+    ///
+    /// # Arguments:
+    /// - file_path - Le chemin du fichier contenant la paire de clés.
+    ///
+    /// # Returns:
+    /// Retourne la paire de clés déverrouillée, ou une erreur.
+    pub fn read_keypair(file_path: &str) -> Result<Keypair, WalletError> {
+        Self::key_io(|| {
+            let content = fs::read_to_string(file_path)?;
+            if !Self::is_encrypted(&content) {
+                return Ok(SolanaAddress::read_keypair_from_file(file_path)?);
+            }
+
+            let envelope: KeystoreEnvelope = serde_json::from_str(&content)?;
+            let password = Self::password(&format!("Enter keystore password for {}:", file_path))?;
+            Self::decrypt(&envelope, &password)
+        })
+    }
+
+    /// Lit uniquement la clé publique d'un fichier de paire de clés (sans mot de passe pour un keystore chiffré).
+    ///
+    /// Pour un keystore chiffré, c'est le champ "pubkey" de l'enveloppe, qui n'est authentifié qu'au déchiffrement :
+    /// un fichier altéré peut donc donner une autre adresse que celle de sa clé privée, tant que le keystore n'a pas été
+    /// déverrouillé (voir `is_encrypted_file`).
+    pub fn read_pubkey(file_path: &str) -> Result<Pubkey, WalletError> {
+        Self::key_io(|| {
+            let content = fs::read_to_string(file_path)?;
+            if !Self::is_encrypted(&content) {
+                return Ok(SolanaAddress::read_keypair_from_file(file_path)?.pubkey());
+            }
+
+            let envelope: KeystoreEnvelope = serde_json::from_str(&content)?;
+            Ok(Pubkey::from_str(&envelope.pubkey)?)
+        })
+    }
+
+    /// Chiffre un fichier de paire de clés en clair (migration vers le format keystore).
+    ///
+    /// # Returns:
+    /// Retourne true si le fichier a été migré, false s'il était déjà chiffré.
+    pub fn migrate_file(file_path: &str, password: &str) -> Result<bool, WalletError> {
+        Self::key_io(|| {
+            let content = fs::read_to_string(file_path)?;
+            if Self::is_encrypted(&content) {
+                return Ok(false);
+            }
+
+            let keypair = SolanaAddress::read_keypair_from_file(file_path)?;
+            let envelope = Self::encrypt(&keypair, password)?;
+
+            // Écrit d'abord dans un fichier temporaire, puis le renomme : le fichier en clair n'est jamais perdu en cas d'échec.
+            let tmp_path = format!("{}.tmp", file_path);
+            Self::write_private_file(&tmp_path, &serde_json::to_string_pretty(&envelope)?)?;
+            fs::rename(&tmp_path, file_path)?;
+            Ok(true)
+        })
+    }
+
+    /// Retourne le mot de passe du keystore depuis "KEYSTORE_PASSWORD", ou le demande à l'utilisateur.
+    /// Au clavier (terminal), le mot de passe est saisi sans écho ; sinon, il est lu depuis l'entrée standard (pipe).
+    pub fn password(prompt: &str) -> Result<String, WalletError> {
+        if let Ok(password) = std::env::var(KEYSTORE_PASSWORD_ENV) {
+            return Ok(password);
+        }
+        let read_error =
+            |e: io::Error| WalletError::KeyIo(format!("Failed to read keystore password: {}", e));
+
+        // La question est affichée sur le terminal ou la sortie d'erreur : la sortie standard reste celle de la commande (ex : JSON).
+        if io::stdin().is_terminal() {
+            return rpassword::prompt_password(format!("{} ", prompt)).map_err(read_error);
+        }
+
+        eprintln!("{}", prompt);
+        let mut password = String::new();
+        io::stdin().read_line(&mut password).map_err(read_error)?;
+        Ok(password.trim_end_matches(['\r', '\n']).to_string())
+    }
+
+    /// Demande un nouveau mot de passe (avec confirmation si celui-ci est saisi au clavier).
+    ///
+    /// # Returns:
+    /// Retourne le mot de passe (éventuellement vide), ou une erreur si la confirmation ne correspond pas.
+    pub fn new_password(prompt: &str) -> Result<String, WalletError> {
+        if let Ok(password) = std::env::var(KEYSTORE_PASSWORD_ENV) {
+            return Ok(password);
+        }
+
+        let password = Self::password(prompt)?;
+        if password.is_empty() {
+            return Ok(password);
+        }
+        if Self::password("Confirm keystore password:")? != password {
+            return Err(WalletError::InvalidInput(
+                "Keystore passwords do not match".to_string(),
+            ));
+        }
+        Ok(password)
+    }
+
+    /// Construit le chiffreur AES-256-GCM-SIV avec la clé dérivée du mot de passe (scrypt).
+    fn cipher(password: &str, kdf: &KdfParams) -> Result<Aes256GcmSiv, WalletError> {
+        if kdf.log_n > MAX_LOG_N
+            || kdf.r == 0
+            || kdf.p == 0
+            || u64::from(kdf.r) * u64::from(kdf.p) > MAX_R_P
+            || (128 * u64::from(kdf.r)) << kdf.log_n > MAX_MEMORY
+        {
+            return Err(format!(
+                "Unsupported keystore KDF parameters (log_n: {}, r: {}, p: {})",
+                kdf.log_n, kdf.r, kdf.p
+            )
+            .into());
+        }
+
+        let salt = BASE64.decode(&kdf.salt)?;
+        let params = scrypt::Params::new(kdf.log_n, kdf.r, kdf.p, 32)
+            .map_err(|_| "Invalid keystore KDF parameters")?;
+
+        let mut key = [0u8; 32];
+        scrypt::scrypt(password.as_bytes(), &salt, &params, &mut key)
+            .map_err(|_| "Failed to derive keystore key")?;
+
+        Ok(Aes256GcmSiv::new(&Key::from(key)))
+    }
+
+    /// Exécute une opération sur un fichier de paire de clés : toute erreur est une erreur de lecture/écriture de clé.
+    fn key_io<T>(operation: impl FnOnce() -> Result<T, WalletError>) -> Result<T, WalletError> {
+        operation().map_err(|e| WalletError::KeyIo(e.message().to_string()))
+    }
+
+    /// Écrit un fichier lisible uniquement par son propriétaire (0o600 sur Unix), en créant les dossiers parents.
+    fn write_private_file(file_path: &str, content: &str) -> io::Result<()> {
+        if let Some(parent) = Path::new(file_path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+
+        use std::io::Write;
+        let mut file = options.open(file_path)?;
+        file.write_all(content.as_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_decrypt_roundtrip() {
+        let keypair = Keypair::new();
+        let envelope = SolanaKeystore::encrypt(&keypair, "correct horse").unwrap();
+
+        assert_eq!(envelope.version, KEYSTORE_VERSION);
+        assert_eq!(envelope.pubkey, keypair.pubkey().to_string());
+
+        let decrypted = SolanaKeystore::decrypt(&envelope, "correct horse").unwrap();
+        assert_eq!(decrypted.to_bytes(), keypair.to_bytes());
+
+        // Un mauvais mot de passe doit être refusé.
+        assert!(SolanaKeystore::decrypt(&envelope, "wrong horse").is_err());
+
+        // Une clé publique altérée (donnée authentifiée) doit être refusée.
+        let mut tampered = envelope.clone();
+        tampered.pubkey = Keypair::new().pubkey().to_string();
+        assert!(SolanaKeystore::decrypt(&tampered, "correct horse").is_err());
+
+        // L'enveloppe sérialisée ne doit pas être confondue avec un fichier en clair.
+        let json = serde_json::to_string(&envelope).unwrap();
+        assert!(SolanaKeystore::is_encrypted(&json));
+        assert!(!SolanaKeystore::is_encrypted("[1,2,3]"));
+    }
+
+    #[test]
+    fn test_decrypt_rejects_excessive_kdf_params() {
+        let keypair = Keypair::new();
+        let envelope = SolanaKeystore::encrypt(&keypair, "correct horse").unwrap();
+
+        // Des paramètres scrypt démesurés (fichier altéré) sont refusés avant la dérivation de la clé.
+        for (log_n, r, p) in [
+            (40, 8, 1),
+            (17, 1 << 20, 1),
+            (17, 8, 1 << 20),
+            (20, 32, 1),
+            (17, 0, 1),
+        ] {
+            let mut tampered = envelope.clone();
+            tampered.kdf.log_n = log_n;
+            tampered.kdf.r = r;
+            tampered.kdf.p = p;
+            assert!(SolanaKeystore::decrypt(&tampered, "correct horse").is_err());
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use solana_sdk::{
+    instruction::{CompiledInstruction, Instruction},
+    pubkey::Pubkey,
+};
+
+/// Longueur maximale d'un memo (en octets UTF-8).
+pub const MAX_MEMO_LENGTH: usize = 256;
+
+/// Caractères de contrôle de la direction du texte (bidi), qui peuvent inverser l'affichage d'un texte dans le terminal.
+const BIDI_CONTROLS: [char; 11] = [
+    '\u{200e}', '\u{200f}', '\u{202a}', '\u{202b}', '\u{202c}', '\u{202d}', '\u{202e}', '\u{2066}',
+    '\u{2067}', '\u{2068}', '\u{2069}',
+];
+
+pub struct SolanaMemo {}
+
+impl SolanaMemo {
+    /// Vérifie un memo saisi par l'utilisateur : non vide, sans caractère de contrôle, et d'au plus MAX_MEMO_LENGTH octets.
+    pub fn validate(memo: &str) -> Result<(), String> {
+        if memo.trim().is_empty() {
+            return Err("Memo cannot be empty".to_string());
+        }
+        if memo.len() > MAX_MEMO_LENGTH {
+            return Err(format!(
+                "Memo is too long: {} bytes (max {} bytes)",
+                memo.len(),
+                MAX_MEMO_LENGTH
+            ));
+        }
+        if memo.chars().any(char::is_control) {
+            return Err("Memo cannot contain control characters".to_string());
+        }
+        Ok(())
+    }
+
+    /// Crée l'instruction du programme SPL Memo. Le signataire est déclaré dans l'instruction :
+    /// le programme vérifie alors qu'il a bien signé la transaction.
+    pub fn instruction(memo: &str, signer: &Pubkey) -> Instruction {
+        spl_memo::build_memo(memo.as_bytes(), &[signer])
+    }
+
+    /// Décode les memos des instructions d'une transaction (programmes SPL Memo v1 et v2), dans l'ordre.
+    ///
+    /// # Arguments:
+    /// - account_keys - Les comptes statiques du message (un programme ne peut pas venir d'une table d'adresses).
+    /// - instructions - Les instructions compilées du message.
+    pub fn decode(account_keys: &[Pubkey], instructions: &[CompiledInstruction]) -> Vec<String> {
+        instructions
+            .iter()
+            .filter(|instruction| {
+                account_keys
+                    .get(usize::from(instruction.program_id_index))
+                    .is_some_and(|program| {
+                        *program == spl_memo::id() || *program == spl_memo::v1::id()
+                    })
+            })
+            .map(|instruction| Self::decode_data(&instruction.data))
+            .collect()
+    }
+
+    /// Décode les données d'une instruction SPL Memo en texte affichable : le memo vient d'un tiers,
+    /// ses caractères de contrôle (séquences d'échappement du terminal, retours à la ligne, bidi) sont échappés (ex : "\u{1b}").
+    pub fn decode_data(data: &[u8]) -> String {
+        let mut memo = String::new();
+        for c in String::from_utf8_lossy(data).chars() {
+            if c.is_control() || BIDI_CONTROLS.contains(&c) {
+                memo.extend(c.escape_default());
+            } else {
+                memo.push(c);
+            }
+        }
+        memo
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert!(SolanaMemo::validate("Invoice #42").is_ok());
+        assert!(SolanaMemo::validate(&"é".repeat(128)).is_ok());
+        assert!(SolanaMemo::validate(&"é".repeat(129)).is_err());
+        assert!(SolanaMemo::validate("  ").is_err());
+        assert!(SolanaMemo::validate("line\nbreak").is_err());
+    }
+
+    #[test]
+    fn test_decode() {
+        let signer = Pubkey::new_unique();
+        let message = solana_sdk::message::Message::new(
+            &[
+                solana_sdk::system_instruction::transfer(&signer, &Pubkey::new_unique(), 1),
+                SolanaMemo::instruction("Invoice #42", &signer),
+            ],
+            Some(&signer),
+        );
+        assert_eq!(
+            SolanaMemo::decode(&message.account_keys, &message.instructions),
+            vec!["Invoice #42".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_decode_data_escapes_control_characters() {
+        assert_eq!(SolanaMemo::decode_data("Café".as_bytes()), "Café");
+        assert_eq!(
+            SolanaMemo::decode_data(b"\x1b[2J\x1b]0;title\x07Paid\r\n"),
+            "\\u{1b}[2J\\u{1b}]0;title\\u{7}Paid\\r\\n"
+        );
+        assert_eq!(
+            SolanaMemo::decode_data("\u{202e}cod.exe".as_bytes()),
+            "\\u{202e}cod.exe"
+        );
+        assert_eq!(SolanaMemo::decode_data(&[0xff, b'a']), "\u{fffd}a");
+    }
+}
@@ -0,0 +1,287 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use solana_sdk::{
+    message::{VersionedMessage, MESSAGE_VERSION_PREFIX},
+    offchain_message::{self, OffchainMessage},
+    pubkey::Pubkey,
+    signature::{Signature, Signer},
+};
+use std::fmt;
+use std::str::FromStr;
+
+/// Version du format de message hors chaîne de Solana utilisée pour signer.
+pub const OFFCHAIN_MESSAGE_VERSION: u8 = 0;
+
+/// Format des octets signés.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MessageFormat {
+    /// Octets quelconques, signés tels quels.
+    Raw,
+    /// Texte UTF-8, signé tel quel.
+    Utf8,
+    /// Message hors chaîne de Solana : en-tête "\xffsolana offchain", version, format et longueur, puis le texte.
+    /// Ce préfixe garantit qu'une signature de message ne peut jamais être celle d'une transaction.
+    Offchain,
+}
+
+impl MessageFormat {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        match input.to_lowercase().as_str() {
+            "raw" => Ok(MessageFormat::Raw),
+            "utf8" | "utf-8" => Ok(MessageFormat::Utf8),
+            "offchain" => Ok(MessageFormat::Offchain),
+            _ => Err(format!(
+                "Invalid message format '{}': expected raw, utf8 or offchain",
+                input
+            )),
+        }
+    }
+}
+
+impl fmt::Display for MessageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format = match self {
+            MessageFormat::Raw => "raw",
+            MessageFormat::Utf8 => "utf8",
+            MessageFormat::Offchain => "offchain",
+        };
+        write!(f, "{}", format)
+    }
+}
+
+/// Encodage d'une signature affichée.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignatureEncoding {
+    Base58,
+    Base64,
+}
+
+impl SignatureEncoding {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        match input.to_lowercase().as_str() {
+            "base58" => Ok(SignatureEncoding::Base58),
+            "base64" => Ok(SignatureEncoding::Base64),
+            _ => Err(format!(
+                "Invalid encoding '{}': expected base58 or base64",
+                input
+            )),
+        }
+    }
+
+    pub fn encode(&self, signature: &Signature) -> String {
+        match self {
+            SignatureEncoding::Base58 => signature.to_string(),
+            SignatureEncoding::Base64 => BASE64.encode(signature.as_ref()),
+        }
+    }
+}
+
+pub struct SolanaMessage {}
+
+impl SolanaMessage {
+    /// Retourne les octets effectivement signés d'un message dans un format donné.
+    ///
+    /// # Returns:
+    /// Retourne une erreur si le message est vide, n'est pas du texte UTF-8 (formats "utf8" et "offchain"),
+    /// ou est trop long pour un message hors chaîne.
+    pub fn signed_bytes(message: &[u8], format: MessageFormat) -> Result<Vec<u8>, String> {
+        if message.is_empty() {
+            return Err("Message cannot be empty".to_string());
+        }
+        if format != MessageFormat::Raw && std::str::from_utf8(message).is_err() {
+            return Err(
+                "The message is not valid UTF-8 text (use --format raw to sign bytes)".to_string(),
+            );
+        }
+        match format {
+            MessageFormat::Raw | MessageFormat::Utf8 => Ok(message.to_vec()),
+            MessageFormat::Offchain => OffchainMessage::new(OFFCHAIN_MESSAGE_VERSION, message)
+                .and_then(|message| message.serialize())
+                .map_err(|_| {
+                    format!(
+                        "The message is too long for an off-chain message ({} bytes max)",
+                        offchain_message::v0::OffchainMessage::MAX_LEN
+                    )
+                }),
+        }
+    }
+
+    /// Signe un message avec un signataire (la paire de clés du wallet).
+    ///
+    /// # Returns:
+    /// Retourne une erreur si les octets signés tels quels ("raw", "utf8") sont un message de transaction :
+    /// la signature permettrait alors d'envoyer cette transaction.
+    pub fn sign(
+        signer: &dyn Signer,
+        message: &[u8],
+        format: MessageFormat,
+    ) -> Result<Signature, String> {
+        let bytes = Self::signed_bytes(message, format)?;
+        if format != MessageFormat::Offchain && Self::is_transaction_message(&bytes) {
+            return Err(
+                "The message is a Solana transaction message: its signature would authorize the transaction (use --format offchain)"
+                    .to_string(),
+            );
+        }
+        signer
+            .try_sign_message(&bytes)
+            .map_err(|e| format!("Failed to sign message: {}", e))
+    }
+
+    /// Indique si des octets sont un message de transaction (legacy ou v0), ou commencent par l'en-tête d'un message v0.
+    pub fn is_transaction_message(bytes: &[u8]) -> bool {
+        bytes.first() == Some(&MESSAGE_VERSION_PREFIX)
+            || bincode::deserialize::<VersionedMessage>(bytes).is_ok()
+    }
+
+    /// Vérifie la signature d'un message par une clé publique.
+    pub fn verify(
+        pubkey: &Pubkey,
+        signature: &Signature,
+        message: &[u8],
+        format: MessageFormat,
+    ) -> Result<bool, String> {
+        let bytes = Self::signed_bytes(message, format)?;
+        Ok(signature.verify(pubkey.as_ref(), &bytes))
+    }
+
+    /// Décrit le format d'un message (pour un message hors chaîne : sa version et son format de texte).
+    pub fn describe(message: &[u8], format: MessageFormat) -> String {
+        if format != MessageFormat::Offchain {
+            return format.to_string();
+        }
+        let text_format = match OffchainMessage::new(OFFCHAIN_MESSAGE_VERSION, message)
+            .map(|message| message.get_format())
+        {
+            Ok(offchain_message::MessageFormat::RestrictedAscii) => "restricted ASCII",
+            Ok(offchain_message::MessageFormat::LimitedUtf8) => "limited UTF-8",
+            Ok(offchain_message::MessageFormat::ExtendedUtf8) => "extended UTF-8",
+            Err(_) => "invalid",
+        };
+        format!(
+            "offchain (version {}, {})",
+            OFFCHAIN_MESSAGE_VERSION, text_format
+        )
+    }
+
+    /// Décode une signature en base58 ou en base64 (détecté automatiquement).
+    pub fn decode_signature(input: &str) -> Result<Signature, String> {
+        let input = input.trim();
+        if let Ok(signature) = Signature::from_str(input) {
+            return Ok(signature);
+        }
+        BASE64
+            .decode(input)
+            .ok()
+            .and_then(|bytes| Signature::try_from(bytes.as_slice()).ok())
+            .ok_or_else(|| format!("Invalid signature (base58 or base64 expected): {}", input))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::Keypair;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let keypair = Keypair::new();
+        let other = Keypair::new();
+        for format in [
+            MessageFormat::Raw,
+            MessageFormat::Utf8,
+            MessageFormat::Offchain,
+        ] {
+            let signature = SolanaMessage::sign(&keypair, b"I own this wallet", format).unwrap();
+            assert!(SolanaMessage::verify(
+                &keypair.pubkey(),
+                &signature,
+                b"I own this wallet",
+                format
+            )
+            .unwrap());
+            assert!(!SolanaMessage::verify(
+                &other.pubkey(),
+                &signature,
+                b"I own this wallet",
+                format
+            )
+            .unwrap());
+            assert!(!SolanaMessage::verify(
+                &keypair.pubkey(),
+                &signature,
+                b"I own this wallet!",
+                format
+            )
+            .unwrap());
+        }
+
+        // Une signature hors chaîne ne vaut pas pour le texte brut (et inversement).
+        let signature = SolanaMessage::sign(&keypair, b"hello", MessageFormat::Offchain).unwrap();
+        assert!(!SolanaMessage::verify(
+            &keypair.pubkey(),
+            &signature,
+            b"hello",
+            MessageFormat::Utf8
+        )
+        .unwrap());
+        let offchain = OffchainMessage::new(0, b"hello").unwrap();
+        assert_eq!(offchain.sign(&keypair).unwrap(), signature);
+    }
+
+    #[test]
+    fn test_signed_bytes() {
+        let bytes = SolanaMessage::signed_bytes(b"hello", MessageFormat::Offchain).unwrap();
+        assert!(bytes.starts_with(OffchainMessage::SIGNING_DOMAIN));
+        assert_eq!(bytes[16], 0); // Version.
+        assert_eq!(bytes[17], 0); // Format : ASCII restreint.
+        assert_eq!(&bytes[18..20], &5u16.to_le_bytes());
+        assert_eq!(&bytes[20..], b"hello");
+
+        assert!(SolanaMessage::signed_bytes(&[0xff, 0xfe], MessageFormat::Utf8).is_err());
+        assert!(SolanaMessage::signed_bytes(&[0xff, 0xfe], MessageFormat::Raw).is_ok());
+        assert!(SolanaMessage::signed_bytes(b"", MessageFormat::Raw).is_err());
+    }
+
+    #[test]
+    fn test_refuse_transaction_messages() {
+        let keypair = Keypair::new();
+        let transfer = solana_sdk::system_instruction::transfer(
+            &keypair.pubkey(),
+            &Pubkey::new_unique(),
+            1_000_000_000,
+        );
+        let legacy = solana_sdk::message::Message::new(
+            std::slice::from_ref(&transfer),
+            Some(&keypair.pubkey()),
+        );
+        let v0 = VersionedMessage::V0(
+            solana_sdk::message::v0::Message::try_compile(
+                &keypair.pubkey(),
+                &[transfer],
+                &[],
+                legacy.recent_blockhash,
+            )
+            .unwrap(),
+        );
+
+        for bytes in [legacy.serialize(), v0.serialize()] {
+            assert!(SolanaMessage::is_transaction_message(&bytes));
+            assert!(SolanaMessage::sign(&keypair, &bytes, MessageFormat::Raw).is_err());
+        }
+        assert!(!SolanaMessage::is_transaction_message(b"I own this wallet"));
+        assert!(SolanaMessage::sign(&keypair, b"I own this wallet", MessageFormat::Raw).is_ok());
+    }
+
+    #[test]
+    fn test_decode_signature() {
+        let signature = Keypair::new().sign_message(b"hello");
+        for encoding in [SignatureEncoding::Base58, SignatureEncoding::Base64] {
+            let encoded = encoding.encode(&signature);
+            assert_eq!(
+                SolanaMessage::decode_signature(&encoded).unwrap(),
+                signature
+            );
+        }
+        assert!(SolanaMessage::decode_signature("not-a-signature").is_err());
+    }
+}
@@ -0,0 +1,207 @@
+use crate::error::WalletError;
+use crate::solana::rpc::WalletRpc;
+use solana_client::rpc_response::{
+    RpcConfirmedTransactionStatusWithSignature, RpcInflationReward, RpcKeyedAccount,
+    RpcSimulateTransactionResult,
+};
+use solana_sdk::{
+    account::Account, clock::Epoch, epoch_schedule::EpochSchedule, hash::Hash, message::Message,
+    pubkey::Pubkey, rent::Rent, signature::Signature, transaction::Transaction,
+};
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, TransactionConfirmationStatus, TransactionStatus,
+};
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// Frais par signature (en lamports) retournés par "get_fee_for_message".
+pub const MOCK_FEE: u64 = 5000;
+/// Délégation minimale (en lamports) retournée par "get_stake_minimum_delegation".
+pub const MOCK_MINIMUM_DELEGATION: u64 = 1_000_000_000;
+
+/// Implémentation en mémoire de `WalletRpc`, pour les tests unitaires.
+///
+/// Les transactions envoyées ne sont pas exécutées : elles sont enregistrées (voir `sent_transactions`),
+/// puis considérées comme confirmées.
+#[derive(Default)]
+pub struct MockRpc {
+    balances: RefCell<HashMap<Pubkey, u64>>,
+    accounts: RefCell<HashMap<Pubkey, Account>>,
+    sent_transactions: RefCell<Vec<Transaction>>,
+}
+
+impl MockRpc {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Retourne le hash du bloc genesis retourné par "get_genesis_hash" (un réseau inconnu, comme un validateur local).
+    pub fn genesis_hash() -> Hash {
+        Hash::new_from_array([1; 32])
+    }
+
+    /// Retourne le blockhash retourné par "get_latest_blockhash".
+    pub fn blockhash() -> Hash {
+        Hash::new_from_array([7; 32])
+    }
+
+    /// Définit la balance (en lamports) d'un compte.
+    pub fn set_balance(&self, pubkey: &Pubkey, lamports: u64) {
+        self.balances.borrow_mut().insert(*pubkey, lamports);
+    }
+
+    /// Déclare un compte retourné par "get_account" (les autres adresses n'existent pas).
+    pub fn set_account(&self, pubkey: &Pubkey, account: Account) {
+        self.accounts.borrow_mut().insert(*pubkey, account);
+    }
+
+    /// Retourne les transactions envoyées, dans l'ordre.
+    pub fn sent_transactions(&self) -> Vec<Transaction> {
+        self.sent_transactions.borrow().clone()
+    }
+
+    fn unsupported(method: &str) -> WalletError {
+        WalletError::Rpc(format!("{} is not supported by the mock RPC", method))
+    }
+}
+
+impl WalletRpc for MockRpc {
+    fn get_balance(&self, pubkey: &Pubkey) -> Result<u64, WalletError> {
+        Ok(self.balances.borrow().get(pubkey).copied().unwrap_or(0))
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, WalletError> {
+        Ok(self.accounts.borrow().get(pubkey).cloned())
+    }
+
+    fn get_genesis_hash(&self) -> Result<Hash, WalletError> {
+        Ok(Self::genesis_hash())
+    }
+
+    fn get_latest_blockhash(&self) -> Result<(Hash, u64), WalletError> {
+        Ok((Self::blockhash(), 100))
+    }
+
+    fn get_block_height(&self) -> Result<u64, WalletError> {
+        Ok(0)
+    }
+
+    fn get_fee_for_message(&self, message: &Message) -> Result<u64, WalletError> {
+        Ok(MOCK_FEE * u64::from(message.header.num_required_signatures))
+    }
+
+    fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> Result<u64, WalletError> {
+        Ok(Rent::default().minimum_balance(data_len))
+    }
+
+    fn send_and_confirm_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Signature, WalletError> {
+        transaction
+            .verify()
+            .map_err(|e| WalletError::Transaction(format!("Invalid transaction: {}", e)))?;
+        self.sent_transactions
+            .borrow_mut()
+            .push(transaction.clone());
+        Ok(transaction.signatures[0])
+    }
+
+    fn simulate_transaction(
+        &self,
+        _transaction: &Transaction,
+    ) -> Result<RpcSimulateTransactionResult, WalletError> {
+        Ok(RpcSimulateTransactionResult {
+            err: None,
+            logs: Some(Vec::new()),
+            accounts: None,
+            units_consumed: Some(0),
+            return_data: None,
+            inner_instructions: None,
+        })
+    }
+
+    fn get_signature_status(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<TransactionStatus>, WalletError> {
+        let sent = self
+            .sent_transactions
+            .borrow()
+            .iter()
+            .any(|transaction| transaction.signatures[0] == *signature);
+        Ok(sent.then_some(TransactionStatus {
+            slot: 1,
+            confirmations: None,
+            status: Ok(()),
+            err: None,
+            confirmation_status: Some(TransactionConfirmationStatus::Finalized),
+        }))
+    }
+
+    fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        _before: Option<Signature>,
+        _until: Option<Signature>,
+        limit: usize,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, WalletError> {
+        Ok(self
+            .sent_transactions
+            .borrow()
+            .iter()
+            .rev()
+            .filter(|transaction| transaction.message.account_keys.contains(address))
+            .take(limit)
+            .map(|transaction| RpcConfirmedTransactionStatusWithSignature {
+                signature: transaction.signatures[0].to_string(),
+                slot: 1,
+                err: None,
+                memo: None,
+                block_time: None,
+                confirmation_status: Some(TransactionConfirmationStatus::Finalized),
+            })
+            .collect())
+    }
+
+    fn get_transaction(
+        &self,
+        _signature: &Signature,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, WalletError> {
+        Err(Self::unsupported("getTransaction"))
+    }
+
+    fn get_token_accounts_by_owner(
+        &self,
+        _owner: &Pubkey,
+        _program_id: &Pubkey,
+    ) -> Result<Vec<RpcKeyedAccount>, WalletError> {
+        Ok(Vec::new())
+    }
+
+    fn get_recent_prioritization_fees(
+        &self,
+        _accounts: &[Pubkey],
+    ) -> Result<Vec<u64>, WalletError> {
+        Ok(Vec::new())
+    }
+
+    fn get_epoch(&self) -> Result<Epoch, WalletError> {
+        Ok(0)
+    }
+
+    fn get_epoch_schedule(&self) -> Result<EpochSchedule, WalletError> {
+        Ok(EpochSchedule::default())
+    }
+
+    fn get_inflation_reward(
+        &self,
+        _address: &Pubkey,
+    ) -> Result<Option<RpcInflationReward>, WalletError> {
+        Ok(None)
+    }
+
+    fn get_stake_minimum_delegation(&self) -> Result<u64, WalletError> {
+        Ok(MOCK_MINIMUM_DELEGATION)
+    }
+}
@@ -1,3 +1,20 @@
 pub mod address;
+pub mod amount;
 pub mod balance;
+pub mod batch;
+pub mod batch_file;
+pub mod cluster;
+pub mod compute_budget;
+pub mod history;
+pub mod instruction;
+pub mod keystore;
+pub mod memo;
+pub mod message;
+#[cfg(test)]
+pub mod mock_rpc;
+pub mod nonce;
+pub mod rpc;
+pub mod stake;
+pub mod token;
 pub mod transaction;
+pub mod transaction_file;
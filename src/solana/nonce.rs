@@ -0,0 +1,57 @@
+use crate::error::WalletError;
+use crate::solana::rpc::WalletRpc;
+use solana_client::nonce_utils;
+use solana_sdk::{hash::Hash, nonce::State, pubkey::Pubkey};
+
+/// État d'un compte nonce durable.
+///
+/// Une transaction qui utilise la valeur du nonce à la place d'un blockhash récent n'expire pas :
+/// elle reste valide jusqu'à ce que le nonce soit avancé (ce que fait sa première instruction).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DurableNonce {
+    /// Adresse du compte nonce.
+    pub account: Pubkey,
+    /// Autorité qui doit signer l'avancement du nonce (et les retraits).
+    pub authority: Pubkey,
+    /// Valeur actuelle du nonce, utilisée comme "recent_blockhash" de la transaction.
+    pub blockhash: Hash,
+    pub lamports_per_signature: u64,
+    /// Balance du compte nonce (en lamports).
+    pub balance: u64,
+}
+
+pub struct SolanaNonce {}
+
+impl SolanaNonce {
+    /// Récupère et décode un compte nonce durable.
+    ///
+    /// # Arguments:
+    /// - rpc - Le client RPC du réseau Solana.
+    /// - nonce_account - L'adresse du compte nonce.
+    ///
+    /// # Returns:
+    /// Retourne l'état du compte nonce, ou une erreur si le compte n'existe pas ou n'est pas un compte nonce initialisé.
+    pub fn fetch(rpc: &dyn WalletRpc, nonce_account: &Pubkey) -> Result<DurableNonce, WalletError> {
+        let account = rpc
+            .get_account(nonce_account)
+            .map_err(|e| e.context(&format!("Failed to fetch nonce account {}", nonce_account)))?
+            .ok_or_else(|| {
+                WalletError::InvalidInput(format!("Nonce account {} not found", nonce_account))
+            })?;
+        let data = nonce_utils::data_from_account(&account)
+            .map_err(|e| format!("{} is not a valid nonce account: {}", nonce_account, e))?;
+
+        Ok(DurableNonce {
+            account: *nonce_account,
+            authority: data.authority,
+            blockhash: data.blockhash(),
+            lamports_per_signature: data.fee_calculator.lamports_per_signature,
+            balance: account.lamports,
+        })
+    }
+
+    /// Retourne le montant minimal (en lamports) d'un compte nonce pour être exempté de rente.
+    pub fn minimum_balance(rpc: &dyn WalletRpc) -> Result<u64, WalletError> {
+        rpc.get_minimum_balance_for_rent_exemption(State::size())
+    }
+}
@@ -0,0 +1,264 @@
+use crate::error::WalletError;
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_client::rpc_config::{RpcSimulateTransactionConfig, RpcTransactionConfig};
+use solana_client::rpc_request::TokenAccountsFilter;
+use solana_client::rpc_response::{
+    RpcConfirmedTransactionStatusWithSignature, RpcInflationReward, RpcKeyedAccount,
+    RpcSimulateTransactionResult,
+};
+use solana_sdk::{
+    account::Account, clock::Epoch, commitment_config::CommitmentConfig,
+    epoch_schedule::EpochSchedule, hash::Hash, message::Message, pubkey::Pubkey,
+    signature::Signature, transaction::Transaction,
+};
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, TransactionStatus, UiTransactionEncoding,
+};
+
+/// Accès au réseau Solana : toutes les requêtes RPC du wallet passent par ce trait.
+///
+/// `SolanaRpc` interroge un noeud RPC. Les tests utilisent une implémentation en mémoire,
+/// qui enregistre les transactions envoyées au lieu de les diffuser.
+pub trait WalletRpc {
+    /// Retourne la balance d'un compte (en lamports).
+    fn get_balance(&self, pubkey: &Pubkey) -> Result<u64, WalletError>;
+
+    /// Récupère un compte, ou None s'il n'existe pas.
+    fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, WalletError>;
+
+    /// Retourne le hash du bloc genesis du réseau (il identifie le réseau : mainnet-beta, devnet, testnet...).
+    fn get_genesis_hash(&self) -> Result<Hash, WalletError>;
+
+    /// Retourne le dernier blockhash du réseau, et la dernière hauteur de bloc à laquelle il reste valide.
+    fn get_latest_blockhash(&self) -> Result<(Hash, u64), WalletError>;
+
+    /// Retourne la hauteur de bloc actuelle du réseau.
+    fn get_block_height(&self) -> Result<u64, WalletError>;
+
+    /// Retourne les frais d'un message (en lamports), frais de priorité compris.
+    fn get_fee_for_message(&self, message: &Message) -> Result<u64, WalletError>;
+
+    /// Retourne le montant minimal (en lamports) d'un compte de "data_len" octets pour être exempté de rente.
+    fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> Result<u64, WalletError>;
+
+    /// Diffuse une transaction signée, et attend sa confirmation.
+    fn send_and_confirm_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Signature, WalletError>;
+
+    /// Simule une transaction, sans vérifier ses signatures (elle peut être non signée).
+    fn simulate_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<RpcSimulateTransactionResult, WalletError>;
+
+    /// Recherche l'état d'une transaction (y compris dans l'historique du noeud), ou None si elle est inconnue.
+    fn get_signature_status(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<TransactionStatus>, WalletError>;
+
+    /// Retourne les signatures des transactions d'une adresse, de la plus récente à la plus ancienne.
+    fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before: Option<Signature>,
+        until: Option<Signature>,
+        limit: usize,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, WalletError>;
+
+    /// Récupère une transaction confirmée (encodée en base64).
+    fn get_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, WalletError>;
+
+    /// Retourne les comptes de tokens d'un propriétaire gérés par un programme (SPL Token ou Token-2022).
+    fn get_token_accounts_by_owner(
+        &self,
+        owner: &Pubkey,
+        program_id: &Pubkey,
+    ) -> Result<Vec<RpcKeyedAccount>, WalletError>;
+
+    /// Retourne les frais de priorité (en micro-lamports par unité de calcul) des slots récents,
+    /// pour les transactions qui écrivent dans les comptes donnés.
+    fn get_recent_prioritization_fees(&self, accounts: &[Pubkey]) -> Result<Vec<u64>, WalletError>;
+
+    /// Retourne l'epoch courante.
+    fn get_epoch(&self) -> Result<Epoch, WalletError>;
+
+    fn get_epoch_schedule(&self) -> Result<EpochSchedule, WalletError>;
+
+    /// Retourne la dernière récompense d'inflation versée à une adresse (None si elle n'en a pas reçu).
+    fn get_inflation_reward(
+        &self,
+        address: &Pubkey,
+    ) -> Result<Option<RpcInflationReward>, WalletError>;
+
+    /// Retourne le montant minimal (en lamports) d'une délégation de stake.
+    fn get_stake_minimum_delegation(&self) -> Result<u64, WalletError>;
+}
+
+/// Implémentation de `WalletRpc` qui interroge un noeud RPC.
+pub struct SolanaRpc {
+    client: RpcClient,
+}
+
+impl SolanaRpc {
+    /// Crée un client pour le noeud RPC, avec le niveau de confirmation des requêtes.
+    /// Aucune requête n'est envoyée avant la première utilisation.
+    pub fn new(rpc_url: &str, commitment: CommitmentConfig) -> Self {
+        Self {
+            client: RpcClient::new_with_commitment(String::from(rpc_url), commitment),
+        }
+    }
+}
+
+impl WalletRpc for SolanaRpc {
+    fn get_balance(&self, pubkey: &Pubkey) -> Result<u64, WalletError> {
+        Ok(self.client.get_balance(pubkey)?)
+    }
+
+    fn get_account(&self, pubkey: &Pubkey) -> Result<Option<Account>, WalletError> {
+        Ok(self
+            .client
+            .get_account_with_commitment(pubkey, self.client.commitment())?
+            .value)
+    }
+
+    fn get_genesis_hash(&self) -> Result<Hash, WalletError> {
+        Ok(self.client.get_genesis_hash()?)
+    }
+
+    fn get_latest_blockhash(&self) -> Result<(Hash, u64), WalletError> {
+        Ok(self
+            .client
+            .get_latest_blockhash_with_commitment(self.client.commitment())?)
+    }
+
+    fn get_block_height(&self) -> Result<u64, WalletError> {
+        Ok(self.client.get_block_height()?)
+    }
+
+    fn get_fee_for_message(&self, message: &Message) -> Result<u64, WalletError> {
+        Ok(self.client.get_fee_for_message(message)?)
+    }
+
+    fn get_minimum_balance_for_rent_exemption(&self, data_len: usize) -> Result<u64, WalletError> {
+        Ok(self
+            .client
+            .get_minimum_balance_for_rent_exemption(data_len)?)
+    }
+
+    fn send_and_confirm_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<Signature, WalletError> {
+        Ok(self.client.send_and_confirm_transaction(transaction)?)
+    }
+
+    fn simulate_transaction(
+        &self,
+        transaction: &Transaction,
+    ) -> Result<RpcSimulateTransactionResult, WalletError> {
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(self.client.commitment()),
+            ..RpcSimulateTransactionConfig::default()
+        };
+        Ok(self
+            .client
+            .simulate_transaction_with_config(transaction, config)?
+            .value)
+    }
+
+    fn get_signature_status(
+        &self,
+        signature: &Signature,
+    ) -> Result<Option<TransactionStatus>, WalletError> {
+        Ok(self
+            .client
+            .get_signature_statuses_with_history(&[*signature])?
+            .value
+            .into_iter()
+            .next()
+            .flatten())
+    }
+
+    fn get_signatures_for_address(
+        &self,
+        address: &Pubkey,
+        before: Option<Signature>,
+        until: Option<Signature>,
+        limit: usize,
+    ) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>, WalletError> {
+        Ok(self.client.get_signatures_for_address_with_config(
+            address,
+            GetConfirmedSignaturesForAddress2Config {
+                before,
+                until,
+                limit: Some(limit),
+                commitment: Some(self.client.commitment()),
+            },
+        )?)
+    }
+
+    fn get_transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta, WalletError> {
+        Ok(self.client.get_transaction_with_config(
+            signature,
+            RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Base64),
+                commitment: Some(self.client.commitment()),
+                max_supported_transaction_version: Some(0),
+            },
+        )?)
+    }
+
+    fn get_token_accounts_by_owner(
+        &self,
+        owner: &Pubkey,
+        program_id: &Pubkey,
+    ) -> Result<Vec<RpcKeyedAccount>, WalletError> {
+        Ok(self
+            .client
+            .get_token_accounts_by_owner(owner, TokenAccountsFilter::ProgramId(*program_id))?)
+    }
+
+    fn get_recent_prioritization_fees(&self, accounts: &[Pubkey]) -> Result<Vec<u64>, WalletError> {
+        Ok(self
+            .client
+            .get_recent_prioritization_fees(accounts)?
+            .iter()
+            .map(|fee| fee.prioritization_fee)
+            .collect())
+    }
+
+    fn get_epoch(&self) -> Result<Epoch, WalletError> {
+        Ok(self.client.get_epoch_info()?.epoch)
+    }
+
+    fn get_epoch_schedule(&self) -> Result<EpochSchedule, WalletError> {
+        Ok(self.client.get_epoch_schedule()?)
+    }
+
+    fn get_inflation_reward(
+        &self,
+        address: &Pubkey,
+    ) -> Result<Option<RpcInflationReward>, WalletError> {
+        Ok(self
+            .client
+            .get_inflation_reward(&[*address], None)?
+            .into_iter()
+            .next()
+            .flatten())
+    }
+
+    fn get_stake_minimum_delegation(&self) -> Result<u64, WalletError> {
+        Ok(self.client.get_stake_minimum_delegation()?)
+    }
+}
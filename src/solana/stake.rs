@@ -0,0 +1,307 @@
+use crate::error::WalletError;
+use crate::solana::rpc::WalletRpc;
+use solana_client::rpc_response::RpcInflationReward;
+use solana_sdk::{
+    account::from_account,
+    feature::{self, Feature},
+    feature_set,
+    pubkey::Pubkey,
+    stake::{
+        self,
+        state::{Authorized, Delegation, Lockup, StakeStateV2},
+    },
+    stake_history::{StakeHistory, StakeHistoryEntry},
+    sysvar,
+};
+use std::fmt;
+
+/// État d'activation d'un compte de stake.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StakeState {
+    /// Compte initialisé, jamais délégué.
+    Initialized,
+    /// Délégation en cours de chauffe (warmup) : une partie du stake n'est pas encore active.
+    Activating,
+    Active,
+    /// Désactivation en cours (cooldown) : le stake n'est pas encore retirable.
+    Deactivating,
+    /// Stake désactivé : toute la balance est retirable.
+    Inactive,
+}
+
+impl fmt::Display for StakeState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match self {
+            StakeState::Initialized => "Initialized (not delegated)",
+            StakeState::Activating => "Activating",
+            StakeState::Active => "Active",
+            StakeState::Deactivating => "Deactivating",
+            StakeState::Inactive => "Inactive",
+        };
+        write!(f, "{}", state)
+    }
+}
+
+/// Compte de stake natif.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StakeAccount {
+    pub address: Pubkey,
+    /// Balance du compte (en lamports).
+    pub balance: u64,
+    pub rent_exempt_reserve: u64,
+    pub authorized: Authorized,
+    pub lockup: Lockup,
+    /// Délégation vers un compte de vote, ou None si le compte n'a jamais été délégué.
+    pub delegation: Option<Delegation>,
+}
+
+/// Activation d'un compte de stake à l'epoch courante.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StakeActivation {
+    pub epoch: u64,
+    pub state: StakeState,
+    /// Stake actif, en chauffe et en cours de désactivation (en lamports).
+    pub effective: u64,
+    pub activating: u64,
+    pub deactivating: u64,
+}
+
+impl StakeActivation {
+    /// Calcule l'activation d'une délégation à une epoch donnée, à partir de l'historique du stake du réseau.
+    ///
+    /// # Arguments:
+    /// - delegation - La délégation du compte (None pour un compte jamais délégué).
+    /// - epoch - L'epoch courante.
+    /// - history - L'historique du stake du réseau (sysvar "StakeHistory").
+    /// - new_rate_activation_epoch - L'epoch d'activation du nouveau taux de chauffe / refroidissement (9 % au lieu de 25 %).
+    pub fn compute(
+        delegation: Option<&Delegation>,
+        epoch: u64,
+        history: &StakeHistory,
+        new_rate_activation_epoch: Option<u64>,
+    ) -> Self {
+        let Some(delegation) = delegation else {
+            return Self::from_entry(epoch, StakeState::Initialized, StakeHistoryEntry::default());
+        };
+        let entry =
+            delegation.stake_activating_and_deactivating(epoch, history, new_rate_activation_epoch);
+        let state = if entry.deactivating > 0 {
+            StakeState::Deactivating
+        } else if entry.activating > 0 {
+            StakeState::Activating
+        } else if entry.effective > 0 {
+            StakeState::Active
+        } else {
+            StakeState::Inactive
+        };
+        Self::from_entry(epoch, state, entry)
+    }
+
+    /// Retourne le stake encore engagé (actif, en chauffe ou en cours de désactivation).
+    pub fn staked(&self) -> u64 {
+        self.effective
+            .saturating_add(self.activating)
+            .saturating_add(self.deactivating)
+    }
+
+    fn from_entry(epoch: u64, state: StakeState, entry: StakeHistoryEntry) -> Self {
+        Self {
+            epoch,
+            state,
+            effective: entry.effective,
+            activating: entry.activating,
+            deactivating: entry.deactivating,
+        }
+    }
+}
+
+impl StakeAccount {
+    /// Retourne le montant retirable (en lamports) : toute la balance si plus rien n'est engagé,
+    /// sinon uniquement les lamports en excès de la réserve de rente et du stake délégué.
+    pub fn withdrawable(&self, activation: &StakeActivation) -> u64 {
+        match self.delegation {
+            Some(delegation) if activation.staked() > 0 => self
+                .balance
+                .saturating_sub(self.rent_exempt_reserve)
+                .saturating_sub(delegation.stake),
+            _ => self.balance,
+        }
+    }
+}
+
+pub struct SolanaStake {}
+
+impl SolanaStake {
+    /// Récupère et décode un compte de stake.
+    ///
+    /// # Arguments:
+    /// - rpc - Le client RPC du réseau Solana.
+    /// - address - L'adresse du compte de stake.
+    ///
+    /// # Returns:
+    /// Retourne le compte de stake, ou une erreur si le compte n'existe pas ou n'est pas un compte de stake initialisé.
+    pub fn fetch(rpc: &dyn WalletRpc, address: &Pubkey) -> Result<StakeAccount, WalletError> {
+        let account = rpc.get_account(address)?.ok_or_else(|| {
+            WalletError::InvalidInput(format!("Stake account {} not found", address))
+        })?;
+        if account.owner != stake::program::id() {
+            return Err(WalletError::InvalidInput(format!(
+                "{} is not a stake account (owner: {})",
+                address, account.owner
+            )));
+        }
+
+        let state: StakeStateV2 = bincode::deserialize(&account.data)
+            .map_err(|e| format!("Invalid stake account {}: {}", address, e))?;
+        let meta = state
+            .meta()
+            .ok_or_else(|| format!("Stake account {} is not initialized", address))?;
+
+        Ok(StakeAccount {
+            address: *address,
+            balance: account.lamports,
+            rent_exempt_reserve: meta.rent_exempt_reserve,
+            authorized: meta.authorized,
+            lockup: meta.lockup,
+            delegation: state.delegation(),
+        })
+    }
+
+    /// Calcule l'activation d'un compte de stake à l'epoch courante.
+    pub fn activation(
+        rpc: &dyn WalletRpc,
+        stake_account: &StakeAccount,
+    ) -> Result<StakeActivation, WalletError> {
+        let epoch = rpc.get_epoch()?;
+
+        let history_account = rpc
+            .get_account(&sysvar::stake_history::id())
+            .map_err(|e| e.context("Failed to fetch the stake history"))?
+            .ok_or_else(|| {
+                WalletError::Rpc("The stake history sysvar was not found".to_string())
+            })?;
+        let history: StakeHistory =
+            from_account(&history_account).ok_or("Invalid stake history sysvar")?;
+
+        // Epoch à partir de laquelle le réseau applique le taux de chauffe / refroidissement réduit.
+        let new_rate_activation_epoch = match rpc
+            .get_account(&feature_set::reduce_stake_warmup_cooldown::id())?
+            .and_then(|account| feature::from_account(&account))
+        {
+            Some(Feature {
+                activated_at: Some(slot),
+            }) => Some(rpc.get_epoch_schedule()?.get_epoch(slot)),
+            _ => None,
+        };
+
+        Ok(StakeActivation::compute(
+            stake_account.delegation.as_ref(),
+            epoch,
+            &history,
+            new_rate_activation_epoch,
+        ))
+    }
+
+    /// Retourne la dernière récompense d'inflation versée à un compte de stake (None s'il n'en a pas reçu).
+    pub fn last_reward(
+        rpc: &dyn WalletRpc,
+        address: &Pubkey,
+    ) -> Result<Option<RpcInflationReward>, WalletError> {
+        rpc.get_inflation_reward(address)
+            .map_err(|e| e.context("Failed to fetch the inflation reward"))
+    }
+
+    /// Retourne le montant minimal (en lamports) d'un compte de stake pour être exempté de rente.
+    pub fn minimum_balance(rpc: &dyn WalletRpc) -> Result<u64, WalletError> {
+        rpc.get_minimum_balance_for_rent_exemption(StakeStateV2::size_of())
+    }
+
+    /// Retourne le montant minimal (en lamports) d'une délégation.
+    pub fn minimum_delegation(rpc: &dyn WalletRpc) -> Result<u64, WalletError> {
+        rpc.get_stake_minimum_delegation()
+    }
+
+    /// Vérifie qu'une adresse est un compte de vote (un validateur).
+    pub fn check_vote_account(
+        rpc: &dyn WalletRpc,
+        vote_account: &Pubkey,
+    ) -> Result<(), WalletError> {
+        let account = rpc.get_account(vote_account)?.ok_or_else(|| {
+            WalletError::InvalidInput(format!("Vote account {} not found", vote_account))
+        })?;
+        if account.owner != solana_sdk::vote::program::id() {
+            return Err(WalletError::InvalidInput(format!(
+                "{} is not a vote account (owner: {})",
+                vote_account, account.owner
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn delegation(activation_epoch: u64, deactivation_epoch: u64) -> Delegation {
+        Delegation {
+            voter_pubkey: Pubkey::new_unique(),
+            stake: 5_000_000_000,
+            activation_epoch,
+            deactivation_epoch,
+            ..Delegation::default()
+        }
+    }
+
+    #[test]
+    fn test_activation() {
+        let history = StakeHistory::default();
+
+        let activation = StakeActivation::compute(None, 10, &history, None);
+        assert_eq!(activation.state, StakeState::Initialized);
+        assert_eq!(activation.staked(), 0);
+
+        // Délégué à l'epoch courante : tout le stake est en chauffe.
+        let activation =
+            StakeActivation::compute(Some(&delegation(10, u64::MAX)), 10, &history, None);
+        assert_eq!(activation.state, StakeState::Activating);
+        assert_eq!(activation.activating, 5_000_000_000);
+
+        // Sans historique pour les epochs passées, le stake est considéré comme entièrement actif.
+        let activation =
+            StakeActivation::compute(Some(&delegation(5, u64::MAX)), 10, &history, None);
+        assert_eq!(activation.state, StakeState::Active);
+        assert_eq!(activation.effective, 5_000_000_000);
+
+        let activation = StakeActivation::compute(Some(&delegation(5, 10)), 10, &history, None);
+        assert_eq!(activation.state, StakeState::Deactivating);
+
+        let activation = StakeActivation::compute(Some(&delegation(5, 8)), 10, &history, None);
+        assert_eq!(activation.state, StakeState::Inactive);
+    }
+
+    #[test]
+    fn test_withdrawable() {
+        let history = StakeHistory::default();
+        let mut account = StakeAccount {
+            address: Pubkey::new_unique(),
+            balance: 5_102_282_880,
+            rent_exempt_reserve: 2_282_880,
+            authorized: Authorized::auto(&Pubkey::new_unique()),
+            lockup: Lockup::default(),
+            delegation: None,
+        };
+        let activation = StakeActivation::compute(None, 10, &history, None);
+        assert_eq!(account.withdrawable(&activation), 5_102_282_880);
+
+        // Stake actif : seuls les lamports en excès sont retirables.
+        account.delegation = Some(delegation(5, u64::MAX));
+        let activation = StakeActivation::compute(account.delegation.as_ref(), 10, &history, None);
+        assert_eq!(account.withdrawable(&activation), 100_000_000);
+
+        // Stake désactivé : toute la balance est retirable (le compte est fermé).
+        account.delegation = Some(delegation(5, 8));
+        let activation = StakeActivation::compute(account.delegation.as_ref(), 10, &history, None);
+        assert_eq!(account.withdrawable(&activation), 5_102_282_880);
+    }
+}
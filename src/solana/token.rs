@@ -0,0 +1,298 @@
+use crate::error::WalletError;
+use crate::solana::amount::SolAmount;
+use crate::solana::rpc::WalletRpc;
+use solana_account_decoder::{parse_token::UiTokenAccount, UiAccountData};
+use solana_sdk::{account::Account, instruction::Instruction, pubkey::Pubkey};
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+use spl_token_2022::extension::StateWithExtensions;
+use spl_token_2022::state::{Account as TokenAccountState, Mint};
+use std::str::FromStr;
+
+/// Programme SPL qui gère un token.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenProgram {
+    /// Programme SPL Token historique.
+    Token,
+    /// Programme SPL Token-2022 (Token Extensions).
+    Token2022,
+}
+
+impl TokenProgram {
+    pub const ALL: [TokenProgram; 2] = [TokenProgram::Token, TokenProgram::Token2022];
+
+    /// Retourne l'adresse du programme.
+    pub fn id(&self) -> Pubkey {
+        match self {
+            TokenProgram::Token => spl_token::id(),
+            TokenProgram::Token2022 => spl_token_2022::id(),
+        }
+    }
+
+    /// Retourne le programme SPL correspondant au propriétaire d'un compte, ou None si ce n'est pas un programme SPL.
+    pub fn from_owner(owner: &Pubkey) -> Option<Self> {
+        Self::ALL.into_iter().find(|program| program.id() == *owner)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TokenProgram::Token => "Token",
+            TokenProgram::Token2022 => "Token-2022",
+        }
+    }
+}
+
+/// Mint d'un token SPL.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenMint {
+    pub address: Pubkey,
+    pub program: TokenProgram,
+    pub decimals: u8,
+}
+
+impl TokenMint {
+    /// Indique si le mint est celui du SOL natif ("wrapped SOL") : ses unités de base sont des lamports.
+    pub fn is_native(&self) -> bool {
+        self.address == spl_token::native_mint::id()
+            || self.address == spl_token_2022::native_mint::id()
+    }
+}
+
+/// Compte de token SPL (balance d'un token, pour un propriétaire).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TokenAccount {
+    pub address: Pubkey,
+    pub mint: Pubkey,
+    /// Propriétaire du compte (l'adresse du wallet).
+    pub owner: Pubkey,
+    pub program: TokenProgram,
+    /// Balance en unités de base du token.
+    pub amount: u64,
+    pub decimals: u8,
+}
+
+impl TokenAccount {
+    /// Retourne la balance avec ses décimales (ex : "1.500000").
+    pub fn ui_amount(&self) -> String {
+        SolAmount::format_decimal(self.amount, self.decimals)
+    }
+}
+
+/// Montant d'un transfert de tokens.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenAmount {
+    /// Montant exact en unités de base du token.
+    Units(u64),
+    /// Toute la balance du compte de token ("ALL" / "max").
+    All,
+}
+
+impl TokenAmount {
+    /// Analyse un montant de tokens saisi avec ses décimales (ex : "1.5"), ou "ALL" / "max".
+    pub fn parse(input: &str, decimals: u8) -> Result<Self, String> {
+        let input = input.trim();
+        if input.eq_ignore_ascii_case("all") || input.eq_ignore_ascii_case("max") {
+            return Ok(TokenAmount::All);
+        }
+        match SolAmount::parse_decimal(input, decimals)? {
+            0 => Err("Amount must be greater than zero".to_string()),
+            units => Ok(TokenAmount::Units(units)),
+        }
+    }
+}
+
+pub struct SolanaToken {}
+
+impl SolanaToken {
+    /// Retourne tous les comptes de tokens (SPL Token et Token-2022) d'un propriétaire.
+    ///
+    /// # Arguments:
+    /// - rpc - Le client RPC du réseau Solana.
+    /// - owner - L'adresse du propriétaire (le wallet).
+    pub fn accounts(rpc: &dyn WalletRpc, owner: &Pubkey) -> Result<Vec<TokenAccount>, WalletError> {
+        let mut accounts = Vec::new();
+        for program in TokenProgram::ALL {
+            let keyed_accounts = rpc
+                .get_token_accounts_by_owner(owner, &program.id())
+                .map_err(|e| e.context(&format!("Failed to fetch {} accounts", program.name())))?;
+
+            for keyed_account in keyed_accounts {
+                let UiAccountData::Json(parsed_account) = keyed_account.account.data else {
+                    return Err(WalletError::Rpc(
+                        "Unexpected token account encoding (jsonParsed expected)".to_string(),
+                    ));
+                };
+                let info: UiTokenAccount =
+                    serde_json::from_value(parsed_account.parsed["info"].clone())
+                        .map_err(|e| format!("Invalid token account: {}", e))?;
+
+                accounts.push(TokenAccount {
+                    address: Pubkey::from_str(&keyed_account.pubkey)?,
+                    mint: Pubkey::from_str(&info.mint)?,
+                    owner: Pubkey::from_str(&info.owner)?,
+                    program,
+                    amount: info.token_amount.amount.parse().map_err(|_| {
+                        format!("Invalid token amount: {}", info.token_amount.amount)
+                    })?,
+                    decimals: info.token_amount.decimals,
+                });
+            }
+        }
+        Ok(accounts)
+    }
+
+    /// Récupère le mint d'un token (son programme et ses décimales).
+    pub fn mint(rpc: &dyn WalletRpc, mint: &Pubkey) -> Result<TokenMint, WalletError> {
+        let account = rpc
+            .get_account(mint)?
+            .ok_or_else(|| format!("Mint {} not found", mint))?;
+        let program = Self::program_of(mint, &account)?;
+        let state = StateWithExtensions::<Mint>::unpack(&account.data)
+            .map_err(|_| format!("{} is not a token mint", mint))?;
+
+        Ok(TokenMint {
+            address: *mint,
+            program,
+            decimals: state.base.decimals,
+        })
+    }
+
+    /// Récupère un compte de token, ou None s'il n'existe pas.
+    pub fn account(
+        rpc: &dyn WalletRpc,
+        address: &Pubkey,
+    ) -> Result<Option<TokenAccount>, WalletError> {
+        let Some(account) = rpc.get_account(address)? else {
+            return Ok(None);
+        };
+        let program = Self::program_of(address, &account)?;
+        let state = StateWithExtensions::<TokenAccountState>::unpack(&account.data)
+            .map_err(|_| format!("{} is not a token account", address))?;
+        let mint = Self::mint(rpc, &state.base.mint)?;
+
+        Ok(Some(TokenAccount {
+            address: *address,
+            mint: state.base.mint,
+            owner: state.base.owner,
+            program,
+            amount: state.base.amount,
+            decimals: mint.decimals,
+        }))
+    }
+
+    /// Retourne l'adresse du compte de token associé (ATA) d'un propriétaire, pour un mint.
+    pub fn associated_address(owner: &Pubkey, mint: &TokenMint) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, &mint.address, &mint.program.id())
+    }
+
+    /// Retourne les instructions d'un transfert de tokens ("transfer_checked") entre les comptes associés
+    /// de l'expéditeur et du destinataire.
+    ///
+    /// # Arguments:
+    /// - sender - Le propriétaire du compte source (qui signe et paie la création éventuelle du compte du destinataire).
+    /// - recipient - L'adresse du wallet destinataire.
+    /// - mint - Le mint du token.
+    /// - amount - Le montant en unités de base du token.
+    /// - create_recipient_account - Crée d'abord le compte associé du destinataire (s'il n'existe pas).
+    pub fn transfer_instructions(
+        sender: &Pubkey,
+        recipient: &Pubkey,
+        mint: &TokenMint,
+        amount: u64,
+        create_recipient_account: bool,
+    ) -> Result<Vec<Instruction>, WalletError> {
+        let program_id = mint.program.id();
+        let source = Self::associated_address(sender, mint);
+        let destination = Self::associated_address(recipient, mint);
+
+        let mut instructions = Vec::new();
+        if create_recipient_account {
+            instructions.push(create_associated_token_account_idempotent(
+                sender,
+                recipient,
+                &mint.address,
+                &program_id,
+            ));
+        }
+        instructions.push(spl_token_2022::instruction::transfer_checked(
+            &program_id,
+            &source,
+            &mint.address,
+            &destination,
+            sender,
+            &[],
+            amount,
+            mint.decimals,
+        )?);
+        Ok(instructions)
+    }
+
+    /// Retourne l'instruction de fermeture d'un compte de token vide : sa réserve de rente est rendue au propriétaire.
+    pub fn close_instruction(account: &TokenAccount) -> Result<Instruction, WalletError> {
+        Ok(spl_token_2022::instruction::close_account(
+            &account.program.id(),
+            &account.address,
+            &account.owner,
+            &account.owner,
+            &[],
+        )?)
+    }
+
+    /// Vérifie qu'un compte appartient à un programme SPL, et retourne ce programme.
+    fn program_of(address: &Pubkey, account: &Account) -> Result<TokenProgram, WalletError> {
+        TokenProgram::from_owner(&account.owner).ok_or_else(|| {
+            format!(
+                "{} is not owned by a token program (owner: {})",
+                address, account.owner
+            )
+            .into()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_token_amount() {
+        assert_eq!(
+            TokenAmount::parse("1.5", 6),
+            Ok(TokenAmount::Units(1_500_000))
+        );
+        assert_eq!(TokenAmount::parse("ALL", 6), Ok(TokenAmount::All));
+        assert_eq!(TokenAmount::parse("42", 0), Ok(TokenAmount::Units(42)));
+        assert!(TokenAmount::parse("0.1", 0).is_err());
+        assert!(TokenAmount::parse("0", 6).is_err());
+    }
+
+    #[test]
+    fn test_transfer_instructions() {
+        let sender = Pubkey::new_unique();
+        let recipient = Pubkey::new_unique();
+        let mint = TokenMint {
+            address: Pubkey::new_unique(),
+            program: TokenProgram::Token2022,
+            decimals: 9,
+        };
+
+        let instructions =
+            SolanaToken::transfer_instructions(&sender, &recipient, &mint, 1_000, true).unwrap();
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(
+            instructions[0].program_id,
+            spl_associated_token_account::id()
+        );
+        assert_eq!(instructions[1].program_id, spl_token_2022::id());
+        assert_eq!(
+            instructions[1].accounts[2].pubkey,
+            SolanaToken::associated_address(&recipient, &mint)
+        );
+
+        let instructions =
+            SolanaToken::transfer_instructions(&sender, &recipient, &mint, 1_000, false).unwrap();
+        assert_eq!(instructions.len(), 1);
+    }
+}
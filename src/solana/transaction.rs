@@ -1,68 +1,475 @@
-use solana_client::rpc_client::RpcClient;
+use crate::error::WalletError;
+use crate::solana::compute_budget::ComputeBudget;
+use crate::solana::memo::SolanaMemo;
+use crate::solana::nonce::DurableNonce;
+use crate::solana::rpc::WalletRpc;
 use solana_sdk::{
+    hash::Hash,
+    instruction::Instruction,
     message::Message,
     pubkey::Pubkey,
-    signature::{Keypair, Signer},
+    signature::{Keypair, Signature, Signer},
     system_instruction,
     transaction::Transaction,
 };
-use std::env;
+
+/// Montant maximal transférable d'un compte, et son détail.
+pub struct TransferableBalance {
+    /// Balance actuelle du compte (en lamports).
+    pub balance: u64,
+    /// Frais de la transaction de transfert (en lamports).
+    pub fee: u64,
+    /// Réserve conservée pour que le compte reste exempté de rente (en lamports).
+    pub rent_reserve: u64,
+    /// Montant transférable : balance - frais - réserve de rente (en lamports).
+    pub lamports: u64,
+}
+
+/// Résumé d'un transfert, affiché avant sa confirmation.
+pub struct TransferPreview {
+    pub sender: Pubkey,
+    pub recipient: Pubkey,
+    /// Montant du transfert (en lamports).
+    pub lamports: u64,
+    /// Frais estimés de la transaction (en lamports), frais de priorité compris.
+    pub fee: u64,
+    /// Instructions "ComputeBudget" de la transaction.
+    pub compute_budget: ComputeBudget,
+    /// Limite d'unités de calcul sur laquelle les frais de priorité sont payés.
+    pub compute_unit_limit: u32,
+    /// Frais de priorité (en lamports), inclus dans "fee".
+    pub priority_fee: u64,
+    /// Balance actuelle de l'expéditeur (en lamports).
+    pub balance: u64,
+    /// Memo ajouté au transfert (instruction SPL Memo).
+    pub memo: Option<String>,
+    /// Avertissements sur le destinataire.
+    pub warnings: Vec<String>,
+}
+
+impl TransferPreview {
+    /// Retourne la balance de l'expéditeur après le transfert, ou None si elle ne couvre pas le montant et les frais.
+    pub fn balance_after(&self) -> Option<u64> {
+        self.balance
+            .checked_sub(self.lamports)
+            .and_then(|remaining| remaining.checked_sub(self.fee))
+    }
+}
 
 pub struct SolanaTransaction {}
 
 impl SolanaTransaction {
+    /// Calcule le montant maximal qu'un compte peut transférer ("ALL" / "max") :
+    /// sa balance, moins les frais de la transaction et la réserve d'exemption de rente.
+    ///
+    /// # Arguments:
+    /// - rpc - Le client RPC du réseau Solana.
+    /// - sender_pubkey - La clé publique du compte expéditeur.
+    /// - recipient_pubkey - La clé publique du destinataire (utilisée pour estimer les frais).
+    /// - nonce - Le compte nonce durable utilisé par la transaction (optionnel).
+    /// - compute_budget - Les instructions "ComputeBudget" de la transaction (frais de priorité).
+    /// - memo - Le memo ajouté au transfert (optionnel).
+    ///
+    /// # Returns:
+    /// Retourne le détail du montant transférable, ou une erreur si la balance ne couvre pas les frais et la réserve.
+    pub fn transferable_balance(
+        rpc: &dyn WalletRpc,
+        sender_pubkey: &Pubkey,
+        recipient_pubkey: &Pubkey,
+        nonce: Option<&DurableNonce>,
+        compute_budget: &ComputeBudget,
+        memo: Option<&str>,
+    ) -> Result<TransferableBalance, WalletError> {
+        let balance = rpc.get_balance(sender_pubkey)?;
+
+        // Les frais ne dépendent pas du montant : ils sont estimés sur le message de transfert de toute la balance.
+        let message = Self::transfer_message(
+            sender_pubkey,
+            recipient_pubkey,
+            balance,
+            nonce,
+            compute_budget,
+            memo,
+        );
+        let fee = Self::estimate_fee(rpc, &message, nonce, compute_budget)?;
+
+        // Réserve minimale d'un compte système (sans données) pour rester exempté de rente.
+        let rent_reserve = rpc.get_minimum_balance_for_rent_exemption(0)?;
+
+        let lamports = balance
+            .checked_sub(fee)
+            .and_then(|remaining| remaining.checked_sub(rent_reserve))
+            .filter(|lamports| *lamports > 0)
+            .ok_or_else(|| {
+                WalletError::InsufficientFunds(format!(
+                    "Insufficient balance: {} lamports does not cover the fee ({} lamports) and the rent-exempt reserve ({} lamports)",
+                    balance, fee, rent_reserve
+                ))
+            })?;
+
+        Ok(TransferableBalance {
+            balance,
+            fee,
+            rent_reserve,
+            lamports,
+        })
+    }
+
+    /// Prépare le résumé d'un transfert : frais estimés, balance de l'expéditeur et avertissements sur le destinataire.
+    ///
+    /// # Arguments:
+    /// - rpc - Le client RPC du réseau Solana.
+    /// - sender_pubkey - La clé publique du compte expéditeur.
+    /// - recipient_pubkey - La clé publique du destinataire.
+    /// - lamports - Le montant en lamports à envoyer.
+    /// - nonce - Le compte nonce durable utilisé par la transaction (optionnel).
+    /// - compute_budget - Les instructions "ComputeBudget" de la transaction (frais de priorité).
+    /// - memo - Le memo ajouté au transfert (optionnel).
+    ///
+    /// # Returns:
+    /// Retourne le résumé du transfert, ou une erreur RPC.
+    pub fn preview_transfer(
+        rpc: &dyn WalletRpc,
+        sender_pubkey: &Pubkey,
+        recipient_pubkey: &Pubkey,
+        lamports: u64,
+        nonce: Option<&DurableNonce>,
+        compute_budget: &ComputeBudget,
+        memo: Option<&str>,
+    ) -> Result<TransferPreview, WalletError> {
+        let message = Self::transfer_message(
+            sender_pubkey,
+            recipient_pubkey,
+            lamports,
+            nonce,
+            compute_budget,
+            memo,
+        );
+        let fee = Self::estimate_fee(rpc, &message, nonce, compute_budget)?;
+
+        let balance = rpc.get_balance(sender_pubkey)?;
+
+        let mut warnings = Vec::new();
+        if sender_pubkey == recipient_pubkey {
+            warnings
+                .push("The recipient is the sender: you are sending SOL to yourself.".to_string());
+        }
+        if !recipient_pubkey.is_on_curve() {
+            warnings.push(
+                "The recipient address is off-curve (e.g. a program derived address): no private key can sign for it."
+                    .to_string(),
+            );
+        }
+        if rpc
+            .get_account(recipient_pubkey)?
+            .is_some_and(|account| account.executable)
+        {
+            warnings.push(
+                "The recipient is a program: SOL sent to a program account is usually lost."
+                    .to_string(),
+            );
+        }
+        // La simulation (sans signature) détecte les transferts que le réseau refuserait. Elle est impossible avec
+        // un nonce durable : sa valeur n'est pas un blockhash récent connu du noeud RPC.
+        if nonce.is_none() {
+            let simulation =
+                rpc.simulate_transaction(&Transaction::new_unsigned(message.clone()))?;
+            if let Some(err) = simulation.err {
+                warnings.push(format!("The simulation of the transaction failed: {}", err));
+            }
+        }
+
+        Ok(TransferPreview {
+            sender: *sender_pubkey,
+            recipient: *recipient_pubkey,
+            lamports,
+            fee,
+            compute_budget: *compute_budget,
+            compute_unit_limit: compute_budget.unit_limit_for(&message),
+            priority_fee: compute_budget.priority_fee(&message),
+            balance,
+            memo: memo.map(String::from),
+            warnings,
+        })
+    }
+
+    /// Construit une transaction de transfert non signée, avec le dernier blockhash du réseau
+    /// (ou avec la valeur d'un nonce durable, qui n'expire pas).
+    ///
+    /// # Arguments:
+    /// - rpc - Le client RPC du réseau Solana.
+    /// - sender_pubkey - La clé publique du compte expéditeur (qui paie aussi les frais).
+    /// - recipient_pubkey - La clé publique du destinataire.
+    /// - lamports - Le montant en lamports à envoyer.
+    /// - nonce - Le compte nonce durable à utiliser à la place d'un blockhash récent (optionnel).
+    /// - compute_budget - Les instructions "ComputeBudget" de la transaction (frais de priorité).
+    /// - memo - Le memo ajouté au transfert (optionnel).
+    pub fn build_transfer(
+        rpc: &dyn WalletRpc,
+        sender_pubkey: &Pubkey,
+        recipient_pubkey: &Pubkey,
+        lamports: u64,
+        nonce: Option<&DurableNonce>,
+        compute_budget: &ComputeBudget,
+        memo: Option<&str>,
+    ) -> Result<Transaction, WalletError> {
+        let mut message = Self::transfer_message(
+            sender_pubkey,
+            recipient_pubkey,
+            lamports,
+            nonce,
+            compute_budget,
+            memo,
+        );
+        message.recent_blockhash = Self::blockhash(rpc, nonce)?;
+        Ok(Transaction::new_unsigned(message))
+    }
+
+    /// Signe et envoie une transaction composée d'instructions quelconques (le premier signataire paie les frais).
+    ///
+    /// # Arguments:
+    /// - rpc - Le client RPC du réseau Solana.
+    /// - instructions - Les instructions de la transaction.
+    /// - signers - Les paires de clés des signataires requis (le premier est le payeur des frais).
+    ///
+    /// # Returns:
+    /// Retourne la signature de la transaction, ou une erreur en cas d'échec.
+    pub fn send_instructions(
+        rpc: &dyn WalletRpc,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<Signature, WalletError> {
+        let payer = signers.first().ok_or("At least one signer is required")?;
+        let message = Message::new(instructions, Some(&payer.pubkey()));
+        let mut transaction = Transaction::new_unsigned(message);
+        transaction.try_sign(signers, Self::blockhash(rpc, None)?)?;
+        Self::broadcast(rpc, &transaction)
+    }
+
+    /// Diffuse une transaction signée sur le réseau Solana, et attend sa confirmation.
+    ///
+    /// # Returns:
+    /// Retourne la signature de la transaction, ou une erreur en cas d'échec.
+    pub fn broadcast(
+        rpc: &dyn WalletRpc,
+        transaction: &Transaction,
+    ) -> Result<Signature, WalletError> {
+        rpc.send_and_confirm_transaction(transaction)
+    }
+
     /// Envoyer des SOL à une adresse spécifique.
     ///
     /// # Arguments:
-    /// - rpc_url - L'URL du point de terminaison RPC pour se connecter au réseau Solana.
+    /// - rpc - Le client RPC du réseau Solana.
     /// - sender_keypair - La paire de clés du compte expéditeur utilisée pour signer la transaction.
     /// - recipient_pubkey - La clé publique du destinataire qui recevra les SOL.
     /// - lamports - Le montant en lamports à envoyer (1 SOL = 1_000_000_000 lamports).
+    /// - nonce - Le compte nonce durable à utiliser à la place d'un blockhash récent (optionnel).
+    ///   Son autorité doit être l'expéditeur.
+    /// - compute_budget - Les instructions "ComputeBudget" de la transaction (frais de priorité).
+    /// - memo - Le memo ajouté au transfert (optionnel).
     ///
     /// # Returns:
-    /// Retourne un "Result" qui est Ok si la transaction est réussie, ou une erreur en cas d'échec.
+    /// Retourne la signature de la transaction, ou une erreur en cas d'échec.
     pub fn send_lamports(
-        rpc_url: &str,
+        rpc: &dyn WalletRpc,
         sender_keypair: &Keypair,
         recipient_pubkey: &Pubkey,
         lamports: u64,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        // Vérifier si nous sommes en mode test.
-        if env::var("TEST_MODE").unwrap_or_default() == "true" {
-            // Simulation de la transaction.
-            println!(
-                "Simulating transaction: {} lamports from {} to {}",
-                lamports,
-                sender_keypair.pubkey(),
-                recipient_pubkey
+        nonce: Option<&DurableNonce>,
+        compute_budget: &ComputeBudget,
+        memo: Option<&str>,
+    ) -> Result<Signature, WalletError> {
+        // Récupère le dernier blockhash (ou la valeur du nonce durable) utilisé comme référence pour la transaction.
+        let recent_blockhash = Self::blockhash(rpc, nonce)?;
+
+        // Crée le message de transfert des lamports du compte expéditeur au destinataire,
+        // en spécifiant le compte expéditeur comme compte de frais.
+        let message = Self::transfer_message(
+            &sender_keypair.pubkey(),
+            recipient_pubkey,
+            lamports,
+            nonce,
+            compute_budget,
+            memo,
+        );
+
+        // Crée la transaction et la signe avec la paire de clés de l'expéditeur.
+        let mut transaction = Transaction::new_unsigned(message);
+        transaction.try_sign(&[sender_keypair], recent_blockhash)?;
+
+        // Envoie la transaction signée au réseau Solana et attend la confirmation.
+        Self::broadcast(rpc, &transaction)
+    }
+
+    /// Crée le message d'un transfert (sans blockhash), précédé des instructions "ComputeBudget". Avec un nonce
+    /// durable, l'instruction "advance_nonce_account" est ajoutée en première position, comme l'exige le runtime.
+    fn transfer_message(
+        sender_pubkey: &Pubkey,
+        recipient_pubkey: &Pubkey,
+        lamports: u64,
+        nonce: Option<&DurableNonce>,
+        compute_budget: &ComputeBudget,
+        memo: Option<&str>,
+    ) -> Message {
+        let mut instructions = compute_budget.instructions();
+        instructions.push(system_instruction::transfer(
+            sender_pubkey,
+            recipient_pubkey,
+            lamports,
+        ));
+        if let Some(memo) = memo {
+            instructions.push(SolanaMemo::instruction(memo, sender_pubkey));
+        }
+        match nonce {
+            Some(nonce) => Message::new_with_nonce(
+                instructions,
+                Some(sender_pubkey),
+                &nonce.account,
+                &nonce.authority,
+            ),
+            None => Message::new(&instructions, Some(sender_pubkey)),
+        }
+    }
+
+    /// Retourne le blockhash de la transaction : la valeur du nonce durable, sinon le dernier blockhash du réseau.
+    fn blockhash(rpc: &dyn WalletRpc, nonce: Option<&DurableNonce>) -> Result<Hash, WalletError> {
+        match nonce {
+            Some(nonce) => Ok(nonce.blockhash),
+            None => Ok(rpc.get_latest_blockhash()?.0),
+        }
+    }
+
+    /// Estime les frais d'un message. Le noeud RPC ne connaît pas la valeur d'un nonce durable :
+    /// les frais sont alors calculés avec le tarif par signature enregistré dans le compte nonce
+    /// (plus les frais de priorité).
+    fn estimate_fee(
+        rpc: &dyn WalletRpc,
+        message: &Message,
+        nonce: Option<&DurableNonce>,
+        compute_budget: &ComputeBudget,
+    ) -> Result<u64, WalletError> {
+        if let Some(nonce) = nonce {
+            let signatures = u64::from(message.header.num_required_signatures);
+            return Ok(
+                nonce.lamports_per_signature * signatures + compute_budget.priority_fee(message)
             );
-            println!("Transaction sent successfully!");
-            Ok(())
-        } else {
-            // Envoi réel de la transaction via le réseau Solana :
+        }
+        let mut message = message.clone();
+        message.recent_blockhash = rpc.get_latest_blockhash()?.0;
+        rpc.get_fee_for_message(&message)
+    }
+}
 
-            // Crée un client pour interagir avec le réseau Solana via RPC.
-            let client = RpcClient::new(String::from(rpc_url));
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solana::mock_rpc::{MockRpc, MOCK_FEE};
+    use solana_sdk::account::Account;
+    use solana_sdk::rent::Rent;
 
-            // Récupère le dernier blockhash utilisé comme référence de frais pour la transaction.
-            let recent_blockhash = client.get_latest_blockhash()?;
+    #[test]
+    fn test_send_lamports() {
+        let rpc = MockRpc::new();
+        let sender = Keypair::new();
+        let recipient = Pubkey::new_unique();
 
-            // Crée une instruction pour transférer des lamports du compte expéditeur au destinataire.
-            let instruction =
-                system_instruction::transfer(&sender_keypair.pubkey(), recipient_pubkey, lamports);
+        let signature = SolanaTransaction::send_lamports(
+            &rpc,
+            &sender,
+            &recipient,
+            1_000,
+            None,
+            &ComputeBudget::default(),
+            Some("Invoice 42"),
+        )
+        .unwrap();
 
-            // Emballe l'instruction dans un message, en spécifiant le compte expéditeur comme compte de frais.
-            let message = Message::new(&[instruction], Some(&sender_keypair.pubkey()));
+        // La transaction envoyée est signée par l'expéditeur, avec le dernier blockhash du réseau.
+        let sent = rpc.sent_transactions();
+        assert_eq!(sent.len(), 1);
+        let transaction = &sent[0];
+        assert_eq!(transaction.signatures[0], signature);
+        assert!(transaction.verify().is_ok());
+        assert_eq!(transaction.message.recent_blockhash, MockRpc::blockhash());
 
-            // Crée la transaction en utilisant la paire de clés de l'expéditeur, le message et le blockhash récent.
-            // La transaction est automatiquement signée par la paire de clés de l'expéditeur lors de la création.
-            let transaction = Transaction::new(&[sender_keypair], message, recent_blockhash);
+        let instructions = &transaction.message.instructions;
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(
+            bincode::deserialize::<system_instruction::SystemInstruction>(&instructions[0].data)
+                .unwrap(),
+            system_instruction::SystemInstruction::Transfer { lamports: 1_000 }
+        );
+        assert_eq!(instructions[1].data, b"Invoice 42");
+    }
 
-            // Envoie la transaction signée au réseau Solana et attend la confirmation.
-            client.send_and_confirm_transaction(&transaction)?;
+    #[test]
+    fn test_transferable_balance() {
+        let rpc = MockRpc::new();
+        let sender = Pubkey::new_unique();
+        rpc.set_balance(&sender, 1_000_000_000);
 
-            // Retourne Ok si tout s'est bien passé.
-            Ok(())
-        }
+        let transferable = SolanaTransaction::transferable_balance(
+            &rpc,
+            &sender,
+            &Pubkey::new_unique(),
+            None,
+            &ComputeBudget::default(),
+            None,
+        )
+        .unwrap();
+        let rent_reserve = Rent::default().minimum_balance(0);
+        assert_eq!(transferable.fee, MOCK_FEE);
+        assert_eq!(transferable.rent_reserve, rent_reserve);
+        assert_eq!(
+            transferable.lamports,
+            1_000_000_000 - MOCK_FEE - rent_reserve
+        );
+
+        // Une balance qui ne couvre pas les frais et la réserve ne permet aucun transfert.
+        rpc.set_balance(&sender, rent_reserve);
+        let error = SolanaTransaction::transferable_balance(
+            &rpc,
+            &sender,
+            &Pubkey::new_unique(),
+            None,
+            &ComputeBudget::default(),
+            None,
+        )
+        .err()
+        .expect("The transfer should be refused");
+        assert_eq!(error.exit_code(), 8);
+        assert!(rpc.sent_transactions().is_empty());
+    }
+
+    #[test]
+    fn test_preview_transfer_warnings() {
+        let rpc = MockRpc::new();
+        let sender = Pubkey::new_unique();
+        let program = Keypair::new().pubkey();
+        rpc.set_balance(&sender, 1_000_000);
+        rpc.set_account(
+            &program,
+            Account {
+                lamports: 1_141_440,
+                executable: true,
+                ..Account::default()
+            },
+        );
+
+        let preview = SolanaTransaction::preview_transfer(
+            &rpc,
+            &sender,
+            &program,
+            1_000,
+            None,
+            &ComputeBudget::default(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(preview.fee, MOCK_FEE);
+        assert_eq!(preview.balance_after(), Some(1_000_000 - 1_000 - MOCK_FEE));
+        assert_eq!(preview.warnings.len(), 1);
+        assert!(preview.warnings[0].starts_with("The recipient is a program"));
     }
 }
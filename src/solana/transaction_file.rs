@@ -0,0 +1,318 @@
+use crate::error::WalletError;
+use crate::solana::compute_budget::ComputeBudget;
+use crate::solana::instruction::{InstructionSummary, SolanaInstruction};
+use crate::solana::memo::SolanaMemo;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde::{Deserialize, Serialize};
+use solana_sdk::{
+    instruction::CompiledInstruction, pubkey::Pubkey, sanitize::Sanitize, signature::Signature,
+    system_instruction::SystemInstruction, system_program, transaction::Transaction,
+};
+use std::fs;
+use std::path::Path;
+
+/// Version actuelle du format des fichiers de transaction (signature hors ligne).
+pub const TRANSACTION_FILE_VERSION: u32 = 1;
+
+/// Transfert de SOL décodé depuis les instructions d'une transaction.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TransferSummary {
+    pub from: String,
+    pub to: String,
+    pub lamports: u64,
+}
+
+/// État de la signature d'un signataire requis.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SignerSummary {
+    pub pubkey: String,
+    pub signed: bool,
+}
+
+/// Résumé lisible d'une transaction.
+///
+/// Il est toujours recalculé à partir de la transaction elle-même : la copie enregistrée dans le fichier
+/// n'est qu'informative (une commande ne lui fait jamais confiance).
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TransactionSummary {
+    pub fee_payer: String,
+    /// Toutes les instructions de la transaction, dans l'ordre (décodées pour les programmes connus).
+    #[serde(default)]
+    pub instructions: Vec<InstructionSummary>,
+    pub transfers: Vec<TransferSummary>,
+    /// Memos (SPL Memo) de la transaction.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub memos: Vec<String>,
+    /// Frais calculés à partir du message : frais par signature et frais de priorité de ses instructions "ComputeBudget".
+    pub fee: u64,
+    pub blockhash: String,
+    /// Compte nonce durable avancé par la transaction : "blockhash" est alors la valeur du nonce.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce_account: Option<String>,
+    pub signers: Vec<SignerSummary>,
+}
+
+impl TransactionSummary {
+    /// Retourne le SOL déplacé par l'ensemble des instructions décodées (en lamports).
+    pub fn lamports(&self) -> u64 {
+        self.instructions.iter().fold(0u64, |total, instruction| {
+            total.saturating_add(instruction.lamports)
+        })
+    }
+
+    /// Retourne les instructions qui n'ont pas pu être décodées.
+    pub fn undecoded_instructions(&self) -> Vec<&InstructionSummary> {
+        self.instructions
+            .iter()
+            .filter(|instruction| !instruction.is_decoded())
+            .collect()
+    }
+}
+
+/// Fichier d'une transaction construite en ligne ("tx build"), signée hors ligne ("tx sign"), puis diffusée ("tx broadcast").
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TransactionFile {
+    pub version: u32,
+    pub summary: TransactionSummary,
+    /// Transaction sérialisée (bincode), encodée en base64.
+    pub transaction: String,
+}
+
+impl TransactionFile {
+    /// Crée le fichier d'une transaction.
+    ///
+    /// # Arguments:
+    /// - transaction - La transaction (non signée, partiellement signée ou signée).
+    pub fn new(transaction: &Transaction) -> Result<Self, WalletError> {
+        Ok(Self {
+            version: TRANSACTION_FILE_VERSION,
+            summary: Self::summarize(transaction),
+            transaction: BASE64.encode(bincode::serialize(transaction)?),
+        })
+    }
+
+    /// Charge un fichier de transaction, en vérifiant sa version.
+    pub fn load(file_path: &str) -> Result<Self, WalletError> {
+        let content = fs::read_to_string(file_path).map_err(|e| {
+            WalletError::File(format!(
+                "Failed to read transaction file {}: {}",
+                file_path, e
+            ))
+        })?;
+        let file: TransactionFile = serde_json::from_str(&content).map_err(|e| {
+            WalletError::File(format!("Invalid transaction file {}: {}", file_path, e))
+        })?;
+        if file.version != TRANSACTION_FILE_VERSION {
+            return Err(WalletError::File(format!(
+                "Unsupported transaction file version: {}",
+                file.version
+            )));
+        }
+        Ok(file)
+    }
+
+    /// Enregistre le fichier de transaction (en créant les dossiers parents).
+    pub fn save(&self, file_path: &str) -> Result<(), WalletError> {
+        if let Some(parent) = Path::new(file_path).parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(file_path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Décode la transaction contenue dans le fichier.
+    pub fn transaction(&self) -> Result<Transaction, WalletError> {
+        let bytes = BASE64.decode(&self.transaction).map_err(|_| {
+            WalletError::File("Invalid transaction encoding (base64 expected)".to_string())
+        })?;
+        let transaction: Transaction = bincode::deserialize(&bytes)
+            .map_err(|_| WalletError::File("Invalid transaction data".to_string()))?;
+        transaction
+            .sanitize()
+            .map_err(|e| WalletError::File(format!("Invalid transaction: {}", e)))?;
+        Ok(transaction)
+    }
+
+    /// Remplace la transaction du fichier (ex : après l'ajout d'une signature), et met à jour son résumé.
+    pub fn set_transaction(&mut self, transaction: &Transaction) -> Result<(), WalletError> {
+        *self = Self::new(transaction)?;
+        Ok(())
+    }
+
+    /// Retourne le résumé calculé à partir de la transaction du fichier.
+    pub fn summary(&self) -> Result<TransactionSummary, WalletError> {
+        Ok(Self::summarize(&self.transaction()?))
+    }
+
+    /// Décode les instructions, les frais et l'état des signatures d'une transaction.
+    fn summarize(transaction: &Transaction) -> TransactionSummary {
+        let message = &transaction.message;
+        let key = |index: u8| {
+            message
+                .account_keys
+                .get(index as usize)
+                .map(Pubkey::to_string)
+                .unwrap_or_default()
+        };
+
+        let is_system = |instruction: &&CompiledInstruction| {
+            message
+                .account_keys
+                .get(instruction.program_id_index as usize)
+                == Some(&system_program::id())
+        };
+
+        let transfers = message
+            .instructions
+            .iter()
+            .filter(is_system)
+            .filter_map(|instruction| {
+                match bincode::deserialize::<SystemInstruction>(&instruction.data) {
+                    Ok(SystemInstruction::Transfer { lamports }) => Some(TransferSummary {
+                        from: key(*instruction.accounts.first()?),
+                        to: key(*instruction.accounts.get(1)?),
+                        lamports,
+                    }),
+                    _ => None,
+                }
+            })
+            .collect();
+
+        // Le runtime n'accepte un nonce durable que si "advance_nonce_account" est la première instruction.
+        let nonce_account =
+            message
+                .instructions
+                .first()
+                .filter(is_system)
+                .and_then(|instruction| {
+                    match bincode::deserialize::<SystemInstruction>(&instruction.data) {
+                        Ok(SystemInstruction::AdvanceNonceAccount) => {
+                            Some(key(*instruction.accounts.first()?))
+                        }
+                        _ => None,
+                    }
+                });
+
+        let signers = message
+            .account_keys
+            .iter()
+            .take(message.header.num_required_signatures as usize)
+            .enumerate()
+            .map(|(position, pubkey)| SignerSummary {
+                pubkey: pubkey.to_string(),
+                signed: transaction
+                    .signatures
+                    .get(position)
+                    .is_some_and(|signature| *signature != Signature::default()),
+            })
+            .collect();
+
+        TransactionSummary {
+            fee_payer: message
+                .account_keys
+                .first()
+                .map(Pubkey::to_string)
+                .unwrap_or_default(),
+            instructions: message
+                .instructions
+                .iter()
+                .map(|instruction| SolanaInstruction::summarize(&message.account_keys, instruction))
+                .collect(),
+            transfers,
+            memos: SolanaMemo::decode(&message.account_keys, &message.instructions),
+            fee: ComputeBudget::fee_for_message(message),
+            blockhash: message.recent_blockhash.to_string(),
+            nonce_account,
+            signers,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solana::compute_budget::LAMPORTS_PER_SIGNATURE;
+    use solana_sdk::{
+        hash::Hash, message::Message, signature::Keypair, signer::Signer, system_instruction,
+    };
+
+    #[test]
+    fn test_summary_follows_signatures() {
+        let sender = Keypair::new();
+        let recipient = Pubkey::new_unique();
+        let instruction = system_instruction::transfer(&sender.pubkey(), &recipient, 42);
+        let message = Message::new(&[instruction], Some(&sender.pubkey()));
+        let mut transaction = Transaction::new_unsigned(message);
+        transaction.message.recent_blockhash = Hash::new_unique();
+
+        let mut file = TransactionFile::new(&transaction).unwrap();
+        let summary = file.summary().unwrap();
+        assert_eq!(summary.fee_payer, sender.pubkey().to_string());
+        assert_eq!(
+            summary.transfers,
+            vec![TransferSummary {
+                from: sender.pubkey().to_string(),
+                to: recipient.to_string(),
+                lamports: 42,
+            }]
+        );
+        assert!(!summary.signers[0].signed);
+        assert_eq!(summary.nonce_account, None);
+        assert_eq!(summary.lamports(), 42);
+        assert!(summary.undecoded_instructions().is_empty());
+
+        let blockhash = transaction.message.recent_blockhash;
+        transaction.partial_sign(&[&sender], blockhash);
+        file.set_transaction(&transaction).unwrap();
+        assert!(file.summary().unwrap().signers[0].signed);
+        assert_eq!(file.summary.fee, LAMPORTS_PER_SIGNATURE);
+        assert_eq!(file.transaction().unwrap(), transaction);
+    }
+
+    #[test]
+    fn test_summary_decodes_durable_nonce() {
+        let sender = Keypair::new();
+        let nonce_account = Pubkey::new_unique();
+        let instruction = system_instruction::transfer(&sender.pubkey(), &Pubkey::new_unique(), 42);
+        let message = Message::new_with_nonce(
+            vec![instruction],
+            Some(&sender.pubkey()),
+            &nonce_account,
+            &sender.pubkey(),
+        );
+        let transaction = Transaction::new_unsigned(message);
+
+        let summary = TransactionFile::new(&transaction)
+            .unwrap()
+            .summary()
+            .unwrap();
+        assert_eq!(summary.nonce_account, Some(nonce_account.to_string()));
+        assert_eq!(summary.transfers.len(), 1);
+    }
+
+    #[test]
+    fn test_fee_is_recomputed_from_the_message() {
+        let sender = Keypair::new();
+        let budget = ComputeBudget {
+            unit_limit: Some(1_000_000),
+            unit_price: Some(1_000_000),
+        };
+        let mut instructions = budget.instructions();
+        instructions.push(system_instruction::transfer(
+            &sender.pubkey(),
+            &Pubkey::new_unique(),
+            42,
+        ));
+        let transaction =
+            Transaction::new_unsigned(Message::new(&instructions, Some(&sender.pubkey())));
+
+        // Le résumé enregistré dans le fichier est ignoré : les frais de priorité sont recalculés.
+        let mut file = TransactionFile::new(&transaction).unwrap();
+        file.summary.fee = 0;
+        assert_eq!(
+            file.summary().unwrap().fee,
+            LAMPORTS_PER_SIGNATURE + 1_000_000
+        );
+        assert_eq!(file.summary().unwrap().instructions.len(), 3);
+    }
+}
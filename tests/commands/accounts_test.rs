@@ -0,0 +1,270 @@
+use crate::common;
+use crate::common::mock_rpc::MockRpcServer;
+use serial_test::serial;
+use std::fs;
+
+// cargo test --test mod -- commands::accounts_test --nocapture
+
+const MNEMONIC: &str =
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+const PUBKEY_0: &str = "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk";
+const PUBKEY_1: &str = "Hh8QwFUA6MtVu1qAoq12ucvFHNwCcVTV7hpWjeY1Hztb";
+const WATCH_ONLY_PUBKEY: &str = "EMLY3VvNZ41yMWyPQy2AiEfJTPpZdzeGNG5zaaq3Lihb";
+
+const REGISTRY_PATH: &str = "./storage/tests/wallet_temp_test_accounts.json";
+const KEYPAIR_PATH: &str = "./storage/tests/keypair/id_temp_test_accounts.json";
+const DERIVATIONS_PATH: &str = "./storage/tests/keypair/derived_temp_test_accounts";
+const TX_PATH: &str = "./storage/tests/tx_temp_test_accounts.json";
+
+/// Variables d'environnement du wallet : l'index de comptes, la paire de clés et le dossier de dérivations temporaires.
+const ENVS: [(&str, &str); 4] = [
+    ("WALLET_REGISTRY_PATH", REGISTRY_PATH),
+    ("KEYPAIR_PATH", KEYPAIR_PATH),
+    ("KEYPAIR_DERIVATIONS_PATH", DERIVATIONS_PATH),
+    ("NB_DERIVATIONS", "1"),
+];
+
+/// Variables d'environnement du wallet ("ENVS"), avec un RPC donné.
+fn rpc_envs(rpc_url: &str) -> [(&str, &str); 5] {
+    [
+        ("WALLET_REGISTRY_PATH", REGISTRY_PATH),
+        ("KEYPAIR_PATH", KEYPAIR_PATH),
+        ("KEYPAIR_DERIVATIONS_PATH", DERIVATIONS_PATH),
+        ("NB_DERIVATIONS", "1"),
+        ("RPC_URL", rpc_url),
+    ]
+}
+
+fn cleanup() {
+    fs::remove_file(REGISTRY_PATH).ok();
+    fs::remove_file(KEYPAIR_PATH).ok();
+    fs::remove_dir_all(DERIVATIONS_PATH).ok();
+    fs::remove_file(TX_PATH).ok();
+}
+
+#[test]
+#[serial]
+fn test_accounts_commands() {
+    common::setup();
+    cleanup();
+
+    /*
+    |--------------------------------------------------------------------------
+    | "recover_seed" enregistre les comptes dérivés dans le wallet
+    |--------------------------------------------------------------------------
+    */
+
+    common::stdout(&common::wallet(
+        &["recover_seed", MNEMONIC, "--unencrypted"],
+        &ENVS,
+    ));
+
+    let output_str = common::stdout(&common::wallet(&["accounts", "list"], &ENVS));
+    assert!(
+        output_str.contains("account-0") && output_str.contains(PUBKEY_0),
+        "Unexpected output: {}",
+        output_str
+    );
+    assert!(
+        output_str.contains("account-1") && output_str.contains(PUBKEY_1),
+        "Unexpected output: {}",
+        output_str
+    );
+    assert!(output_str.contains("m/44'/501'/1'/0'"));
+
+    /*
+    |--------------------------------------------------------------------------
+    | Ajout d'un compte watch-only, renommage et compte par défaut
+    |--------------------------------------------------------------------------
+    */
+
+    let output_str = common::stdout(&common::wallet(
+        &["accounts", "add", "cold", "--pubkey", WATCH_ONLY_PUBKEY],
+        &ENVS,
+    ));
+    assert!(
+        output_str.contains(&format!(
+            "Account 'cold' added (index 2, watch-only): {}",
+            WATCH_ONLY_PUBKEY
+        )),
+        "Unexpected output: {}",
+        output_str
+    );
+
+    // Un label déjà utilisé est refusé.
+    let output_str = common::stderr(
+        &common::wallet(&["accounts", "add", "cold", "--pubkey", PUBKEY_1], &ENVS),
+        6,
+    );
+    assert!(output_str.contains("Failed to add account: Account label 'cold' is already used"));
+
+    let output_str = common::stdout(&common::wallet(
+        &["accounts", "rename", "account-1", "savings"],
+        &ENVS,
+    ));
+    assert!(output_str.contains("Account 'account-1' renamed to 'savings'"));
+
+    let output_str = common::stdout(&common::wallet(&["accounts", "default", "savings"], &ENVS));
+    assert!(output_str.contains(&format!("Default account: 'savings' ({})", PUBKEY_1)));
+
+    /*
+    |--------------------------------------------------------------------------
+    | "pubkey" utilise le compte par défaut, ou celui donné par "--account"
+    |--------------------------------------------------------------------------
+    */
+
+    let output_str = common::stdout(&common::wallet(&["pubkey"], &ENVS));
+    assert!(output_str.contains(&format!("Solana Public Key: {}", PUBKEY_1)));
+
+    let output_str = common::stdout(&common::wallet(&["pubkey", "--account", "0"], &ENVS));
+    assert!(output_str.contains(&format!("Solana Public Key: {}", PUBKEY_0)));
+
+    let output_str = common::stdout(&common::wallet(&["pubkey", "--account", "cold"], &ENVS));
+    assert!(output_str.contains(&format!("Solana Public Key: {}", WATCH_ONLY_PUBKEY)));
+
+    /*
+    |--------------------------------------------------------------------------
+    | "send" signe avec le compte sélectionné, et refuse un compte watch-only
+    |--------------------------------------------------------------------------
+    */
+
+    let server = MockRpcServer::start();
+    server.set_balance(PUBKEY_1, 1_000_000);
+
+    common::stdout(&common::wallet(
+        &["send", PUBKEY_0, "1000", "--yes"],
+        &rpc_envs(server.url()),
+    ));
+    let (from, to, lamports) = server.sent_transfers()[0];
+    assert_eq!(from.to_string(), PUBKEY_1);
+    assert_eq!(to.to_string(), PUBKEY_0);
+    assert_eq!(lamports, 1000);
+
+    let output_str = common::stderr(
+        &common::wallet(
+            &["send", PUBKEY_0, "1000", "--yes", "--account", "cold"],
+            &rpc_envs(server.url()),
+        ),
+        6,
+    );
+    assert!(
+        output_str.contains(
+            "Failed to send transaction: Account 'cold' is watch-only: it has no keypair on this machine"
+        ),
+        "Unexpected output: {}",
+        output_str
+    );
+
+    /*
+    |--------------------------------------------------------------------------
+    | "balance" interroge la balance du compte sélectionné
+    |--------------------------------------------------------------------------
+    */
+
+    server.set_balance(WATCH_ONLY_PUBKEY, 1_500_000_000);
+
+    let output_str = common::stdout(&common::wallet(
+        &["balance", "--account", "cold"],
+        &rpc_envs(server.url()),
+    ));
+    assert!(
+        output_str.contains("Balance: 1.500000000 SOL (1500000000 lamports)"),
+        "Unexpected output: {}",
+        output_str
+    );
+
+    /*
+    |--------------------------------------------------------------------------
+    | Compte watch-only : lecture par label, signature hors ligne uniquement
+    |--------------------------------------------------------------------------
+    */
+
+    let output_str = common::stdout(&common::wallet(
+        &["history", "--account", "cold"],
+        &rpc_envs(server.url()),
+    ));
+    assert!(
+        output_str.contains(&format!("No transaction found for {}", WATCH_ONLY_PUBKEY)),
+        "Unexpected output: {}",
+        output_str
+    );
+
+    let output_str = common::stdout(&common::wallet(
+        &["token", "list", "--account", "cold"],
+        &rpc_envs(server.url()),
+    ));
+    assert!(
+        output_str.contains(&format!("No token account found for {}", WATCH_ONLY_PUBKEY)),
+        "Unexpected output: {}",
+        output_str
+    );
+
+    let output_str = common::stderr(
+        &common::wallet(
+            &["token", "close", PUBKEY_0, "--yes", "--account", "cold"],
+            &rpc_envs(server.url()),
+        ),
+        6,
+    );
+    assert!(
+        output_str.contains(
+            "Failed to close token account: Account 'cold' is watch-only: it has no keypair on this machine. \
+             Build an unsigned transaction with \"tx build --account cold\""
+        ),
+        "Unexpected output: {}",
+        output_str
+    );
+
+    // La transaction non signée d'un compte watch-only peut être construite ici, mais pas signée.
+    let output_str = common::stdout(&common::wallet(
+        &[
+            "tx",
+            "build",
+            PUBKEY_0,
+            "1000",
+            "--account",
+            "cold",
+            "--outfile",
+            TX_PATH,
+        ],
+        &rpc_envs(server.url()),
+    ));
+    assert!(
+        output_str.contains(&format!("Unsigned transaction written to {}", TX_PATH)),
+        "Unexpected output: {}",
+        output_str
+    );
+    let output_str = common::stderr(
+        &common::wallet(
+            &["tx", "sign", TX_PATH, "--account", "cold"],
+            &rpc_envs(server.url()),
+        ),
+        6,
+    );
+    assert!(
+        output_str.contains("Failed to sign transaction: Account 'cold' is watch-only"),
+        "Unexpected output: {}",
+        output_str
+    );
+
+    /*
+    |--------------------------------------------------------------------------
+    | Suppression d'un compte (le fichier de la paire de clés est conservé)
+    |--------------------------------------------------------------------------
+    */
+
+    let output_str = common::stdout(&common::wallet(&["accounts", "remove", "savings"], &ENVS));
+    assert!(output_str.contains("Account 'savings' removed from wallet (keypair file kept:"));
+
+    let output_str = common::stdout(&common::wallet(&["accounts", "list"], &ENVS));
+    assert!(!output_str.contains("savings"));
+    assert!(output_str.contains("cold"));
+
+    let output_str = common::stderr(
+        &common::wallet(&["pubkey", "--account", "savings"], &ENVS),
+        6,
+    );
+    assert!(output_str.contains("Account 'savings' not found in wallet"));
+
+    cleanup();
+}
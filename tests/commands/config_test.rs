@@ -0,0 +1,270 @@
+use crate::common;
+use crate::common::mock_rpc::MockRpcServer;
+use serde_json::Value;
+use serial_test::serial;
+use solana_sdk::signature::{write_keypair_file, Keypair, Signer};
+use std::fs;
+
+// cargo test --test mod -- commands::config_test --nocapture
+
+const CONFIG_PATH: &str = "./storage/tests/config_temp_test_config.toml";
+const KEYSTORE_PATH: &str = "./storage/tests/keypair/config_temp_test";
+
+/// "RPC_URL", "KEYPAIR_PATH" et "WALLET_PROFILE" ne sont pas transmises, sauf si elles sont données dans "envs" :
+/// les valeurs viennent alors du profil.
+const UNSET: [&str; 3] = ["RPC_URL", "KEYPAIR_PATH", "WALLET_PROFILE"];
+
+/// Variables d'environnement du wallet : le fichier de configuration temporaire, suivi de variables supplémentaires.
+fn envs<'a>(extra: &[(&'a str, &'a str)]) -> Vec<(&'a str, &'a str)> {
+    let mut envs = vec![("WALLET_CONFIG", CONFIG_PATH)];
+    envs.extend_from_slice(extra);
+    envs
+}
+
+fn run_json(args: &[&str], extra: &[(&str, &str)]) -> Value {
+    let output = common::wallet_unset(args, &UNSET, &envs(extra));
+    serde_json::from_str(&common::stdout(&output)).expect("Invalid JSON output")
+}
+
+#[test]
+#[serial]
+fn test_config_profiles() {
+    common::setup();
+
+    let server = MockRpcServer::start();
+    let keypair = Keypair::new();
+    fs::create_dir_all(KEYSTORE_PATH).expect("Failed to create keystore");
+    let keypair_path = format!("{}/id.json", KEYSTORE_PATH);
+    write_keypair_file(&keypair, &keypair_path).expect("Failed to write keypair");
+    server.set_balance(&keypair.pubkey().to_string(), 42_000_000);
+
+    fs::write(
+        CONFIG_PATH,
+        format!(
+            "profile = \"devnet\"\n\n\
+             [profiles.mock]\n\
+             rpc_url = \"{}\"\n\
+             commitment = \"confirmed\"\n\
+             keystore = \"{}\"\n",
+            server.url(),
+            KEYSTORE_PATH
+        ),
+    )
+    .expect("Failed to write config file");
+
+    /*
+    |--------------------------------------------------------------------------
+    | Le profil du fichier donne l'URL RPC et la paire de clés
+    |--------------------------------------------------------------------------
+    */
+
+    let value = run_json(&["balance", "--profile", "mock", "--output", "json"], &[]);
+    assert_eq!(value["pubkey"], keypair.pubkey().to_string());
+    assert_eq!(value["balance"]["lamports"], 42_000_000u64);
+
+    let value = run_json(
+        &["config", "show", "--profile", "mock", "--output", "json"],
+        &[],
+    );
+    assert_eq!(value["config_files"][0], CONFIG_PATH);
+    let settings = &value["settings"];
+    assert_eq!(settings["profile"]["value"], "mock");
+    assert_eq!(settings["profile"]["source"], "flag --profile");
+    assert_eq!(settings["rpc_url"]["value"], server.url());
+    assert_eq!(
+        settings["rpc_url"]["source"],
+        format!("profile mock ({})", CONFIG_PATH)
+    );
+    assert_eq!(settings["commitment"]["value"], "confirmed");
+    assert_eq!(settings["keypair_path"]["value"], keypair_path);
+    assert_eq!(settings["output"]["source"], "flag --output");
+
+    /*
+    |--------------------------------------------------------------------------
+    | Priorités : option > variable d'environnement > profil > valeur par défaut
+    |--------------------------------------------------------------------------
+    */
+
+    // Sans "--profile", le profil actif est celui du fichier (un profil intégré).
+    let value = run_json(&["config", "show", "--output", "json"], &[]);
+    let settings = &value["settings"];
+    assert_eq!(settings["profile"]["value"], "devnet");
+    assert_eq!(
+        settings["profile"]["source"],
+        format!("file {}", CONFIG_PATH)
+    );
+    assert_eq!(
+        settings["rpc_url"]["value"],
+        "https://api.devnet.solana.com"
+    );
+    assert_eq!(settings["rpc_url"]["source"], "profile devnet (built-in)");
+    assert_eq!(settings["commitment"]["source"], "default");
+
+    let value = run_json(
+        &["config", "show", "--output", "json"],
+        &[
+            ("WALLET_PROFILE", "mock"),
+            ("RPC_URL", "http://localhost:8899"),
+        ],
+    );
+    let settings = &value["settings"];
+    assert_eq!(settings["profile"]["source"], "env WALLET_PROFILE");
+    assert_eq!(settings["rpc_url"]["value"], "http://localhost:8899");
+    assert_eq!(settings["rpc_url"]["source"], "env RPC_URL");
+    assert_eq!(settings["websocket_url"]["value"], "ws://localhost:8900");
+
+    let value = run_json(
+        &["config", "show", "--profile", "testnet", "--output", "json"],
+        &[("WALLET_PROFILE", "mock")],
+    );
+    assert_eq!(value["settings"]["profile"]["value"], "testnet");
+
+    /*
+    |--------------------------------------------------------------------------
+    | Erreurs : profil inconnu, fichier invalide
+    |--------------------------------------------------------------------------
+    */
+
+    let error = common::stderr(
+        &common::wallet_unset(&["config", "show", "--profile", "nope"], &UNSET, &envs(&[])),
+        2,
+    );
+    assert!(error.contains("Unknown profile 'nope'"), "{}", error);
+    assert!(error.contains("mock"), "{}", error);
+
+    fs::write(CONFIG_PATH, "[profiles.mock]\nrpc = \"typo\"\n")
+        .expect("Failed to write config file");
+    let error = common::stderr(
+        &common::wallet_unset(&["config", "show"], &UNSET, &envs(&[])),
+        11,
+    );
+    assert!(error.contains("Invalid config file"), "{}", error);
+
+    fs::remove_file(CONFIG_PATH).ok();
+    fs::remove_dir_all(KEYSTORE_PATH).ok();
+}
+
+#[test]
+#[serial]
+fn test_global_flags() {
+    common::setup();
+
+    let registry_path = "./storage/tests/wallet_temp_test_config.json";
+    let flag_keypair_path = "./storage/tests/keypair/id_temp_test_config_flag.json";
+    let default_keypair_path = "./storage/tests/keypair/id_temp_test_config_default.json";
+    fs::remove_file(registry_path).ok();
+
+    let server = MockRpcServer::start();
+    let flag_keypair = Keypair::new();
+    let default_keypair = Keypair::new();
+    write_keypair_file(&flag_keypair, flag_keypair_path).expect("Failed to write keypair");
+    write_keypair_file(&default_keypair, default_keypair_path).expect("Failed to write keypair");
+    server.set_balance(&flag_keypair.pubkey().to_string(), 7_000);
+    fs::write(CONFIG_PATH, "profile = \"devnet\"\n").expect("Failed to write config file");
+
+    // Les variables d'environnement pointent vers un noeud injoignable : les options doivent être prioritaires.
+    let unreachable = [
+        ("WALLET_REGISTRY_PATH", registry_path),
+        ("RPC_URL", "http://127.0.0.1:1"),
+        ("KEYPAIR_PATH", "./storage/tests/keypair/missing.json"),
+    ];
+
+    // "accounts add" importe le fichier de l'option globale "--keypair".
+    run_json(
+        &[
+            "accounts",
+            "add",
+            "savings",
+            "--keypair",
+            default_keypair_path,
+            "--output",
+            "json",
+        ],
+        &unreachable,
+    );
+    run_json(
+        &["accounts", "default", "savings", "--output", "json"],
+        &unreachable,
+    );
+
+    /*
+    |--------------------------------------------------------------------------
+    | "--url" et "--keypair" remplacent le noeud et le compte par défaut
+    |--------------------------------------------------------------------------
+    */
+
+    let value = run_json(
+        &[
+            "balance",
+            "--url",
+            server.url(),
+            "--keypair",
+            flag_keypair_path,
+            "--output",
+            "json",
+        ],
+        &unreachable,
+    );
+    assert_eq!(value["pubkey"], flag_keypair.pubkey().to_string());
+    assert_eq!(value["balance"]["lamports"], 7_000u64);
+
+    // Sans "--keypair", le compte par défaut du wallet est utilisé.
+    let value = run_json(&["pubkey", "--output", "json"], &unreachable);
+    assert_eq!(value["pubkey"], default_keypair.pubkey().to_string());
+
+    /*
+    |--------------------------------------------------------------------------
+    | Monikers, "--commitment", "--derivations" et "--config"
+    |--------------------------------------------------------------------------
+    */
+
+    let value = run_json(
+        &[
+            "config",
+            "show",
+            "-ut",
+            "--commitment",
+            "processed",
+            "--derivations",
+            "3",
+            "--config",
+            CONFIG_PATH,
+            "--output",
+            "json",
+        ],
+        &[
+            ("WALLET_CONFIG", "./storage/tests/missing.toml"),
+            ("RPC_URL", "http://127.0.0.1:1"),
+        ],
+    );
+    assert_eq!(value["config_files"][0], CONFIG_PATH);
+    let settings = &value["settings"];
+    assert_eq!(
+        settings["rpc_url"]["value"],
+        "https://api.testnet.solana.com"
+    );
+    assert_eq!(settings["rpc_url"]["source"], "flag --url");
+    assert_eq!(
+        settings["websocket_url"]["value"],
+        "wss://api.testnet.solana.com"
+    );
+    assert_eq!(settings["commitment"]["value"], "processed");
+    assert_eq!(settings["commitment"]["source"], "flag --commitment");
+    assert_eq!(settings["nb_derivations"]["value"], "3");
+    assert_eq!(settings["nb_derivations"]["source"], "flag --derivations");
+
+    let error = common::stderr(
+        &common::wallet_unset(
+            &["config", "show", "--commitment", "fast"],
+            &UNSET,
+            &envs(&[]),
+        ),
+        2,
+    );
+    assert!(error.contains("invalid value 'fast'"), "{}", error);
+
+    fs::remove_file(CONFIG_PATH).ok();
+    fs::remove_file(registry_path).ok();
+    fs::remove_file(flag_keypair_path).ok();
+    fs::remove_file(default_keypair_path).ok();
+}
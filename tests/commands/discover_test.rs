@@ -0,0 +1,95 @@
+use crate::common;
+use crate::common::mock_rpc::MockRpcServer;
+use serial_test::serial;
+use std::process::Command;
+use std::str;
+
+// cargo test --test mod -- commands::discover_test --nocapture
+
+#[test]
+#[serial]
+fn test_discover_command() {
+    common::setup();
+
+    let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    // Approvisionne quelques comptes de schémas différents sur le RPC simulé.
+    let rpc = MockRpcServer::start();
+    // bip44change (m/44'/501'/1'/0') : le compte 0 est vide, le scan doit continuer jusqu'au compte 1.
+    rpc.set_balance("Hh8QwFUA6MtVu1qAoq12ucvFHNwCcVTV7hpWjeY1Hztb", 5_000_000);
+    // bip44 (m/44'/501'/0') : Style Ledger.
+    rpc.set_balance("GjJyeC1r2RgkuoCWMyPYkCWSGSGLcz266EaAkLA27AhL", 1_000);
+    // root : la seed sans dérivation.
+    rpc.set_balance("EHqmfkN89RJ7Y33CXM6uCzhVeuywHoJXZZLszBHHZy7o", 42);
+
+    // Exécute la commande "discover" avec une gap limit de 2.
+    let output = Command::new("cargo")
+        .args(["run", "--", "discover", mnemonic, "--gap-limit", "2"])
+        .env("RPC_URL", rpc.url())
+        .output()
+        .expect("Failed to execute command");
+
+    // Vérifie que la commande s'est exécutée avec succès.
+    assert!(output.status.success(), "Error: Command failed to execute");
+
+    // Convertit la sortie de la commande en chaîne de caractères.
+    let output_str = str::from_utf8(&output.stdout).expect("Invalid UTF-8 output");
+
+    /*
+    |--------------------------------------------------------------------------
+    | Les vérifications de la sortie de la commande
+    |--------------------------------------------------------------------------
+    */
+
+    assert!(
+        output_str.contains("Discovering accounts (gap limit: 2)..."),
+        "Unexpected output: {}",
+        output_str
+    );
+    assert!(
+        output_str.contains("Found 3 funded account(s)."),
+        "Unexpected output: {}",
+        output_str
+    );
+
+    // Vérifie chaque ligne du tableau (schéma, chemin, clé publique et balance).
+    let find_row = |pubkey: &str| -> Vec<String> {
+        output_str
+            .lines()
+            .find(|line| line.contains(pubkey))
+            .unwrap_or_else(|| panic!("Row for {} not found in: {}", pubkey, output_str))
+            .split_whitespace()
+            .map(String::from)
+            .collect()
+    };
+
+    let row = find_row("Hh8QwFUA6MtVu1qAoq12ucvFHNwCcVTV7hpWjeY1Hztb");
+    assert_eq!(
+        row,
+        [
+            "bip44change",
+            "slip10",
+            "m/44'/501'/1'/0'",
+            &row[3],
+            "5000000"
+        ]
+    );
+
+    let row = find_row("GjJyeC1r2RgkuoCWMyPYkCWSGSGLcz266EaAkLA27AhL");
+    assert_eq!(row, ["bip44", "slip10", "m/44'/501'/0'", &row[3], "1000"]);
+
+    let row = find_row("EHqmfkN89RJ7Y33CXM6uCzhVeuywHoJXZZLszBHHZy7o");
+    assert_eq!(row[0], "root");
+    assert_eq!(row[4], "42");
+
+    /*
+    |--------------------------------------------------------------------------
+    | Les autres vérifications
+    |--------------------------------------------------------------------------
+    */
+
+    // Les balances doivent avoir été demandées via RPC (getBalance), y compris pour les schémas du moteur "legacy".
+    let balance_requests = rpc.requests("getBalance");
+    // slip10 : bip44change (4 comptes), bip44 (3), root (1) - legacy : bip44change (2), bip44 (2), deprecated (2).
+    assert_eq!(balance_requests.len(), 14);
+}
@@ -10,7 +10,7 @@ fn test_generate_seed_command() {
 
     // Exécute la commande "generate_seed".
     let output = Command::new("cargo")
-        .args(["run", "--", "generate_seed"])
+        .args(["run", "--", "generate_seed", "--unencrypted"])
         .output()
         .expect("Failed to execute command");
 
@@ -92,3 +92,66 @@ fn test_generate_seed_command() {
     let pubkey = pubkey_line.split(':').nth(1).unwrap().trim();
     common::verify_pubkey(pubkey);
 }
+
+#[test]
+#[serial]
+fn test_generate_seed_command_with_words_and_language() {
+    common::setup();
+
+    // Exécute une commande du wallet, et retourne sa sortie standard.
+    let run = |args: &[&str]| -> String {
+        let output = Command::new("cargo")
+            .args(["run", "--"])
+            .args(args)
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success(), "Error: Command failed to execute");
+        str::from_utf8(&output.stdout)
+            .expect("Invalid UTF-8 output")
+            .to_string()
+    };
+    let line = |output: &str, prefix: &str| -> String {
+        output
+            .lines()
+            .find(|line| line.starts_with(prefix))
+            .unwrap_or_else(|| panic!("{} line not found", prefix))
+            .to_string()
+    };
+
+    // Phrase de 24 mots en espagnol.
+    let output_str = run(&[
+        "generate_seed",
+        "--words",
+        "24",
+        "--language",
+        "spanish",
+        "--unencrypted",
+    ]);
+    let phrase = line(&output_str, "BIP39 Mnemonic (random phrase):")
+        .split_once(':')
+        .unwrap()
+        .1
+        .trim()
+        .to_string();
+    assert_eq!(phrase.split_whitespace().count(), 24);
+    assert!(
+        output_str.contains("Mnemonic language: spanish (24 words)"),
+        "Error: Mnemonic language not found in output"
+    );
+    let pubkey = line(&output_str, "Solana Public Key:");
+
+    // "recover_seed" détecte la langue de la phrase, et retrouve les mêmes clés.
+    let output_str = run(&["recover_seed", &phrase, "--unencrypted"]);
+    assert!(
+        output_str.contains("Mnemonic language: spanish (24 words)"),
+        "Error: Detected language not found in output"
+    );
+    assert_eq!(line(&output_str, "Solana Public Key:"), pubkey);
+
+    // Nombre de mots invalide.
+    let output = Command::new("cargo")
+        .args(["run", "--", "generate_seed", "--words", "13"])
+        .output()
+        .expect("Failed to execute command");
+    assert!(!output.status.success());
+}
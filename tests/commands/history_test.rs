@@ -0,0 +1,254 @@
+use crate::common;
+use crate::common::mock_rpc::MockRpcServer;
+use serde_json::{json, Value};
+use serial_test::serial;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{write_keypair_file, Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::Transaction;
+use std::fs;
+use std::str;
+
+// cargo test --test mod -- commands::history_test --nocapture
+
+const KEYPAIR_PATH: &str = "./storage/tests/keypair/id_temp_test_history.json";
+
+/// Variables d'environnement du wallet : la paire de clés temporaire et un RPC donné.
+fn envs(rpc_url: &str) -> [(&str, &str); 3] {
+    [
+        ("KEYPAIR_PATH", KEYPAIR_PATH),
+        (
+            "WALLET_REGISTRY_PATH",
+            "./storage/tests/wallet_temp_test_history.json",
+        ),
+        ("RPC_URL", rpc_url),
+    ]
+}
+
+/// Transfert de SOL signé par "payer", avec un memo optionnel.
+fn transfer(payer: &Keypair, recipient: &Pubkey, lamports: u64, memo: Option<&str>) -> Transaction {
+    let mut instructions = vec![system_instruction::transfer(
+        &payer.pubkey(),
+        recipient,
+        lamports,
+    )];
+    if let Some(memo) = memo {
+        instructions.push(spl_memo::build_memo(memo.as_bytes(), &[&payer.pubkey()]));
+    }
+    let message = Message::new(&instructions, Some(&payer.pubkey()));
+    Transaction::new(&[payer], message, Hash::new_unique())
+}
+
+fn token_balance(index: u8, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Value {
+    json!({
+        "accountIndex": index,
+        "mint": mint.to_string(),
+        "owner": owner.to_string(),
+        "uiTokenAmount": {
+            "amount": amount.to_string(),
+            "decimals": 6,
+            "uiAmount": null,
+            "uiAmountString": amount.to_string()
+        }
+    })
+}
+
+#[test]
+#[serial]
+fn test_history_command() {
+    common::setup();
+
+    let wallet = Keypair::new();
+    write_keypair_file(&wallet, KEYPAIR_PATH).expect("Failed to write keypair");
+    let other = Keypair::new();
+    let mint = Pubkey::new_unique();
+
+    // Trois transactions, de la plus ancienne à la plus récente : réception de SOL, envoi de SOL, réception de tokens.
+    let rpc = MockRpcServer::start();
+    let received = transfer(&other, &wallet.pubkey(), 1_000_000_000, None);
+    rpc.add_transaction(
+        &received,
+        1_700_000_000,
+        json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [5_000_000_000u64, 0, 1],
+            "postBalances": [3_999_995_000u64, 1_000_000_000, 1],
+        }),
+    );
+    let sent = transfer(
+        &wallet,
+        &other.pubkey(),
+        500_000_000,
+        Some("payout, November"),
+    );
+    rpc.add_transaction(
+        &sent,
+        1_700_000_100,
+        json!({
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [1_000_000_000, 3_999_995_000u64, 1],
+            "postBalances": [499_995_000, 4_499_995_000u64, 1],
+        }),
+    );
+    // Les comptes de tokens ne sont pas dans le message : seuls les propriétaires comptent.
+    let tokens = transfer(&other, &wallet.pubkey(), 0, None);
+    rpc.add_transaction(
+        &tokens,
+        1_700_000_200,
+        json!({
+            "err": { "InstructionError": [0, { "Custom": 1 }] },
+            "status": { "Err": { "InstructionError": [0, { "Custom": 1 }] } },
+            "fee": 5000,
+            "preBalances": [4_499_995_000u64, 499_995_000, 1],
+            "postBalances": [4_499_990_000u64, 499_995_000, 1],
+            "preTokenBalances": [token_balance(1, &mint, &other.pubkey(), 2_000_000), token_balance(2, &mint, &wallet.pubkey(), 0)],
+            "postTokenBalances": [token_balance(1, &mint, &other.pubkey(), 500_000), token_balance(2, &mint, &wallet.pubkey(), 1_500_000)],
+        }),
+    );
+
+    /*
+    |--------------------------------------------------------------------------
+    | Tableau : une ligne par variation de balance
+    |--------------------------------------------------------------------------
+    */
+
+    let output_str = common::stdout(&common::wallet(&["history"], &envs(rpc.url())));
+    common::assert_contains(&output_str, "TIME");
+    let lines: Vec<&str> = output_str.lines().skip(1).collect();
+    assert_eq!(lines.len(), 3, "Unexpected history: {}", output_str);
+
+    let columns: Vec<&str> = lines[0].split_whitespace().collect();
+    assert_eq!(
+        columns,
+        [
+            "2023-11-14T22:16:40Z",
+            &tokens.signatures[0].to_string(),
+            &other.pubkey().to_string(),
+            "+1.500000",
+            &mint.to_string(),
+            "0.000000000",
+            "failed"
+        ]
+    );
+    let columns: Vec<&str> = lines[1].split_whitespace().collect();
+    assert_eq!(
+        columns,
+        [
+            "2023-11-14T22:15:00Z",
+            &sent.signatures[0].to_string(),
+            &other.pubkey().to_string(),
+            "-0.500000000",
+            "SOL",
+            "0.000005000",
+            "ok",
+            "payout,",
+            "November"
+        ]
+    );
+    common::assert_contains(lines[2], "+1.000000000");
+
+    /*
+    |--------------------------------------------------------------------------
+    | JSON et CSV
+    |--------------------------------------------------------------------------
+    */
+
+    let output_str = common::stdout(&common::wallet(
+        &["history", "--format", "json"],
+        &envs(rpc.url()),
+    ));
+    let entries: Value = serde_json::from_str(&output_str).expect("Invalid JSON output");
+    assert_eq!(entries.as_array().unwrap().len(), 3);
+    assert_eq!(entries[1]["signature"], sent.signatures[0].to_string());
+    assert_eq!(entries[1]["fee"], 5000);
+    assert_eq!(entries[1]["changes"][0]["delta"], -500_000_000);
+    assert_eq!(entries[1]["changes"][0]["asset"], "SOL");
+    assert!(entries[0]["error"].is_string());
+    assert_eq!(entries[1]["memos"][0], "payout, November");
+
+    let output_str = common::stdout(&common::wallet(
+        &["history", "--format", "csv"],
+        &envs(rpc.url()),
+    ));
+    let lines: Vec<&str> = output_str.lines().collect();
+    assert_eq!(
+        lines[0],
+        "time,signature,slot,counterparty,delta,asset,fee,status,memo"
+    );
+    assert_eq!(
+        lines[2],
+        format!(
+            "2023-11-14T22:15:00Z,{},2,{},-0.500000000,SOL,0.000005000,ok,\"payout, November\"",
+            sent.signatures[0],
+            other.pubkey()
+        )
+    );
+
+    /*
+    |--------------------------------------------------------------------------
+    | Pagination : "--limit", "--before" et "--until"
+    |--------------------------------------------------------------------------
+    */
+
+    let output_str = common::stdout(&common::wallet(
+        &["history", "--limit", "2"],
+        &envs(rpc.url()),
+    ));
+    assert_eq!(
+        output_str.lines().count(),
+        4,
+        "Unexpected history: {}",
+        output_str
+    );
+    common::assert_contains(
+        &output_str,
+        &format!("Next page: --before {}", sent.signatures[0]),
+    );
+
+    let output_str = common::stdout(&common::wallet(
+        &["history", "--before", &sent.signatures[0].to_string()],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(&output_str, &received.signatures[0].to_string());
+    assert!(!output_str.contains(&sent.signatures[0].to_string()));
+
+    let output_str = common::stdout(&common::wallet(
+        &["history", "--until", &sent.signatures[0].to_string()],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(&output_str, &tokens.signatures[0].to_string());
+    assert!(!output_str.contains(&sent.signatures[0].to_string()));
+
+    // Une autre adresse que celle du wallet.
+    let output_str = common::stdout(&common::wallet(
+        &["history", &other.pubkey().to_string()],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(&output_str, "-1.000000000");
+
+    let output_str = common::stdout(&common::wallet(
+        &["history", &Pubkey::new_unique().to_string()],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(&output_str, "No transaction found for");
+
+    let output_str = common::stderr(
+        &common::wallet(
+            &["history", "--before", "not-a-signature"],
+            &envs(rpc.url()),
+        ),
+        2,
+    );
+    common::assert_contains(
+        &output_str,
+        "Failed to retrieve history: Invalid signature: not-a-signature",
+    );
+
+    fs::remove_file(KEYPAIR_PATH).ok();
+}
@@ -0,0 +1,158 @@
+use crate::common;
+use crate::common::mock_rpc::MockRpcServer;
+use serial_test::serial;
+use std::fs;
+
+// cargo test --test mod -- commands::keystore_test --nocapture
+
+const MNEMONIC: &str =
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+const PUBKEY: &str = "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk";
+const REGISTRY_PATH: &str = "./storage/tests/wallet_temp_test_keystore.json";
+
+/// Variables d'environnement du wallet : une paire de clés donnée et l'index de comptes temporaire,
+/// suivies de variables supplémentaires (mot de passe du keystore, RPC).
+fn envs<'a>(keypair_path: &'a str, extra: &[(&'a str, &'a str)]) -> Vec<(&'a str, &'a str)> {
+    let mut envs = vec![
+        ("KEYPAIR_PATH", keypair_path),
+        ("WALLET_REGISTRY_PATH", REGISTRY_PATH),
+        ("NB_DERIVATIONS", "0"),
+    ];
+    envs.extend_from_slice(extra);
+    envs
+}
+
+#[test]
+#[serial]
+fn test_encrypted_keystore() {
+    common::setup();
+
+    let keypair_path = "./storage/tests/keypair/id_temp_test_encrypted_keystore.json";
+
+    /*
+    |--------------------------------------------------------------------------
+    | Étape 1/3 : "recover_seed" écrit un keystore chiffré
+    |--------------------------------------------------------------------------
+    */
+
+    let output = common::wallet(
+        &["recover_seed", MNEMONIC],
+        &envs(keypair_path, &[("KEYSTORE_PASSWORD", "s3cret")]),
+    );
+    assert!(common::stdout(&output).contains(&format!("Solana Public Key: {}", PUBKEY)));
+
+    // Le fichier est une enveloppe JSON versionnée, et non le tableau d'octets de la clé privée.
+    let content = fs::read_to_string(keypair_path).expect("Keystore file not found");
+    let envelope: serde_json::Value = serde_json::from_str(&content).expect("Invalid keystore");
+    assert_eq!(envelope["version"], 1);
+    assert_eq!(envelope["pubkey"], PUBKEY);
+    assert_eq!(envelope["kdf"]["name"], "scrypt");
+    assert_eq!(envelope["cipher"]["name"], "aes-256-gcm-siv");
+    assert!(!content.trim_start().starts_with('['));
+
+    /*
+    |--------------------------------------------------------------------------
+    | Étape 2/3 : "pubkey" lit la clé publique sans mot de passe
+    |--------------------------------------------------------------------------
+    */
+
+    let output = common::wallet(&["pubkey"], &envs(keypair_path, &[]));
+    let output_str = common::stdout(&output);
+    assert!(output_str.contains(&format!("Solana Public Key: {}", PUBKEY)));
+    // Sans mot de passe, la clé publique de l'enveloppe n'est pas authentifiée.
+    assert!(output_str.contains("unverified until the keystore is unlocked"));
+
+    /*
+    |--------------------------------------------------------------------------
+    | Étape 3/3 : "send" déverrouille le keystore
+    |--------------------------------------------------------------------------
+    */
+
+    let recipient = "EMLY3VvNZ41yMWyPQy2AiEfJTPpZdzeGNG5zaaq3Lihb";
+
+    let output = common::wallet(
+        &["send", recipient, "1000", "--yes"],
+        &envs(keypair_path, &[("KEYSTORE_PASSWORD", "wrong")]),
+    );
+    let output_str = common::stderr(&output, 5);
+    assert!(
+        output_str.contains("Failed to send transaction: Failed to read keypair from file"),
+        "Unexpected output: {}",
+        output_str
+    );
+
+    let rpc = MockRpcServer::start();
+    rpc.set_balance(PUBKEY, 1_000_000);
+
+    let output = common::wallet(
+        &["send", recipient, "1000", "--yes"],
+        &envs(
+            keypair_path,
+            &[("KEYSTORE_PASSWORD", "s3cret"), ("RPC_URL", rpc.url())],
+        ),
+    );
+    let output_str = common::stdout(&output);
+    assert!(
+        output_str.contains("Transaction sent successfully!"),
+        "Unexpected output: {}",
+        output_str
+    );
+    // La transaction est signée par la paire de clés déchiffrée.
+    let (from, to, lamports) = rpc.sent_transfers()[0];
+    assert_eq!(from.to_string(), PUBKEY);
+    assert_eq!(to.to_string(), recipient);
+    assert_eq!(lamports, 1000);
+    assert!(rpc.sent_transactions()[0].verify().is_ok());
+
+    fs::remove_file(keypair_path).expect("Failed to remove temporary keystore file");
+    fs::remove_file(REGISTRY_PATH).ok();
+}
+
+#[test]
+#[serial]
+fn test_migrate_keystore_command() {
+    common::setup();
+
+    let keypair_path = "./storage/tests/keypair/id_temp_test_migrate_keystore.json";
+    fs::remove_file(keypair_path).ok();
+
+    // Un mot de passe vide (entrée standard vide) est refusé : rien n'est écrit en clair.
+    let output = common::wallet(&["recover_seed", MNEMONIC], &envs(keypair_path, &[]));
+    let output_str = common::stderr(&output, 2);
+    assert!(output_str.contains("the keystore password cannot be empty (use --unencrypted"));
+    assert!(fs::metadata(keypair_path).is_err());
+
+    // Écrit une paire de clés en clair, avec "--unencrypted".
+    let output = common::wallet(
+        &["recover_seed", MNEMONIC, "--unencrypted"],
+        &envs(keypair_path, &[]),
+    );
+    assert!(common::stdout(&output).contains("Warning: keypairs will be stored unencrypted."));
+    let content = fs::read_to_string(keypair_path).expect("Keypair file not found");
+    assert!(content.trim_start().starts_with('['));
+
+    // Migre le fichier en clair vers le format keystore chiffré.
+    let output = common::wallet(
+        &["migrate_keystore", keypair_path],
+        &envs(keypair_path, &[("KEYSTORE_PASSWORD", "s3cret")]),
+    );
+    assert!(common::stdout(&output).contains(&format!("Encrypted: {}", keypair_path)));
+
+    let content = fs::read_to_string(keypair_path).expect("Keystore file not found");
+    let envelope: serde_json::Value = serde_json::from_str(&content).expect("Invalid keystore");
+    assert_eq!(envelope["pubkey"], PUBKEY);
+
+    // Une seconde migration ne fait rien.
+    let output = common::wallet(
+        &["migrate_keystore", keypair_path],
+        &envs(keypair_path, &[("KEYSTORE_PASSWORD", "s3cret")]),
+    );
+    assert!(common::stdout(&output).contains(&format!("Already encrypted: {}", keypair_path)));
+
+    // La clé publique est toujours la même.
+    let output = common::wallet(&["pubkey"], &envs(keypair_path, &[]));
+    assert!(common::stdout(&output).contains(&format!("Solana Public Key: {}", PUBKEY)));
+
+    fs::remove_file(keypair_path).expect("Failed to remove temporary keystore file");
+    fs::remove_file(REGISTRY_PATH).ok();
+}
@@ -0,0 +1,238 @@
+use crate::common;
+use crate::common::mock_rpc::{MockRpcServer, MAINNET_GENESIS_HASH};
+use serial_test::serial;
+use solana_sdk::signature::{write_keypair_file, Keypair, Signer};
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+use std::str;
+
+// cargo test --test mod -- commands::mainnet_test --nocapture
+
+const KEYPAIR_PATH: &str = "./storage/tests/keypair/id_temp_test_mainnet.json";
+const CONFIG_PATH: &str = "./storage/tests/config_temp_test_mainnet.toml";
+const LEDGER_PATH: &str = "./storage/tests/spending_temp_test_mainnet.json";
+const NONCE_KEYPAIR_PATH: &str = "./storage/tests/keypair/nonce_temp_test_mainnet.json";
+const RECIPIENT: &str = "EMLY3VvNZ41yMWyPQy2AiEfJTPpZdzeGNG5zaaq3Lihb";
+
+/// Exécute le wallet sur un RPC simulé, avec le profil "limited" du fichier de configuration temporaire,
+/// en écrivant éventuellement une réponse sur l'entrée standard (fermée sinon).
+fn execute(server: &MockRpcServer, args: &[&str], input: Option<&str>) -> Output {
+    let mut child = Command::new("cargo")
+        .args(["run", "--"])
+        .args(args)
+        .args(["--profile", "limited"])
+        .env("WALLET_CONFIG", CONFIG_PATH)
+        .env("KEYPAIR_PATH", KEYPAIR_PATH)
+        .env("RPC_URL", server.url())
+        .env("SPENDING_LEDGER_PATH", LEDGER_PATH)
+        .env(
+            "WALLET_REGISTRY_PATH",
+            "./storage/tests/wallet_temp_test_mainnet.json",
+        )
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute command");
+    let mut stdin = child.stdin.take().unwrap();
+    if let Some(input) = input {
+        stdin.write_all(input.as_bytes()).unwrap();
+    }
+    drop(stdin);
+    child.wait_with_output().expect("Failed to execute command")
+}
+
+/// Exécute "send" vers le destinataire de test, sans confirmation.
+fn send(server: &MockRpcServer, args: &[&str], input: Option<&str>) -> Output {
+    execute(
+        server,
+        &[&["send", RECIPIENT, "--yes"], args].concat(),
+        input,
+    )
+}
+
+fn assert_success(output: &Output) -> String {
+    let stderr = str::from_utf8(&output.stderr).expect("Invalid UTF-8 output");
+    assert!(output.status.success(), "Command failed: {}", stderr);
+    stderr.to_string()
+}
+
+/// Écrit le fichier de configuration avec les plafonds du profil "limited".
+fn write_config(limits: &str) {
+    fs::write(CONFIG_PATH, format!("[profiles.limited]\n{}", limits))
+        .expect("Failed to write config file");
+}
+
+fn setup(server: &MockRpcServer) {
+    common::setup();
+    let keypair = Keypair::new();
+    write_keypair_file(&keypair, KEYPAIR_PATH).expect("Failed to write keypair");
+    server.set_balance(&keypair.pubkey().to_string(), 1_000_000_000);
+    fs::remove_file(LEDGER_PATH).ok();
+    write_config("");
+}
+
+fn cleanup() {
+    fs::remove_file(KEYPAIR_PATH).ok();
+    fs::remove_file(CONFIG_PATH).ok();
+    fs::remove_file(LEDGER_PATH).ok();
+    fs::remove_file(NONCE_KEYPAIR_PATH).ok();
+}
+
+#[test]
+#[serial]
+fn test_mainnet_guard() {
+    let server = MockRpcServer::start();
+    setup(&server);
+
+    /*
+    |--------------------------------------------------------------------------
+    | Hors mainnet : le réseau est affiché, aucune confirmation supplémentaire
+    |--------------------------------------------------------------------------
+    */
+
+    let stderr = assert_success(&send(&server, &["1000"], None));
+    assert!(
+        stderr.contains(&format!("Cluster: devnet ({})", server.url())),
+        "The cluster should be shown: {}",
+        stderr
+    );
+    assert_eq!(server.sent_transactions().len(), 1);
+
+    /*
+    |--------------------------------------------------------------------------
+    | Sur mainnet : refus sans "mainnet" tapé, ni "--allow-mainnet"
+    |--------------------------------------------------------------------------
+    */
+
+    server.set_genesis_hash(MAINNET_GENESIS_HASH);
+
+    let stderr = common::stderr(&send(&server, &["1000"], None), 10);
+    assert!(stderr.contains("Cluster: mainnet-beta"), "{}", stderr);
+    assert!(stderr.contains("Mainnet operation cancelled"), "{}", stderr);
+    let output = send(&server, &["1000"], Some("yes\n"));
+    common::stderr(&output, 10);
+    let stdout = str::from_utf8(&output.stdout).expect("Invalid UTF-8 output");
+    assert!(
+        stdout.contains("Type \"mainnet\" to continue"),
+        "{}",
+        stdout
+    );
+    assert_eq!(server.sent_transactions().len(), 1);
+
+    // Confirmation tapée, ou option "--allow-mainnet".
+    assert_success(&send(&server, &["1000"], Some("mainnet\n")));
+    assert_success(&send(&server, &["1000", "--allow-mainnet"], None));
+    assert_eq!(server.sent_transactions().len(), 3);
+
+    cleanup();
+}
+
+#[test]
+#[serial]
+fn test_spending_limits() {
+    let server = MockRpcServer::start();
+    setup(&server);
+
+    /*
+    |--------------------------------------------------------------------------
+    | Plafond par transaction
+    |--------------------------------------------------------------------------
+    */
+
+    write_config("spending_limit_per_transaction = \"0.001\"\n");
+    let stderr = common::stderr(&send(&server, &["0.002", "--sol"], None), 12);
+    assert!(
+        stderr.contains(
+            "exceeds the spending limit per transaction of profile limited (0.001000000 SOL)"
+        ),
+        "{}",
+        stderr
+    );
+    assert_success(&send(&server, &["0.001", "--sol"], None));
+    assert_eq!(server.sent_transactions().len(), 1);
+
+    /*
+    |--------------------------------------------------------------------------
+    | Plafond par jour : les envois du jour sont comptés
+    |--------------------------------------------------------------------------
+    */
+
+    write_config("spending_limit_per_day = \"0.005\"\n");
+    assert_success(&send(&server, &["0.003", "--sol"], None));
+    assert_success(&send(&server, &["0.002", "--sol"], None));
+    let stderr = common::stderr(&send(&server, &["1"], None), 12);
+    assert!(
+        stderr.contains("would exceed the spending limit per day of profile limited (0.005000000 SOL, 0.005000000 SOL already sent today)"),
+        "{}",
+        stderr
+    );
+    assert_eq!(server.sent_transactions().len(), 3);
+
+    /*
+    |--------------------------------------------------------------------------
+    | Les comptes nonce et de stake créés, et leurs retraits, sont aussi plafonnés
+    |--------------------------------------------------------------------------
+    */
+
+    write_config("spending_limit_per_transaction = \"0.001\"\n");
+    let stderr = common::stderr(
+        &execute(
+            &server,
+            &[
+                "nonce",
+                "create",
+                NONCE_KEYPAIR_PATH,
+                "--amount",
+                "0.002",
+                "--sol",
+            ],
+            None,
+        ),
+        12,
+    );
+    assert!(
+        stderr.contains("exceeds the spending limit per transaction"),
+        "{}",
+        stderr
+    );
+    let stderr = common::stderr(
+        &execute(
+            &server,
+            &["stake", "create", "2", "--sol", "--seed", "limited-1"],
+            None,
+        ),
+        12,
+    );
+    assert!(
+        stderr.contains("exceeds the spending limit per transaction"),
+        "{}",
+        stderr
+    );
+    assert_eq!(server.sent_transactions().len(), 3);
+    fs::remove_file(NONCE_KEYPAIR_PATH).ok();
+
+    // Une limite invalide est refusée à la lecture du fichier.
+    write_config("spending_limit_per_day = \"lots\"\n");
+    common::stderr(&send(&server, &["1"], None), 11);
+
+    cleanup();
+}
+
+#[test]
+#[serial]
+fn test_moniker_selects_profile() {
+    let server = MockRpcServer::start();
+    setup(&server);
+
+    // "-um" choisit le profil mainnet : "--profile limited" ne peut pas s'appliquer à ce réseau.
+    let stderr = common::stderr(&execute(&server, &["config", "show", "-um"], None), 2);
+    assert!(
+        stderr.contains("--url selects the mainnet network, but --profile is limited"),
+        "{}",
+        stderr
+    );
+
+    cleanup();
+}
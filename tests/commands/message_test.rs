@@ -0,0 +1,222 @@
+use crate::common;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serial_test::serial;
+use solana_sdk::offchain_message::OffchainMessage;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{write_keypair_file, Keypair, Signer};
+use std::fs;
+use std::str;
+
+// cargo test --test mod -- commands::message_test --nocapture
+
+const KEYPAIR_PATH: &str = "./storage/tests/keypair/id_temp_test_message.json";
+const REGISTRY_PATH: &str = "./storage/tests/wallet_temp_test_message.json";
+const MESSAGE_PATH: &str = "./storage/tests/message_temp_test.bin";
+
+/// Variables d'environnement du wallet : la paire de clés et l'index de comptes temporaires.
+const ENVS: [(&str, &str); 2] = [
+    ("KEYPAIR_PATH", KEYPAIR_PATH),
+    ("WALLET_REGISTRY_PATH", REGISTRY_PATH),
+];
+
+/// Retourne la signature affichée par "sign_message".
+fn signature(output_str: &str) -> String {
+    output_str
+        .lines()
+        .find_map(|line| line.strip_prefix("Signature:"))
+        .unwrap_or_else(|| panic!("No signature in output: {}", output_str))
+        .trim()
+        .to_string()
+}
+
+#[test]
+#[serial]
+fn test_message_commands() {
+    common::setup();
+    fs::remove_file(REGISTRY_PATH).ok();
+
+    let keypair = Keypair::new();
+    write_keypair_file(&keypair, KEYPAIR_PATH).expect("Failed to write keypair");
+    let pubkey = keypair.pubkey().to_string();
+
+    /*
+    |--------------------------------------------------------------------------
+    | Signature d'un message hors chaîne (format par défaut)
+    |--------------------------------------------------------------------------
+    */
+
+    let output_str = common::stdout(&common::wallet(
+        &["sign_message", "I own this wallet"],
+        &ENVS,
+    ));
+    common::assert_contains(&output_str, &format!("Public key: {}", pubkey));
+    common::assert_contains(
+        &output_str,
+        "Format:     offchain (version 0, restricted ASCII)",
+    );
+    let offchain_signature = signature(&output_str);
+    let expected = OffchainMessage::new(0, b"I own this wallet")
+        .unwrap()
+        .sign(&keypair)
+        .unwrap();
+    assert_eq!(offchain_signature, expected.to_string());
+
+    let output_str = common::stdout(&common::wallet(
+        &[
+            "verify_message",
+            &pubkey,
+            &offchain_signature,
+            "I own this wallet",
+        ],
+        &ENVS,
+    ));
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "Valid signature: the offchain (version 0, restricted ASCII) message was signed by {}",
+            pubkey
+        ),
+    );
+
+    // Autre message, autre format ou autre signataire : la signature est refusée.
+    let output_str = common::stderr(
+        &common::wallet(
+            &[
+                "verify_message",
+                &pubkey,
+                &offchain_signature,
+                "I own this wallet!",
+            ],
+            &ENVS,
+        ),
+        1,
+    );
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "Failed to verify message: Invalid signature: the offchain message was not signed by {}",
+            pubkey
+        ),
+    );
+    let output_str = common::stderr(
+        &common::wallet(
+            &[
+                "verify_message",
+                &pubkey,
+                &offchain_signature,
+                "I own this wallet",
+                "--format",
+                "utf8",
+            ],
+            &ENVS,
+        ),
+        1,
+    );
+    common::assert_contains(&output_str, "Invalid signature");
+    let other = Pubkey::new_unique().to_string();
+    let output_str = common::stderr(
+        &common::wallet(
+            &[
+                "verify_message",
+                &other,
+                &offchain_signature,
+                "I own this wallet",
+            ],
+            &ENVS,
+        ),
+        1,
+    );
+    common::assert_contains(&output_str, "Invalid signature");
+
+    /*
+    |--------------------------------------------------------------------------
+    | Texte UTF-8, octets bruts et signature en base64
+    |--------------------------------------------------------------------------
+    */
+
+    let output_str = common::stdout(&common::wallet(
+        &[
+            "sign_message",
+            "Hello",
+            "--format",
+            "utf8",
+            "--encoding",
+            "base64",
+        ],
+        &ENVS,
+    ));
+    common::assert_contains(&output_str, "Format:     utf8");
+    let utf8_signature = signature(&output_str);
+    assert_eq!(
+        utf8_signature,
+        BASE64.encode(keypair.sign_message(b"Hello").as_ref())
+    );
+    let output_str = common::stdout(&common::wallet(
+        &[
+            "verify_message",
+            &pubkey,
+            &utf8_signature,
+            "Hello",
+            "--format",
+            "utf8",
+        ],
+        &ENVS,
+    ));
+    common::assert_contains(&output_str, "Valid signature: the utf8 message");
+
+    fs::write(MESSAGE_PATH, [0xde, 0xad, 0xbe, 0xef]).unwrap();
+    let output_str = common::stderr(
+        &common::wallet(&["sign_message", "--file", MESSAGE_PATH], &ENVS),
+        2,
+    );
+    common::assert_contains(
+        &output_str,
+        "Failed to sign message: The message is not valid UTF-8 text",
+    );
+    let output_str = common::stdout(&common::wallet(
+        &["sign_message", "--file", MESSAGE_PATH, "--format", "raw"],
+        &ENVS,
+    ));
+    let raw_signature = signature(&output_str);
+    assert_eq!(
+        raw_signature,
+        keypair.sign_message(&[0xde, 0xad, 0xbe, 0xef]).to_string()
+    );
+
+    /*
+    |--------------------------------------------------------------------------
+    | Comptes du wallet : vérification par label, pas de signature watch-only
+    |--------------------------------------------------------------------------
+    */
+
+    common::stdout(&common::wallet(
+        &["accounts", "add", "treasury", "--pubkey", &pubkey],
+        &ENVS,
+    ));
+    let output_str = common::stdout(&common::wallet(
+        &[
+            "verify_message",
+            "treasury",
+            &raw_signature,
+            "--file",
+            MESSAGE_PATH,
+            "--format",
+            "raw",
+        ],
+        &ENVS,
+    ));
+    common::assert_contains(&output_str, "Valid signature: the raw message");
+
+    let output_str = common::stderr(
+        &common::wallet(&["sign_message", "Hello", "--account", "treasury"], &ENVS),
+        6,
+    );
+    common::assert_contains(
+        &output_str,
+        "Failed to sign message: Account 'treasury' is watch-only",
+    );
+
+    fs::remove_file(KEYPAIR_PATH).ok();
+    fs::remove_file(REGISTRY_PATH).ok();
+    fs::remove_file(MESSAGE_PATH).ok();
+}
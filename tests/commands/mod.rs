@@ -1,6 +1,20 @@
+pub mod accounts_test;
 pub mod balance_by_pubkey_test;
+pub mod config_test;
+pub mod discover_test;
 pub mod generate_seed_test;
+pub mod history_test;
+pub mod keystore_test;
+pub mod mainnet_test;
+pub mod message_test;
+pub mod nonce_test;
+pub mod output_test;
+pub mod send_batch_test;
 pub mod send_test;
+pub mod stake_test;
+pub mod token_test;
+pub mod tx_test;
+pub mod validate_mnemonic_test;
 
 pub mod pubkey_test;
 pub mod recover_seed_test;
@@ -0,0 +1,235 @@
+use crate::common;
+use crate::common::mock_rpc::MockRpcServer;
+use serial_test::serial;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{write_keypair_file, Keypair, Signer};
+use std::fs;
+use std::str;
+
+// cargo test --test mod -- commands::nonce_test --nocapture
+
+const RECIPIENT: &str = "EMLY3VvNZ41yMWyPQy2AiEfJTPpZdzeGNG5zaaq3Lihb";
+const TX_PATH: &str = "./storage/tests/tx_temp_test_nonce.json";
+const SIGNER_PATH: &str = "./storage/tests/keypair/id_temp_test_nonce.json";
+const NONCE_KEYPAIR_PATH: &str = "./storage/tests/keypair/nonce_temp_test.json";
+
+/// Variables d'environnement du wallet : la paire de clés temporaire et un RPC donné.
+fn envs(rpc_url: &str) -> [(&str, &str); 3] {
+    [
+        ("KEYPAIR_PATH", SIGNER_PATH),
+        (
+            "WALLET_REGISTRY_PATH",
+            "./storage/tests/wallet_temp_test_nonce.json",
+        ),
+        ("RPC_URL", rpc_url),
+    ]
+}
+
+#[test]
+#[serial]
+fn test_nonce_commands() {
+    common::setup();
+
+    let signer = Keypair::new();
+    write_keypair_file(&signer, SIGNER_PATH).expect("Failed to write keypair");
+    fs::remove_file(NONCE_KEYPAIR_PATH).ok();
+
+    let rpc = MockRpcServer::start();
+    rpc.set_balance(&signer.pubkey().to_string(), 1_000_000_000);
+
+    let nonce_account = Pubkey::new_unique().to_string();
+    let nonce = rpc.set_nonce_account(&nonce_account, &signer.pubkey(), 1_447_680);
+
+    /*
+    |--------------------------------------------------------------------------
+    | "nonce create" génère la paire de clés du compte nonce
+    |--------------------------------------------------------------------------
+    */
+
+    let output_str = common::stdout(&common::wallet(
+        &["nonce", "create", NONCE_KEYPAIR_PATH],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(
+        &output_str,
+        &format!("Nonce account keypair written to {}", NONCE_KEYPAIR_PATH),
+    );
+    // Le compte nonce signe sa création, avec le payeur.
+    let transaction = &rpc.sent_transactions()[0];
+    assert!(transaction.verify().is_ok());
+    assert_eq!(transaction.message.header.num_required_signatures, 2);
+    common::assert_contains(
+        &output_str,
+        &format!("(authority: {}, balance: 0.000890880 SOL)", signer.pubkey()),
+    );
+
+    // Un montant inférieur au minimum d'exemption de rente est refusé.
+    let output_str = common::stderr(
+        &common::wallet(
+            &["nonce", "create", NONCE_KEYPAIR_PATH, "--amount", "1000"],
+            &envs(rpc.url()),
+        ),
+        2,
+    );
+    common::assert_contains(
+        &output_str,
+        "Failed to create nonce account: A nonce account needs at least 890880 lamports",
+    );
+
+    /*
+    |--------------------------------------------------------------------------
+    | "nonce show", "advance", "withdraw" et "authorize"
+    |--------------------------------------------------------------------------
+    */
+
+    let output_str = common::stdout(&common::wallet(
+        &["nonce", "show", &nonce_account],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(
+        &output_str,
+        &format!("Authority:              {}", signer.pubkey()),
+    );
+    common::assert_contains(&output_str, &format!("Nonce:                  {}", nonce));
+    common::assert_contains(&output_str, "Balance:                0.001447680 SOL");
+
+    let output_str = common::stdout(&common::wallet(
+        &["nonce", "advance", &nonce_account],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(
+        &output_str,
+        &format!("Nonce account {} advanced", nonce_account),
+    );
+
+    let output_str = common::stdout(&common::wallet(
+        &["nonce", "withdraw", &nonce_account, RECIPIENT, "ALL"],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "Withdrew 0.001447680 SOL (1447680 lamports) from nonce account {} to {}",
+            nonce_account, RECIPIENT
+        ),
+    );
+
+    let output_str = common::stdout(&common::wallet(
+        &["nonce", "authorize", &nonce_account, RECIPIENT],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "Nonce account {} authority changed from {} to {}",
+            nonce_account,
+            signer.pubkey(),
+            RECIPIENT
+        ),
+    );
+
+    /*
+    |--------------------------------------------------------------------------
+    | "send --nonce" et "tx build --nonce" utilisent la valeur du nonce
+    |--------------------------------------------------------------------------
+    */
+
+    let output_str = common::stdout(&common::wallet(
+        &[
+            "send",
+            RECIPIENT,
+            "1000",
+            "--nonce",
+            &nonce_account,
+            "--yes",
+        ],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "Using durable nonce account {} (nonce: {}, authority: {})",
+            nonce_account,
+            nonce,
+            signer.pubkey()
+        ),
+    );
+    common::assert_contains(&output_str, "Transaction sent successfully!");
+
+    let blockhash_requests = rpc.requests("getLatestBlockhash").len();
+    let output_str = common::stdout(&common::wallet(
+        &[
+            "tx",
+            "build",
+            RECIPIENT,
+            "1000",
+            "--nonce",
+            &nonce_account,
+            "--outfile",
+            TX_PATH,
+        ],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(
+        &output_str,
+        &format!("Durable nonce: {} (nonce: {})", nonce_account, nonce),
+    );
+    assert_eq!(
+        rpc.requests("getLatestBlockhash").len(),
+        blockhash_requests,
+        "The transaction blockhash must be the nonce value"
+    );
+
+    let content = fs::read_to_string(TX_PATH).expect("Transaction file not found");
+    let file: serde_json::Value = serde_json::from_str(&content).expect("Invalid transaction file");
+    assert_eq!(file["summary"]["blockhash"], nonce.to_string());
+    assert_eq!(file["summary"]["nonce_account"], nonce_account);
+
+    /*
+    |--------------------------------------------------------------------------
+    | Un nonce dont l'autorité n'est pas l'expéditeur est refusé par "send"
+    |--------------------------------------------------------------------------
+    */
+
+    let other_nonce_account = Pubkey::new_unique().to_string();
+    let other_authority = Pubkey::new_unique();
+    rpc.set_nonce_account(&other_nonce_account, &other_authority, 1_447_680);
+
+    let output_str = common::stderr(
+        &common::wallet(
+            &[
+                "send",
+                RECIPIENT,
+                "1000",
+                "--nonce",
+                &other_nonce_account,
+                "--yes",
+            ],
+            &envs(rpc.url()),
+        ),
+        6,
+    );
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "The authority of nonce account {} is {}, not the sender",
+            other_nonce_account, other_authority
+        ),
+    );
+
+    let output_str = common::stderr(
+        &common::wallet(
+            &["nonce", "advance", &other_nonce_account],
+            &envs(rpc.url()),
+        ),
+        6,
+    );
+    common::assert_contains(
+        &output_str,
+        "Failed to advance nonce account: The authority of nonce account",
+    );
+
+    fs::remove_file(TX_PATH).ok();
+    fs::remove_file(SIGNER_PATH).ok();
+    fs::remove_file(NONCE_KEYPAIR_PATH).ok();
+}
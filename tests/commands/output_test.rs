@@ -0,0 +1,112 @@
+use crate::common;
+use crate::common::mock_rpc::MockRpcServer;
+use serde_json::Value;
+use serial_test::serial;
+use solana_sdk::signature::{write_keypair_file, Keypair, Signer};
+use std::fs;
+use std::process::Output;
+use std::str;
+
+// cargo test --test mod -- commands::output_test --nocapture
+
+const KEYPAIR_PATH: &str = "./storage/tests/keypair/id_temp_test_output.json";
+const REGISTRY_PATH: &str = "./storage/tests/wallet_temp_test_output.json";
+
+/// Exécute le wallet avec "--output json", la paire de clés et l'index de comptes temporaires.
+fn execute(args: &[&str], rpc_url: &str) -> Output {
+    let args = [args, &["--output", "json"]].concat();
+    let envs = [
+        ("KEYPAIR_PATH", KEYPAIR_PATH),
+        ("WALLET_REGISTRY_PATH", REGISTRY_PATH),
+        ("RPC_URL", rpc_url),
+    ];
+    common::wallet(&args, &envs)
+}
+
+/// Parse la sortie standard d'une commande : elle doit contenir un seul document JSON.
+fn document(output: &Output) -> Value {
+    let stdout = str::from_utf8(&output.stdout).expect("Invalid UTF-8 output");
+    serde_json::from_str(stdout)
+        .unwrap_or_else(|e| panic!("Invalid JSON output ({}): {}", e, stdout))
+}
+
+fn run(args: &[&str], rpc_url: &str) -> Value {
+    let output = execute(args, rpc_url);
+    assert!(output.status.success(), "Error: Command failed to execute");
+    document(&output)
+}
+
+/// Exécute une commande qui doit échouer avec le code de sortie attendu, et retourne le document d'erreur.
+fn run_err(args: &[&str], rpc_url: &str, exit_code: i32) -> Value {
+    let output = execute(args, rpc_url);
+    assert_eq!(output.status.code(), Some(exit_code));
+    document(&output)["error"].clone()
+}
+
+#[test]
+#[serial]
+fn test_json_output() {
+    common::setup();
+    fs::remove_file(REGISTRY_PATH).ok();
+
+    let server = MockRpcServer::start();
+    let keypair = Keypair::new();
+    write_keypair_file(&keypair, KEYPAIR_PATH).expect("Failed to write keypair");
+    let pubkey = keypair.pubkey().to_string();
+    server.set_balance(&pubkey, 1_500_000_000);
+
+    let value = run(&["pubkey"], server.url());
+    assert_eq!(value["pubkey"], pubkey);
+    assert_eq!(value.get("cluster"), None);
+
+    // Les commandes qui interrogent le réseau indiquent le réseau détecté.
+    let value = run(&["balance"], server.url());
+    assert_eq!(value["cluster"], "devnet");
+    assert_eq!(value["pubkey"], pubkey);
+    assert_eq!(value["balance"]["lamports"], 1_500_000_000u64);
+    assert_eq!(value["balance"]["sol"], "1.500000000");
+
+    let value = run(&["accounts", "list"], server.url());
+    assert_eq!(value["accounts"], Value::Array(vec![]));
+
+    /*
+    |--------------------------------------------------------------------------
+    | Signature puis vérification d'un message
+    |--------------------------------------------------------------------------
+    */
+
+    let value = run(&["sign_message", "I own this wallet"], server.url());
+    assert_eq!(value["pubkey"], pubkey);
+    let signature = value["signature"]
+        .as_str()
+        .expect("No signature")
+        .to_string();
+
+    let value = run(
+        &["verify_message", &pubkey, &signature, "I own this wallet"],
+        server.url(),
+    );
+    assert_eq!(value["valid"], true);
+    assert_eq!(value["pubkey"], pubkey);
+
+    /*
+    |--------------------------------------------------------------------------
+    | Les erreurs sont un document JSON avec leur type et leur code de sortie
+    |--------------------------------------------------------------------------
+    */
+
+    let error = run_err(&["accounts", "remove", "unknown"], server.url(), 6);
+    assert_eq!(error["kind"], "account");
+    assert_eq!(error["code"], 6);
+    assert!(error["message"].as_str().unwrap().contains("unknown"));
+
+    let error = run_err(
+        &["verify_message", &pubkey, &signature, "Another message"],
+        server.url(),
+        1,
+    );
+    assert_eq!(error["kind"], "other");
+
+    fs::remove_file(KEYPAIR_PATH).ok();
+    fs::remove_file(REGISTRY_PATH).ok();
+}
@@ -34,7 +34,7 @@ fn test_pubkey_command() {
     */
 
     let recover_output = Command::new("cargo")
-        .args(["run", "--", "generate_seed"])
+        .args(["run", "--", "generate_seed", "--unencrypted"])
         .output()
         .expect("Failed to 'recover_output' execute command");
 
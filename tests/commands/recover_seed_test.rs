@@ -12,7 +12,7 @@ fn test_recover_seed_command() {
 
     // Exécute la commande "recover_seed" avec la mnémonic donnée.
     let output = Command::new("cargo")
-        .args(["run", "--", "recover_seed", mnemonic])
+        .args(["run", "--", "recover_seed", mnemonic, "--unencrypted"])
         .output()
         .expect("Failed to execute command");
 
@@ -91,3 +91,60 @@ fn test_recover_seed_command() {
     let pubkey = pubkey_line.split(':').nth(1).unwrap().trim();
     common::verify_pubkey(pubkey);
 }
+
+#[test]
+#[serial]
+fn test_recover_seed_command_with_derivation_scheme() {
+    common::setup();
+
+    let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    // Récupère la clé publique du compte 0 avec les options de dérivation données.
+    let first_pubkey = |derivation_args: &[&str]| -> (String, String) {
+        let output = Command::new("cargo")
+            .args(["run", "--", "recover_seed", mnemonic, "--unencrypted"])
+            .args(derivation_args)
+            .output()
+            .expect("Failed to execute command");
+        assert!(output.status.success(), "Error: Command failed to execute");
+
+        let output_str = str::from_utf8(&output.stdout)
+            .expect("Invalid UTF-8 output")
+            .to_string();
+        let scheme_line = output_str
+            .lines()
+            .find(|line| line.starts_with("Derivation scheme:"))
+            .expect("Derivation scheme line not found")
+            .to_string();
+        let pubkey = output_str
+            .lines()
+            .find(|line| line.starts_with("Solana Public Key:"))
+            .expect("Public key line not found")
+            .split(':')
+            .nth(1)
+            .unwrap()
+            .trim()
+            .to_string();
+        (scheme_line, pubkey)
+    };
+
+    // Schéma par défaut (Trezor) : même adresse que Phantom / Solflare / solana-keygen.
+    let (scheme_line, default_pubkey) = first_pubkey(&[]);
+    assert!(scheme_line.contains("bip44change (m/44'/501'/{account}'/0')"));
+    assert_eq!(
+        default_pubkey,
+        "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk"
+    );
+
+    // Un chemin personnalisé identique au schéma par défaut doit donner la même adresse.
+    let (scheme_line, custom_pubkey) =
+        first_pubkey(&["--derivation-path", "m/44'/501'/{account}'/0'"]);
+    assert!(scheme_line.contains("custom"));
+    assert_eq!(custom_pubkey, default_pubkey);
+
+    // Le schéma Ledger donne une autre adresse.
+    let (scheme_line, ledger_pubkey) = first_pubkey(&["--scheme", "bip44"]);
+    assert!(scheme_line.contains("bip44 (m/44'/501'/{account}')"));
+    common::verify_pubkey(&ledger_pubkey);
+    assert_ne!(ledger_pubkey, default_pubkey);
+}
@@ -0,0 +1,353 @@
+use crate::common;
+use crate::common::mock_rpc::{MockRpcServer, MOCK_FEE, MOCK_RENT_EXEMPT_RESERVE};
+use serde_json::{json, Value};
+use serial_test::serial;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{write_keypair_file, Keypair, Signature, Signer};
+use solana_sdk::system_program;
+use std::fs;
+use std::str;
+
+// cargo test --test mod -- commands::send_batch_test --nocapture
+
+const KEYPAIR_PATH: &str = "./storage/tests/keypair/id_temp_test_send_batch.json";
+const CSV_PATH: &str = "./storage/tests/batch_temp_test.csv";
+const RESULTS_PATH: &str = "./storage/tests/batch_temp_test.csv.results.json";
+const CONFIG_PATH: &str = "./storage/tests/config_temp_test_send_batch.toml";
+const LEDGER_PATH: &str = "./storage/tests/spending_temp_test_send_batch.json";
+
+/// Variables d'environnement du wallet : la paire de clés temporaire et un RPC donné.
+fn envs(rpc_url: &str) -> [(&str, &str); 3] {
+    [
+        ("KEYPAIR_PATH", KEYPAIR_PATH),
+        (
+            "WALLET_REGISTRY_PATH",
+            "./storage/tests/wallet_temp_test_send_batch.json",
+        ),
+        ("RPC_URL", rpc_url),
+    ]
+}
+
+fn read_results() -> Value {
+    serde_json::from_str(&fs::read_to_string(RESULTS_PATH).expect("Missing results file"))
+        .expect("Invalid results file")
+}
+
+/// Simule une interruption : les paiements des lignes données redeviennent en attente d'une transaction inconnue.
+fn set_pending(lines: std::ops::RangeInclusive<u64>) -> String {
+    let signature = Signature::new_unique().to_string();
+    let mut results = read_results();
+    let mut lamports = 0;
+    for payment in results["payments"].as_array_mut().unwrap() {
+        if lines.contains(&payment["line"].as_u64().unwrap()) {
+            payment["status"] = json!("pending");
+            payment["signature"] = json!(signature);
+            payment["last_valid_block_height"] = json!(100);
+            lamports += payment["lamports"].as_u64().unwrap();
+        }
+    }
+    results["transactions"] = json!({ signature.clone(): lamports });
+    fs::write(RESULTS_PATH, results.to_string()).unwrap();
+    signature
+}
+
+#[test]
+#[serial]
+fn test_send_batch_command() {
+    common::setup();
+    fs::remove_file(RESULTS_PATH).ok();
+
+    let sender = Keypair::new();
+    write_keypair_file(&sender, KEYPAIR_PATH).expect("Failed to write keypair");
+    let rpc = MockRpcServer::start();
+    rpc.set_balance(&sender.pubkey().to_string(), 100_000_000_000);
+
+    /*
+    |--------------------------------------------------------------------------
+    | Validation de tout le fichier avant le moindre envoi
+    |--------------------------------------------------------------------------
+    */
+
+    let recipient = Pubkey::new_unique();
+    fs::write(
+        CSV_PATH,
+        format!(
+            "recipient,amount\nnot-a-key,1\n{r},1\n{r},abc\n",
+            r = recipient
+        ),
+    )
+    .unwrap();
+    let output_str = common::stderr(
+        &common::wallet(
+            &["send_batch", CSV_PATH, "--sol", "--yes"],
+            &envs(rpc.url()),
+        ),
+        2,
+    );
+    common::assert_contains(&output_str, "Failed to send batch: Invalid CSV file");
+    common::assert_contains(&output_str, "Line 2: Invalid public key: not-a-key");
+    common::assert_contains(&output_str, "Line 4: Invalid amount 'abc'");
+    assert!(!output_str.contains("Line 3"));
+    assert!(fs::metadata(RESULTS_PATH).is_err());
+
+    /*
+    |--------------------------------------------------------------------------
+    | Réserve d'exemption de rente : des destinataires, et de l'expéditeur
+    |--------------------------------------------------------------------------
+    */
+
+    // Un paiement trop petit pour créer le compte de son destinataire est refusé (pas vers un compte existant).
+    let existing = Pubkey::new_unique();
+    rpc.set_account(&existing, &system_program::id(), 1_000_000, &[]);
+    let missing = Pubkey::new_unique();
+    fs::write(
+        CSV_PATH,
+        format!("{},1\n{},0.0001\n{},0.0001\n", recipient, existing, missing),
+    )
+    .unwrap();
+    let output_str = common::stderr(
+        &common::wallet(
+            &["send_batch", CSV_PATH, "--sol", "--yes"],
+            &envs(rpc.url()),
+        ),
+        2,
+    );
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "Failed to send batch: Payments below the rent-exempt minimum ({} lamports) to accounts which do not exist yet:\n  Line 3: 100000 lamports to {}",
+            MOCK_RENT_EXEMPT_RESERVE, missing
+        ),
+    );
+    assert!(!output_str.contains("Line 2"));
+    assert!(rpc.sent_transactions().is_empty());
+    assert!(fs::metadata(RESULTS_PATH).is_err());
+
+    // L'expéditeur doit garder sa réserve d'exemption de rente.
+    fs::write(CSV_PATH, format!("{},1\n", recipient)).unwrap();
+    rpc.set_balance(&sender.pubkey().to_string(), 1_000_000_000 + MOCK_FEE + 1);
+    let output_str = common::stderr(
+        &common::wallet(
+            &["send_batch", CSV_PATH, "--sol", "--yes"],
+            &envs(rpc.url()),
+        ),
+        8,
+    );
+    common::assert_contains(
+        &output_str,
+        "(payments + fees + rent-exempt reserve of 890880 lamports)",
+    );
+    assert!(rpc.sent_transactions().is_empty());
+    fs::remove_file(RESULTS_PATH).ok();
+    rpc.set_balance(&sender.pubkey().to_string(), 100_000_000_000);
+
+    /*
+    |--------------------------------------------------------------------------
+    | Envoi : plusieurs transferts par transaction
+    |--------------------------------------------------------------------------
+    */
+
+    let mut csv = String::from("recipient,amount,memo\n");
+    for index in 0..30 {
+        csv.push_str(&format!("{},0.5\n", Pubkey::new_unique()));
+        if index == 0 {
+            csv.push_str(&format!("{},1.25,\"Invoice #42, May\"\n", recipient));
+        }
+    }
+    fs::write(CSV_PATH, &csv).unwrap();
+
+    // Sans "--yes" ni réponse, l'envoi est annulé.
+    let output = common::wallet(&["send_batch", CSV_PATH, "--sol"], &envs(rpc.url()));
+    common::assert_contains(
+        &common::stderr(&output, 10),
+        "Failed to send batch: Batch cancelled",
+    );
+    let output_str = String::from_utf8_lossy(&output.stdout);
+    common::assert_contains(
+        &output_str,
+        "Payments:      31 (31 recipients, 0 already sent)",
+    );
+    common::assert_contains(&output_str, "Total amount:  16.250000000 SOL");
+    common::assert_contains(&output_str, "Transactions:  2");
+    common::assert_contains(
+        &output_str,
+        "Estimated fee: 0.000010000 SOL (10000 lamports)",
+    );
+
+    let output_str = common::stdout(&common::wallet(
+        &["send_batch", CSV_PATH, "--sol", "--yes"],
+        &envs(rpc.url()),
+    ));
+    assert_eq!(rpc.sent_transactions().len(), 2);
+    assert_eq!(rpc.sent_transfers().len(), 31);
+    common::assert_contains(&output_str, "Transaction 2/2 confirmed");
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "Batch complete: 31 payments sent (16.250000000 SOL) in 2 transactions. Results written to {}",
+            RESULTS_PATH
+        ),
+    );
+    let results = read_results();
+    assert_eq!(results["sender"], sender.pubkey().to_string());
+    let payments = results["payments"].as_array().unwrap();
+    assert_eq!(payments.len(), 31);
+    assert!(payments.iter().all(|payment| payment["status"] == "sent"));
+    assert_eq!(payments[1]["line"], 3);
+    assert_eq!(payments[1]["lamports"], 1_250_000_000u64);
+    assert_eq!(payments[1]["memo"], "Invoice #42, May");
+
+    let output_str = common::stdout(&common::wallet(
+        &["send_batch", CSV_PATH, "--sol", "--yes"],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(&output_str, "All 31 payments were already sent");
+
+    /*
+    |--------------------------------------------------------------------------
+    | Reprise après une interruption : aucun paiement n'est envoyé deux fois
+    |--------------------------------------------------------------------------
+    */
+
+    // Transaction inconnue, mais son blockhash est encore valide : elle peut encore être confirmée.
+    let signature = set_pending(20..=32);
+    rpc.set_block_height(50);
+    let output_str = common::stderr(
+        &common::wallet(
+            &["send_batch", CSV_PATH, "--sol", "--yes"],
+            &envs(rpc.url()),
+        ),
+        9,
+    );
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "Failed to send batch: Transaction {} is not confirmed yet but may still be (valid until block height 100, current: 50)",
+            signature
+        ),
+    );
+
+    rpc.set_signature_status(&signature, Value::Null);
+    let output_str = common::stdout(&common::wallet(
+        &["send_batch", CSV_PATH, "--sol", "--yes"],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "Transaction {} was confirmed: 13 payments marked as sent",
+            signature
+        ),
+    );
+    common::assert_contains(&output_str, "All 31 payments were already sent");
+
+    // Blockhash expiré sans confirmation : les paiements sont renvoyés.
+    let signature = set_pending(30..=32);
+    rpc.set_block_height(150);
+    let output_str = common::stdout(&common::wallet(
+        &["send_batch", CSV_PATH, "--sol", "--yes"],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "Transaction {} expired without being confirmed: 3 payments will be sent again",
+            signature
+        ),
+    );
+    common::assert_contains(
+        &output_str,
+        "Payments:      3 (3 recipients, 28 already sent)",
+    );
+    common::assert_contains(&output_str, "Batch complete: 3 payments sent");
+
+    // Un fichier de résultats ne peut pas être repris avec un CSV modifié.
+    fs::write(CSV_PATH, format!("{}{},1\n", csv, recipient)).unwrap();
+    let output_str = common::stderr(
+        &common::wallet(
+            &["send_batch", CSV_PATH, "--sol", "--yes"],
+            &envs(rpc.url()),
+        ),
+        11,
+    );
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "Failed to send batch: Cannot resume from {}: the CSV file was modified",
+            RESULTS_PATH
+        ),
+    );
+
+    fs::remove_file(KEYPAIR_PATH).ok();
+    fs::remove_file(CSV_PATH).ok();
+    fs::remove_file(RESULTS_PATH).ok();
+}
+
+#[test]
+#[serial]
+fn test_send_batch_records_confirmed_pending_transaction() {
+    common::setup();
+    fs::remove_file(RESULTS_PATH).ok();
+    fs::remove_file(LEDGER_PATH).ok();
+
+    let sender = Keypair::new();
+    write_keypair_file(&sender, KEYPAIR_PATH).expect("Failed to write keypair");
+    let rpc = MockRpcServer::start();
+    rpc.set_balance(&sender.pubkey().to_string(), 100_000_000_000);
+
+    // Un profil avec un plafond de dépense par jour : chaque transaction est enregistrée dans le registre des dépenses.
+    fs::write(
+        CONFIG_PATH,
+        "[profiles.limited]\nspending_limit_per_day = \"10\"\n",
+    )
+    .unwrap();
+    let url = rpc.url();
+    let envs = [
+        envs(url).as_slice(),
+        &[
+            ("WALLET_CONFIG", CONFIG_PATH),
+            ("WALLET_PROFILE", "limited"),
+            ("SPENDING_LEDGER_PATH", LEDGER_PATH),
+        ],
+    ]
+    .concat();
+
+    let csv: String = (0..3)
+        .map(|_| format!("{},0.5\n", Pubkey::new_unique()))
+        .collect();
+    fs::write(CSV_PATH, &csv).unwrap();
+    common::stdout(&common::wallet(
+        &["send_batch", CSV_PATH, "--sol", "--yes"],
+        &envs,
+    ));
+
+    // Transaction interrompue (ex : Ctrl-C après la diffusion), puis confirmée : la reprise l'enregistre.
+    let signature = set_pending(1..=3);
+    rpc.set_signature_status(&signature, Value::Null);
+    let output_str = common::stdout(&common::wallet(
+        &["send_batch", CSV_PATH, "--sol", "--yes"],
+        &envs,
+    ));
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "Transaction {} was confirmed: 3 payments marked as sent",
+            signature
+        ),
+    );
+    let ledger: Value =
+        serde_json::from_str(&fs::read_to_string(LEDGER_PATH).expect("Missing ledger file"))
+            .expect("Invalid ledger file");
+    let entries = ledger["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[1]["signature"], signature);
+    assert_eq!(entries[1]["lamports"], 1_500_000_000u64);
+    assert_eq!(entries[1]["profile"], "limited");
+    assert!(read_results().get("transactions").is_none());
+
+    fs::remove_file(KEYPAIR_PATH).ok();
+    fs::remove_file(CSV_PATH).ok();
+    fs::remove_file(RESULTS_PATH).ok();
+    fs::remove_file(CONFIG_PATH).ok();
+    fs::remove_file(LEDGER_PATH).ok();
+}
@@ -1,7 +1,82 @@
 use crate::common;
+use crate::common::mock_rpc::{MockRpcServer, MOCK_FEE, MOCK_RENT_EXEMPT_RESERVE};
 use serial_test::serial;
-use std::process::Command;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{write_keypair_file, Keypair, Signer};
+use std::fs;
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
 use std::str;
+use std::str::FromStr;
+
+// cargo test --test mod -- commands::send_test --nocapture
+
+/// Expéditeur temporaire : une paire de clés écrite dans un fichier, et approvisionnée sur un RPC simulé.
+struct TestSender {
+    keypair: Keypair,
+    keypair_path: String,
+    rpc: MockRpcServer,
+}
+
+impl TestSender {
+    fn new(name: &str, lamports: u64) -> Self {
+        let keypair = Keypair::new();
+        let keypair_path = format!("./storage/tests/keypair/id_temp_test_{}.json", name);
+        write_keypair_file(&keypair, &keypair_path).expect("Failed to write keypair");
+
+        let rpc = MockRpcServer::start();
+        rpc.set_balance(&keypair.pubkey().to_string(), lamports);
+
+        Self {
+            keypair,
+            keypair_path,
+            rpc,
+        }
+    }
+
+    /// Exécute "send" avec cet expéditeur, en écrivant éventuellement une réponse sur l'entrée standard.
+    fn execute(&self, args: &[&str], input: Option<&str>) -> Output {
+        let mut child = Command::new("cargo")
+            .args(["run", "--", "send"])
+            .args(args)
+            .env("KEYPAIR_PATH", &self.keypair_path)
+            .env(
+                "WALLET_REGISTRY_PATH",
+                "./storage/tests/wallet_temp_test_send.json",
+            )
+            .env("RPC_URL", self.rpc.url())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to execute command");
+        if let Some(input) = input {
+            let mut stdin = child.stdin.take().unwrap();
+            stdin.write_all(input.as_bytes()).unwrap();
+        }
+        child.wait_with_output().expect("Failed to execute command")
+    }
+
+    fn send(&self, args: &[&str], input: Option<&str>) -> String {
+        let output = self.execute(args, input);
+        assert!(output.status.success(), "Error: Command failed to execute");
+
+        str::from_utf8(&output.stdout)
+            .expect("Invalid UTF-8 output")
+            .to_string()
+    }
+
+    /// Exécute "send", qui doit échouer avec le code de sortie attendu, et retourne son message d'erreur.
+    fn send_err(&self, args: &[&str], input: Option<&str>, exit_code: i32) -> String {
+        common::stderr(&self.execute(args, input), exit_code)
+    }
+}
+
+impl Drop for TestSender {
+    fn drop(&mut self) {
+        fs::remove_file(&self.keypair_path).ok();
+    }
+}
 
 #[test]
 #[serial]
@@ -12,44 +87,383 @@ fn test_send_command() {
     let recipient = "EMLY3VvNZ41yMWyPQy2AiEfJTPpZdzeGNG5zaaq3Lihb";
     let amount = "2000000";
 
-    // Exécute la commande "send" avec le récepteur ("recipient") et le montant en lamports ("amount").
-    let output = Command::new("cargo")
-        .args(["run", "--", "send", recipient, amount])
-        .output()
-        .expect("Failed to execute command");
+    let sender = TestSender::new("send", 1_000_000_000);
+
+    // Exécute la commande "send" avec le récepteur ("recipient") et le montant en lamports ("amount"), sans confirmation.
+    let output_str = sender.send(&[recipient, amount, "--yes"], None);
+
+    /*
+    |--------------------------------------------------------------------------
+    | Les vérifications de la sortie de la commande
+    |--------------------------------------------------------------------------
+    */
+
+    // La transaction signée a été reçue par le RPC simulé : elle transfère le montant au destinataire.
+    assert_eq!(
+        sender.rpc.sent_transfers(),
+        vec![(
+            sender.keypair.pubkey(),
+            Pubkey::from_str(recipient).unwrap(),
+            amount.parse().unwrap()
+        )]
+    );
+    let transaction = &sender.rpc.sent_transactions()[0];
+    assert!(transaction.verify().is_ok());
+    assert!(
+        output_str.contains(&format!(
+            "Transaction signature: {}",
+            transaction.signatures[0]
+        )),
+        "Output should contain the signature: {}",
+        output_str
+    );
+    assert!(
+        output_str.contains("Transaction sent successfully!"),
+        "Expected successful transaction, got: {}",
+        output_str
+    );
+}
+
+#[test]
+#[serial]
+fn test_send_command_with_sol_amount() {
+    common::setup();
+
+    let recipient = "EMLY3VvNZ41yMWyPQy2AiEfJTPpZdzeGNG5zaaq3Lihb";
+
+    let sender = TestSender::new("send_sol", 1_000_000_000);
+
+    // 0.3 SOL n'est pas représentable exactement en flottant : le montant doit être exact en lamports.
+    sender.send(&[recipient, "0.3", "--sol", "--yes"], None);
+    assert_eq!(sender.rpc.sent_transfers()[0].2, 300_000_000);
+}
+
+#[test]
+#[serial]
+fn test_send_command_with_invalid_amounts() {
+    common::setup();
+
+    let recipient = "EMLY3VvNZ41yMWyPQy2AiEfJTPpZdzeGNG5zaaq3Lihb";
+
+    let cases: [(&[&str], &str); 4] = [
+        (
+            &["0.0000000001", "--sol"],
+            "SOL amounts have at most 9 decimals",
+        ),
+        (&["18446744074", "--sol"], "amount is too large"),
+        (&["1.5"], "lamports cannot have decimals"),
+        (&["0", "--lamports"], "Amount must be greater than zero"),
+    ];
+
+    for (amount_args, expected_error) in cases {
+        let output = Command::new("cargo")
+            .args(["run", "--", "send", recipient])
+            .args(amount_args)
+            .output()
+            .expect("Failed to execute command");
+        let output_str = common::stderr(&output, 2);
+        assert!(
+            output_str.contains("Failed to send transaction: Invalid amount")
+                || output_str.contains("Failed to send transaction: Amount"),
+            "Unexpected output: {}",
+            output_str
+        );
+        assert!(
+            output_str.contains(expected_error),
+            "Unexpected output: {}",
+            output_str
+        );
+    }
+}
+
+#[test]
+#[serial]
+fn test_send_command_all() {
+    common::setup();
+
+    let recipient = "EMLY3VvNZ41yMWyPQy2AiEfJTPpZdzeGNG5zaaq3Lihb";
+    let balance = 1_000_000_000;
+    let sender = TestSender::new("send_all", balance);
+
+    // Envoie toute la balance, moins les frais et la réserve d'exemption de rente.
+    let output_str = sender.send(&[recipient, "ALL", "--yes"], None);
+    let expected = balance - MOCK_FEE - MOCK_RENT_EXEMPT_RESERVE;
+    assert!(
+        output_str.contains(&format!(
+            "Sending all available funds: 0.999104120 SOL ({} lamports), keeping {} lamports for the fee and {} lamports of rent-exempt reserve",
+            expected, MOCK_FEE, MOCK_RENT_EXEMPT_RESERVE
+        )),
+        "Unexpected output: {}",
+        output_str
+    );
+    assert_eq!(
+        sender.rpc.sent_transfers(),
+        vec![(
+            sender.keypair.pubkey(),
+            Pubkey::from_str(recipient).unwrap(),
+            expected
+        )]
+    );
+    // Frais estimés pour le calcul du montant, puis pour le résumé de la transaction.
+    assert_eq!(sender.rpc.requests("getFeeForMessage").len(), 2);
+}
+
+#[test]
+#[serial]
+fn test_send_command_preview_and_confirmation() {
+    common::setup();
+
+    let recipient = "EMLY3VvNZ41yMWyPQy2AiEfJTPpZdzeGNG5zaaq3Lihb";
+    let sender = TestSender::new("send_preview", 1_000_000_000);
 
-    // Vérifie que la commande s'est exécutée avec succès.
-    assert!(output.status.success(), "Error: Command failed to execute");
+    /*
+    |--------------------------------------------------------------------------
+    | Résumé de la transaction, puis annulation ("N" par défaut)
+    |--------------------------------------------------------------------------
+    */
 
-    // Convertit la sortie de la commande en chaîne de caractères.
+    let output = sender.execute(&[recipient, "0.25", "--sol"], Some("\n"));
+    let error = common::stderr(&output, 10);
+    assert!(
+        error.contains("Failed to send transaction: Transaction cancelled"),
+        "Unexpected error: {}",
+        error
+    );
     let output_str = str::from_utf8(&output.stdout).expect("Invalid UTF-8 output");
+    for expected in [
+        "Transaction preview:".to_string(),
+        format!("From:          {}", sender.keypair.pubkey()),
+        format!("To:            {}", recipient),
+        "Amount:        0.250000000 SOL (250000000 lamports)".to_string(),
+        format!("Estimated fee: 0.000005000 SOL ({} lamports)", MOCK_FEE),
+        "Balance after: 0.749995000 SOL (749995000 lamports)".to_string(),
+        "Send this transaction? [y/N]:".to_string(),
+    ] {
+        assert!(
+            output_str.contains(&expected),
+            "Expected '{}' in output: {}",
+            expected,
+            output_str
+        );
+    }
+    assert!(!output_str.contains("Warning:"));
+    assert!(sender.rpc.sent_transactions().is_empty());
 
     /*
     |--------------------------------------------------------------------------
-    | Les vérifications de la sortie de la commande
+    | Confirmation ("y")
+    |--------------------------------------------------------------------------
+    */
+
+    sender.send(&[recipient, "0.25", "--sol"], Some("y\n"));
+    assert_eq!(sender.rpc.sent_transfers()[0].2, 250_000_000);
+
+    /*
+    |--------------------------------------------------------------------------
+    | Balance insuffisante : la transaction est refusée avant la confirmation
     |--------------------------------------------------------------------------
     */
 
-    // Vérifiez si la sortie contient soit le message de simulation.
+    let output = sender.execute(&[recipient, "1", "--sol", "--yes"], None);
+    let output_str = common::stderr(&output, 8);
     assert!(
-        output_str.contains("Simulating transaction:"),
+        output_str.contains("Failed to send transaction: Insufficient balance: 1000000000 lamports available, 1000005000 lamports needed (amount + fee)"),
         "Unexpected output: {}",
         output_str
     );
+    assert_eq!(sender.rpc.sent_transactions().len(), 1);
+}
+
+#[test]
+#[serial]
+fn test_send_command_preview_warnings() {
+    common::setup();
+
+    let sender = TestSender::new("send_warnings", 1_000_000_000);
+    let sender_pubkey = sender.keypair.pubkey().to_string();
+
+    // Envoi à soi-même.
+    let output_str = sender.send(&[&sender_pubkey, "1000", "--yes"], None);
     assert!(
-        output_str.contains(&format!("Simulating transaction: {} lamports", amount)),
-        "Output should contain the correct amount: {}",
+        output_str.contains("Warning: The recipient is the sender"),
+        "Unexpected output: {}",
         output_str
     );
+
+    // Destinataire hors de la courbe ed25519 (adresse dérivée du programme System avec la seed "wallet").
+    let pda = "4XuzpqgtUrbmfFEoc7GRgmx8cooeEJrv6nBE6K4G5uVZ";
+    let output_str = sender.send(&[pda, "1000", "--yes"], None);
     assert!(
-        output_str.contains(recipient),
-        "Output should contain the recipient address: {}",
+        output_str.contains("Warning: The recipient address is off-curve"),
+        "Unexpected output: {}",
         output_str
     );
-    // Si la simulation a réussi, vérifiez le message de succès.
+
+    // Destinataire qui est un programme.
+    let program = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+    sender.rpc.set_program(program);
+    let output_str = sender.send(&[program, "1000", "--yes"], None);
     assert!(
-        output_str.contains("Transaction sent successfully!"),
-        "Expected successful transaction, got: {}",
+        output_str.contains("Warning: The recipient is a program"),
+        "Unexpected output: {}",
+        output_str
+    );
+    assert!(!output_str.contains("Warning: The recipient is the sender"));
+}
+
+#[test]
+#[serial]
+fn test_send_command_priority_fee() {
+    common::setup();
+
+    let recipient = "EMLY3VvNZ41yMWyPQy2AiEfJTPpZdzeGNG5zaaq3Lihb";
+    let sender = TestSender::new("send_priority_fee", 1_000_000_000);
+
+    // Prix fixe : 10 000 micro-lamports x 200 000 unités (limite par défaut du transfert) = 2 000 lamports.
+    let output_str = sender.send(
+        &[recipient, "1000", "--priority-fee", "10000", "--yes"],
+        None,
+    );
+    for expected in [
+        "Priority fee:  0.000002000 SOL (2000 lamports) (10000 micro-lamports x 200000 compute units, included in the fee)",
+        "Estimated fee: 0.000007000 SOL (7000 lamports)",
+        "Total fee: 0.000007000 SOL (7000 lamports, including 2000 lamports of priority fee)",
+    ] {
+        assert!(
+            output_str.contains(expected),
+            "Expected '{}' in output: {}",
+            expected,
+            output_str
+        );
+    }
+
+    // Mode "auto" : percentile des frais récents des comptes modifiés, payé sur la limite donnée.
+    sender
+        .rpc
+        .set_prioritization_fees(&[0, 100_000, 2_000_000, 50_000, 300_000]);
+    let output_str = sender.send(
+        &[
+            recipient,
+            "1000",
+            "--priority-fee",
+            "auto",
+            "--compute-unit-limit",
+            "1000",
+            "--yes",
+        ],
+        None,
+    );
+    for expected in [
+        "Priority fee (auto): 300000 micro-lamports per compute unit (percentile 75 of the last 5 slots)",
+        "(300000 micro-lamports x 1000 compute units, included in the fee)",
+        "Total fee: 0.000005300 SOL (5300 lamports, including 300 lamports of priority fee)",
+    ] {
+        assert!(
+            output_str.contains(expected),
+            "Expected '{}' in output: {}",
+            expected,
+            output_str
+        );
+    }
+    let request = &sender.rpc.requests("getRecentPrioritizationFees")[0];
+    assert_eq!(
+        request["params"][0],
+        serde_json::json!([sender.keypair.pubkey().to_string(), recipient])
+    );
+
+    let output_str = sender.send(
+        &[
+            recipient,
+            "1000",
+            "--priority-fee",
+            "auto",
+            "--priority-fee-percentile",
+            "50",
+            "--yes",
+        ],
+        None,
+    );
+    assert!(
+        output_str.contains("100000 micro-lamports per compute unit (percentile 50"),
+        "Unexpected output: {}",
+        output_str
+    );
+
+    let output_str = sender.send_err(
+        &[recipient, "1000", "--priority-fee", "fast", "--yes"],
+        None,
+        2,
+    );
+    assert!(
+        output_str.contains("Failed to send transaction: Invalid priority fee 'fast'"),
+        "Unexpected output: {}",
+        output_str
+    );
+}
+
+#[test]
+#[serial]
+fn test_send_command_memo() {
+    common::setup();
+
+    let recipient = "EMLY3VvNZ41yMWyPQy2AiEfJTPpZdzeGNG5zaaq3Lihb";
+    let sender = TestSender::new("send_memo", 1_000_000_000);
+
+    // L'instruction SPL Memo s'ajoute au transfert : la limite de calcul par défaut double.
+    let output_str = sender.send(
+        &[
+            recipient,
+            "1000",
+            "--memo",
+            "Deposit 4815162342",
+            "--priority-fee",
+            "10000",
+            "--yes",
+        ],
+        None,
+    );
+    for expected in [
+        "Memo:          Deposit 4815162342",
+        "(10000 micro-lamports x 400000 compute units, included in the fee)",
+        "Transaction sent successfully!",
+    ] {
+        assert!(
+            output_str.contains(expected),
+            "Expected '{}' in output: {}",
+            expected,
+            output_str
+        );
+    }
+    // Le memo est la dernière instruction de la transaction diffusée.
+    let transaction = &sender.rpc.sent_transactions()[0];
+    let memo = transaction.message.instructions.last().unwrap();
+    assert_eq!(memo.data, b"Deposit 4815162342");
+
+    let long_memo = "x".repeat(257);
+    let output_str = sender.send_err(&[recipient, "1000", "--memo", &long_memo, "--yes"], None, 2);
+    assert!(
+        output_str
+            .contains("Failed to send transaction: Memo is too long: 257 bytes (max 256 bytes)"),
+        "Unexpected output: {}",
         output_str
     );
 }
+
+#[test]
+#[serial]
+fn test_send_command_rejected_by_the_network() {
+    common::setup();
+
+    let recipient = "EMLY3VvNZ41yMWyPQy2AiEfJTPpZdzeGNG5zaaq3Lihb";
+    let sender = TestSender::new("send_rejected", 1_000_000_000);
+    sender.rpc.set_send_error("Blockhash not found");
+
+    // Le refus du noeud RPC est une erreur RPC (code de sortie 7), et rien n'est enregistré.
+    let error = sender.send_err(&[recipient, "2000000", "--yes"], None, 7);
+    assert!(
+        error.contains("Blockhash not found"),
+        "Unexpected error: {}",
+        error
+    );
+    assert!(sender.rpc.sent_transactions().is_empty());
+}
@@ -0,0 +1,387 @@
+use crate::common;
+use crate::common::mock_rpc::MockRpcServer;
+use serial_test::serial;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{write_keypair_file, Keypair, Signer};
+use solana_sdk::stake::{
+    self,
+    stake_flags::StakeFlags,
+    state::{Authorized, Delegation, Lockup, Meta, Stake, StakeStateV2},
+};
+use std::fs;
+use std::str;
+
+// cargo test --test mod -- commands::stake_test --nocapture
+
+const RECIPIENT: &str = "EMLY3VvNZ41yMWyPQy2AiEfJTPpZdzeGNG5zaaq3Lihb";
+const SIGNER_PATH: &str = "./storage/tests/keypair/id_temp_test_stake.json";
+const AUTHORITY_PATH: &str = "./storage/tests/keypair/id_temp_test_stake_authority.json";
+const STAKE_KEYPAIR_PATH: &str = "./storage/tests/keypair/stake_temp_test.json";
+const REGISTRY_PATH: &str = "./storage/tests/wallet_temp_test_stake.json";
+const RESERVE: u64 = 2_282_880;
+
+/// Variables d'environnement du wallet : la paire de clés temporaire et un RPC donné.
+fn envs(rpc_url: &str) -> [(&str, &str); 3] {
+    [
+        ("KEYPAIR_PATH", SIGNER_PATH),
+        ("WALLET_REGISTRY_PATH", REGISTRY_PATH),
+        ("RPC_URL", rpc_url),
+    ]
+}
+
+/// Déclare un compte de stake (délégué à "vote_account" depuis "activation_epoch" si donné).
+fn set_stake_account(
+    rpc: &MockRpcServer,
+    address: &Pubkey,
+    authority: &Pubkey,
+    lamports: u64,
+    delegation: Option<(Pubkey, u64, u64)>,
+) {
+    let meta = Meta {
+        rent_exempt_reserve: RESERVE,
+        authorized: Authorized::auto(authority),
+        lockup: Lockup::default(),
+    };
+    let state = match delegation {
+        None => StakeStateV2::Initialized(meta),
+        Some((vote_account, activation_epoch, deactivation_epoch)) => StakeStateV2::Stake(
+            meta,
+            Stake {
+                delegation: Delegation {
+                    voter_pubkey: vote_account,
+                    stake: lamports - RESERVE,
+                    activation_epoch,
+                    deactivation_epoch,
+                    ..Delegation::default()
+                },
+                credits_observed: 0,
+            },
+            StakeFlags::empty(),
+        ),
+    };
+    let mut data = bincode::serialize(&state).unwrap();
+    data.resize(StakeStateV2::size_of(), 0);
+    rpc.set_account(address, &stake::program::id(), lamports, &data);
+}
+
+#[test]
+#[serial]
+fn test_stake_commands() {
+    common::setup();
+    fs::remove_file(REGISTRY_PATH).ok();
+    fs::remove_file(STAKE_KEYPAIR_PATH).ok();
+
+    let signer = Keypair::new();
+    write_keypair_file(&signer, SIGNER_PATH).expect("Failed to write keypair");
+    let authority = Keypair::new();
+    write_keypair_file(&authority, AUTHORITY_PATH).expect("Failed to write keypair");
+
+    let rpc = MockRpcServer::start();
+    rpc.set_balance(&signer.pubkey().to_string(), 10_000_000_000);
+    rpc.set_epoch(100);
+    let vote_account = Pubkey::new_unique();
+    rpc.set_account(&vote_account, &solana_sdk::vote::program::id(), 1, &[]);
+
+    // L'autorité est un compte du wallet, sélectionné par son label.
+    let output_str = common::stdout(&common::wallet(
+        &["accounts", "add", "treasury", "--keypair", AUTHORITY_PATH],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(&output_str, "treasury");
+
+    /*
+    |--------------------------------------------------------------------------
+    | "stake create" avec une paire de clés ou une seed
+    |--------------------------------------------------------------------------
+    */
+
+    let output_str = common::stdout(&common::wallet(
+        &[
+            "stake",
+            "create",
+            "2",
+            "--sol",
+            "--stake-keypair",
+            STAKE_KEYPAIR_PATH,
+            "--stake-authority",
+            "treasury",
+        ],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(
+        &output_str,
+        &format!("Stake account keypair written to {}", STAKE_KEYPAIR_PATH),
+    );
+    // Le compte de stake signe sa création, avec le payeur.
+    let transaction = rpc.sent_transactions().pop().unwrap();
+    assert!(transaction.verify().is_ok());
+    assert_eq!(transaction.message.header.num_required_signatures, 2);
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "(stake authority: {}, withdraw authority: {}, balance: 2.000000000 SOL)",
+            authority.pubkey(),
+            signer.pubkey()
+        ),
+    );
+
+    let seeded_address =
+        Pubkey::create_with_seed(&signer.pubkey(), "treasury-1", &stake::program::id()).unwrap();
+    let output_str = common::stdout(&common::wallet(
+        &["stake", "create", "2", "--sol", "--seed", "treasury-1"],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "Stake account address: {} (seed \"treasury-1\", base {})",
+            seeded_address,
+            signer.pubkey()
+        ),
+    );
+    common::assert_contains(
+        &output_str,
+        &format!("Stake account created: {}", seeded_address),
+    );
+
+    // Sans "--stake-keypair" ni "--seed", la commande est refusée par clap.
+    let output = common::wallet(&["stake", "create", "2", "--sol"], &envs(rpc.url()));
+    assert!(!output.status.success());
+
+    /*
+    |--------------------------------------------------------------------------
+    | "stake delegate", "show", "deactivate"
+    |--------------------------------------------------------------------------
+    */
+
+    let stake_account = Pubkey::new_unique();
+    set_stake_account(
+        &rpc,
+        &stake_account,
+        &authority.pubkey(),
+        5_000_000_000 + RESERVE,
+        None,
+    );
+
+    let output_str = common::stdout(&common::wallet(
+        &[
+            "stake",
+            "delegate",
+            &stake_account.to_string(),
+            &vote_account.to_string(),
+            "--authority",
+            "treasury",
+        ],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "Stake account {} delegated to {} (5.000000000 SOL",
+            stake_account, vote_account
+        ),
+    );
+
+    // Sans "--authority", le wallet n'est pas l'autorité du compte.
+    let output_str = common::stderr(
+        &common::wallet(
+            &["stake", "deactivate", &stake_account.to_string()],
+            &envs(rpc.url()),
+        ),
+        6,
+    );
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "Failed to deactivate stake account: The stake authority of stake account {} is {}, not {}",
+            stake_account,
+            authority.pubkey(),
+            signer.pubkey()
+        ),
+    );
+
+    // Un compte de vote inconnu est refusé.
+    let output_str = common::stderr(
+        &common::wallet(
+            &[
+                "stake",
+                "delegate",
+                &stake_account.to_string(),
+                RECIPIENT,
+                "--authority",
+                "treasury",
+            ],
+            &envs(rpc.url()),
+        ),
+        2,
+    );
+    common::assert_contains(
+        &output_str,
+        &format!("Vote account {} not found", RECIPIENT),
+    );
+
+    set_stake_account(
+        &rpc,
+        &stake_account,
+        &authority.pubkey(),
+        5_000_000_000 + RESERVE,
+        Some((vote_account, 90, u64::MAX)),
+    );
+    rpc.set_inflation_reward(&stake_account, 99, 1_234_567);
+
+    let output_str = common::stdout(&common::wallet(
+        &["stake", "show", &stake_account.to_string()],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(
+        &output_str,
+        &format!("Stake authority:     {}", authority.pubkey()),
+    );
+    common::assert_contains(&output_str, "State:               Active");
+    common::assert_contains(
+        &output_str,
+        &format!("Delegated to:        {}", vote_account),
+    );
+    common::assert_contains(&output_str, "Active stake:        5.000000000 SOL");
+    common::assert_contains(
+        &output_str,
+        "Last reward:         0.001234567 SOL (epoch 99, commission 5%)",
+    );
+
+    let output_str = common::stdout(&common::wallet(
+        &[
+            "stake",
+            "deactivate",
+            &stake_account.to_string(),
+            "--authority",
+            "treasury",
+        ],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(
+        &output_str,
+        &format!("Stake account {} deactivated", stake_account),
+    );
+
+    /*
+    |--------------------------------------------------------------------------
+    | "stake withdraw" : uniquement ce qui n'est plus engagé
+    |--------------------------------------------------------------------------
+    */
+
+    let output_str = common::stderr(
+        &common::wallet(
+            &[
+                "stake",
+                "withdraw",
+                &stake_account.to_string(),
+                RECIPIENT,
+                "1",
+                "--sol",
+                "--authority",
+                "treasury",
+            ],
+            &envs(rpc.url()),
+        ),
+        8,
+    );
+    common::assert_contains(
+        &output_str,
+        "Only 0.000000000 SOL can be withdrawn from stake account",
+    );
+
+    // Désactivé avant l'epoch courante : toute la balance est retirable.
+    set_stake_account(
+        &rpc,
+        &stake_account,
+        &authority.pubkey(),
+        5_000_000_000 + RESERVE,
+        Some((vote_account, 90, 95)),
+    );
+    let output_str = common::stdout(&common::wallet(
+        &["stake", "show", &stake_account.to_string()],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(&output_str, "State:               Inactive");
+    let output_str = common::stdout(&common::wallet(
+        &[
+            "stake",
+            "withdraw",
+            &stake_account.to_string(),
+            RECIPIENT,
+            "ALL",
+            "--authority",
+            "treasury",
+        ],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "Withdrew 5.002282880 SOL (5002282880 lamports) from stake account {} to {}",
+            stake_account, RECIPIENT
+        ),
+    );
+
+    /*
+    |--------------------------------------------------------------------------
+    | "stake split" et "stake merge"
+    |--------------------------------------------------------------------------
+    */
+
+    let output_str = common::stdout(&common::wallet(
+        &[
+            "stake",
+            "split",
+            &stake_account.to_string(),
+            "1",
+            "--sol",
+            "--seed",
+            "split-1",
+            "--authority",
+            "treasury",
+        ],
+        &envs(rpc.url()),
+    ));
+    let split_address =
+        Pubkey::create_with_seed(&signer.pubkey(), "split-1", &stake::program::id()).unwrap();
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "Split 1.000000000 SOL from stake account {} into {}",
+            stake_account, split_address
+        ),
+    );
+
+    let other_account = Pubkey::new_unique();
+    set_stake_account(
+        &rpc,
+        &other_account,
+        &authority.pubkey(),
+        3_000_000_000,
+        None,
+    );
+    let output_str = common::stdout(&common::wallet(
+        &[
+            "stake",
+            "merge",
+            &stake_account.to_string(),
+            &other_account.to_string(),
+            "--authority",
+            "treasury",
+        ],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "Stake account {} merged into {}",
+            other_account, stake_account
+        ),
+    );
+
+    fs::remove_file(SIGNER_PATH).ok();
+    fs::remove_file(AUTHORITY_PATH).ok();
+    fs::remove_file(STAKE_KEYPAIR_PATH).ok();
+    fs::remove_file(REGISTRY_PATH).ok();
+}
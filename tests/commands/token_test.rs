@@ -0,0 +1,216 @@
+use crate::common;
+use crate::common::mock_rpc::MockRpcServer;
+use serial_test::serial;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{write_keypair_file, Keypair, Signer};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+use std::fs;
+use std::str;
+
+// cargo test --test mod -- commands::token_test --nocapture
+
+const RECIPIENT: &str = "EMLY3VvNZ41yMWyPQy2AiEfJTPpZdzeGNG5zaaq3Lihb";
+const KEYPAIR_PATH: &str = "./storage/tests/keypair/id_temp_test_token.json";
+
+/// Variables d'environnement du wallet : la paire de clés temporaire et un RPC donné.
+fn envs(rpc_url: &str) -> [(&str, &str); 3] {
+    [
+        ("KEYPAIR_PATH", KEYPAIR_PATH),
+        (
+            "WALLET_REGISTRY_PATH",
+            "./storage/tests/wallet_temp_test_token.json",
+        ),
+        ("RPC_URL", rpc_url),
+    ]
+}
+
+#[test]
+#[serial]
+fn test_token_commands() {
+    common::setup();
+
+    let owner = Keypair::new();
+    write_keypair_file(&owner, KEYPAIR_PATH).expect("Failed to write keypair");
+    let recipient: Pubkey = RECIPIENT.parse().unwrap();
+
+    // Un token SPL Token (6 décimales) et un token Token-2022 (9 décimales).
+    let rpc = MockRpcServer::start();
+    let usdc = Pubkey::new_unique();
+    let token_2022 = Pubkey::new_unique();
+    rpc.set_mint(&usdc, &spl_token::id(), 6);
+    rpc.set_mint(&token_2022, &spl_token_2022::id(), 9);
+
+    let usdc_account =
+        get_associated_token_address_with_program_id(&owner.pubkey(), &usdc, &spl_token::id());
+    let empty_account = Pubkey::new_unique();
+    rpc.set_token_account(
+        &usdc_account,
+        &usdc,
+        &owner.pubkey(),
+        &spl_token::id(),
+        2_500_000,
+    );
+    rpc.set_token_account(
+        &empty_account,
+        &token_2022,
+        &owner.pubkey(),
+        &spl_token_2022::id(),
+        0,
+    );
+
+    /*
+    |--------------------------------------------------------------------------
+    | "token list" affiche les comptes des deux programmes
+    |--------------------------------------------------------------------------
+    */
+
+    let output_str = common::stdout(&common::wallet(&["token", "list"], &envs(rpc.url())));
+    common::assert_contains(&output_str, "TOKEN ACCOUNT");
+    for (account, mint, program, decimals, amount) in [
+        (usdc_account, usdc, "Token", 6, "2.500000"),
+        (empty_account, token_2022, "Token-2022", 9, "0.000000000"),
+    ] {
+        let line = output_str
+            .lines()
+            .find(|line| line.starts_with(&account.to_string()))
+            .unwrap_or_else(|| panic!("Token account {} not listed: {}", account, output_str));
+        let columns: Vec<&str> = line.split_whitespace().collect();
+        assert_eq!(
+            columns,
+            [
+                account.to_string().as_str(),
+                mint.to_string().as_str(),
+                program,
+                decimals.to_string().as_str(),
+                amount
+            ]
+        );
+    }
+
+    /*
+    |--------------------------------------------------------------------------
+    | "token transfer" crée le compte associé du destinataire s'il n'existe pas
+    |--------------------------------------------------------------------------
+    */
+
+    let recipient_account =
+        get_associated_token_address_with_program_id(&recipient, &usdc, &spl_token::id());
+
+    let output_str = common::stdout(&common::wallet(
+        &[
+            "token",
+            "transfer",
+            &usdc.to_string(),
+            RECIPIENT,
+            "1.5",
+            "--memo",
+            "Invoice 42",
+            "--yes",
+        ],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(
+        &output_str,
+        &format!(
+            "To:            {} (owner: {})",
+            recipient_account, RECIPIENT
+        ),
+    );
+    common::assert_contains(&output_str, "Amount:        1.500000 (1500000 base units)");
+    common::assert_contains(&output_str, "Memo:          Invoice 42");
+    common::assert_contains(&output_str, "Balance after: 1.000000");
+    common::assert_contains(
+        &output_str,
+        "the recipient has no token account for this mint",
+    );
+    // La transaction diffusée crée le compte du destinataire, transfère les tokens, puis ajoute le memo.
+    let transaction = &rpc.sent_transactions()[0];
+    assert!(transaction.verify().is_ok());
+    assert_eq!(transaction.message.instructions.len(), 3);
+    assert_eq!(
+        transaction.message.instructions[2].data,
+        b"Invoice 42".to_vec()
+    );
+
+    // Le compte du destinataire existe : il n'est pas recréé.
+    rpc.set_token_account(&recipient_account, &usdc, &recipient, &spl_token::id(), 0);
+    let output_str = common::stdout(&common::wallet(
+        &[
+            "token",
+            "transfer",
+            &usdc.to_string(),
+            RECIPIENT,
+            "ALL",
+            "--yes",
+        ],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(&output_str, "Amount:        2.500000 (2500000 base units)");
+    assert!(!output_str.contains("the recipient has no token account"));
+
+    // Montants invalides : trop de décimales, balance insuffisante, destinataire qui est un compte de token.
+    for (amount, recipient, expected, exit_code) in [
+        (
+            "0.0000001",
+            RECIPIENT.to_string(),
+            "at most 6 decimals are allowed",
+            2,
+        ),
+        (
+            "3",
+            RECIPIENT.to_string(),
+            "Insufficient token balance: 2.500000 available, 3.000000 needed",
+            8,
+        ),
+        (
+            "1",
+            recipient_account.to_string(),
+            "is a token account: give the wallet address of the recipient",
+            2,
+        ),
+    ] {
+        let output_str = common::stderr(
+            &common::wallet(
+                &[
+                    "token",
+                    "transfer",
+                    &usdc.to_string(),
+                    &recipient,
+                    amount,
+                    "--yes",
+                ],
+                &envs(rpc.url()),
+            ),
+            exit_code,
+        );
+        common::assert_contains(&output_str, "Failed to transfer tokens: ");
+        common::assert_contains(&output_str, expected);
+    }
+
+    /*
+    |--------------------------------------------------------------------------
+    | "token close" ferme un compte vide, et refuse un compte qui détient des tokens
+    |--------------------------------------------------------------------------
+    */
+
+    let output_str = common::stdout(&common::wallet(
+        &["token", "close", &empty_account.to_string(), "--yes"],
+        &envs(rpc.url()),
+    ));
+    common::assert_contains(
+        &output_str,
+        &format!("Token account {} closed", empty_account),
+    );
+
+    let output_str = common::stderr(
+        &common::wallet(
+            &["token", "close", &usdc_account.to_string(), "--yes"],
+            &envs(rpc.url()),
+        ),
+        2,
+    );
+    common::assert_contains(&output_str, "Failed to close token account: Token account");
+    common::assert_contains(&output_str, "still holds 2.500000 tokens");
+
+    fs::remove_file(KEYPAIR_PATH).ok();
+}
@@ -0,0 +1,249 @@
+use crate::common;
+use crate::common::mock_rpc::{MockRpcServer, MOCK_BLOCKHASH};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serial_test::serial;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{write_keypair_file, Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+use std::fs;
+use std::process::{Command, Output};
+use std::str;
+
+// cargo test --test mod -- commands::tx_test --nocapture
+
+const RECIPIENT: &str = "EMLY3VvNZ41yMWyPQy2AiEfJTPpZdzeGNG5zaaq3Lihb";
+const TX_PATH: &str = "./storage/tests/tx_temp_test.json";
+const SIGNER_PATH: &str = "./storage/tests/keypair/id_temp_test_tx.json";
+const OTHER_SIGNER_PATH: &str = "./storage/tests/keypair/id_temp_test_tx_other.json";
+
+/// Exécute une commande "tx" avec une paire de clés et un RPC donnés.
+fn execute(args: &[&str], keypair_path: &str, rpc_url: &str) -> Output {
+    Command::new("cargo")
+        .args(["run", "--", "tx"])
+        .args(args)
+        .env("KEYPAIR_PATH", keypair_path)
+        .env(
+            "WALLET_REGISTRY_PATH",
+            "./storage/tests/wallet_temp_test_tx.json",
+        )
+        .env("RPC_URL", rpc_url)
+        .env_remove("KEYSTORE_PASSWORD")
+        .output()
+        .expect("Failed to execute command")
+}
+
+fn run(args: &[&str], keypair_path: &str, rpc_url: &str) -> String {
+    let output = execute(args, keypair_path, rpc_url);
+    assert!(output.status.success(), "Error: Command failed to execute");
+
+    str::from_utf8(&output.stdout)
+        .expect("Invalid UTF-8 output")
+        .to_string()
+}
+
+/// Exécute une commande qui doit échouer avec le code de sortie attendu, et retourne son message d'erreur.
+fn run_err(args: &[&str], keypair_path: &str, rpc_url: &str, exit_code: i32) -> String {
+    common::stderr(&execute(args, keypair_path, rpc_url), exit_code)
+}
+
+#[test]
+#[serial]
+fn test_tx_build_sign_broadcast() {
+    common::setup();
+
+    let signer = Keypair::new();
+    write_keypair_file(&signer, SIGNER_PATH).expect("Failed to write keypair");
+    write_keypair_file(&Keypair::new(), OTHER_SIGNER_PATH).expect("Failed to write keypair");
+
+    let rpc = MockRpcServer::start();
+    rpc.set_balance(&signer.pubkey().to_string(), 1_000_000_000);
+
+    // Aucun noeud n'écoute sur ce port : les étapes hors ligne ne doivent pas utiliser le réseau.
+    let offline_rpc_url = "http://127.0.0.1:9";
+
+    /*
+    |--------------------------------------------------------------------------
+    | Étape 1/3 : "tx build" (en ligne) écrit une transaction non signée
+    |--------------------------------------------------------------------------
+    */
+
+    let output_str = run(
+        &[
+            "build",
+            RECIPIENT,
+            "0.5",
+            "--sol",
+            "--memo",
+            "Cold storage",
+            "--outfile",
+            TX_PATH,
+        ],
+        SIGNER_PATH,
+        rpc.url(),
+    );
+    for expected in [
+        format!("Transaction file: {} (version 1)", TX_PATH),
+        "Instructions:  2".to_string(),
+        format!(
+            "Transfer 0.500000000 SOL (500000000 lamports) from {} to {}",
+            signer.pubkey(),
+            RECIPIENT
+        ),
+        "Estimated fee: 0.000005000 SOL (5000 lamports)".to_string(),
+        format!("Blockhash:     {}", MOCK_BLOCKHASH),
+        "Signatures:    0/1".to_string(),
+        format!("{}: missing", signer.pubkey()),
+        format!("Unsigned transaction written to {}", TX_PATH),
+    ] {
+        assert!(
+            output_str.contains(&expected),
+            "Expected '{}' in output: {}",
+            expected,
+            output_str
+        );
+    }
+
+    let content = fs::read_to_string(TX_PATH).expect("Transaction file not found");
+    let file: serde_json::Value = serde_json::from_str(&content).expect("Invalid transaction file");
+    assert_eq!(file["version"], 1);
+    assert_eq!(file["summary"]["transfers"][0]["lamports"], 500_000_000);
+    assert_eq!(file["summary"]["signers"][0]["signed"], false);
+    assert_eq!(file["summary"]["memos"][0], "Cold storage");
+
+    // Une transaction non signée ne peut pas être diffusée.
+    let output_str = run_err(&["broadcast", TX_PATH], SIGNER_PATH, rpc.url(), 9);
+    assert!(
+        output_str.contains("Failed to broadcast transaction: Transaction is not fully signed"),
+        "Unexpected output: {}",
+        output_str
+    );
+
+    /*
+    |--------------------------------------------------------------------------
+    | Étape 2/3 : "tx sign" (hors ligne) ajoute la signature
+    |--------------------------------------------------------------------------
+    */
+
+    // Une paire de clés qui n'est pas signataire de la transaction est refusée.
+    let output_str = run_err(
+        &["sign", TX_PATH, "--yes"],
+        OTHER_SIGNER_PATH,
+        offline_rpc_url,
+        6,
+    );
+    assert!(
+        output_str.contains("is not a required signer of this transaction"),
+        "Unexpected output: {}",
+        output_str
+    );
+
+    let output_str = run(&["sign", TX_PATH, "--yes"], SIGNER_PATH, offline_rpc_url);
+    assert!(
+        output_str.contains("Memo: Cold storage"),
+        "Unexpected output: {}",
+        output_str
+    );
+    assert!(
+        output_str.contains(&format!(
+            "Transaction signed by {}, written to {}",
+            signer.pubkey(),
+            TX_PATH
+        )),
+        "Unexpected output: {}",
+        output_str
+    );
+
+    let content = fs::read_to_string(TX_PATH).expect("Transaction file not found");
+    let file: serde_json::Value = serde_json::from_str(&content).expect("Invalid transaction file");
+    assert_eq!(file["summary"]["signers"][0]["signed"], true);
+
+    /*
+    |--------------------------------------------------------------------------
+    | Étape 3/3 : "tx broadcast" (en ligne) diffuse la transaction signée
+    |--------------------------------------------------------------------------
+    */
+
+    let output_str = run(&["broadcast", TX_PATH], SIGNER_PATH, rpc.url());
+    assert!(output_str.contains("Signatures:    1/1"));
+    let transaction = &rpc.sent_transactions()[0];
+    assert!(transaction.verify().is_ok());
+    assert!(
+        output_str.contains(&format!(
+            "Transaction signature: {}",
+            transaction.signatures[0]
+        )),
+        "Unexpected output: {}",
+        output_str
+    );
+
+    fs::remove_file(TX_PATH).ok();
+    fs::remove_file(SIGNER_PATH).ok();
+    fs::remove_file(OTHER_SIGNER_PATH).ok();
+}
+
+#[test]
+#[serial]
+fn test_tx_sign_refuses_undecoded_instructions() {
+    common::setup();
+
+    let signer = Keypair::new();
+    write_keypair_file(&signer, SIGNER_PATH).expect("Failed to write keypair");
+
+    // Transaction d'un programme inconnu du wallet : ses instructions ne peuvent pas être relues.
+    let program_id = Pubkey::new_unique();
+    let instruction = Instruction::new_with_bytes(
+        program_id,
+        &[1, 2, 3],
+        vec![AccountMeta::new(signer.pubkey(), true)],
+    );
+    let transaction =
+        Transaction::new_unsigned(Message::new(&[instruction], Some(&signer.pubkey())));
+    let file = serde_json::json!({
+        "version": 1,
+        // Le résumé du fichier est ignoré : il est recalculé à partir de la transaction.
+        "summary": {
+            "fee_payer": signer.pubkey().to_string(),
+            "transfers": [],
+            "fee": 0,
+            "blockhash": transaction.message.recent_blockhash.to_string(),
+            "signers": [],
+        },
+        "transaction": BASE64.encode(bincode::serialize(&transaction).unwrap()),
+    });
+    fs::write(TX_PATH, file.to_string()).expect("Failed to write transaction file");
+
+    let offline_rpc_url = "http://127.0.0.1:9";
+    let output_str = run_err(&["sign", TX_PATH, "--yes"], SIGNER_PATH, offline_rpc_url, 2);
+    assert!(
+        output_str.contains(&format!(
+            "1 instruction(s) which cannot be decoded (program {})",
+            program_id
+        )),
+        "Unexpected output: {}",
+        output_str
+    );
+
+    let output_str = run(
+        &["sign", TX_PATH, "--yes", "--force"],
+        SIGNER_PATH,
+        offline_rpc_url,
+    );
+    for expected in [
+        format!("Unknown program {}", program_id),
+        "Warning: this instruction cannot be decoded".to_string(),
+        format!("Accounts: {}", signer.pubkey()),
+        format!("Transaction signed by {}", signer.pubkey()),
+    ] {
+        assert!(
+            output_str.contains(&expected),
+            "Expected '{}' in output: {}",
+            expected,
+            output_str
+        );
+    }
+
+    fs::remove_file(TX_PATH).ok();
+    fs::remove_file(SIGNER_PATH).ok();
+}
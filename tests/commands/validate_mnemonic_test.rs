@@ -0,0 +1,92 @@
+use crate::common;
+use serde_json::Value;
+use serial_test::serial;
+use std::process::{Command, Output};
+use std::str;
+
+// cargo test --test mod -- commands::validate_mnemonic_test --nocapture
+
+const MNEMONIC: &str =
+    "shed scorpion manual wheat monster phone winter toe dream kitchen salad column";
+
+fn execute(args: &[&str]) -> Output {
+    Command::new("cargo")
+        .args(["run", "--"])
+        .args(args)
+        .output()
+        .expect("Failed to execute command")
+}
+
+#[test]
+#[serial]
+fn test_validate_mnemonic_command() {
+    common::setup();
+
+    /*
+    |--------------------------------------------------------------------------
+    | Phrase valide : langue et nombre de mots
+    |--------------------------------------------------------------------------
+    */
+
+    let output = execute(&["validate_mnemonic", MNEMONIC]);
+    assert!(output.status.success(), "Error: Command failed to execute");
+    let stdout = str::from_utf8(&output.stdout).expect("Invalid UTF-8 output");
+    assert!(
+        stdout.contains("Valid mnemonic: english (12 words)"),
+        "{}",
+        stdout
+    );
+
+    let output = execute(&["--output", "json", "validate_mnemonic", MNEMONIC]);
+    let json: Value = serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["valid"], true);
+    assert_eq!(json["language"], "english");
+    assert_eq!(json["words"], 12);
+
+    /*
+    |--------------------------------------------------------------------------
+    | Mot mal orthographié : mots proches et corrections du checksum
+    |--------------------------------------------------------------------------
+    */
+
+    let typo = MNEMONIC.replace("monster", "monstr");
+    let stderr = common::stderr(&execute(&["validate_mnemonic", &typo]), 3);
+    assert!(
+        stderr.contains("word 5 \"monstr\" is not in the english wordlist (did you mean: monster"),
+        "{}",
+        stderr
+    );
+    assert!(
+        stderr.contains("replacements of word 5 with a valid checksum: monster"),
+        "{}",
+        stderr
+    );
+
+    // Mot tronqué : le préfixe de 4 lettres suffit à le retrouver.
+    let truncated = MNEMONIC.replace("scorpion", "scor");
+    let stderr = common::stderr(&execute(&["validate_mnemonic", &truncated]), 3);
+    assert!(stderr.contains("(did you mean: scorpion"), "{}", stderr);
+
+    // Mot de la liste, mais checksum invalide.
+    let checksum = MNEMONIC.replace("column", "abandon");
+    let stderr = common::stderr(&execute(&["validate_mnemonic", &checksum]), 3);
+    assert!(
+        stderr.contains("invalid checksum (single-word corrections with a valid checksum:"),
+        "{}",
+        stderr
+    );
+
+    /*
+    |--------------------------------------------------------------------------
+    | "recover_seed" affiche les mêmes suggestions
+    |--------------------------------------------------------------------------
+    */
+
+    let stderr = common::stderr(&execute(&["recover_seed", &typo]), 3);
+    assert!(
+        stderr.contains("Failed to recover seed: Invalid mnemonic phrase: word 5 \"monstr\""),
+        "{}",
+        stderr
+    );
+    assert!(stderr.contains("did you mean: monster"), "{}", stderr);
+}
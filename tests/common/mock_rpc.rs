@@ -0,0 +1,628 @@
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use serde_json::{json, Value};
+use solana_sdk::compute_budget;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::Message;
+use solana_sdk::nonce::state::{Data, DurableNonce, State, Versions};
+use solana_sdk::program_option::COption;
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::stake_history::StakeHistory;
+use solana_sdk::system_instruction::SystemInstruction;
+use solana_sdk::system_program;
+use solana_sdk::sysvar;
+use solana_sdk::transaction::Transaction;
+use spl_token_2022::state::{Account as TokenAccount, AccountState, Mint};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Blockhash retourné par "getLatestBlockhash".
+pub const MOCK_BLOCKHASH: &str = "4sGjMW1sUnHzSxGspuhpqLDx6wiyjNtZAMdL4VZHirAn";
+/// Frais par signature (en lamports) retournés par "getFeeForMessage" (plus les frais de priorité du message).
+pub const MOCK_FEE: u64 = 5000;
+/// Réserve d'exemption de rente (en lamports) d'un compte système, retournée par "getMinimumBalanceForRentExemption".
+pub const MOCK_RENT_EXEMPT_RESERVE: u64 = 890_880;
+/// Délégation minimale (en lamports) retournée par "getStakeMinimumDelegation".
+pub const MOCK_MINIMUM_DELEGATION: u64 = 1_000_000_000;
+/// Hash du bloc genesis de devnet, retourné par défaut par "getGenesisHash".
+pub const DEVNET_GENESIS_HASH: &str = "EtWTRABZaYq6iMfeYKouRu166VU2xqa1wcaWoxPkrZBG";
+/// Hash du bloc genesis de mainnet-beta.
+pub const MAINNET_GENESIS_HASH: &str = "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d";
+
+/// État partagé du serveur RPC simulé.
+#[derive(Default)]
+struct MockRpcState {
+    balances: HashMap<String, u64>,
+    /// Comptes retournés par "getAccountInfo" (les autres adresses n'existent pas).
+    accounts: HashMap<String, Value>,
+    /// Décimales des mints déclarés.
+    mints: HashMap<String, u8>,
+    /// Comptes de tokens retournés par "getTokenAccountsByOwner" : (propriétaire, programme, compte "jsonParsed").
+    token_accounts: Vec<(String, String, Value)>,
+    /// Frais de priorité (en micro-lamports) retournés par "getRecentPrioritizationFees", un par slot.
+    prioritization_fees: Vec<u64>,
+    /// Epoch courante retournée par "getEpochInfo".
+    epoch: u64,
+    /// Transactions retournées par "getTransaction", par signature.
+    transactions: HashMap<String, Value>,
+    /// Signatures retournées par "getSignaturesForAddress", de la plus récente à la plus ancienne, par adresse.
+    signatures: HashMap<String, Vec<String>>,
+    /// Récompenses d'inflation retournées par "getInflationReward" : (epoch, montant en lamports).
+    inflation_rewards: HashMap<String, (u64, u64)>,
+    /// Hauteur de bloc retournée par "getBlockHeight".
+    block_height: u64,
+    /// Erreurs des transactions retournées par "getSignatureStatuses" (null : transaction réussie), par signature.
+    signature_statuses: HashMap<String, Value>,
+    /// Transactions reçues par "sendTransaction" (elles ne sont pas exécutées), dans l'ordre.
+    sent_transactions: Vec<Transaction>,
+    /// Erreur retournée par "sendTransaction" (None : les transactions sont acceptées et confirmées).
+    send_error: Option<String>,
+    /// Hash du bloc genesis retourné par "getGenesisHash" (None : devnet).
+    genesis_hash: Option<String>,
+    requests: Vec<Value>,
+}
+
+/// Serveur JSON-RPC local (HTTP) imitant un noeud Solana, pour tester les commandes sans réseau.
+///
+/// Le serveur tourne dans un thread en arrière-plan pendant toute la durée du processus de test.
+/// Il suffit de passer son URL à la commande via la variable d'environnement "RPC_URL".
+pub struct MockRpcServer {
+    url: String,
+    state: Arc<Mutex<MockRpcState>>,
+}
+
+impl MockRpcServer {
+    /// Démarre le serveur sur un port libre de 127.0.0.1.
+    pub fn start() -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind mock RPC server");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(MockRpcState::default()));
+
+        let server_state = Arc::clone(&state);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let connection_state = Arc::clone(&server_state);
+                thread::spawn(move || Self::handle_connection(stream, connection_state));
+            }
+        });
+
+        Self { url, state }
+    }
+
+    /// Retourne l'URL à utiliser comme "RPC_URL".
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Définit la balance (en lamports) retournée pour une clé publique.
+    pub fn set_balance(&self, pubkey: &str, lamports: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.balances.insert(pubkey.to_string(), lamports);
+    }
+
+    /// Déclare un compte exécutable (programme) retourné par "getAccountInfo".
+    pub fn set_program(&self, pubkey: &str) {
+        let account = json!({
+            "data": ["", "base64"],
+            "executable": true,
+            "lamports": 1_141_440,
+            "owner": "BPFLoaderUpgradeab1e11111111111111111111111",
+            "rentEpoch": 0,
+            "space": 0
+        });
+        let mut state = self.state.lock().unwrap();
+        state.accounts.insert(pubkey.to_string(), account);
+    }
+
+    /// Déclare un compte quelconque (propriétaire, balance et données), retourné par "getAccountInfo".
+    pub fn set_account(&self, pubkey: &Pubkey, owner: &Pubkey, lamports: u64, data: &[u8]) {
+        let account = json!({
+            "data": [BASE64.encode(data), "base64"],
+            "executable": false,
+            "lamports": lamports,
+            "owner": owner.to_string(),
+            "rentEpoch": 0,
+            "space": data.len()
+        });
+        let mut state = self.state.lock().unwrap();
+        state.accounts.insert(pubkey.to_string(), account);
+    }
+
+    /// Définit l'epoch courante, avec un historique du stake vide (le stake délégué avant cette epoch est actif).
+    pub fn set_epoch(&self, epoch: u64) {
+        let history = bincode::serialize(&StakeHistory::default()).unwrap();
+        self.set_account(
+            &sysvar::stake_history::id(),
+            &sysvar::id(),
+            1_000_000,
+            &history,
+        );
+        self.state.lock().unwrap().epoch = epoch;
+    }
+
+    /// Définit la dernière récompense d'inflation (en lamports) versée à un compte de stake.
+    pub fn set_inflation_reward(&self, pubkey: &Pubkey, epoch: u64, lamports: u64) {
+        let mut state = self.state.lock().unwrap();
+        state
+            .inflation_rewards
+            .insert(pubkey.to_string(), (epoch, lamports));
+    }
+
+    /// Définit la hauteur de bloc actuelle (l'expiration des blockhashs est "lastValidBlockHeight": 100).
+    pub fn set_block_height(&self, block_height: u64) {
+        self.state.lock().unwrap().block_height = block_height;
+    }
+
+    /// Déclare l'état d'une transaction retourné par "getSignatureStatuses" (err : null pour une transaction réussie).
+    pub fn set_signature_status(&self, signature: &str, err: Value) {
+        let mut state = self.state.lock().unwrap();
+        state.signature_statuses.insert(signature.to_string(), err);
+    }
+
+    /// Ajoute une transaction confirmée (la plus récente) à l'historique de tous ses comptes.
+    ///
+    /// # Arguments:
+    /// - transaction - La transaction signée.
+    /// - block_time - La date du bloc (Unix timestamp).
+    /// - meta - Les métadonnées de la transaction ("err", "fee", "preBalances", "postBalances", etc.).
+    pub fn add_transaction(&self, transaction: &Transaction, block_time: i64, meta: Value) {
+        let signature = transaction.signatures[0].to_string();
+        let data = BASE64.encode(bincode::serialize(transaction).unwrap());
+
+        let mut state = self.state.lock().unwrap();
+        let slot = state.transactions.len() as u64 + 1;
+        let confirmed = json!({
+            "slot": slot,
+            "blockTime": block_time,
+            "transaction": [data, "base64"],
+            "meta": meta,
+        });
+        state.transactions.insert(signature.clone(), confirmed);
+        for account in &transaction.message.account_keys {
+            state
+                .signatures
+                .entry(account.to_string())
+                .or_default()
+                .insert(0, signature.clone());
+        }
+    }
+
+    /// Déclare un compte nonce durable initialisé, retourné par "getAccountInfo".
+    ///
+    /// # Returns:
+    /// Retourne la valeur actuelle du nonce (le "blockhash" des transactions qui l'utilisent).
+    pub fn set_nonce_account(&self, pubkey: &str, authority: &Pubkey, lamports: u64) -> Hash {
+        let blockhash = Hash::new_unique();
+        let durable_nonce = DurableNonce::from_blockhash(&blockhash);
+        let state = Versions::new(State::Initialized(Data::new(
+            *authority,
+            durable_nonce,
+            MOCK_FEE,
+        )));
+        let data = bincode::serialize(&state).expect("Failed to serialize nonce account");
+
+        let account = json!({
+            "data": [BASE64.encode(&data), "base64"],
+            "executable": false,
+            "lamports": lamports,
+            "owner": "11111111111111111111111111111111",
+            "rentEpoch": 0,
+            "space": data.len()
+        });
+        let mut state = self.state.lock().unwrap();
+        state.accounts.insert(pubkey.to_string(), account);
+
+        *durable_nonce.as_hash()
+    }
+
+    /// Déclare le mint d'un token, géré par un programme SPL (Token ou Token-2022).
+    pub fn set_mint(&self, mint: &Pubkey, program: &Pubkey, decimals: u8) {
+        let state = Mint {
+            mint_authority: COption::None,
+            supply: u64::MAX,
+            decimals,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        let mut data = vec![0; Mint::LEN];
+        Mint::pack(state, &mut data).unwrap();
+
+        let mut state = self.state.lock().unwrap();
+        state.mints.insert(mint.to_string(), decimals);
+        state
+            .accounts
+            .insert(mint.to_string(), Self::program_account(program, &data));
+    }
+
+    /// Déclare un compte de token (le mint doit avoir été déclaré avec "set_mint").
+    pub fn set_token_account(
+        &self,
+        address: &Pubkey,
+        mint: &Pubkey,
+        owner: &Pubkey,
+        program: &Pubkey,
+        amount: u64,
+    ) {
+        let state = TokenAccount {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            delegate: COption::None,
+            state: AccountState::Initialized,
+            is_native: COption::None,
+            delegated_amount: 0,
+            close_authority: COption::None,
+        };
+        let mut data = vec![0; TokenAccount::LEN];
+        TokenAccount::pack(state, &mut data).unwrap();
+
+        let mut state = self.state.lock().unwrap();
+        let decimals = state.mints[&mint.to_string()];
+        let parsed = json!({
+            "pubkey": address.to_string(),
+            "account": {
+                "data": {
+                    "program": if *program == spl_token::id() { "spl-token" } else { "spl-token-2022" },
+                    "parsed": {
+                        "type": "account",
+                        "info": {
+                            "isNative": false,
+                            "mint": mint.to_string(),
+                            "owner": owner.to_string(),
+                            "state": "initialized",
+                            "tokenAmount": {
+                                "amount": amount.to_string(),
+                                "decimals": decimals,
+                                "uiAmount": null,
+                                "uiAmountString": amount.to_string()
+                            }
+                        }
+                    },
+                    "space": TokenAccount::LEN
+                },
+                "executable": false,
+                "lamports": 2_039_280,
+                "owner": program.to_string(),
+                "rentEpoch": 0,
+                "space": TokenAccount::LEN
+            }
+        });
+        state
+            .token_accounts
+            .retain(|(_, _, account)| account["pubkey"] != address.to_string());
+        state
+            .token_accounts
+            .push((owner.to_string(), program.to_string(), parsed));
+        state
+            .accounts
+            .insert(address.to_string(), Self::program_account(program, &data));
+    }
+
+    /// Définit les frais de priorité récents (en micro-lamports par unité de calcul), un par slot.
+    pub fn set_prioritization_fees(&self, fees: &[u64]) {
+        let mut state = self.state.lock().unwrap();
+        state.prioritization_fees = fees.to_vec();
+    }
+
+    /// Définit le hash du bloc genesis retourné par "getGenesisHash" (le cluster détecté par le wallet).
+    pub fn set_genesis_hash(&self, genesis_hash: &str) {
+        self.state.lock().unwrap().genesis_hash = Some(genesis_hash.to_string());
+    }
+
+    /// Fait échouer les prochains "sendTransaction" avec un message d'erreur.
+    pub fn set_send_error(&self, message: &str) {
+        self.state.lock().unwrap().send_error = Some(message.to_string());
+    }
+
+    /// Retourne les transactions reçues par "sendTransaction", dans l'ordre.
+    pub fn sent_transactions(&self) -> Vec<Transaction> {
+        self.state.lock().unwrap().sent_transactions.clone()
+    }
+
+    /// Retourne les transferts de SOL des transactions reçues par "sendTransaction" : (expéditeur, destinataire, lamports).
+    pub fn sent_transfers(&self) -> Vec<(Pubkey, Pubkey, u64)> {
+        let mut transfers = Vec::new();
+        for transaction in self.sent_transactions() {
+            let message = &transaction.message;
+            for instruction in &message.instructions {
+                if *instruction.program_id(&message.account_keys) != system_program::id() {
+                    continue;
+                }
+                if let Ok(SystemInstruction::Transfer { lamports }) =
+                    bincode::deserialize(&instruction.data)
+                {
+                    let from = message.account_keys[instruction.accounts[0] as usize];
+                    let to = message.account_keys[instruction.accounts[1] as usize];
+                    transfers.push((from, to, lamports));
+                }
+            }
+        }
+        transfers
+    }
+
+    /// Retourne les requêtes reçues pour une méthode RPC donnée.
+    pub fn requests(&self, method: &str) -> Vec<Value> {
+        let state = self.state.lock().unwrap();
+        state
+            .requests
+            .iter()
+            .filter(|request| request["method"] == method)
+            .cloned()
+            .collect()
+    }
+
+    /// Traite les requêtes HTTP d'une connexion (keep-alive) jusqu'à sa fermeture.
+    fn handle_connection(stream: TcpStream, state: Arc<Mutex<MockRpcState>>) {
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+        let mut writer = stream;
+
+        loop {
+            // Lecture des en-têtes HTTP (seul "Content-Length" nous intéresse).
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                    return;
+                }
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    if name.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap_or(0);
+                    }
+                }
+            }
+
+            let mut body = vec![0; content_length];
+            if reader.read_exact(&mut body).is_err() {
+                return;
+            }
+            let request: Value = serde_json::from_slice(&body).unwrap_or(Value::Null);
+            let response = Self::respond(&request, &state).to_string();
+
+            let http_response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                response.len(),
+                response
+            );
+            if writer.write_all(http_response.as_bytes()).is_err() {
+                return;
+            }
+        }
+    }
+
+    /// Construit la réponse JSON-RPC à une requête.
+    fn respond(request: &Value, state: &Arc<Mutex<MockRpcState>>) -> Value {
+        let mut state = state.lock().unwrap();
+        state.requests.push(request.clone());
+
+        let id = request["id"].clone();
+        let params = &request["params"];
+        let context = json!({ "slot": 1 });
+
+        let result = match request["method"].as_str().unwrap_or_default() {
+            "getVersion" => json!({ "solana-core": "1.18.26", "feature-set": 0 }),
+            "getGenesisHash" => json!(state.genesis_hash.as_deref().unwrap_or(DEVNET_GENESIS_HASH)),
+            "getBalance" => {
+                let pubkey = params[0].as_str().unwrap_or_default();
+                let lamports = state.balances.get(pubkey).copied().unwrap_or(0);
+                json!({ "context": context, "value": lamports })
+            }
+            "getAccountInfo" => {
+                let pubkey = params[0].as_str().unwrap_or_default();
+                let account = state.accounts.get(pubkey).cloned();
+                json!({ "context": context, "value": account })
+            }
+            "getTokenAccountsByOwner" => {
+                let owner = params[0].as_str().unwrap_or_default();
+                let program = params[1]["programId"].as_str().unwrap_or_default();
+                let accounts: Vec<Value> = state
+                    .token_accounts
+                    .iter()
+                    .filter(|(account_owner, account_program, _)| {
+                        account_owner == owner && account_program == program
+                    })
+                    .map(|(_, _, account)| account.clone())
+                    .collect();
+                json!({ "context": context, "value": accounts })
+            }
+            "getLatestBlockhash" => json!({
+                "context": context,
+                "value": { "blockhash": MOCK_BLOCKHASH, "lastValidBlockHeight": 100 }
+            }),
+            "getFeeForMessage" => {
+                let fee = params[0]
+                    .as_str()
+                    .and_then(|message| BASE64.decode(message).ok())
+                    .and_then(|bytes| bincode::deserialize::<Message>(&bytes).ok())
+                    .map(|message| Self::fee_for_message(&message));
+                json!({ "context": context, "value": fee })
+            }
+            "getRecentPrioritizationFees" => json!(state
+                .prioritization_fees
+                .iter()
+                .enumerate()
+                .map(|(slot, fee)| json!({ "slot": slot, "prioritizationFee": fee }))
+                .collect::<Vec<_>>()),
+            "getMinimumBalanceForRentExemption" => json!(MOCK_RENT_EXEMPT_RESERVE),
+            "getSignaturesForAddress" => {
+                let address = params[0].as_str().unwrap_or_default();
+                let config = &params[1];
+                let limit = config["limit"].as_u64().unwrap_or(1000) as usize;
+                let signatures = state.signatures.get(address).cloned().unwrap_or_default();
+                // Signatures plus anciennes que "before" (exclue), jusqu'à "until" (exclue).
+                let start = config["before"]
+                    .as_str()
+                    .and_then(|before| signatures.iter().position(|s| s == before))
+                    .map(|position| position + 1)
+                    .unwrap_or(0);
+                let page: Vec<Value> = signatures[start.min(signatures.len())..]
+                    .iter()
+                    .take_while(|signature| config["until"].as_str() != Some(signature.as_str()))
+                    .take(limit)
+                    .map(|signature| {
+                        let transaction = &state.transactions[signature];
+                        json!({
+                            "signature": signature,
+                            "slot": transaction["slot"],
+                            "err": transaction["meta"]["err"],
+                            "memo": null,
+                            "blockTime": transaction["blockTime"],
+                            "confirmationStatus": "finalized"
+                        })
+                    })
+                    .collect();
+                json!(page)
+            }
+            "getTransaction" => {
+                let signature = params[0].as_str().unwrap_or_default();
+                state
+                    .transactions
+                    .get(signature)
+                    .cloned()
+                    .unwrap_or(Value::Null)
+            }
+            "getBlockHeight" => json!(state.block_height),
+            // La transaction est enregistrée, puis considérée comme confirmée par "getSignatureStatuses".
+            "sendTransaction" => {
+                if let Some(message) = state.send_error.clone() {
+                    return json!({
+                        "jsonrpc": "2.0",
+                        "id": id,
+                        "error": { "code": -32002, "message": message }
+                    });
+                }
+                let transaction: Transaction = params[0]
+                    .as_str()
+                    .and_then(|data| BASE64.decode(data).ok())
+                    .and_then(|bytes| bincode::deserialize(&bytes).ok())
+                    .expect("Invalid transaction sent to the mock RPC server");
+                let signature = transaction.signatures[0].to_string();
+                state
+                    .signature_statuses
+                    .entry(signature.clone())
+                    .or_insert(Value::Null);
+                state.sent_transactions.push(transaction);
+                json!(signature)
+            }
+            "simulateTransaction" => json!({
+                "context": context,
+                "value": {
+                    "err": null,
+                    "logs": [],
+                    "accounts": null,
+                    "unitsConsumed": 0,
+                    "returnData": null,
+                    "innerInstructions": null
+                }
+            }),
+            "getSignatureStatuses" => {
+                let statuses: Vec<Value> = params[0]
+                    .as_array()
+                    .map(Vec::as_slice)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|signature| {
+                        let err = signature
+                            .as_str()
+                            .and_then(|signature| state.signature_statuses.get(signature));
+                        match err {
+                            Some(err) => json!({
+                                "slot": 1,
+                                "confirmations": null,
+                                "err": err,
+                                "status": if err.is_null() { json!({ "Ok": null }) } else { json!({ "Err": err }) },
+                                "confirmationStatus": "finalized"
+                            }),
+                            None => Value::Null,
+                        }
+                    })
+                    .collect();
+                json!({ "context": context, "value": statuses })
+            }
+            "getStakeMinimumDelegation" => {
+                json!({ "context": context, "value": MOCK_MINIMUM_DELEGATION })
+            }
+            "getEpochInfo" => json!({
+                "absoluteSlot": state.epoch * 432_000,
+                "blockHeight": state.epoch * 432_000,
+                "epoch": state.epoch,
+                "slotIndex": 0,
+                "slotsInEpoch": 432_000,
+                "transactionCount": 0
+            }),
+            "getInflationReward" => json!(params[0]
+                .as_array()
+                .map(Vec::as_slice)
+                .unwrap_or_default()
+                .iter()
+                .map(|address| {
+                    let reward = address
+                        .as_str()
+                        .and_then(|address| state.inflation_rewards.get(address));
+                    reward.map(|(epoch, amount)| {
+                        json!({
+                            "epoch": epoch,
+                            "effectiveSlot": (epoch + 1) * 432_000,
+                            "amount": amount,
+                            "postBalance": 0,
+                            "commission": 5
+                        })
+                    })
+                })
+                .collect::<Vec<_>>()),
+            method => {
+                return json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": -32601, "message": format!("Method not found: {}", method) }
+                })
+            }
+        };
+
+        json!({ "jsonrpc": "2.0", "id": id, "result": result })
+    }
+
+    /// Compte (non exécutable) appartenant à un programme, avec ses données encodées en base64.
+    fn program_account(program: &Pubkey, data: &[u8]) -> Value {
+        json!({
+            "data": [BASE64.encode(data), "base64"],
+            "executable": false,
+            "lamports": 2_039_280,
+            "owner": program.to_string(),
+            "rentEpoch": 0,
+            "space": data.len()
+        })
+    }
+
+    /// Calcule les frais d'un message comme le runtime : frais par signature, plus le prix
+    /// d'une unité de calcul × la limite d'unités de calcul (200 000 par instruction par défaut).
+    fn fee_for_message(message: &Message) -> u64 {
+        let mut unit_limit = None;
+        let mut unit_price = 0;
+        let mut instructions = 0;
+        for instruction in &message.instructions {
+            if message.account_keys[instruction.program_id_index as usize] != compute_budget::id() {
+                instructions += 1;
+                continue;
+            }
+            // Instructions "SetComputeUnitLimit" (2, u32) et "SetComputeUnitPrice" (3, u64), en little-endian.
+            match instruction.data.split_first() {
+                Some((2, limit)) => {
+                    unit_limit = Some(u32::from_le_bytes(limit.try_into().unwrap()))
+                }
+                Some((3, price)) => unit_price = u64::from_le_bytes(price.try_into().unwrap()),
+                _ => {}
+            }
+        }
+        let unit_limit = unit_limit.unwrap_or((instructions * 200_000).min(1_400_000));
+        let priority_fee = (u128::from(unit_price) * u128::from(unit_limit)).div_ceil(1_000_000);
+
+        MOCK_FEE * u64::from(message.header.num_required_signatures) + priority_fee as u64
+    }
+}
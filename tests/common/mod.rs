@@ -1,3 +1,7 @@
+pub mod mock_rpc;
+
+use std::process::{Command, Output};
+use std::str;
 use std::sync::Once;
 
 static INIT: Once = Once::new();
@@ -26,3 +30,61 @@ pub fn verify_pubkey(pubkey: &str) {
         "Public key contains non-alphanumeric characters"
     );
 }
+
+/// Vérifie qu'une commande a échoué avec le code de sortie attendu, et retourne son message d'erreur (stderr).
+pub fn stderr(output: &Output, exit_code: i32) -> String {
+    let stderr = str::from_utf8(&output.stderr)
+        .expect("Invalid UTF-8 output")
+        .to_string();
+    assert_eq!(
+        output.status.code(),
+        Some(exit_code),
+        "Unexpected exit code, stderr: {}",
+        stderr
+    );
+    stderr
+}
+
+/// Exécute le wallet ("cargo run") avec les variables d'environnement données.
+/// "KEYSTORE_PASSWORD" n'est pas transmise, sauf si elle fait partie de "envs".
+pub fn wallet(args: &[&str], envs: &[(&str, &str)]) -> Output {
+    wallet_unset(args, &[], envs)
+}
+
+/// Exécute le wallet comme "wallet", sans transmettre les variables d'environnement "unset"
+/// (par exemple celles chargées depuis ".env.testing"), sauf si elles font partie de "envs".
+pub fn wallet_unset(args: &[&str], unset: &[&str], envs: &[(&str, &str)]) -> Output {
+    let mut command = Command::new("cargo");
+    command
+        .args(["run", "--"])
+        .args(args)
+        .env_remove("KEYSTORE_PASSWORD");
+    for var in unset {
+        command.env_remove(var);
+    }
+    command
+        .envs(envs.iter().copied())
+        .output()
+        .expect("Failed to execute command")
+}
+
+/// Vérifie qu'une commande a réussi, et retourne sa sortie standard.
+pub fn stdout(output: &Output) -> String {
+    assert!(
+        output.status.success(),
+        "Error: Command failed to execute, stderr: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    str::from_utf8(&output.stdout)
+        .expect("Invalid UTF-8 output")
+        .to_string()
+}
+
+pub fn assert_contains(output_str: &str, expected: &str) {
+    assert!(
+        output_str.contains(expected),
+        "Expected '{}' in output: {}",
+        expected,
+        output_str
+    );
+}